@@ -40,9 +40,42 @@ pub enum Command {
         /// Process ID (skip automatic detection)
         #[arg(long)]
         pid: Option<u32>,
+        /// Search script (JSON) with judge counts and settings; skips the prompts
+        #[arg(long, value_name = "FILE")]
+        script: Option<String>,
+    },
+    /// Generate code signatures from known-good offsets
+    GenerateSignatures {
+        /// Offsets file with correct offsets for the running build
+        #[arg(long, value_name = "FILE")]
+        offsets_file: String,
+        /// Output file path (signature set JSON)
+        #[arg(short, long, default_value = "signatures.json")]
+        output: String,
+        /// Process ID (skip automatic detection)
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    /// Report how well signatures still resolve on a build
+    CheckSignatures {
+        /// Signature set JSON (default: builtin signatures)
+        #[arg(long, value_name = "FILE")]
+        signatures: Option<String>,
+        /// Check against a memory snapshot instead of the running game
+        #[arg(long, value_name = "FILE", conflicts_with = "pid")]
+        snapshot: Option<String>,
+        /// Process ID (skip automatic detection)
+        #[arg(long)]
+        pid: Option<u32>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Analyze memory structure (debug mode)
+    #[command(args_conflicts_with_subcommands = true)]
     Analyze {
+        #[command(subcommand)]
+        target: Option<AnalyzeTarget>,
         /// Address to analyze (hex, e.g., 0x14314A50C)
         #[arg(long)]
         address: Option<String>,
@@ -95,6 +128,21 @@ pub enum Command {
         #[arg(long)]
         entry_size: Option<usize>,
     },
+    /// Record tracker memory regions into a snapshot file for offline replay
+    Snapshot {
+        /// Load offsets from file
+        #[arg(long, value_name = "FILE")]
+        offsets_file: Option<String>,
+        /// Process ID (skip automatic detection)
+        #[arg(long)]
+        pid: Option<u32>,
+        /// Output file path
+        #[arg(short, long, default_value = "infst.snap")]
+        output: String,
+        /// Record the whole module image (for offset discovery on a new build)
+        #[arg(long)]
+        module: bool,
+    },
     /// Explore memory structure at a specific address
     Explore {
         /// Base address to explore (hex, e.g., 0x1431865A0)
@@ -109,12 +157,39 @@ pub enum Command {
         /// Start address (hex, e.g., 0x1431B08A0)
         #[arg(long)]
         address: String,
-        /// Number of bytes to dump (default: 256)
-        #[arg(long, default_value = "256")]
-        size: usize,
+        /// Number of bytes to dump (default: 256, or the layout size)
+        #[arg(long)]
+        size: Option<usize>,
         /// Include ASCII representation
         #[arg(long)]
         ascii: bool,
+        /// Label fields of a known structure (judge, play, settings, song, ...)
+        #[arg(long)]
+        layout: Option<String>,
+        /// Process ID (skip automatic detection)
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    /// Watch a memory range and print the bytes that change
+    WatchMemory {
+        /// Start address (hex, e.g., 0x1431B08A0)
+        #[arg(long)]
+        address: String,
+        /// Number of bytes to watch (default: 256, or the layout size)
+        #[arg(long)]
+        size: Option<usize>,
+        /// Polling interval in milliseconds
+        #[arg(long, default_value = "100")]
+        interval: u64,
+        /// Report changes in aligned words of this many bytes
+        #[arg(long, default_value = "4")]
+        align: usize,
+        /// Label fields of a known structure (judge, play, settings, song, ...)
+        #[arg(long)]
+        layout: Option<String>,
+        /// Record changes to a JSON lines file
+        #[arg(short, long)]
+        output: Option<String>,
         /// Process ID (skip automatic detection)
         #[arg(long)]
         pid: Option<u32>,
@@ -162,6 +237,15 @@ pub enum Command {
         /// Output format
         #[arg(long, short, value_enum, default_value = "tsv")]
         format: ExportFormat,
+        /// Only export songs with a BPM at or above this (soflan songs: highest BPM)
+        #[arg(long)]
+        min_bpm: Option<u32>,
+        /// Only export songs with a BPM at or below this (soflan songs: lowest BPM)
+        #[arg(long)]
+        max_bpm: Option<u32>,
+        /// Only export songs from this version (name or folder number, e.g. "HAPPY SKY")
+        #[arg(long, value_name = "VERSION")]
+        origin_version: Option<String>,
         /// Process ID (skip automatic detection)
         #[arg(long)]
         pid: Option<u32>,
@@ -209,6 +293,11 @@ pub enum Command {
         #[arg(long, env = "INFST_API_TOKEN")]
         token: Option<String>,
     },
+    /// Manage the known offsets database
+    Offsets {
+        #[command(subcommand)]
+        action: OffsetsAction,
+    },
     /// Manage INFINITAS launcher (URL handler + Special K)
     Launch {
         #[command(subcommand)]
@@ -242,12 +331,74 @@ pub enum LaunchAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum OffsetsAction {
+    /// List known offsets (bundled and user entries)
+    List,
+    /// Add an offsets file to the user database
+    Import {
+        /// Offsets file (version line followed by key = value lines)
+        file: String,
+    },
+    /// Export known offsets in the offsets file format
+    Export {
+        /// Game version to export (default: all entries)
+        version: Option<String>,
+        /// Output file path (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Rewrite an offsets file in the structured JSON format
+    Convert {
+        /// Offsets file to convert (legacy or structured)
+        file: String,
+        /// Output file path (defaults to rewriting the input file)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Compare the offsets of two game builds
+    Diff {
+        /// Older build: offsets file, or game version in the cache or database
+        from: String,
+        /// Newer build: offsets file, or game version in the cache or database
+        to: String,
+        /// Check builtin signatures against a snapshot of the newer build
+        #[arg(long, value_name = "FILE", conflicts_with = "pid")]
+        snapshot: Option<String>,
+        /// Check builtin signatures against the running game (newer build)
+        #[arg(long)]
+        pid: Option<u32>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Clone, clap::ValueEnum)]
 pub enum ExportFormat {
     Tsv,
     Json,
 }
 
+#[derive(Subcommand)]
+pub enum AnalyzeTarget {
+    /// Classify song entry fields across all loaded songs
+    SongFields {
+        /// Load offsets from file
+        #[arg(long, value_name = "FILE")]
+        offsets_file: Option<String>,
+        /// Process ID (skip automatic detection)
+        #[arg(long)]
+        pid: Option<u32>,
+        /// Maximum number of song list entries to read
+        #[arg(long, default_value = "5000")]
+        max_entries: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ValidateTarget {
     /// Validate a song entry structure
@@ -6,16 +6,30 @@
 mod id_search;
 mod pattern_search;
 mod song_counter;
+mod song_fields;
 mod title_search;
 
 use anyhow::{Result, bail};
 use infst::{MemoryReader, OffsetSearcher, ProcessHandle, SongInfo};
 
 use super::hex_utils::parse_hex_address;
+use crate::cli::AnalyzeTarget;
 use pattern_search::search_song_patterns;
 use song_counter::{count_songs_new_structure, count_songs_old_structure};
 use title_search::search_for_title_strings;
 
+/// Run an analysis subcommand.
+pub fn run_target(target: AnalyzeTarget) -> Result<()> {
+    match target {
+        AnalyzeTarget::SongFields {
+            offsets_file,
+            pid,
+            max_entries,
+            json,
+        } => song_fields::run(offsets_file.as_deref(), pid, max_entries, json),
+    }
+}
+
 /// Run the memory structure analysis mode.
 pub fn run(address: Option<String>, pid: Option<u32>) -> Result<()> {
     let current_version = env!("CARGO_PKG_VERSION");
@@ -83,7 +97,7 @@ pub fn run(address: Option<String>, pid: Option<u32>) -> Result<()> {
     println!();
     println!("=== Song count analysis ===");
     let old_count = count_songs_old_structure(&reader, analyze_addr);
-    println!("  Old structure (0x3F0): {} songs", old_count);
+    println!("  Old structure: {} songs", old_count);
 
     // Count songs with new structure
     let new_count = count_songs_new_structure(&reader, analyze_addr);
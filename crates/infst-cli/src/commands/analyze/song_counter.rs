@@ -1,19 +1,20 @@
 //! Song counting functions for memory analysis.
 
-use infst::{MemoryReader, ReadMemory, SongInfo};
+use infst::{MemoryReader, ReadMemory, SongInfo, song_layout_or_current};
 
-/// Count songs using old structure (0x3F0 bytes per entry).
+/// Count songs using old structure (song entries of the detected layout).
 pub fn count_songs_old_structure(reader: &MemoryReader, start: u64) -> usize {
+    let layout = song_layout_or_current(reader, start);
     let mut count = 0;
     let mut addr = start;
     while count < 5000 {
-        match SongInfo::read_from_memory(reader, addr) {
+        match SongInfo::read_from_memory_with_layout(reader, addr, layout) {
             Ok(Some(song)) if !song.title.is_empty() => {
                 count += 1;
             }
             _ => break,
         }
-        addr += SongInfo::MEMORY_SIZE as u64;
+        addr += layout.size as u64;
     }
     count
 }
@@ -0,0 +1,50 @@
+//! Song entry field classification.
+//!
+//! Classifies every offset of the loaded song entries to help map the
+//! unknown and estimated fields of the song entry layout.
+
+use anyhow::Result;
+use infst::{
+    MemoryReader, OffsetSearcher, ProcessHandle, SongFieldReport, builtin_signatures, load_offsets,
+};
+
+/// Classify song entry fields of the running game
+pub fn run(
+    offsets_file: Option<&str>,
+    pid: Option<u32>,
+    max_entries: usize,
+    json: bool,
+) -> Result<()> {
+    let process = if let Some(pid) = pid {
+        ProcessHandle::open(pid)?
+    } else {
+        ProcessHandle::find_and_open()?
+    };
+    eprintln!(
+        "Found process (PID: {}, Base: 0x{:X})",
+        process.pid, process.base_address
+    );
+
+    let reader = MemoryReader::new(&process);
+
+    let offsets = if let Some(path) = offsets_file {
+        load_offsets(path)?
+    } else {
+        let signatures = builtin_signatures();
+        let mut searcher = OffsetSearcher::new(&reader);
+        searcher.search_all_with_signatures(&signatures)?
+    };
+
+    eprintln!(
+        "Reading up to {} song entries from 0x{:X}...",
+        max_entries, offsets.song_list
+    );
+    let report = SongFieldReport::scan(&reader, offsets.song_list, max_entries);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
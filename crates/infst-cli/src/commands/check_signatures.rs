@@ -0,0 +1,48 @@
+//! Check signatures command implementation.
+//!
+//! Evaluates a signature set (the builtin one by default) against the running
+//! game or a snapshot, so a broken signature shows up before detection fails.
+
+use anyhow::{Context, Result};
+use infst::{
+    MemoryReader, ProcessHandle, SignatureCoverageReport, SnapshotMemoryReader, builtin_signatures,
+    load_signatures,
+};
+
+/// Run the check-signatures command
+pub fn run(
+    signatures_file: Option<&str>,
+    snapshot: Option<&str>,
+    pid: Option<u32>,
+    json: bool,
+) -> Result<()> {
+    let signatures = match signatures_file {
+        Some(path) => load_signatures(path).with_context(|| format!("Failed to load {}", path))?,
+        None => builtin_signatures(),
+    };
+
+    let report = if let Some(path) = snapshot {
+        eprintln!("Checking signatures against {}...", path);
+        let reader = SnapshotMemoryReader::open(path)?;
+        SignatureCoverageReport::evaluate(&reader, &signatures)?
+    } else {
+        let process = if let Some(pid) = pid {
+            ProcessHandle::open(pid)?
+        } else {
+            ProcessHandle::find_and_open()?
+        };
+        eprintln!(
+            "Checking signatures against process {} (Base: 0x{:X})...",
+            process.pid, process.base_address
+        );
+        let reader = MemoryReader::new(&process);
+        SignatureCoverageReport::evaluate(&reader, &signatures)?
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
@@ -7,6 +7,7 @@
 //! This is a debugging tool used when investigating new game versions.
 
 use anyhow::Result;
+use infst::process::layout::song;
 use infst::{MemoryReader, ProcessHandle, ReadMemory};
 
 /// Run the memory explore command
@@ -193,7 +194,7 @@ pub fn run(base_addr: u64, pid: Option<u32>) -> Result<()> {
     // Check first entry (5.1.1.) with both old and new offsets
     println!();
     println!("=== Analyzing first entry (5.1.1.) structure ===");
-    if let Ok(data) = reader.read_bytes(base_addr, 1200) {
+    if let Ok(data) = reader.read_bytes(base_addr, song::LAYOUT.size) {
         println!("  Reading from 0x{:X}:", base_addr);
 
         // Check title
@@ -209,13 +210,13 @@ pub fn run(base_addr: u64, pid: Option<u32>) -> Result<()> {
             old_song_id, old_levels
         );
 
-        // Check NEW offsets (discovered from 'fun')
-        let new_levels = &data[480..490];
-        let new_song_id = i32::from_le_bytes([data[816], data[817], data[818], data[819]]);
-        println!(
-            "    NEW: song_id at 816 = {}, levels at 480 = {:?}",
-            new_song_id, new_levels
-        );
+        // Check NEW layout fields (discovered from 'fun')
+        println!("    NEW ({} layout):", song::LAYOUT.name);
+        for field in song::LAYOUT.fields {
+            if let Some(value) = field.format_value(&data) {
+                println!("      {} at {} = {}", field.name, field.offset, value);
+            }
+        }
 
         // Dump some key offsets to understand structure
         for offset in [
@@ -223,7 +224,7 @@ pub fn run(base_addr: u64, pid: Option<u32>) -> Result<()> {
         ]
         .iter()
         {
-            if *offset + 64 <= 1200 {
+            if *offset + 64 <= song::LAYOUT.size {
                 let str_bytes = &data[*offset..*offset + 64];
                 let len = str_bytes.iter().position(|&b| b == 0).unwrap_or(64);
                 if len > 0 && str_bytes[0] >= 0x20 && str_bytes[0] < 0x80 {
@@ -236,14 +237,17 @@ pub fn run(base_addr: u64, pid: Option<u32>) -> Result<()> {
 
     // Try scanning with NEW offsets
     println!();
-    println!("=== Scanning with NEW offsets (song_id at 816) ===");
-    const NEW_ENTRY_SIZE: u64 = 1200; // Hypothesized new entry size
+    println!(
+        "=== Scanning with NEW offsets (song_id at {}) ===",
+        song::SONG_ID.offset
+    );
+    const NEW_ENTRY_SIZE: u64 = song::LAYOUT.size as u64;
     let new_max_entries = (0x800000u64 / NEW_ENTRY_SIZE).min(2000);
     let mut found_with_new = Vec::new();
 
     for i in 0..new_max_entries {
         let entry_addr = base_addr + i * NEW_ENTRY_SIZE;
-        if let Ok(data) = reader.read_bytes(entry_addr, 1200) {
+        if let Ok(data) = reader.read_bytes(entry_addr, song::LAYOUT.size) {
             // Check for valid title
             let title_len = data.iter().take(64).position(|&b| b == 0).unwrap_or(64);
             if title_len == 0 || data[0] < 0x20 {
@@ -256,8 +260,11 @@ pub fn run(base_addr: u64, pid: Option<u32>) -> Result<()> {
             }
 
             // Read with NEW offsets
-            let song_id = i32::from_le_bytes([data[816], data[817], data[818], data[819]]);
-            let levels = &data[480..490];
+            let (Ok(song_id), Ok(levels)) =
+                (song::SONG_ID.decode(&data), song::LEVELS.decode(&data))
+            else {
+                continue;
+            };
 
             if (1000..=50000).contains(&song_id) {
                 found_with_new.push((i, song_id, title.to_string(), levels.to_vec()));
@@ -1,19 +1,37 @@
 //! Export command for exporting play data.
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use infst::{
-    MemoryReader, OffsetSearcher, ScoreMap, fetch_song_database, generate_tracker_json,
-    generate_tracker_tsv, get_unlock_states,
+    BpmRange, MemoryReader, OffsetSearcher, OriginVersion, ScoreMap, fetch_song_database,
+    filter_songs_by_bpm, filter_songs_by_origin, generate_tracker_json, generate_tracker_tsv,
+    get_unlock_states, unknown_folders,
 };
 
 use crate::cli::ExportFormat;
 use crate::cli_utils;
 
 /// Export all play data
-pub fn run(output: Option<&str>, format: ExportFormat, pid: Option<u32>) -> Result<()> {
+pub fn run(
+    output: Option<&str>,
+    format: ExportFormat,
+    bpm_range: BpmRange,
+    origin_version: Option<&str>,
+    pid: Option<u32>,
+) -> Result<()> {
     let current_version = env!("CARGO_PKG_VERSION");
     eprintln!("infst {} - Export Mode", current_version);
 
+    let origin_version = match origin_version {
+        Some(name) => match OriginVersion::find(name) {
+            Some(version) => Some(version),
+            None => {
+                let known: Vec<&str> = OriginVersion::all().map(|v| v.name).collect();
+                bail!("Unknown version '{}' (known: {})", name, known.join(", "));
+            }
+        },
+        None => None,
+    };
+
     let process = cli_utils::open_process(pid)?;
 
     eprintln!(
@@ -30,9 +48,27 @@ pub fn run(output: Option<&str>, format: ExportFormat, pid: Option<u32>) -> Resu
 
     // Load song database
     eprintln!("Loading song database...");
-    let song_db = fetch_song_database(&reader, offsets.song_list)?;
+    let mut song_db = fetch_song_database(&reader, offsets.song_list)?;
     eprintln!("Loaded {} songs", song_db.len());
 
+    let unknown = unknown_folders(&song_db);
+    if !unknown.is_empty() {
+        eprintln!(
+            "Songs with unknown folder values (folder: songs): {:?}",
+            unknown
+        );
+    }
+
+    if let Some(version) = origin_version {
+        song_db = filter_songs_by_origin(&song_db, version);
+        eprintln!("{} songs from {}", song_db.len(), version);
+    }
+
+    if !bpm_range.is_unbounded() {
+        song_db = filter_songs_by_bpm(&song_db, &bpm_range);
+        eprintln!("{} songs within BPM {}", song_db.len(), bpm_range);
+    }
+
     // Load unlock data
     eprintln!("Loading unlock data...");
     let unlock_db = get_unlock_states(&reader, offsets.unlock_data, &song_db)?;
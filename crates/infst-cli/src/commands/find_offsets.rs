@@ -4,22 +4,117 @@
 //! Requires user interaction (playing a song) to detect play-related offsets
 //! through state changes.
 //!
+//! With `--script`, the judge counts and options come from a search script
+//! instead, and memory from the snapshots it names or from the running game
+//! (prompting before each settings step).
+//!
 //! The output file can be used as input for other commands via `--offsets-file`.
 
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use infst::config::find_game_version;
-use infst::{MemoryReader, OffsetSearcher, OffsetsCollection, ProcessHandle, save_offsets};
+use infst::{
+    InteractiveSearchResult, MemoryReader, OffsetSearcher, OffsetsCollection, ProcessHandle,
+    SearchScript, SnapshotMemoryReader, run_live_search_script, run_search_script, save_offsets,
+};
 use tracing::{debug, info, warn};
 
 use crate::prompter::CliPrompter;
 
-/// Run the find-offsets interactive mode
-pub fn run(output: &str, pid: Option<u32>) -> Result<()> {
+/// Run the find-offsets mode
+pub fn run(output: &str, pid: Option<u32>, script: Option<&str>) -> Result<()> {
     let current_version = env!("CARGO_PKG_VERSION");
     info!("infst {} - Offset Search Mode", current_version);
 
+    let result = match script {
+        Some(path) => {
+            let script = SearchScript::load(path)?;
+            println!("Loaded search script from {}", path);
+            if let Some(snapshots) = script.snapshots() {
+                search_snapshots(&script, &snapshots)?
+            } else if script.has_snapshots() {
+                bail!("Search script must name a snapshot for every step, or none");
+            } else {
+                let process = open_process(pid)?;
+                let reader = MemoryReader::new(&process);
+                let game_version = detect_game_version(&reader, process.base_address);
+                run_live_search_script(
+                    &script,
+                    &reader,
+                    &CliPrompter,
+                    &OffsetsCollection::default(),
+                    &game_version,
+                )?
+            }
+        }
+        None => {
+            let process = open_process(pid)?;
+            let reader = MemoryReader::new(&process);
+            let game_version = detect_game_version(&reader, process.base_address);
+
+            // Run interactive search
+            let prompter = CliPrompter;
+            let mut searcher = OffsetSearcher::new(&reader);
+            let old_offsets = OffsetsCollection::default();
+            searcher.interactive_search(&prompter, &old_offsets, &game_version)?
+        }
+    };
+
+    // Display results
+    println!();
+    println!("=== Offset Search Results ===");
+    println!("Version:      {}", result.offsets.version);
+    println!("Play Type:    {}", result.play_type.short_name());
+    println!("SongList:     0x{:X}", result.offsets.song_list);
+    println!("JudgeData:    0x{:X}", result.offsets.judge_data);
+    println!("PlaySettings: 0x{:X}", result.offsets.play_settings);
+    println!("PlayData:     0x{:X}", result.offsets.play_data);
+    println!("CurrentSong:  0x{:X}", result.offsets.current_song);
+    println!("DataMap:      0x{:X}", result.offsets.data_map);
+    println!("UnlockData:   0x{:X}", result.offsets.unlock_data);
+
+    // Save to file
+    save_offsets(output, &result.offsets)?;
+    println!();
+    println!("Offsets saved to: {}", output);
+
+    Ok(())
+}
+
+/// Run a search script over the snapshots it names
+fn search_snapshots(
+    script: &SearchScript,
+    snapshots: &[&std::path::Path],
+) -> Result<InteractiveSearchResult> {
+    let mut readers = snapshots
+        .iter()
+        .map(|path| {
+            println!("Loading snapshot {}...", path.display());
+            SnapshotMemoryReader::open(path)
+        })
+        .collect::<infst::Result<Vec<_>>>()?;
+    let settings = readers.split_off(1);
+    let play = readers.remove(0);
+
+    let game_version = play
+        .header()
+        .game_version
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("Game version: {}", game_version);
+
+    Ok(run_search_script(
+        script,
+        &play,
+        &settings,
+        &OffsetsCollection::default(),
+        &game_version,
+    )?)
+}
+
+/// Open the game process, waiting for it unless a PID is given
+fn open_process(pid: Option<u32>) -> Result<ProcessHandle> {
     // Open process (either by PID or auto-detect)
     let process = if let Some(pid) = pid {
         println!("Opening process with PID {}...", pid);
@@ -42,11 +137,12 @@ pub fn run(output: &str, pid: Option<u32>) -> Result<()> {
         "Found INFINITAS process (base: {:#x})",
         process.base_address
     );
+    Ok(process)
+}
 
-    let reader = MemoryReader::new(&process);
-
-    // Game version detection
-    let game_version = match find_game_version(&reader, process.base_address) {
+/// Game version of the running build, or "unknown"
+fn detect_game_version(reader: &MemoryReader, base_address: u64) -> String {
+    match find_game_version(reader, base_address) {
         Ok(Some(version)) => {
             println!("Detected game version: {}", version);
             version
@@ -59,32 +155,5 @@ pub fn run(output: &str, pid: Option<u32>) -> Result<()> {
             warn!("Failed to check game version: {}", e);
             "unknown".to_string()
         }
-    };
-
-    // Run interactive search
-    let prompter = CliPrompter;
-    let mut searcher = OffsetSearcher::new(&reader);
-    let old_offsets = OffsetsCollection::default();
-
-    let result = searcher.interactive_search(&prompter, &old_offsets, &game_version)?;
-
-    // Display results
-    println!();
-    println!("=== Offset Search Results ===");
-    println!("Version:      {}", result.offsets.version);
-    println!("Play Type:    {}", result.play_type.short_name());
-    println!("SongList:     0x{:X}", result.offsets.song_list);
-    println!("JudgeData:    0x{:X}", result.offsets.judge_data);
-    println!("PlaySettings: 0x{:X}", result.offsets.play_settings);
-    println!("PlayData:     0x{:X}", result.offsets.play_data);
-    println!("CurrentSong:  0x{:X}", result.offsets.current_song);
-    println!("DataMap:      0x{:X}", result.offsets.data_map);
-    println!("UnlockData:   0x{:X}", result.offsets.unlock_data);
-
-    // Save to file
-    save_offsets(output, &result.offsets)?;
-    println!();
-    println!("Offsets saved to: {}", output);
-
-    Ok(())
+    }
 }
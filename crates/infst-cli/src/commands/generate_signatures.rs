@@ -0,0 +1,66 @@
+//! Generate signatures command implementation.
+//!
+//! Derives code signatures from offsets known to be correct for the running
+//! build, so new `CodeSignature` entries do not have to be written by hand
+//! after `find-offsets`.
+
+use anyhow::{Context, Result};
+use infst::config::find_game_version;
+use infst::{MemoryReader, ProcessHandle, SignatureGenerator, load_offsets, save_signatures};
+
+/// Run the generate-signatures command
+pub fn run(offsets_file: &str, output: &str, pid: Option<u32>) -> Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    println!("infst {} - Signature Generation Mode", current_version);
+
+    let mut offsets =
+        load_offsets(offsets_file).with_context(|| format!("Failed to load {}", offsets_file))?;
+
+    // Open process
+    let process = if let Some(pid) = pid {
+        ProcessHandle::open(pid)?
+    } else {
+        ProcessHandle::find_and_open()?
+    };
+
+    println!(
+        "Found process (PID: {}, Base: 0x{:X}, Size: 0x{:X})",
+        process.pid, process.base_address, process.module_size
+    );
+
+    let reader = MemoryReader::new(&process);
+    if let Some(version) = find_game_version(&reader, process.base_address)? {
+        if !offsets.version.is_empty() && offsets.version != version {
+            println!(
+                "Warning: offsets are for {}, but the game is {}",
+                offsets.version, version
+            );
+        }
+        offsets.version = version;
+    }
+
+    println!("Scanning code section for references...");
+    let signatures = SignatureGenerator::new(&reader).generate(&offsets)?;
+
+    println!();
+    for (name, addr) in offsets.named() {
+        if addr == 0 {
+            continue;
+        }
+        match signatures.entry(name) {
+            Some(entry) => {
+                println!("{:<13} {} signature(s)", name, entry.signatures.len());
+                for signature in &entry.signatures {
+                    println!("              {}", signature.pattern);
+                }
+            }
+            None => println!("{:<13} no unique reference found", name),
+        }
+    }
+
+    save_signatures(output, &signatures)?;
+    println!();
+    println!("Signatures saved to: {}", output);
+
+    Ok(())
+}
@@ -32,6 +32,23 @@ pub fn format_hex_address(addr: u64) -> String {
     format!("0x{:X}", addr)
 }
 
+/// Format bytes as space-separated hex pairs.
+///
+/// # Examples
+///
+/// ```
+/// use infst_cli::commands::hex_utils::format_hex_bytes;
+///
+/// assert_eq!(format_hex_bytes(&[0x00, 0x20, 0x03]), "00 20 03");
+/// ```
+pub fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +82,10 @@ mod tests {
         assert_eq!(format_hex_address(0xDEADBEEF), "0xDEADBEEF");
         assert_eq!(format_hex_address(0), "0x0");
     }
+
+    #[test]
+    fn test_format_hex_bytes() {
+        assert_eq!(format_hex_bytes(&[0xDE, 0xAD, 0x0F]), "DE AD 0F");
+        assert_eq!(format_hex_bytes(&[]), "");
+    }
 }
@@ -8,12 +8,29 @@
 //! ```text
 //! 0x000: 48 65 6C 6C 6F 20 57 6F  72 6C 64 00 00 00 00 00  |Hello World.....|
 //! ```
+//!
+//! With `--layout`, each line is followed by the fields of that structure
+//! starting on it, e.g. `p1_pgreat=800 p1_great=150`.
 
-use anyhow::Result;
+use anyhow::{Result, bail};
+use infst::process::StructLayout;
+use infst::process::layout;
 use infst::{MemoryReader, ProcessHandle, ReadMemory};
 
+/// Default number of bytes to dump without a layout
+const DEFAULT_SIZE: usize = 256;
+
 /// Run the hexdump command
-pub fn run(address: u64, size: usize, ascii: bool, pid: Option<u32>) -> Result<()> {
+pub fn run(
+    address: u64,
+    size: Option<usize>,
+    ascii: bool,
+    layout_name: Option<&str>,
+    pid: Option<u32>,
+) -> Result<()> {
+    let layout = layout_name.map(find_layout).transpose()?;
+    let size = size.unwrap_or(layout.map_or(DEFAULT_SIZE, |l| l.size));
+
     let process = if let Some(pid) = pid {
         ProcessHandle::open(pid)?
     } else {
@@ -23,7 +40,13 @@ pub fn run(address: u64, size: usize, ascii: bool, pid: Option<u32>) -> Result<(
     let reader = MemoryReader::new(&process);
     let bytes = reader.read_bytes(address, size)?;
 
-    println!("Hexdump at 0x{:X} ({} bytes):", address, size);
+    match layout {
+        Some(layout) => println!(
+            "Hexdump at 0x{:X} ({} bytes, {} layout):",
+            address, size, layout.name
+        ),
+        None => println!("Hexdump at 0x{:X} ({} bytes):", address, size),
+    }
     println!();
 
     for (i, chunk) in bytes.chunks(16).enumerate() {
@@ -64,8 +87,69 @@ pub fn run(address: u64, size: usize, ascii: bool, pid: Option<u32>) -> Result<(
             print!("|");
         }
 
+        if let Some(layout) = layout {
+            let labels = line_labels(layout, &bytes, offset);
+            if !labels.is_empty() {
+                print!(" {}", labels.join(" "));
+            }
+        }
+
         println!();
     }
 
     Ok(())
 }
+
+pub(crate) fn find_layout(name: &str) -> Result<&'static StructLayout> {
+    match layout::by_name(name) {
+        Some(layout) => Ok(layout),
+        None => {
+            let names: Vec<&str> = layout::ALL.iter().map(|l| l.name).collect();
+            bail!(
+                "Unknown layout '{}' (available: {})",
+                name,
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// `name=value` labels for the fields starting on the 16-byte line at `offset`
+fn line_labels(layout: &StructLayout, bytes: &[u8], offset: usize) -> Vec<String> {
+    layout
+        .fields
+        .iter()
+        .filter(|field| (offset..offset + 16).contains(&field.offset))
+        .map(|field| match field.format_value(bytes) {
+            Some(value) => format!("{}={}", field.name, value),
+            None => format!("{}=?", field.name),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_labels() {
+        let mut bytes = vec![0u8; layout::play::LAYOUT.size];
+        bytes[0..4].copy_from_slice(&1001i32.to_le_bytes());
+        bytes[24..28].copy_from_slice(&4i32.to_le_bytes());
+
+        let layout = &layout::play::LAYOUT;
+        assert_eq!(
+            line_labels(layout, &bytes, 0),
+            vec!["song_id=1001", "difficulty=0"]
+        );
+        assert_eq!(line_labels(layout, &bytes, 16), vec!["lamp=4"]);
+        assert_eq!(line_labels(layout, &bytes[..20], 16), vec!["lamp=?"]);
+    }
+
+    #[test]
+    fn test_find_layout_lists_names() {
+        assert!(find_layout("judge").is_ok());
+        let err = find_layout("nope").unwrap_err().to_string();
+        assert!(err.contains("settings"));
+    }
+}
@@ -3,21 +3,26 @@
 //! This module contains the implementation of each CLI command.
 
 pub mod analyze;
+pub mod check_signatures;
 pub mod dump;
 pub mod explore;
 pub mod export;
 pub mod find_offsets;
+pub mod generate_signatures;
 pub mod hex_utils;
 pub mod hexdump;
 
 pub mod launch;
 pub mod login;
 pub mod offset;
+pub mod offsets;
 pub mod scan;
 pub mod search;
+pub mod snapshot;
 pub mod status;
 pub mod sync;
 pub mod tracking;
 pub mod upload;
 pub mod validate;
+pub mod watch_memory;
 pub mod web;
@@ -0,0 +1,206 @@
+//! Offsets database commands.
+//!
+//! Manages the user offsets database, which overrides and extends the
+//! database bundled into the binary, converts offsets files to the
+//! structured format, and compares the offsets of two game builds.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use infst::{
+    MemoryReader, OffsetCache, OffsetDatabase, OffsetDiff, OffsetsCollection, OffsetsFormat,
+    ProcessHandle, SignatureScanner, SnapshotMemoryReader, builtin_signatures, load_offsets,
+    load_offsets_document, save_offsets_document,
+};
+
+use crate::cli::OffsetsAction;
+
+/// Path of the user offsets database
+pub fn user_database_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().context("Failed to determine config directory")?;
+    Ok(config_dir.join("infst").join("offsets.txt"))
+}
+
+/// The bundled database with the user database layered on top
+pub fn load_database() -> OffsetDatabase {
+    match user_database_path() {
+        Ok(path) => OffsetDatabase::with_overrides(path),
+        Err(_) => OffsetDatabase::bundled(),
+    }
+}
+
+pub fn run(action: OffsetsAction) -> Result<()> {
+    match action {
+        OffsetsAction::List => list(),
+        OffsetsAction::Import { file } => import(&file),
+        OffsetsAction::Export { version, output } => export(version.as_deref(), output.as_deref()),
+        OffsetsAction::Convert { file, output } => convert(&file, output.as_deref()),
+        OffsetsAction::Diff {
+            from,
+            to,
+            snapshot,
+            pid,
+            json,
+        } => diff(&from, &to, snapshot.as_deref(), pid, json),
+    }
+}
+
+fn list() -> Result<()> {
+    let bundled = OffsetDatabase::bundled();
+    let user_path = user_database_path()?;
+    let user = if user_path.exists() {
+        OffsetDatabase::load(&user_path)
+            .with_context(|| format!("Failed to load {}", user_path.display()))?
+    } else {
+        OffsetDatabase::new()
+    };
+
+    let mut db = bundled.clone();
+    db.merge(user.clone());
+    if db.is_empty() {
+        println!("No known offsets.");
+        println!("User database: {}", user_path.display());
+        return Ok(());
+    }
+
+    println!(
+        "{:<22} {:<9} {:>12} {:>12} {:>12} {:>12}",
+        "Version", "Source", "songList", "judgeData", "playData", "dataMap"
+    );
+    for offsets in db.entries() {
+        let source = match (
+            user.get(&offsets.version).is_some(),
+            bundled.get(&offsets.version).is_some(),
+        ) {
+            (true, true) => "user*",
+            (true, false) => "user",
+            _ => "bundled",
+        };
+        println!(
+            "{:<22} {:<9} {:>#12x} {:>#12x} {:>#12x} {:>#12x}",
+            offsets.version,
+            source,
+            offsets.song_list,
+            offsets.judge_data,
+            offsets.play_data,
+            offsets.data_map
+        );
+    }
+
+    println!();
+    println!("{} entries (* = user entry overrides bundled)", db.len());
+    println!("User database: {}", user_path.display());
+    Ok(())
+}
+
+fn import(file: &str) -> Result<()> {
+    let offsets = load_offsets(file).with_context(|| format!("Failed to load {}", file))?;
+    let version = offsets.version.clone();
+
+    let path = user_database_path()?;
+    let mut user = if path.exists() {
+        OffsetDatabase::load(&path).with_context(|| format!("Failed to load {}", path.display()))?
+    } else {
+        OffsetDatabase::new()
+    };
+
+    let replaced = user.insert(offsets)?;
+    user.save(&path)?;
+
+    if replaced.is_some() {
+        println!("Replaced offsets for {} in {}", version, path.display());
+    } else {
+        println!("Added offsets for {} to {}", version, path.display());
+    }
+    Ok(())
+}
+
+fn export(version: Option<&str>, output: Option<&str>) -> Result<()> {
+    let db = load_database();
+    let text = match version {
+        Some(version) => {
+            let offsets = db
+                .get(version)
+                .with_context(|| format!("No offsets known for {}", version))?;
+            let mut single = OffsetDatabase::new();
+            single.insert(offsets.clone())?;
+            single.to_text()
+        }
+        None => db.to_text(),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, &text)?;
+            eprintln!("Exported offsets to {}", path);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+fn convert(file: &str, output: Option<&str>) -> Result<()> {
+    let content = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))?;
+    if OffsetsFormat::detect(&content) == OffsetsFormat::Json && output.is_none() {
+        println!("{} is already in the structured format", file);
+        return Ok(());
+    }
+
+    let document =
+        load_offsets_document(file).with_context(|| format!("Failed to load {}", file))?;
+    let output = output.unwrap_or(file);
+    save_offsets_document(output, &document)?;
+    println!(
+        "Converted {} ({} offsets) to {}",
+        file,
+        document.offsets.iter().count(),
+        output
+    );
+    Ok(())
+}
+
+fn diff(from: &str, to: &str, snapshot: Option<&str>, pid: Option<u32>, json: bool) -> Result<()> {
+    let old = resolve_offsets(from)?;
+    let new = resolve_offsets(to)?;
+    let mut diff = OffsetDiff::new(&old, &new);
+
+    let scanner = SignatureScanner::new(&builtin_signatures())?;
+    if let Some(path) = snapshot {
+        eprintln!("Scanning signatures in {}...", path);
+        let reader = SnapshotMemoryReader::open(path)?;
+        diff = diff.with_signature_hits(&scanner.scan(&reader)?);
+    } else if let Some(pid) = pid {
+        eprintln!("Scanning signatures in process {}...", pid);
+        let process = ProcessHandle::open(pid)?;
+        let reader = MemoryReader::new(&process);
+        diff = diff.with_signature_hits(&scanner.scan(&reader)?);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff.to_text());
+        if diff.signatures.is_none() {
+            println!();
+            println!("Signatures: not checked (use --snapshot or --pid for the newer build)");
+        }
+    }
+    Ok(())
+}
+
+/// Offsets from a file, or from the offset cache or database by game version
+fn resolve_offsets(spec: &str) -> Result<OffsetsCollection> {
+    if Path::new(spec).exists() {
+        return load_offsets(spec).with_context(|| format!("Failed to load {}", spec));
+    }
+    if let Some(entry) = OffsetCache::load().get(spec) {
+        return Ok(entry.offsets.clone());
+    }
+    load_database().get(spec).cloned().with_context(|| {
+        format!(
+            "{} is neither an offsets file nor a known game version",
+            spec
+        )
+    })
+}
@@ -0,0 +1,82 @@
+//! Snapshot command implementation.
+//!
+//! Records the memory regions used by the tracker into a compressed snapshot
+//! file that can be replayed offline with `SnapshotMemoryReader`. With
+//! `--module`, the whole module image is recorded as well, which is enough for
+//! `find-offsets --script` to search it.
+
+use anyhow::Result;
+use infst::config::find_game_version;
+use infst::{
+    MemoryReader, OffsetSearcher, ProcessHandle, SnapshotRecorder, builtin_signatures, load_offsets,
+};
+
+/// Run the snapshot command
+pub fn run(offsets_file: Option<&str>, pid: Option<u32>, output: &str, module: bool) -> Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    println!("infst {} - Snapshot Mode", current_version);
+
+    // Open process
+    let process = if let Some(pid) = pid {
+        ProcessHandle::open(pid)?
+    } else {
+        ProcessHandle::find_and_open()?
+    };
+
+    println!(
+        "Found process (PID: {}, Base: 0x{:X}, Size: 0x{:X})",
+        process.pid, process.base_address, process.module_size
+    );
+
+    let reader = MemoryReader::new(&process);
+    let game_version = find_game_version(&reader, process.base_address)?;
+
+    // Load or search for offsets
+    let offsets = if let Some(path) = offsets_file {
+        Some(load_offsets(path)?)
+    } else {
+        let signatures = builtin_signatures();
+        let mut searcher = OffsetSearcher::new(&reader);
+        match searcher.search_all_with_signatures(&signatures) {
+            Ok(offsets) => Some(offsets),
+            // A new build's offsets are what the module snapshot is for
+            Err(e) if module => {
+                println!(
+                    "Offset search failed ({}), recording the module image only",
+                    e
+                );
+                None
+            }
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    let mut recorder = SnapshotRecorder::new(&reader)
+        .pid(process.pid)
+        .module_size(process.module_size);
+    if let Some(ref version) = game_version {
+        println!("Game version: {}", version);
+        recorder = recorder.game_version(version.clone());
+    }
+    if module {
+        recorder = recorder.record_region(process.base_address, process.module_size as usize);
+    }
+    if let Some(mut offsets) = offsets {
+        if let Some(version) = game_version {
+            offsets.version = version;
+        }
+        recorder = recorder.record_offsets(&offsets);
+    }
+
+    let snapshot = recorder.finish();
+    snapshot.save(output)?;
+
+    println!(
+        "Snapshot saved to: {} ({} regions, {} bytes recorded)",
+        output,
+        snapshot.regions().len(),
+        snapshot.total_bytes()
+    );
+
+    Ok(())
+}
@@ -3,7 +3,8 @@
 use anyhow::{Result, bail};
 use infst::config::find_game_version;
 use infst::{
-    MemoryReader, OffsetSearcher, ProcessHandle, StatusInfo, builtin_signatures, load_offsets,
+    MemoryReader, OffsetSearcher, ProcessHandle, RelationPriors, StatusInfo, builtin_signatures,
+    load_offsets,
 };
 
 /// Run the status command
@@ -44,11 +45,11 @@ pub fn run(offsets_file: Option<&str>, pid: Option<u32>, json: bool) -> Result<(
     };
 
     // Load or search for offsets
-    let offsets = if let Some(path) = offsets_file {
+    let (offsets, rankings) = if let Some(path) = offsets_file {
         match load_offsets(path) {
             Ok(offsets) => {
                 println!("Loaded offsets from {}", path);
-                offsets
+                (offsets, None)
             }
             Err(e) => {
                 bail!("Failed to load offsets from {}: {}", path, e);
@@ -57,13 +58,15 @@ pub fn run(offsets_file: Option<&str>, pid: Option<u32>, json: bool) -> Result<(
     } else {
         println!("Searching for offsets...");
         let signatures = builtin_signatures();
-        let mut searcher = OffsetSearcher::new(&reader);
+        let mut searcher = OffsetSearcher::builder(&reader)
+            .with_priors(RelationPriors::learned())
+            .build();
         match searcher.search_all_with_signatures(&signatures) {
             Ok(mut offsets) => {
                 if let Some(ref version) = game_version {
                     offsets.version = version.clone();
                 }
-                offsets
+                (offsets, Some(searcher.rankings().clone()))
             }
             Err(e) => {
                 bail!("Failed to detect offsets: {}", e);
@@ -72,7 +75,7 @@ pub fn run(offsets_file: Option<&str>, pid: Option<u32>, json: bool) -> Result<(
     };
 
     // Collect status
-    let status = StatusInfo::collect(
+    let mut status = StatusInfo::collect(
         &reader,
         process.pid,
         process.base_address,
@@ -80,6 +83,9 @@ pub fn run(offsets_file: Option<&str>, pid: Option<u32>, json: bool) -> Result<(
         game_version,
         &offsets,
     );
+    if let Some(rankings) = rankings {
+        status = status.with_rankings(rankings);
+    }
 
     if json {
         println!("{}", serde_json::to_string_pretty(&status)?);
@@ -171,6 +177,27 @@ pub fn run(offsets_file: Option<&str>, pid: Option<u32>, json: bool) -> Result<(
             }
         }
 
+        if let Some(ref rankings) = status.rankings {
+            println!();
+            println!("=== Candidate Ranking ===");
+            for (name, ranking) in rankings.iter() {
+                let Some(best) = ranking.best() else {
+                    println!("{:<13} no valid candidates", name);
+                    continue;
+                };
+                match ranking.runner_up() {
+                    Some(runner_up) => println!(
+                        "{:<13} best 0x{:X} ({:.2}), runner-up 0x{:X} ({:.2})",
+                        name, best.address, best.score, runner_up.address, runner_up.score
+                    ),
+                    None => println!(
+                        "{:<13} best 0x{:X} ({:.2}), no other candidates",
+                        name, best.address, best.score
+                    ),
+                }
+            }
+        }
+
         println!();
         println!(
             "Overall validation: {}",
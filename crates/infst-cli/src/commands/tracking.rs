@@ -7,9 +7,10 @@ use std::time::Duration;
 use anyhow::{Context, Result};
 use infst::config::find_game_version;
 use infst::{
-    ApiConfig, GitConfig, Infst, InfstConfig, MemoryReader, OffsetSearcher, OffsetsCollection,
-    ProcessHandle, ScoreMap, SongInfo, load_offsets, save_offsets_to_cache,
-    try_load_cached_offsets,
+    ApiConfig, CachedValidation, GitConfig, Infst, InfstConfig, MemoryReader, OffsetSearcher,
+    OffsetSource, OffsetsCollection, ProcessHandle, ReadMemory, ScoreMap, SongCache, SongInfo,
+    load_offsets, record_cached_validation, record_offset_relations, save_offsets_to_cache,
+    try_load_cached_offsets, unknown_folders,
 };
 use tracing::{debug, error, info, warn};
 
@@ -195,7 +196,8 @@ fn wait_for_process(shutdown: &ShutdownSignal) -> Option<ProcessHandle> {
 
 /// Validate or search for offsets
 ///
-/// Uses cached offsets if available and valid, otherwise performs a full search.
+/// Uses known offsets for the game version or cached offsets if available and
+/// valid, otherwise performs a full search.
 fn validate_or_search_offsets(
     infst: &Infst,
     reader: &MemoryReader,
@@ -203,14 +205,32 @@ fn validate_or_search_offsets(
     offsets_from_file: bool,
     shutdown: &ShutdownSignal,
 ) -> Result<Option<OffsetsCollection>> {
-    // Try to use cached offsets first (if not loading from file)
+    // Known offsets for this build skip the search entirely
+    if !offsets_from_file
+        && let Some(version) = game_version
+        && let Some(known_offsets) = lookup_known_offsets(reader, version)
+    {
+        return Ok(Some(known_offsets));
+    }
+
+    // Try to use cached offsets next (if not loading from file)
     if !offsets_from_file
         && let Some(version) = game_version
         && let Some(cached_offsets) = try_load_cached_offsets(version)
     {
-        // Validate cached offsets still work
+        // Validate cached offsets still point at game data; a failure drops
+        // the cache entry
         let searcher = OffsetSearcher::new(reader);
-        if searcher.validate_basic_memory_access(&cached_offsets) {
+        let memory_access = searcher.validate_basic_memory_access(&cached_offsets);
+        let content = memory_access && searcher.validate_signature_offsets(&cached_offsets);
+        record_cached_validation(
+            version,
+            CachedValidation {
+                memory_access: Some(memory_access),
+                content: Some(content),
+            },
+        );
+        if content {
             info!("Using cached offsets (validated)");
             return Ok(Some(cached_offsets));
         } else {
@@ -249,8 +269,15 @@ fn validate_or_search_offsets(
             debug!("Signature-based offset detection successful!");
             // Save to cache for next startup
             if let Some(version) = game_version {
-                save_offsets_to_cache(version, found_offsets);
+                // The search validates each offset's content as it goes
+                save_offsets_to_cache(
+                    version,
+                    found_offsets,
+                    OffsetSource::Search,
+                    CachedValidation::content(true),
+                );
             }
+            record_offset_relations(found_offsets, reader.base_address());
         }
         Ok(offsets)
     } else {
@@ -258,38 +285,73 @@ fn validate_or_search_offsets(
     }
 }
 
-/// Load song database using various strategies
-fn load_song_database(
-    reader: &MemoryReader,
-    song_list: u64,
-    shutdown: &ShutdownSignal,
-) -> Result<Option<HashMap<u32, SongInfo>>> {
+/// Look up the game version in the offsets database
+///
+/// Entries get the same checks as cached offsets: readable memory, and
+/// content that looks like the game data each offset should point at.
+fn lookup_known_offsets(reader: &MemoryReader, version: &str) -> Option<OffsetsCollection> {
+    let known_offsets = super::offsets::load_database().get(version)?.clone();
+
+    let searcher = OffsetSearcher::new(reader);
+    if searcher.validate_basic_memory_access(&known_offsets)
+        && searcher.validate_signature_offsets(&known_offsets)
+    {
+        info!("Using known offsets for {}", version);
+        Some(known_offsets)
+    } else {
+        warn!(
+            "Known offsets for {} failed validation, searching instead",
+            version
+        );
+        None
+    }
+}
+
+/// Scan memory for loaded songs, matched against tracker.tsv if present
+fn scan_song_database(reader: &MemoryReader, song_list: u64) -> HashMap<u32, SongInfo> {
     let tsv_path = "tracker.tsv";
 
     if std::path::Path::new(tsv_path).exists() {
         debug!("Building song database from TSV + memory scan...");
-        let db = infst::chart::build_song_database_from_tsv_with_memory(
+        return infst::chart::build_song_database_from_tsv_with_memory(
             reader, song_list, tsv_path, 0x100000, // 1MB scan
         );
-
-        if db.is_empty() {
-            debug!("TSV+memory approach returned empty, trying legacy...");
-            return load_song_database_with_retry(reader, song_list, shutdown);
-        }
-        return Ok(Some(db));
     }
 
-    // No TSV, use memory-only approach
     debug!("No TSV file found, using memory scan...");
-    let song_db = infst::chart::fetch_song_database_from_memory_scan(reader, song_list, 0x100000);
+    infst::chart::fetch_song_database_from_memory_scan(reader, song_list, 0x100000)
+}
 
-    if song_db.is_empty() {
+/// Load song database using various strategies
+///
+/// A non-empty song cache is used right away, with live memory only adding
+/// or refreshing entries.
+fn load_song_database(
+    reader: &MemoryReader,
+    song_list: u64,
+    song_cache: Option<&SongCache>,
+    shutdown: &ShutdownSignal,
+) -> Result<Option<HashMap<u32, SongInfo>>> {
+    let live = scan_song_database(reader, song_list);
+
+    if let Some(cache) = song_cache.filter(|cache| !cache.is_empty()) {
+        info!(
+            "Loaded {} songs from song cache ({} found in memory)",
+            cache.len(),
+            live.len()
+        );
+        let mut song_db = cache.to_song_db();
+        song_db.extend(live);
+        return Ok(Some(song_db));
+    }
+
+    if live.is_empty() {
         debug!("Memory scan found no songs, trying legacy approach...");
         return load_song_database_with_retry(reader, song_list, shutdown);
     }
 
-    info!("Loaded {} songs from memory scan", song_db.len());
-    Ok(Some(song_db))
+    info!("Loaded {} songs from memory scan", live.len());
+    Ok(Some(live))
 }
 
 /// Run a single tracking session with a connected process
@@ -319,13 +381,36 @@ fn run_tracking_session(
     }
 
     // Load game resources
-    let song_db = match load_song_database(&reader, infst.offsets().song_list, shutdown)? {
+    let song_cache = game_version.as_deref().map(SongCache::load);
+    let song_db = match load_song_database(
+        &reader,
+        infst.offsets().song_list,
+        song_cache.as_ref(),
+        shutdown,
+    )? {
         Some(db) => db,
         None => return Ok(()), // Shutdown requested
     };
 
     debug!("Loaded {} songs", song_db.len());
+    let unknown = unknown_folders(&song_db);
+    if !unknown.is_empty() {
+        warn!(
+            "Songs with unknown folder values (folder: songs): {:?}",
+            unknown
+        );
+    }
     infst.set_song_db(song_db.clone());
+    if let Some(mut cache) = song_cache
+        && let Some(path) = SongCache::default_path(&cache.game_version)
+    {
+        if cache.merge(&song_db) > 0
+            && let Err(e) = cache.save_to_path(&path)
+        {
+            warn!("Failed to save song cache: {}", e);
+        }
+        infst.set_song_cache(cache, path);
+    }
 
     // Load score map
     let score_map = load_score_map(&reader, infst.offsets().data_map, &song_db);
@@ -0,0 +1,165 @@
+//! Watch-memory command implementation.
+//!
+//! Polls a memory range and prints the offsets whose bytes changed, instead
+//! of running `hexdump` repeatedly and diffing by eye.
+//!
+//! # Output Format
+//!
+//! ```text
+//! [   12.350s] +0x010: 00 00 00 00 -> 20 03 00 00  p1_pgreat: 0 -> 800
+//! ```
+//!
+//! With `--output`, every change is also appended to a JSON lines file that
+//! can be loaded with `infst::process::read_watch_log`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use infst::process::{MemoryWatcher, StructLayout, WatchEvent, WatchRecorder};
+use infst::{MemoryReader, ProcessHandle};
+
+use super::hex_utils::format_hex_bytes;
+use super::hexdump::find_layout;
+use crate::input;
+use crate::shutdown::ShutdownSignal;
+
+/// Default number of bytes to watch without a layout
+const DEFAULT_SIZE: usize = 256;
+
+/// Run the watch-memory command
+pub fn run(
+    address: u64,
+    size: Option<usize>,
+    interval_ms: u64,
+    align: usize,
+    layout_name: Option<&str>,
+    output: Option<&str>,
+    pid: Option<u32>,
+) -> Result<()> {
+    let layout = layout_name.map(find_layout).transpose()?;
+    let size = size.unwrap_or(layout.map_or(DEFAULT_SIZE, |l| l.size));
+    let interval = Duration::from_millis(interval_ms);
+
+    let process = if let Some(pid) = pid {
+        ProcessHandle::open(pid)?
+    } else {
+        ProcessHandle::find_and_open()?
+    };
+
+    let reader = MemoryReader::new(&process);
+    let mut watcher = MemoryWatcher::new(reader)
+        .watch(address, size)
+        .with_interval(interval)
+        .with_granularity(align);
+    let mut recorder = output.map(WatchRecorder::create).transpose()?;
+
+    println!(
+        "Watching 0x{:X} ({} bytes) every {}ms. Press Esc or q to stop.",
+        address, size, interval_ms
+    );
+    if let Some(path) = output {
+        println!("Recording changes to {}", path);
+    }
+    println!();
+
+    let shutdown = Arc::new(ShutdownSignal::new());
+    let _keyboard_handle = input::spawn_keyboard_monitor(Arc::clone(&shutdown));
+
+    let mut changes = 0;
+    watcher.run(shutdown.as_atomic(), |watcher, event| {
+        let mut line = format_event(event, address);
+        if let (Some(layout), Some(before), Some(after)) = (
+            layout,
+            watcher.previous_read(address),
+            watcher.last_read(address),
+        ) {
+            let labels = field_changes(layout, before, after, event, address);
+            if !labels.is_empty() {
+                line = format!("{}  {}", line, labels.join(" "));
+            }
+        }
+        println!("{}", line);
+
+        if let Some(recorder) = &mut recorder {
+            recorder.record(event)?;
+        }
+        changes += 1;
+        Ok(())
+    })?;
+
+    println!();
+    println!("Stopped after {} changes", changes);
+    Ok(())
+}
+
+/// `[   12.350s] +0x010: 00 00 -> 20 03`
+fn format_event(event: &WatchEvent, base: u64) -> String {
+    format!(
+        "[{:>9.3}s] +0x{:03X}: {} -> {}",
+        event.timestamp.as_secs_f64(),
+        event.offset_from(base),
+        format_hex_bytes(&event.old_bytes),
+        format_hex_bytes(&event.new_bytes)
+    )
+}
+
+/// `name: old -> new` for every layout field overlapping the changed bytes
+fn field_changes(
+    layout: &StructLayout,
+    before: &[u8],
+    after: &[u8],
+    event: &WatchEvent,
+    base: u64,
+) -> Vec<String> {
+    let start = event.offset_from(base) as usize;
+    let end = start + event.new_bytes.len();
+
+    layout
+        .fields
+        .iter()
+        .filter(|field| field.offset < end && field.end() > start)
+        .filter_map(|field| {
+            let old = field.format_value(before)?;
+            let new = field.format_value(after)?;
+            (old != new).then(|| format!("{}: {} -> {}", field.name, old, new))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use infst::process::layout;
+
+    fn event(offset: u64, old: &[u8], new: &[u8]) -> WatchEvent {
+        WatchEvent {
+            address: 0x1000 + offset,
+            old_bytes: old.to_vec(),
+            new_bytes: new.to_vec(),
+            timestamp: Duration::from_millis(12_350),
+        }
+    }
+
+    #[test]
+    fn test_format_event() {
+        let event = event(0x10, &[0, 0], &[0x20, 0x03]);
+        assert_eq!(
+            format_event(&event, 0x1000),
+            "[   12.350s] +0x010: 00 00 -> 20 03"
+        );
+    }
+
+    #[test]
+    fn test_field_changes_labels_overlapping_fields() {
+        let layout = &layout::play::LAYOUT;
+        let before = vec![0u8; layout.size];
+        let mut after = before.clone();
+        after[24] = 4;
+
+        // One changed byte inside the 4-byte lamp field
+        let changes = field_changes(layout, &before, &after, &event(24, &[0], &[4]), 0x1000);
+        assert_eq!(changes, vec!["lamp: 0 -> 4"]);
+        assert!(field_changes(layout, &before, &before, &event(0, &[0], &[0]), 0x1000).is_empty());
+    }
+}
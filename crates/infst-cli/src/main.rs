@@ -10,6 +10,7 @@ mod validation;
 use anyhow::Result;
 use clap::Parser;
 use cli::{Args, Command};
+use infst::BpmRange;
 use tracing_subscriber::EnvFilter;
 
 fn main() -> Result<()> {
@@ -17,8 +18,33 @@ fn main() -> Result<()> {
     init_logging();
 
     match args.command {
-        Some(Command::FindOffsets { output, pid }) => commands::find_offsets::run(&output, pid),
-        Some(Command::Analyze { address, pid }) => commands::analyze::run(address, pid),
+        Some(Command::FindOffsets {
+            output,
+            pid,
+            script,
+        }) => commands::find_offsets::run(&output, pid, script.as_deref()),
+        Some(Command::GenerateSignatures {
+            offsets_file,
+            output,
+            pid,
+        }) => commands::generate_signatures::run(&offsets_file, &output, pid),
+        Some(Command::CheckSignatures {
+            signatures,
+            snapshot,
+            pid,
+            json,
+        }) => {
+            commands::check_signatures::run(signatures.as_deref(), snapshot.as_deref(), pid, json)
+        }
+        Some(Command::Analyze {
+            target: Some(target),
+            ..
+        }) => commands::analyze::run_target(target),
+        Some(Command::Analyze {
+            target: None,
+            address,
+            pid,
+        }) => commands::analyze::run(address, pid),
         Some(Command::Status {
             offsets_file,
             pid,
@@ -44,6 +70,12 @@ fn main() -> Result<()> {
             output.as_deref(),
             entry_size,
         ),
+        Some(Command::Snapshot {
+            offsets_file,
+            pid,
+            output,
+            module,
+        }) => commands::snapshot::run(offsets_file.as_deref(), pid, &output, module),
         Some(Command::Explore { address, pid }) => {
             let addr = commands::hex_utils::parse_hex_address(&address)?;
             commands::explore::run(addr, pid)
@@ -52,10 +84,31 @@ fn main() -> Result<()> {
             address,
             size,
             ascii,
+            layout,
+            pid,
+        }) => {
+            let addr = commands::hex_utils::parse_hex_address(&address)?;
+            commands::hexdump::run(addr, size, ascii, layout.as_deref(), pid)
+        }
+        Some(Command::WatchMemory {
+            address,
+            size,
+            interval,
+            align,
+            layout,
+            output,
             pid,
         }) => {
             let addr = commands::hex_utils::parse_hex_address(&address)?;
-            commands::hexdump::run(addr, size, ascii, pid)
+            commands::watch_memory::run(
+                addr,
+                size,
+                interval,
+                align,
+                layout.as_deref(),
+                output.as_deref(),
+                pid,
+            )
         }
         Some(Command::Search {
             string,
@@ -70,8 +123,17 @@ fn main() -> Result<()> {
         Some(Command::Export {
             output,
             format,
+            min_bpm,
+            max_bpm,
+            origin_version,
+            pid,
+        }) => commands::export::run(
+            output.as_deref(),
+            format,
+            BpmRange::new(min_bpm, max_bpm)?,
+            origin_version.as_deref(),
             pid,
-        }) => commands::export::run(output.as_deref(), format, pid),
+        ),
         Some(Command::Login { endpoint }) => commands::login::run(&endpoint),
         Some(Command::Sync {
             endpoint,
@@ -85,6 +147,7 @@ fn main() -> Result<()> {
             endpoint,
             token,
         }) => commands::upload::run(&tracker, &mapping, endpoint.as_deref(), token.as_deref()),
+        Some(Command::Offsets { action }) => commands::offsets::run(action),
         Some(Command::Launch { action }) => commands::launch::run(action),
         None => commands::tracking::run(
             args.offsets_file.as_deref(),
@@ -1,6 +1,6 @@
 //! CLI implementation of SearchPrompter for interactive offset search
 
-use infst::SearchPrompter;
+use infst::{SearchPrompter, SearchReporter};
 use std::io::{self, BufRead, Write};
 
 /// CLI prompter for interactive offset search
@@ -33,7 +33,9 @@ impl SearchPrompter for CliPrompter {
             }
         }
     }
+}
 
+impl SearchReporter for CliPrompter {
     fn display_message(&self, message: &str) {
         println!("{}", message);
     }
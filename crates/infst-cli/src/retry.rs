@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use anyhow::{Result, bail};
 use infst::config::database;
 use infst::{
-    MemoryReader, OffsetSearcher, OffsetsCollection, SongInfo, builtin_signatures,
+    MemoryReader, OffsetSearcher, OffsetsCollection, RelationPriors, SongInfo, builtin_signatures,
     fetch_song_database,
 };
 use tracing::{debug, info, warn};
@@ -105,6 +105,7 @@ pub fn search_offsets_with_retry(
     shutdown: &ShutdownSignal,
 ) -> Result<Option<OffsetsCollection>> {
     let signatures = builtin_signatures();
+    let priors = RelationPriors::learned();
 
     loop {
         // Check for shutdown signal
@@ -112,7 +113,9 @@ pub fn search_offsets_with_retry(
             return Ok(None);
         }
 
-        let mut searcher = OffsetSearcher::new(reader);
+        let mut searcher = OffsetSearcher::builder(reader)
+            .with_priors(priors.clone())
+            .build();
 
         match searcher.search_all_with_signatures(&signatures) {
             Ok(mut offsets) => {
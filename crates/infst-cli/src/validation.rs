@@ -43,7 +43,7 @@ pub fn validate_song_database(db: &HashMap<u32, SongInfo>) -> ValidationResult {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use infst::UnlockType;
+    use infst::{Bpm, UnlockType};
     use std::sync::Arc;
 
     fn make_test_song(id: u32, notes: [u32; 10]) -> SongInfo {
@@ -53,7 +53,7 @@ mod tests {
             title_english: Arc::from("Test"),
             artist: Arc::from("Artist"),
             genre: Arc::from("Genre"),
-            bpm: Arc::from("150"),
+            bpm: Bpm::fixed(150),
             folder: 1,
             levels: [0; 10],
             total_notes: notes,
@@ -23,6 +23,35 @@ enum Command {
         output: String,
         #[arg(long)]
         pid: Option<u32>,
+        #[arg(long, value_name = "FILE")]
+        script: Option<String>,
+    },
+    GenerateSignatures {
+        #[arg(long, value_name = "FILE")]
+        offsets_file: String,
+        #[arg(short, long, default_value = "signatures.json")]
+        output: String,
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    CheckSignatures {
+        #[arg(long)]
+        signatures: Option<String>,
+        #[arg(long, conflicts_with = "pid")]
+        snapshot: Option<String>,
+        #[arg(long)]
+        pid: Option<u32>,
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(args_conflicts_with_subcommands = true)]
+    Analyze {
+        #[command(subcommand)]
+        target: Option<AnalyzeTarget>,
+        #[arg(long)]
+        address: Option<String>,
+        #[arg(long)]
+        pid: Option<u32>,
     },
     Status {
         #[arg(long, value_name = "FILE")]
@@ -35,11 +64,29 @@ enum Command {
     Hexdump {
         #[arg(long)]
         address: String,
-        #[arg(long, default_value = "256")]
-        size: usize,
+        #[arg(long)]
+        size: Option<usize>,
         #[arg(long)]
         ascii: bool,
         #[arg(long)]
+        layout: Option<String>,
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    WatchMemory {
+        #[arg(long)]
+        address: String,
+        #[arg(long)]
+        size: Option<usize>,
+        #[arg(long, default_value = "100")]
+        interval: u64,
+        #[arg(long, default_value = "4")]
+        align: usize,
+        #[arg(long)]
+        layout: Option<String>,
+        #[arg(short, long)]
+        output: Option<String>,
+        #[arg(long)]
         pid: Option<u32>,
     },
     Offset {
@@ -54,7 +101,59 @@ enum Command {
         #[arg(long, short, value_enum, default_value = "tsv")]
         format: ExportFormat,
         #[arg(long)]
+        min_bpm: Option<u32>,
+        #[arg(long)]
+        max_bpm: Option<u32>,
+        #[arg(long, value_name = "VERSION")]
+        origin_version: Option<String>,
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    Offsets {
+        #[command(subcommand)]
+        action: OffsetsAction,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum AnalyzeTarget {
+    SongFields {
+        #[arg(long, value_name = "FILE")]
+        offsets_file: Option<String>,
+        #[arg(long)]
+        pid: Option<u32>,
+        #[arg(long, default_value = "5000")]
+        max_entries: usize,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum OffsetsAction {
+    List,
+    Import {
+        file: String,
+    },
+    Export {
+        version: Option<String>,
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    Convert {
+        file: String,
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    Diff {
+        from: String,
+        to: String,
+        #[arg(long, conflicts_with = "pid")]
+        snapshot: Option<String>,
+        #[arg(long)]
         pid: Option<u32>,
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -75,9 +174,14 @@ fn test_parse_no_args() {
 fn test_parse_find_offsets() {
     let args = Args::try_parse_from(["infst", "find-offsets"]).unwrap();
     match args.command {
-        Some(Command::FindOffsets { output, pid }) => {
+        Some(Command::FindOffsets {
+            output,
+            pid,
+            script,
+        }) => {
             assert_eq!(output, "offsets.txt");
             assert!(pid.is_none());
+            assert!(script.is_none());
         }
         _ => panic!("Expected FindOffsets command"),
     }
@@ -94,6 +198,45 @@ fn test_parse_find_offsets_with_output() {
     }
 }
 
+#[test]
+fn test_parse_find_offsets_with_script() {
+    let args =
+        Args::try_parse_from(["infst", "find-offsets", "--script", "discovery.json"]).unwrap();
+    match args.command {
+        Some(Command::FindOffsets { script, .. }) => {
+            assert_eq!(script.as_deref(), Some("discovery.json"));
+        }
+        _ => panic!("Expected FindOffsets command"),
+    }
+}
+
+#[test]
+fn test_parse_check_signatures() {
+    let args = Args::try_parse_from([
+        "infst",
+        "check-signatures",
+        "--signatures",
+        "signatures.json",
+        "--snapshot",
+        "new.snap",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::CheckSignatures {
+            signatures,
+            snapshot,
+            pid,
+            json,
+        }) => {
+            assert_eq!(signatures.as_deref(), Some("signatures.json"));
+            assert_eq!(snapshot.as_deref(), Some("new.snap"));
+            assert!(pid.is_none());
+            assert!(!json);
+        }
+        _ => panic!("Expected CheckSignatures command"),
+    }
+}
+
 #[test]
 fn test_parse_status_with_json() {
     let args = Args::try_parse_from(["infst", "status", "--json"]).unwrap();
@@ -117,7 +260,7 @@ fn test_parse_hexdump() {
             ..
         }) => {
             assert_eq!(address, "0x1000");
-            assert_eq!(size, 512);
+            assert_eq!(size, Some(512));
             assert!(!ascii);
         }
         _ => panic!("Expected Hexdump command"),
@@ -136,6 +279,58 @@ fn test_parse_hexdump_with_ascii() {
     }
 }
 
+#[test]
+fn test_parse_hexdump_with_layout() {
+    let args = Args::try_parse_from([
+        "infst",
+        "hexdump",
+        "--address",
+        "0x1000",
+        "--layout",
+        "judge",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::Hexdump { size, layout, .. }) => {
+            assert_eq!(size, None);
+            assert_eq!(layout.as_deref(), Some("judge"));
+        }
+        _ => panic!("Expected Hexdump command"),
+    }
+}
+
+#[test]
+fn test_parse_watch_memory() {
+    let args = Args::try_parse_from([
+        "infst",
+        "watch-memory",
+        "--address",
+        "0x1431B08A0",
+        "--size",
+        "64",
+        "-o",
+        "watch.jsonl",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::WatchMemory {
+            address,
+            size,
+            interval,
+            align,
+            output,
+            ..
+        }) => {
+            assert_eq!(address, "0x1431B08A0");
+            assert_eq!(size, Some(64));
+            assert_eq!(interval, 100);
+            assert_eq!(align, 4);
+            assert_eq!(output.as_deref(), Some("watch.jsonl"));
+        }
+        _ => panic!("Expected WatchMemory command"),
+    }
+}
+
 #[test]
 fn test_parse_offset() {
     let args =
@@ -174,6 +369,153 @@ fn test_parse_export_json_format() {
     }
 }
 
+#[test]
+fn test_parse_export_bpm_range() {
+    let args =
+        Args::try_parse_from(["infst", "export", "--min-bpm", "150", "--max-bpm", "180"]).unwrap();
+    match args.command {
+        Some(Command::Export {
+            min_bpm, max_bpm, ..
+        }) => {
+            assert_eq!(min_bpm, Some(150));
+            assert_eq!(max_bpm, Some(180));
+        }
+        _ => panic!("Expected Export command"),
+    }
+}
+
+#[test]
+fn test_parse_export_origin_version() {
+    let args = Args::try_parse_from(["infst", "export", "--origin-version", "HAPPY SKY"]).unwrap();
+    match args.command {
+        Some(Command::Export { origin_version, .. }) => {
+            assert_eq!(origin_version.as_deref(), Some("HAPPY SKY"));
+        }
+        _ => panic!("Expected Export command"),
+    }
+}
+
+#[test]
+fn test_parse_generate_signatures() {
+    let args = Args::try_parse_from([
+        "infst",
+        "generate-signatures",
+        "--offsets-file",
+        "offsets.json",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::GenerateSignatures {
+            offsets_file,
+            output,
+            pid,
+        }) => {
+            assert_eq!(offsets_file, "offsets.json");
+            assert_eq!(output, "signatures.json");
+            assert!(pid.is_none());
+        }
+        _ => panic!("Expected GenerateSignatures command"),
+    }
+
+    assert!(Args::try_parse_from(["infst", "generate-signatures"]).is_err());
+}
+
+#[test]
+fn test_parse_offsets_subcommands() {
+    let args = Args::try_parse_from(["infst", "offsets", "import", "offsets.txt"]).unwrap();
+    match args.command {
+        Some(Command::Offsets {
+            action: OffsetsAction::Import { file },
+        }) => assert_eq!(file, "offsets.txt"),
+        _ => panic!("Expected Offsets Import command"),
+    }
+
+    let args = Args::try_parse_from([
+        "infst",
+        "offsets",
+        "export",
+        "P2D:J:B:A:2026012800",
+        "-o",
+        "out.txt",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::Offsets {
+            action: OffsetsAction::Export { version, output },
+        }) => {
+            assert_eq!(version.as_deref(), Some("P2D:J:B:A:2026012800"));
+            assert_eq!(output.as_deref(), Some("out.txt"));
+        }
+        _ => panic!("Expected Offsets Export command"),
+    }
+
+    let args = Args::try_parse_from(["infst", "offsets", "convert", "offsets.txt"]).unwrap();
+    match args.command {
+        Some(Command::Offsets {
+            action: OffsetsAction::Convert { file, output },
+        }) => {
+            assert_eq!(file, "offsets.txt");
+            assert!(output.is_none());
+        }
+        _ => panic!("Expected Offsets Convert command"),
+    }
+
+    let args = Args::try_parse_from(["infst", "offsets", "list"]).unwrap();
+    assert!(matches!(
+        args.command,
+        Some(Command::Offsets {
+            action: OffsetsAction::List
+        })
+    ));
+}
+
+#[test]
+fn test_parse_offsets_diff() {
+    let args = Args::try_parse_from([
+        "infst",
+        "offsets",
+        "diff",
+        "old.txt",
+        "P2D:J:B:A:2026012800",
+        "--snapshot",
+        "new.snap",
+        "--json",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::Offsets {
+            action:
+                OffsetsAction::Diff {
+                    from,
+                    to,
+                    snapshot,
+                    pid,
+                    json,
+                },
+        }) => {
+            assert_eq!(from, "old.txt");
+            assert_eq!(to, "P2D:J:B:A:2026012800");
+            assert_eq!(snapshot.as_deref(), Some("new.snap"));
+            assert!(pid.is_none());
+            assert!(json);
+        }
+        _ => panic!("Expected Offsets Diff command"),
+    }
+
+    let result = Args::try_parse_from([
+        "infst",
+        "offsets",
+        "diff",
+        "a.txt",
+        "b.txt",
+        "--snapshot",
+        "new.snap",
+        "--pid",
+        "1234",
+    ]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_parse_global_offsets_file() {
     let args = Args::try_parse_from(["infst", "--offsets-file", "my-offsets.txt"]).unwrap();
@@ -203,3 +545,48 @@ fn test_missing_required_arg_fails() {
     let result = Args::try_parse_from(["infst", "hexdump"]);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_parse_analyze_song_fields() {
+    let args = Args::try_parse_from([
+        "infst",
+        "analyze",
+        "song-fields",
+        "--offsets-file",
+        "offsets.txt",
+        "--max-entries",
+        "2000",
+    ])
+    .unwrap();
+    match args.command {
+        Some(Command::Analyze {
+            target:
+                Some(AnalyzeTarget::SongFields {
+                    offsets_file,
+                    pid,
+                    max_entries,
+                    json,
+                }),
+            address,
+            ..
+        }) => {
+            assert_eq!(offsets_file.as_deref(), Some("offsets.txt"));
+            assert!(pid.is_none());
+            assert_eq!(max_entries, 2000);
+            assert!(!json);
+            assert!(address.is_none());
+        }
+        _ => panic!("Expected Analyze SongFields command"),
+    }
+
+    // The plain analysis mode keeps its own arguments
+    let args = Args::try_parse_from(["infst", "analyze", "--address", "0x1431B08A0"]).unwrap();
+    match args.command {
+        Some(Command::Analyze {
+            target: None,
+            address,
+            ..
+        }) => assert_eq!(address.as_deref(), Some("0x1431B08A0")),
+        _ => panic!("Expected Analyze command"),
+    }
+}
@@ -0,0 +1,120 @@
+//! Signature scan benchmark on a synthetic 80MB code image
+//!
+//! Compares scanning once per pattern (`find_pattern_with_wildcards`) with the
+//! single-pass multi-pattern scanner, on one thread and on all cores.
+//!
+//! Run with `cargo bench -p infst --bench signature_scan`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use infst::offset::{SignatureScanner, builtin_signatures};
+use infst::process::MultiPattern;
+use infst::process::pattern::find_pattern_with_wildcards;
+
+const IMAGE_SIZE: usize = 80 * 1024 * 1024;
+const BASE: u64 = 0x140000000;
+const ITERATIONS: u32 = 3;
+
+/// Pseudo-random bytes skewed towards common x86-64 opcodes
+fn synthetic_code(len: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            match state % 16 {
+                0..=2 => 0x48,
+                3 | 4 => 0x89,
+                5 | 6 => 0x8B,
+                7 => 0x8D,
+                8 => 0x00,
+                9 => 0xCC,
+                _ => (state >> 32) as u8,
+            }
+        })
+        .collect()
+}
+
+/// Plant every signature a few times across the image
+fn plant(image: &mut [u8], patterns: &[Vec<Option<u8>>]) {
+    for (i, pattern) in patterns.iter().enumerate() {
+        for copy in 0..4 {
+            let at = (i * 7919 + copy * IMAGE_SIZE / 4 + 0x1234) % (image.len() - pattern.len());
+            for (j, byte) in pattern.iter().enumerate() {
+                if let Some(byte) = byte {
+                    image[at + j] = *byte;
+                }
+            }
+        }
+    }
+}
+
+fn bench<T>(name: &str, mut run: impl FnMut() -> T) -> T {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let value = black_box(run());
+        best = best.min(start.elapsed());
+        result = Some(value);
+    }
+    let throughput = IMAGE_SIZE as f64 / best.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<28} {:>10.2?}  {:>8.0} MB/s", name, best, throughput);
+    result.expect("at least one iteration")
+}
+
+fn main() {
+    let signatures = builtin_signatures();
+    let patterns: Vec<Vec<Option<u8>>> = signatures
+        .entries
+        .iter()
+        .flat_map(|entry| &entry.signatures)
+        .map(|signature| {
+            let pattern = signature.parsed_pattern().expect("builtin pattern");
+            pattern
+                .to_bytes()
+                .expect("builtin patterns are plain bytes")
+        })
+        .collect();
+
+    let mut image = synthetic_code(IMAGE_SIZE);
+    plant(&mut image, &patterns);
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!(
+        "{} signatures, {}MB image, {} threads\n",
+        patterns.len(),
+        IMAGE_SIZE / (1024 * 1024),
+        threads
+    );
+
+    let per_pattern = bench("per-pattern scan", || {
+        patterns
+            .iter()
+            .map(|pattern| {
+                let bytes: Vec<u8> = pattern.iter().map(|b| b.unwrap_or(0)).collect();
+                let mask: Vec<bool> = pattern.iter().map(Option::is_none).collect();
+                find_pattern_with_wildcards(&image, &bytes, &mask)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let multi = MultiPattern::new(patterns.clone());
+    let single_pass = bench("multi-pattern, 1 thread", || multi.find_all(&image));
+    let parallel = bench("multi-pattern, all threads", || {
+        multi.find_all_parallel(&image, threads)
+    });
+
+    let scanner = SignatureScanner::new(&signatures).expect("builtin signatures");
+    let hits = bench("SignatureScanner", || scanner.scan_buffer(&image, BASE));
+
+    assert_eq!(single_pass, per_pattern);
+    assert_eq!(parallel, per_pattern);
+    assert_eq!(
+        hits.total_matches(),
+        per_pattern.iter().map(Vec::len).sum::<usize>()
+    );
+    println!("\n{} matches, results identical", hits.total_matches());
+}
@@ -0,0 +1,233 @@
+//! Song BPM
+//!
+//! A song has a fixed BPM or a BPM range (soflan). The text form is the one
+//! shown in exports: `"150"` or `"120~180"` (lowest first), zero-padded to
+//! three digits, and an empty string when the BPM is unknown. Serde uses the
+//! text form.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+/// BPM of a song (`min == max` for a fixed BPM, `max == 0` when unknown)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bpm {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Bpm {
+    pub const fn new(min: u32, max: u32) -> Self {
+        Self { min, max }
+    }
+
+    /// Fixed BPM
+    pub const fn fixed(bpm: u32) -> Self {
+        Self { min: bpm, max: bpm }
+    }
+
+    /// BPM from the min/max fields of a song entry (`bpm_min` is 0 for a fixed BPM)
+    ///
+    /// The fields are ordered here, since a reversed range would not parse
+    /// back from the text format.
+    pub fn from_memory(bpm_min: i32, bpm_max: i32) -> Self {
+        let a = bpm_min.max(0) as u32;
+        let b = bpm_max.max(0) as u32;
+        let (min, max) = (a.min(b), a.max(b));
+        if min != 0 && min != max {
+            Self::new(min, max)
+        } else {
+            Self::fixed(max)
+        }
+    }
+
+    /// Whether the BPM is unknown (e.g. songs loaded from a TSV file)
+    pub fn is_unknown(&self) -> bool {
+        self.max == 0
+    }
+
+    /// Whether the BPM changes during the song
+    pub fn is_soflan(&self) -> bool {
+        self.min != self.max
+    }
+}
+
+impl fmt::Display for Bpm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_unknown() {
+            Ok(())
+        } else if self.is_soflan() {
+            write!(f, "{:03}~{:03}", self.min, self.max)
+        } else {
+            write!(f, "{:03}", self.max)
+        }
+    }
+}
+
+impl FromStr for Bpm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let parse = |part: &str| {
+            part.trim()
+                .parse::<u32>()
+                .map_err(|_| Error::InvalidBpm(s.to_string()))
+        };
+        match s.split_once('~') {
+            Some((min, max)) => {
+                let (min, max) = (parse(min)?, parse(max)?);
+                if min > max {
+                    return Err(Error::InvalidBpm(s.to_string()));
+                }
+                Ok(Self::new(min, max))
+            }
+            None => Ok(Self::fixed(parse(s)?)),
+        }
+    }
+}
+
+impl Serialize for Bpm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bpm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Inclusive BPM filter; either bound may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BpmRange {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+impl BpmRange {
+    /// Create a range, failing if `min` is above `max`
+    pub fn new(min: Option<u32>, max: Option<u32>) -> Result<Self> {
+        if let (Some(lo), Some(hi)) = (min, max)
+            && lo > hi
+        {
+            return Err(Error::InvalidBpm(format!(
+                "minimum {} is above maximum {}",
+                lo, hi
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    /// Whether the range has no bounds (matches every song)
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    /// Whether any part of the song's BPM range lies within this range
+    ///
+    /// Songs with an unknown BPM only match an unbounded range.
+    pub fn matches(&self, bpm: &Bpm) -> bool {
+        if self.is_unbounded() {
+            return true;
+        }
+        !bpm.is_unknown()
+            && self.min.is_none_or(|min| bpm.max >= min)
+            && self.max.is_none_or(|max| bpm.min <= max)
+    }
+}
+
+impl fmt::Display for BpmRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |b: Option<u32>| b.map(|b| b.to_string()).unwrap_or_default();
+        write!(f, "{}-{}", bound(self.min), bound(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_matches_text_format() {
+        assert_eq!(Bpm::fixed(150).to_string(), "150");
+        assert_eq!(Bpm::fixed(85).to_string(), "085");
+        assert_eq!(Bpm::new(120, 180).to_string(), "120~180");
+        assert_eq!(Bpm::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_from_memory() {
+        assert_eq!(Bpm::from_memory(130, 150), Bpm::new(130, 150));
+        assert_eq!(Bpm::from_memory(0, 180), Bpm::fixed(180));
+        assert_eq!(Bpm::from_memory(160, 160), Bpm::fixed(160));
+        assert!(Bpm::from_memory(130, 150).is_soflan());
+        assert!(!Bpm::from_memory(0, 180).is_soflan());
+        assert_eq!(Bpm::from_memory(200, 150), Bpm::new(150, 200));
+        assert_eq!(Bpm::from_memory(150, 0), Bpm::fixed(150));
+    }
+
+    #[test]
+    fn test_reversed_memory_pair_round_trips() {
+        let bpm = Bpm::from_memory(200, 150);
+        let json = serde_json::to_string(&bpm).unwrap();
+        assert_eq!(json, "\"150~200\"");
+        assert_eq!(serde_json::from_str::<Bpm>(&json).unwrap(), bpm);
+
+        let range = BpmRange::new(Some(190), None).unwrap();
+        assert!(range.matches(&bpm));
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for text in ["150", "085", "120~180", ""] {
+            let bpm: Bpm = text.parse().unwrap();
+            assert_eq!(bpm.to_string(), text);
+        }
+        assert!("fast".parse::<Bpm>().is_err());
+        assert!("120~".parse::<Bpm>().is_err());
+        assert!("180~120".parse::<Bpm>().is_err());
+    }
+
+    #[test]
+    fn test_serde_uses_text_format() {
+        let json = serde_json::to_string(&Bpm::new(120, 180)).unwrap();
+        assert_eq!(json, "\"120~180\"");
+        let bpm: Bpm = serde_json::from_str("\"150\"").unwrap();
+        assert_eq!(bpm, Bpm::fixed(150));
+    }
+
+    #[test]
+    fn test_range_matches_overlap() {
+        let range = BpmRange::new(Some(150), Some(200)).unwrap();
+        assert!(range.matches(&Bpm::fixed(150)));
+        assert!(range.matches(&Bpm::new(120, 180)));
+        assert!(!range.matches(&Bpm::fixed(140)));
+        assert!(!range.matches(&Bpm::new(210, 300)));
+        assert!(!range.matches(&Bpm::default()));
+
+        assert!(
+            BpmRange::new(None, Some(120))
+                .unwrap()
+                .matches(&Bpm::new(100, 300))
+        );
+        assert!(BpmRange::default().matches(&Bpm::default()));
+        assert_eq!(range.to_string(), "150-200");
+        assert_eq!(BpmRange::new(Some(150), None).unwrap().to_string(), "150-");
+    }
+
+    #[test]
+    fn test_range_rejects_inverted_bounds() {
+        assert!(BpmRange::new(Some(200), Some(100)).is_err());
+        assert!(BpmRange::new(Some(150), Some(150)).is_ok());
+    }
+}
@@ -4,16 +4,28 @@
 //! - `Difficulty` - difficulty levels (SPB, SPN, SPH, SPA, SPL, DPB, DPN, DPH, DPA, DPL)
 //! - `Chart`, `ChartInfo` - chart identifiers and metadata
 //! - `SongInfo` - song metadata
+//! - `Bpm`, `BpmRange` - song BPM and BPM filters
+//! - `OriginVersion` - IIDX version a song originates from
+//! - `SongCache` - song database persisted per game version
+//! - `detect_song_layout` - song entry layout detection
 //! - `UnlockData` - unlock state management
 
+mod bpm;
 mod difficulty;
 mod encoding_fixes;
 mod song;
+mod song_cache;
+mod song_layout;
 mod types;
 mod unlock;
+mod version;
 
+pub use bpm::*;
 pub use difficulty::*;
 pub use encoding_fixes::*;
 pub use song::*;
+pub use song_cache::*;
+pub use song_layout::*;
 pub use types::*;
 pub use unlock::*;
+pub use version::*;
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::ops::RangeInclusive;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -9,19 +10,22 @@ use tracing::{debug, info, warn};
 
 use crate::error::Result;
 use crate::play::UnlockType;
+use crate::process::layout::song::{self, SongLayout};
 use crate::process::{ByteBuffer, ReadMemory, decode_shift_jis};
 
+use super::bpm::Bpm;
 use super::encoding_fixes::{fix_artist_encoding, fix_title_encoding};
+use super::song_layout::song_layout_or_current;
 
 /// Song metadata
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SongInfo {
     pub id: u32,
     pub title: Arc<str>,
     pub title_english: Arc<str>,
     pub artist: Arc<str>,
     pub genre: Arc<str>,
-    pub bpm: Arc<str>,
+    pub bpm: Bpm,
     pub folder: i32,
     /// Level for each difficulty: SPB, SPN, SPH, SPA, SPL, DPB, DPN, DPH, DPA, DPL
     pub levels: [u8; 10],
@@ -31,41 +35,18 @@ pub struct SongInfo {
 }
 
 impl SongInfo {
-    /// Size of one song entry in memory
-    /// Version 2026012800+: 0x4B0 = 1200 bytes (was 0x3F0 = 1008 bytes in older versions)
-    pub const MEMORY_SIZE: usize = 0x4B0; // 1200 bytes
+    /// Size of one song entry in memory (current layout)
+    /// Version 2026012800+: 0x4B0 = 1200 bytes (was 0x3F0 = 1008 bytes in older versions,
+    /// see [`song::LEGACY`])
+    pub const MEMORY_SIZE: usize = song::CURRENT.size; // 1200 bytes
+
+    /// Song IDs accepted as real songs when validating memory (IIDX IDs start
+    /// at 1000; the upper bound leaves room for new songs)
+    pub const ID_RANGE: RangeInclusive<u32> = 1000..=50000;
 
     /// Offset from text table to metadata table (legacy, kept for compatibility)
     pub const METADATA_TABLE_OFFSET: usize = 0x7E0;
 
-    // Memory layout constants
-    // INFINITAS stores song metadata in fixed-size blocks with the following layout:
-    const SLAB: usize = 64; // String block size (64 bytes per Shift-JIS string field)
-    const WORD: usize = 4; // i32/u32 size
-
-    // Memory offsets (relative to song entry start)
-    // Version 2026012800+ layout - 3 additional 64-byte fields compared to older versions
-    //
-    // String fields (each 64 bytes, Shift-JIS encoded):
-    //   0x000: Title
-    //   0x040: Title (English)
-    //   0x080: Genre
-    //   0x0C0: Artist
-    //   0x100-0x1BF: Additional fields (unknown purpose)
-    const TITLE_OFFSET: usize = 0;
-    const TITLE_ENGLISH_OFFSET: usize = Self::SLAB; // 64
-    const GENRE_OFFSET: usize = Self::SLAB * 2; // 128
-    const ARTIST_OFFSET: usize = Self::SLAB * 3; // 192
-
-    // Metadata section (updated for version 2026012800+):
-    // Old offsets were: folder=280, levels=288, bpm=320, notes=432, song_id=624
-    // New offsets add 192 bytes (3 x 64-byte fields) to most positions
-    const FOLDER_OFFSET: usize = 472; // folder byte (estimated)
-    const LEVELS_OFFSET: usize = 480; // 10 bytes for difficulty levels
-    const BPM_OFFSET: usize = 512; // 8 bytes: max, min (estimated)
-    const NOTES_OFFSET: usize = 624; // 40 bytes: 10 x i32 (estimated)
-    const SONG_ID_OFFSET: usize = 816; // 4 bytes
-
     /// Get level for a specific difficulty index
     pub fn get_level(&self, difficulty_index: usize) -> u8 {
         self.levels.get(difficulty_index).copied().unwrap_or(0)
@@ -85,23 +66,21 @@ impl SongInfo {
             return Ok(None);
         }
         let entry = &buffer[offset..offset + Self::MEMORY_SIZE];
-        Self::parse_entry(entry)
+        Self::parse_entry(entry, &song::CURRENT)
     }
 
-    /// Parse a single song entry from a MEMORY_SIZE-length slice
-    fn parse_entry(entry: &[u8]) -> Result<Option<Self>> {
-        let buf = ByteBuffer::new(entry);
-
+    /// Parse a single song entry laid out as `layout` (slice of `layout.size` bytes)
+    pub fn parse_entry(entry: &[u8], layout: &SongLayout) -> Result<Option<Self>> {
         // Check if entry is valid (first 4 bytes should not be 0)
-        if buf.read_i32_at(0).unwrap_or(0) == 0 {
+        if ByteBuffer::new(entry).read_i32_at(0).unwrap_or(0) == 0 {
             return Ok(None);
         }
 
         // Parse strings (Shift-JIS encoded, with encoding fixes for non-Shift-JIS characters)
-        let mut title = decode_shift_jis(buf.slice_at(Self::TITLE_OFFSET, Self::SLAB)?);
-        let title_english = decode_shift_jis(buf.slice_at(Self::TITLE_ENGLISH_OFFSET, Self::SLAB)?);
-        let genre = decode_shift_jis(buf.slice_at(Self::GENRE_OFFSET, Self::SLAB)?);
-        let mut artist = decode_shift_jis(buf.slice_at(Self::ARTIST_OFFSET, Self::SLAB)?);
+        let mut title = song::TITLE.decode(entry)?;
+        let title_english = song::TITLE_ENGLISH.decode(entry)?;
+        let genre = song::GENRE.decode(entry)?;
+        let mut artist = song::ARTIST.decode(entry)?;
 
         if let Some(fixed) = fix_title_encoding(&title) {
             title = fixed;
@@ -110,31 +89,14 @@ impl SongInfo {
             artist = fixed;
         }
 
-        // Parse folder (1 byte)
-        let folder = entry[Self::FOLDER_OFFSET] as i32;
-
-        // Parse difficulty levels (10 bytes)
-        let mut levels = [0u8; 10];
-        levels.copy_from_slice(buf.slice_at(Self::LEVELS_OFFSET, 10)?);
+        let folder = layout.folder.decode(entry)? as i32;
+        let levels = layout.levels.decode(entry)?;
+        let bpm_max = layout.bpm_max.decode(entry)?;
+        let bpm_min = layout.bpm_min.decode(entry)?;
 
-        // Parse BPM (8 bytes: max, min)
-        let bpm_max = buf.read_i32_at(Self::BPM_OFFSET)?;
-        let bpm_min = buf.read_i32_at(Self::BPM_OFFSET + Self::WORD)?;
-
-        let bpm: Arc<str> = if bpm_min != 0 && bpm_min != bpm_max {
-            format!("{:03}~{:03}", bpm_min, bpm_max).into()
-        } else {
-            format!("{:03}", bpm_max).into()
-        };
-
-        // Parse note counts (40 bytes = 10 x i32)
-        let mut total_notes = [0u32; 10];
-        for (i, note_count) in total_notes.iter_mut().enumerate() {
-            *note_count = buf.read_u32_at(Self::NOTES_OFFSET + i * Self::WORD)?;
-        }
-
-        // Parse song ID (4 bytes)
-        let song_id = buf.read_i32_at(Self::SONG_ID_OFFSET)?;
+        let bpm = Bpm::from_memory(bpm_min, bpm_max);
+        let total_notes = layout.notes.decode(entry)?;
+        let song_id = layout.song_id.decode(entry)?;
 
         Ok(Some(SongInfo {
             id: song_id as u32,
@@ -152,8 +114,17 @@ impl SongInfo {
 
     /// Read song info from memory at the given address
     pub fn read_from_memory<R: ReadMemory>(reader: &R, address: u64) -> Result<Option<Self>> {
-        let buffer = reader.read_bytes(address, Self::MEMORY_SIZE)?;
-        Self::parse_entry(&buffer)
+        Self::read_from_memory_with_layout(reader, address, &song::CURRENT)
+    }
+
+    /// Read song info laid out as `layout` from memory at the given address
+    pub fn read_from_memory_with_layout<R: ReadMemory>(
+        reader: &R,
+        address: u64,
+        layout: &SongLayout,
+    ) -> Result<Option<Self>> {
+        let buffer = reader.read_bytes(address, layout.size)?;
+        Self::parse_entry(&buffer, layout)
     }
 
     /// Read song info with fallback to metadata table for new INFINITAS versions.
@@ -167,21 +138,23 @@ impl SongInfo {
     /// * `text_address` - Address of the text entry
     /// * `text_base` - Base address of the text table
     /// * `entry_index` - Index of this entry in the table
+    /// * `layout` - Song entry layout of the table
     pub fn read_from_memory_with_fallback<R: ReadMemory>(
         reader: &R,
         text_address: u64,
         text_base: u64,
         entry_index: u64,
+        layout: &SongLayout,
     ) -> Result<Option<Self>> {
         // First, try standard read
-        let result = Self::read_from_memory(reader, text_address)?;
+        let result = Self::read_from_memory_with_layout(reader, text_address, layout)?;
 
         match result {
             Some(mut song) if song.id == 0 && !song.title.is_empty() => {
                 // Try to read song_id from metadata table
                 let metadata_addr = text_base
                     + Self::METADATA_TABLE_OFFSET as u64
-                    + entry_index * Self::MEMORY_SIZE as u64;
+                    + entry_index * layout.size as u64;
 
                 if let Ok(metadata) = reader.read_bytes(metadata_addr, 32) {
                     let buf = ByteBuffer::new(&metadata);
@@ -300,7 +273,7 @@ pub fn build_song_id_title_map<R: ReadMemory>(
     text_base: u64,
     scan_size: usize,
 ) -> HashMap<u32, Arc<str>> {
-    const ENTRY_SIZE: u64 = SongInfo::MEMORY_SIZE as u64; // 0x3F0 = 1008 bytes
+    const ENTRY_SIZE: u64 = SongInfo::MEMORY_SIZE as u64; // 0x4B0 = 1200 bytes
     const METADATA_OFFSET: u64 = SongInfo::METADATA_TABLE_OFFSET as u64; // 0x7E0 = 2016 bytes
 
     let mut result = HashMap::new();
@@ -367,7 +340,8 @@ pub fn fetch_song_database_bulk<R: ReadMemory>(
     song_list_addr: u64,
 ) -> Result<HashMap<u32, SongInfo>> {
     const MAX_ENTRIES: usize = 5000;
-    let bulk_size = MAX_ENTRIES * SongInfo::MEMORY_SIZE;
+    let layout = song_layout_or_current(reader, song_list_addr);
+    let bulk_size = MAX_ENTRIES * layout.size;
 
     // Try bulk read
     let buffer = match reader.read_bytes(song_list_addr, bulk_size) {
@@ -391,12 +365,12 @@ pub fn fetch_song_database_bulk<R: ReadMemory>(
     const MAX_CONSECUTIVE_FAILURES: u32 = 10;
 
     for entry_index in 0..MAX_ENTRIES {
-        let offset = entry_index * SongInfo::MEMORY_SIZE;
-        if offset + SongInfo::MEMORY_SIZE > buffer.len() {
+        let offset = entry_index * layout.size;
+        if offset + layout.size > buffer.len() {
             break;
         }
 
-        match SongInfo::parse_from_buffer(&buffer, offset) {
+        match SongInfo::parse_entry(&buffer[offset..offset + layout.size], layout) {
             Ok(Some(song)) if !song.title.is_empty() && song.id > 0 => {
                 result.entry(song.id).or_insert(song);
                 consecutive_failures = 0;
@@ -404,7 +378,7 @@ pub fn fetch_song_database_bulk<R: ReadMemory>(
             Ok(Some(mut song)) if song.id == 0 && !song.title.is_empty() => {
                 // Try metadata table fallback
                 if let Some(ref meta_buf) = metadata_buffer {
-                    let meta_offset = entry_index * SongInfo::MEMORY_SIZE;
+                    let meta_offset = entry_index * layout.size;
                     if meta_offset + 8 <= meta_buf.len() {
                         let meta = ByteBuffer::new(&meta_buf[meta_offset..]);
                         let alt_song_id = meta.read_i32_at(0).unwrap_or(0);
@@ -444,13 +418,14 @@ pub fn fetch_song_database<R: ReadMemory>(
     reader: &R,
     song_list_addr: u64,
 ) -> Result<HashMap<u32, SongInfo>> {
+    let layout = song_layout_or_current(reader, song_list_addr);
     let mut result = HashMap::new();
     let mut entry_index: u64 = 0;
     let mut consecutive_failures = 0;
     const MAX_CONSECUTIVE_FAILURES: u32 = 10;
 
     loop {
-        let address = song_list_addr + entry_index * SongInfo::MEMORY_SIZE as u64;
+        let address = song_list_addr + entry_index * layout.size as u64;
 
         // Use fallback method for new INFINITAS versions where metadata is split
         match SongInfo::read_from_memory_with_fallback(
@@ -458,6 +433,7 @@ pub fn fetch_song_database<R: ReadMemory>(
             address,
             song_list_addr,
             entry_index,
+            layout,
         )? {
             Some(song) if !song.title.is_empty() && song.id > 0 => {
                 // Avoid duplicates
@@ -568,7 +544,7 @@ pub fn load_song_database_from_tsv<P: AsRef<Path>>(
             title_english: Arc::from(""),
             artist: Arc::from(""),
             genre: Arc::from(""),
-            bpm: Arc::from(""),
+            bpm: Bpm::default(),
             folder: 0,
             levels,
             total_notes,
@@ -741,11 +717,9 @@ fn normalize_title_for_matching(title: &str) -> String {
 /// Fetch a single song by its song_id from memory
 ///
 /// This function searches through the song list entries to find a specific song.
-/// song_id is stored at offset 816 within each 1200-byte entry.
 ///
 /// Memory structure:
-/// - entry[i] = song_list_addr + i * ENTRY_SIZE (0x3F0 = 1008 bytes)
-/// - song_id is at offset 624 within each entry
+/// - entry[i] = song_list_addr + i * layout.size (detected song layout)
 pub fn fetch_song_by_id<R: ReadMemory>(
     reader: &R,
     song_list_addr: u64,
@@ -756,16 +730,15 @@ pub fn fetch_song_by_id<R: ReadMemory>(
         return None;
     }
 
-    const ENTRY_SIZE: u64 = SongInfo::MEMORY_SIZE as u64; // 0x3F0 = 1008 bytes
-
-    let max_entries = (scan_size as u64 / ENTRY_SIZE).min(5000);
+    let layout = song_layout_or_current(reader, song_list_addr);
+    let entry_size = layout.size as u64;
+    let max_entries = (scan_size as u64 / entry_size).min(5000);
 
     // Scan each entry for the target song_id
     for i in 0..max_entries {
-        let entry_addr = song_list_addr + i * ENTRY_SIZE;
+        let entry_addr = song_list_addr + i * entry_size;
 
-        // Use the proper read_from_memory function that reads song_id from offset 624
-        match SongInfo::read_from_memory(reader, entry_addr) {
+        match SongInfo::read_from_memory_with_layout(reader, entry_addr, layout) {
             Ok(Some(song)) if song.id == target_song_id => {
                 debug!(
                     "Dynamically loaded song_id={} title={:?} folder={}",
@@ -782,28 +755,28 @@ pub fn fetch_song_by_id<R: ReadMemory>(
 
 /// Build song database directly from memory for new INFINITAS versions
 ///
-/// This function scans memory to find all loaded songs. Each entry is 1008 bytes
-/// and contains all song metadata including song_id at offset 624.
+/// This function scans memory to find all loaded songs. Each entry contains all
+/// song metadata including song_id; the entry layout is detected first.
 ///
 /// Memory structure:
-/// - entry[i] = song_list_base + i * ENTRY_SIZE (0x3F0 = 1008 bytes)
+/// - entry[i] = song_list_base + i * layout.size
 pub fn fetch_song_database_from_memory_scan<R: ReadMemory>(
     reader: &R,
     song_list_base: u64,
     scan_size: usize,
 ) -> HashMap<u32, SongInfo> {
-    const ENTRY_SIZE: u64 = SongInfo::MEMORY_SIZE as u64; // 0x3F0 = 1008 bytes
+    let layout = song_layout_or_current(reader, song_list_base);
+    let entry_size = layout.size as u64;
 
     let mut result = HashMap::new();
-    let max_entries = (scan_size as u64 / ENTRY_SIZE).min(5000);
+    let max_entries = (scan_size as u64 / entry_size).min(5000);
 
     // Note: With lazy loading, songs may be scattered across the entry table.
     // We scan all entries to find all loaded songs.
     for i in 0..max_entries {
-        let entry_addr = song_list_base + i * ENTRY_SIZE;
+        let entry_addr = song_list_base + i * entry_size;
 
-        // Use the proper read_from_memory function
-        let song = match SongInfo::read_from_memory(reader, entry_addr) {
+        let song = match SongInfo::read_from_memory_with_layout(reader, entry_addr, layout) {
             Ok(Some(song)) => song,
             _ => continue,
         };
@@ -841,15 +814,15 @@ mod tests {
         // Write title as Shift-JIS at offset 0
         let (encoded, _, _) = encoding_rs::SHIFT_JIS.encode(title);
         let title_bytes = encoded.as_ref();
-        let len = title_bytes.len().min(SongInfo::SLAB);
+        let len = title_bytes.len().min(song::SLAB);
         entry[..len].copy_from_slice(&title_bytes[..len]);
-        // Write song_id at SONG_ID_OFFSET
-        entry[SongInfo::SONG_ID_OFFSET..SongInfo::SONG_ID_OFFSET + 4]
-            .copy_from_slice(&(song_id as i32).to_le_bytes());
+        // Write song_id
+        let id = song::SONG_ID.offset;
+        entry[id..id + 4].copy_from_slice(&(song_id as i32).to_le_bytes());
         // Write at least one non-zero level and note count for the entry to be meaningful
-        entry[SongInfo::LEVELS_OFFSET] = 12; // SPB level = 12
-        entry[SongInfo::NOTES_OFFSET..SongInfo::NOTES_OFFSET + 4]
-            .copy_from_slice(&100u32.to_le_bytes()); // SPB notes = 100
+        entry[song::LEVELS.offset] = 12; // SPB level = 12
+        let notes = song::NOTES.offset;
+        entry[notes..notes + 4].copy_from_slice(&100u32.to_le_bytes()); // SPB notes = 100
         entry
     }
 
@@ -950,4 +923,37 @@ mod tests {
             assert_eq!(bulk_song.title.as_ref(), per_song.title.as_ref());
         }
     }
+
+    #[test]
+    fn test_fetch_song_database_legacy_layout() {
+        let layout = &song::LEGACY;
+        let mut buffer = Vec::new();
+        for (i, title) in ["Legacy1", "Legacy2", "Legacy3"].iter().enumerate() {
+            let mut entry = vec![0u8; layout.size];
+            entry[..title.len()].copy_from_slice(title.as_bytes());
+            let id = layout.song_id.offset;
+            entry[id..id + 4].copy_from_slice(&(3001 + i as i32).to_le_bytes());
+            entry[layout.levels.offset + 1] = 7; // SPN level = 7
+            let notes = layout.notes.offset + 4;
+            entry[notes..notes + 4].copy_from_slice(&555u32.to_le_bytes()); // SPN notes
+            buffer.extend_from_slice(&entry);
+        }
+        buffer.extend_from_slice(&vec![0u8; 10 * layout.size]);
+
+        let base: u64 = 0x1000;
+        let reader = MockMemoryBuilder::new()
+            .base(base)
+            .write_bytes(0, &buffer)
+            .build();
+
+        let db = fetch_song_database_bulk(&reader, base).unwrap();
+        assert_eq!(db.len(), 3);
+        let song = &db[&3002];
+        assert_eq!(song.title.as_ref(), "Legacy2");
+        assert_eq!(song.levels[1], 7);
+        assert_eq!(song.total_notes[1], 555);
+
+        let found = fetch_song_by_id(&reader, base, 3003, buffer.len()).unwrap();
+        assert_eq!(found.title.as_ref(), "Legacy3");
+    }
 }
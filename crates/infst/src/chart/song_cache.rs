@@ -0,0 +1,248 @@
+//! Song database cache across runs
+//!
+//! Newer builds only load songs into memory once they are scrolled to, so a
+//! fresh memory scan misses most of the library. The merged song database is
+//! saved per game version (`dirs::cache_dir()/infst/songs/<version>.json`)
+//! and loaded at startup; live memory then only adds or refreshes entries.
+//!
+//! A cache written with another schema version or for another game version
+//! is ignored.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::SongInfo;
+
+/// Schema version of the song cache file
+pub const SONG_CACHE_SCHEMA_VERSION: u32 = 1;
+
+/// Cache directory name inside the infst cache directory
+const CACHE_DIR: &str = "songs";
+
+/// Song database of one game version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SongCache {
+    pub schema_version: u32,
+    pub game_version: String,
+    /// Songs keyed by song ID
+    pub songs: BTreeMap<u32, SongInfo>,
+}
+
+impl SongCache {
+    /// Create an empty cache for a game version
+    pub fn new(game_version: &str) -> Self {
+        Self {
+            schema_version: SONG_CACHE_SCHEMA_VERSION,
+            game_version: game_version.to_string(),
+            songs: BTreeMap::new(),
+        }
+    }
+
+    /// Default cache file path for a game version in the user cache directory
+    pub fn default_path(game_version: &str) -> Option<PathBuf> {
+        let file_name: String = game_version
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        dirs::cache_dir().map(|dir| {
+            dir.join("infst")
+                .join(CACHE_DIR)
+                .join(format!("{}.json", file_name))
+        })
+    }
+
+    /// Load the cache for a game version from the default path
+    ///
+    /// Returns an empty cache if the file is missing or unusable.
+    pub fn load(game_version: &str) -> Self {
+        match Self::default_path(game_version) {
+            Some(path) => Self::load_from_path(path, game_version),
+            None => {
+                debug!("No user cache directory available");
+                Self::new(game_version)
+            }
+        }
+    }
+
+    /// Load the cache for a game version from a specific path
+    ///
+    /// Returns an empty cache if the file is missing, unreadable, or written
+    /// with another schema or for another game version.
+    pub fn load_from_path<P: AsRef<Path>>(path: P, game_version: &str) -> Self {
+        let path = path.as_ref();
+
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("Song cache not found or unreadable: {}", e);
+                return Self::new(game_version);
+            }
+        };
+
+        let cache = match serde_json::from_str::<SongCache>(&content) {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("Failed to parse song cache: {}", e);
+                return Self::new(game_version);
+            }
+        };
+
+        if cache.schema_version != SONG_CACHE_SCHEMA_VERSION {
+            info!(
+                "Ignoring song cache with schema version {} (expected {})",
+                cache.schema_version, SONG_CACHE_SCHEMA_VERSION
+            );
+            return Self::new(game_version);
+        }
+        if cache.game_version != game_version {
+            info!(
+                "Ignoring song cache for {} (game is {})",
+                cache.game_version, game_version
+            );
+            return Self::new(game_version);
+        }
+
+        debug!("Loaded song cache with {} songs", cache.len());
+        cache
+    }
+
+    /// Save the cache to the default path for its game version
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = Self::default_path(&self.game_version).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no user cache directory available",
+            )
+        })?;
+        self.save_to_path(path)
+    }
+
+    /// Save the cache to a specific path, creating the parent directory if needed
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
+        let path = path.as_ref();
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        debug!("Saved {} songs to {}", self.len(), path.display());
+        Ok(())
+    }
+
+    /// Add or refresh entries from live memory
+    ///
+    /// Returns the number of entries that were added or changed.
+    pub fn merge(&mut self, songs: &HashMap<u32, SongInfo>) -> usize {
+        let mut changed = 0;
+        for (&song_id, song) in songs {
+            if self.songs.get(&song_id) != Some(song) {
+                self.songs.insert(song_id, song.clone());
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Cached songs as a song database
+    pub fn to_song_db(&self) -> HashMap<u32, SongInfo> {
+        self.songs
+            .iter()
+            .map(|(&song_id, song)| (song_id, song.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.songs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.songs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION: &str = "P2D:J:B:A:2026012800";
+
+    fn song(id: u32, title: &str) -> SongInfo {
+        SongInfo {
+            id,
+            title: title.into(),
+            levels: [1, 5, 9, 11, 0, 0, 5, 9, 11, 0],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("songs").join("cache.json");
+
+        let mut cache = SongCache::new(VERSION);
+        cache.merge(&HashMap::from([(1001, song(1001, "GAMBOL"))]));
+        cache.save_to_path(&path).unwrap();
+
+        let loaded = SongCache::load_from_path(&path, VERSION);
+        assert_eq!(loaded, cache);
+        assert_eq!(loaded.to_song_db()[&1001].title.as_ref(), "GAMBOL");
+    }
+
+    #[test]
+    fn test_other_version_or_schema_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let mut cache = SongCache::new(VERSION);
+        cache.merge(&HashMap::from([(1001, song(1001, "GAMBOL"))]));
+        cache.save_to_path(&path).unwrap();
+        assert!(SongCache::load_from_path(&path, "P2D:J:B:A:2026020100").is_empty());
+
+        cache.schema_version = SONG_CACHE_SCHEMA_VERSION + 1;
+        cache.save_to_path(&path).unwrap();
+        assert!(SongCache::load_from_path(&path, VERSION).is_empty());
+
+        assert!(SongCache::load_from_path(dir.path().join("missing.json"), VERSION).is_empty());
+    }
+
+    #[test]
+    fn test_merge_adds_and_refreshes() {
+        let mut cache = SongCache::new(VERSION);
+        let live = HashMap::from([(1001, song(1001, "GAMBOL")), (1002, song(1002, "?"))]);
+        assert_eq!(cache.merge(&live), 2);
+        assert_eq!(cache.merge(&live), 0);
+
+        // Songs missing from live memory stay cached
+        assert_eq!(
+            cache.merge(&HashMap::from([(1002, song(1002, "SPACE FUNK"))])),
+            1
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.songs[&1002].title.as_ref(), "SPACE FUNK");
+    }
+
+    #[test]
+    fn test_default_path_is_file_name_safe() {
+        if let Some(path) = SongCache::default_path(VERSION) {
+            assert_eq!(
+                path.file_name().unwrap().to_str(),
+                Some("P2D_J_B_A_2026012800.json")
+            );
+        }
+    }
+}
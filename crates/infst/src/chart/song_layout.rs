@@ -0,0 +1,196 @@
+//! Song entry layout detection
+//!
+//! The song entry grew from 0x3F0 to 0x4B0 bytes in version 2026012800. The
+//! known layouts are described in [`crate::process::layout::song`]; the one
+//! in use is picked by reading the first entries of the song list with each
+//! candidate layout and counting the entries that look like real songs.
+
+use tracing::debug;
+
+use crate::process::ReadMemory;
+use crate::process::layout::song::{self, SongLayout};
+
+use super::SongInfo;
+
+/// Number of song list entries sampled per candidate layout
+const SAMPLE_ENTRIES: u64 = 32;
+
+/// Upper bound for the note count of a single chart
+const MAX_NOTES: u32 = 10000;
+
+/// Check whether an entry parsed with a layout looks like a real song
+///
+/// Requires a title, a song ID in the valid range, levels 0-12 with at least
+/// one chart, and note counts that agree with the levels (no notes on
+/// difficulties without a level).
+pub fn is_plausible_song_entry(entry: &[u8], layout: &SongLayout) -> bool {
+    let Ok(Some(song)) = SongInfo::parse_entry(entry, layout) else {
+        return false;
+    };
+
+    !song.title.trim().is_empty()
+        && SongInfo::ID_RANGE.contains(&song.id)
+        && song.levels.iter().all(|&level| level <= 12)
+        && song.levels.iter().any(|&level| level > 0)
+        && song
+            .levels
+            .iter()
+            .zip(song.total_notes)
+            .all(|(&level, notes)| {
+                if level == 0 {
+                    notes == 0
+                } else {
+                    notes <= MAX_NOTES
+                }
+            })
+}
+
+/// Count the plausible entries among the first entries of the song list
+pub fn score_song_layout<R: ReadMemory>(
+    reader: &R,
+    song_list_addr: u64,
+    layout: &SongLayout,
+) -> usize {
+    (0..SAMPLE_ENTRIES)
+        .filter_map(|i| {
+            reader
+                .read_bytes(song_list_addr + i * layout.size as u64, layout.size)
+                .ok()
+        })
+        .filter(|entry| is_plausible_song_entry(entry, layout))
+        .count()
+}
+
+/// Detect the song entry layout used by the song list at `song_list_addr`
+///
+/// Returns the known layout with the most plausible entries (the newest one
+/// on a tie), or `None` if no layout yields a single plausible entry.
+pub fn detect_song_layout<R: ReadMemory>(
+    reader: &R,
+    song_list_addr: u64,
+) -> Option<&'static SongLayout> {
+    if song_list_addr == 0 {
+        return None;
+    }
+
+    let mut best: Option<(&'static SongLayout, usize)> = None;
+    for layout in song::KNOWN {
+        let score = score_song_layout(reader, song_list_addr, layout);
+        debug!(
+            "Song layout {}: {}/{} plausible entries",
+            layout.name, score, SAMPLE_ENTRIES
+        );
+        if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((layout, score));
+        }
+    }
+
+    best.map(|(layout, _)| layout)
+}
+
+/// Detected song layout, falling back to the current layout
+pub fn song_layout_or_current<R: ReadMemory>(
+    reader: &R,
+    song_list_addr: u64,
+) -> &'static SongLayout {
+    match detect_song_layout(reader, song_list_addr) {
+        Some(layout) => layout,
+        None => {
+            debug!("No song layout detected, using {}", song::CURRENT.name);
+            &song::CURRENT
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    const BASE: u64 = 0x1000;
+
+    fn build_entry(layout: &SongLayout, title: &str, song_id: u32) -> Vec<u8> {
+        let mut entry = vec![0u8; layout.size];
+        entry[..title.len()].copy_from_slice(title.as_bytes());
+        let id = layout.song_id.offset;
+        entry[id..id + 4].copy_from_slice(&(song_id as i32).to_le_bytes());
+        let levels = layout.levels.offset;
+        entry[levels..levels + 10].copy_from_slice(&[0, 5, 9, 11, 0, 0, 5, 9, 11, 0]);
+        for (diff, notes) in [
+            (1, 400u32),
+            (2, 700),
+            (3, 1000),
+            (6, 450),
+            (7, 800),
+            (8, 1100),
+        ] {
+            let at = layout.notes.offset + diff * 4;
+            entry[at..at + 4].copy_from_slice(&notes.to_le_bytes());
+        }
+        entry
+    }
+
+    fn song_list(layout: &SongLayout, count: u32) -> Vec<u8> {
+        (0..count)
+            .flat_map(|i| build_entry(layout, &format!("Song {}", i), 1001 + i))
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_current_layout() {
+        let reader = MockMemoryBuilder::new()
+            .base(BASE)
+            .write_bytes(0, &song_list(&song::CURRENT, 40))
+            .build();
+
+        let layout = detect_song_layout(&reader, BASE).unwrap();
+        assert_eq!(layout.name, song::CURRENT.name);
+    }
+
+    #[test]
+    fn test_detects_legacy_layout() {
+        let reader = MockMemoryBuilder::new()
+            .base(BASE)
+            .write_bytes(0, &song_list(&song::LEGACY, 40))
+            .build();
+
+        let layout = detect_song_layout(&reader, BASE).unwrap();
+        assert_eq!(layout.name, song::LEGACY.name);
+        assert_eq!(layout.size, 0x3F0);
+    }
+
+    #[test]
+    fn test_no_layout_for_empty_list() {
+        let reader = MockMemoryBuilder::new()
+            .base(BASE)
+            .write_bytes(0, &vec![0u8; 0x10000])
+            .build();
+
+        assert!(detect_song_layout(&reader, BASE).is_none());
+        assert_eq!(
+            song_layout_or_current(&reader, BASE).name,
+            song::CURRENT.name
+        );
+    }
+
+    #[test]
+    fn test_implausible_entries() {
+        let layout = &song::CURRENT;
+
+        let mut entry = build_entry(layout, "Song", 999);
+        assert!(!is_plausible_song_entry(&entry, layout));
+
+        entry = build_entry(layout, "Song", 1001);
+        assert!(is_plausible_song_entry(&entry, layout));
+
+        // Level out of range
+        entry[layout.levels.offset + 3] = 13;
+        assert!(!is_plausible_song_entry(&entry, layout));
+
+        // Notes on a difficulty without a level
+        entry = build_entry(layout, "Song", 1001);
+        let spb_notes = layout.notes.offset;
+        entry[spb_notes..spb_notes + 4].copy_from_slice(&300u32.to_le_bytes());
+        assert!(!is_plausible_song_entry(&entry, layout));
+    }
+}
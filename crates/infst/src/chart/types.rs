@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::chart::{Difficulty, SongInfo};
+use crate::chart::{Bpm, Difficulty, OriginVersion, SongInfo};
 
 /// Chart identifier (song + difficulty)
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -19,7 +19,10 @@ pub struct ChartInfo {
     pub title_english: Arc<str>,
     pub artist: Arc<str>,
     pub genre: Arc<str>,
-    pub bpm: Arc<str>,
+    pub bpm: Bpm,
+    /// Folder value of the song (see [`OriginVersion`])
+    #[serde(default)]
+    pub folder: i32,
     pub difficulty: Difficulty,
     pub level: u8,
     pub total_notes: u32,
@@ -35,7 +38,8 @@ impl ChartInfo {
             title_english: song.title_english.clone(),
             artist: song.artist.clone(),
             genre: song.genre.clone(),
-            bpm: song.bpm.clone(),
+            bpm: song.bpm,
+            folder: song.folder,
             difficulty,
             level: song.get_level(diff_index),
             total_notes: song.get_total_notes(diff_index),
@@ -43,6 +47,11 @@ impl ChartInfo {
         }
     }
 
+    /// Version the song first appeared in, `None` for unknown folder values
+    pub fn origin_version(&self) -> Option<OriginVersion> {
+        OriginVersion::from_folder(self.folder)
+    }
+
     /// Calculate max EX score (total_notes * 2)
     pub fn max_ex_score(&self) -> u32 {
         self.total_notes * 2
@@ -80,7 +89,7 @@ mod tests {
             title_english: Arc::from("Test Song EN"),
             artist: Arc::from("Test Artist"),
             genre: Arc::from("Test Genre"),
-            bpm: Arc::from("150"),
+            bpm: Bpm::fixed(150),
             folder: 1,
             levels,
             total_notes: notes,
@@ -99,6 +108,7 @@ mod tests {
         assert_eq!(chart.level, 11);
         assert_eq!(chart.total_notes, 1200);
         assert!(chart.unlocked);
+        assert!(chart.origin_version().is_none());
     }
 
     #[test]
@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use crate::chart::{Difficulty, SongInfo};
 use crate::error::Result;
 use crate::play::UnlockType;
-use crate::process::{ByteBuffer, ReadMemory};
+use crate::process::layout::unlock;
+use crate::process::{FromMemory, ReadMemory, StructLayout};
 
 /// Unlock data structure from memory
 #[derive(Debug, Clone, Default)]
@@ -15,36 +16,30 @@ pub struct UnlockData {
 
 impl UnlockData {
     /// Size of unlock data structure in memory (32 bytes)
-    pub const MEMORY_SIZE: usize = 32;
+    pub const MEMORY_SIZE: usize = unlock::LAYOUT.size;
 
     /// Check if a specific difficulty is unlocked (raw bit check)
     pub fn is_difficulty_unlocked(&self, difficulty: Difficulty) -> bool {
         let bit = 1 << (difficulty as i32);
         (self.unlocks & bit) != 0
     }
+}
 
-    /// Parse from raw bytes
-    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() < Self::MEMORY_SIZE {
-            return None;
-        }
-
-        let buf = ByteBuffer::new(bytes);
-        let song_id = buf.read_u32_at(0).ok()?;
-        let unlock_type_val = buf.read_i32_at(4).ok()?;
-        let unlocks = buf.read_i32_at(8).ok()?;
+impl FromMemory for UnlockData {
+    const LAYOUT: &'static StructLayout = &unlock::LAYOUT;
 
-        let unlock_type = match unlock_type_val {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let unlock_type = match unlock::UNLOCK_TYPE.decode(bytes)? {
             1 => UnlockType::Base,
             2 => UnlockType::Bits,
             3 => UnlockType::Sub,
             _ => UnlockType::Base,
         };
 
-        Some(Self {
-            song_id,
+        Ok(Self {
+            song_id: unlock::SONG_ID.decode(bytes)?,
             unlock_type,
-            unlocks,
+            unlocks: unlock::UNLOCKS.decode(bytes)?,
         })
     }
 }
@@ -95,7 +90,7 @@ fn parse_unlock_buffer(
     while position + UnlockData::MEMORY_SIZE <= buffer.len() {
         let chunk = &buffer[position..position + UnlockData::MEMORY_SIZE];
 
-        if let Some(data) = UnlockData::from_bytes(chunk) {
+        if let Ok(data) = UnlockData::decode(chunk) {
             if data.song_id == 0 {
                 break;
             }
@@ -228,7 +223,7 @@ mod tests {
     }
 
     #[test]
-    fn test_from_bytes() {
+    fn test_decode() {
         let bytes = [
             0xE8, 0x03, 0x00, 0x00, // song_id = 1000
             0x01, 0x00, 0x00, 0x00, // unlock_type = Base
@@ -238,7 +233,7 @@ mod tests {
             0x00, 0x00,
         ];
 
-        let unlock = UnlockData::from_bytes(&bytes).unwrap();
+        let unlock = UnlockData::decode(&bytes).unwrap();
         assert_eq!(unlock.song_id, 1000);
         assert_eq!(unlock.unlock_type, UnlockType::Base);
         assert_eq!(unlock.unlocks, 0x1F);
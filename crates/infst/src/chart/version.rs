@@ -0,0 +1,195 @@
+//! Origin version of a song
+//!
+//! The folder value of a song entry is the IIDX version the song first
+//! appeared in, numbered like the arcade releases (folder 12 = HAPPY SKY).
+//! Only 2nd style onward is mapped: the folder values of 1st style, substream
+//! and INFINITAS originals have not been confirmed against game data. Values
+//! not in the table are left unmapped and reported by [`unknown_folders`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::SongInfo;
+
+/// IIDX version a song originates from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct OriginVersion {
+    /// Folder value in the song entry
+    pub folder: i32,
+    /// Version name
+    pub name: &'static str,
+}
+
+/// Folder values, version names and alternative names accepted by [`OriginVersion::find`]
+const VERSIONS: &[(i32, &str, &[&str])] = &[
+    (2, "2nd style", &[]),
+    (3, "3rd style", &[]),
+    (4, "4th style", &[]),
+    (5, "5th style", &[]),
+    (6, "6th style", &[]),
+    (7, "7th style", &[]),
+    (8, "8th style", &[]),
+    (9, "9th style", &[]),
+    (10, "10th style", &[]),
+    (11, "IIDX RED", &["RED"]),
+    (12, "HAPPY SKY", &[]),
+    (13, "DistorteD", &[]),
+    (14, "GOLD", &[]),
+    (15, "DJ TROOPERS", &[]),
+    (16, "EMPRESS", &[]),
+    (17, "SIRIUS", &[]),
+    (18, "Resort Anthem", &[]),
+    (19, "Lincle", &[]),
+    (20, "tricoro", &[]),
+    (21, "SPADA", &[]),
+    (22, "PENDUAL", &[]),
+    (23, "copula", &[]),
+    (24, "SINOBUZ", &[]),
+    (25, "CANNON BALLERS", &[]),
+    (26, "Rootage", &[]),
+    (27, "HEROIC VERSE", &[]),
+    (28, "BISTROVER", &[]),
+    (29, "CastHour", &[]),
+    (30, "RESIDENT", &[]),
+    (31, "EPOLIS", &[]),
+    (32, "Pinky Crush", &[]),
+    (33, "Sparkle Shower", &[]),
+];
+
+impl OriginVersion {
+    /// Version for a folder value, `None` if the value is not known
+    pub fn from_folder(folder: i32) -> Option<Self> {
+        VERSIONS
+            .iter()
+            .find(|(f, _, _)| *f == folder)
+            .map(|&(folder, name, _)| Self { folder, name })
+    }
+
+    /// Find a version by name or folder number
+    ///
+    /// Matching ignores case, spaces and punctuation ("happy sky", "HAPPYSKY"
+    /// and "12" all find HAPPY SKY).
+    pub fn find(query: &str) -> Option<Self> {
+        if let Ok(folder) = query.trim().parse::<i32>() {
+            return Self::from_folder(folder);
+        }
+
+        let query = normalize(query);
+        VERSIONS
+            .iter()
+            .find(|(_, name, aliases)| {
+                normalize(name) == query || aliases.iter().any(|alias| normalize(alias) == query)
+            })
+            .map(|&(folder, name, _)| Self { folder, name })
+    }
+
+    /// All known versions in release order
+    pub fn all() -> impl Iterator<Item = Self> {
+        VERSIONS
+            .iter()
+            .map(|&(folder, name, _)| Self { folder, name })
+    }
+}
+
+impl fmt::Display for OriginVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl SongInfo {
+    /// Version the song first appeared in, `None` for unknown folder values
+    pub fn origin_version(&self) -> Option<OriginVersion> {
+        OriginVersion::from_folder(self.folder)
+    }
+}
+
+/// Folder values without a known version, with the number of songs using each
+pub fn unknown_folders(song_db: &HashMap<u32, SongInfo>) -> BTreeMap<i32, usize> {
+    let mut unknown = BTreeMap::new();
+    for song in song_db.values() {
+        if song.origin_version().is_none() {
+            *unknown.entry(song.folder).or_insert(0) += 1;
+        }
+    }
+    unknown
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(id: u32, folder: i32) -> SongInfo {
+        SongInfo {
+            id,
+            folder,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_folder() {
+        assert_eq!(OriginVersion::from_folder(12).unwrap().name, "HAPPY SKY");
+        assert_eq!(OriginVersion::from_folder(2).unwrap().name, "2nd style");
+        assert!(OriginVersion::from_folder(0).is_none());
+        assert!(OriginVersion::from_folder(1).is_none());
+        assert!(OriginVersion::from_folder(80).is_none());
+        assert!(OriginVersion::from_folder(99).is_none());
+    }
+
+    #[test]
+    fn test_find_by_name_alias_or_number() {
+        assert_eq!(OriginVersion::find("happy sky").unwrap().folder, 12);
+        assert_eq!(OriginVersion::find("HAPPYSKY").unwrap().folder, 12);
+        assert_eq!(OriginVersion::find("RED").unwrap().folder, 11);
+        assert_eq!(OriginVersion::find("2nd style").unwrap().folder, 2);
+        assert!(OriginVersion::find("1st style").is_none());
+        assert_eq!(OriginVersion::find("21").unwrap().name, "SPADA");
+        assert!(OriginVersion::find("HAPPY").is_none());
+        assert!(OriginVersion::find("99").is_none());
+    }
+
+    #[test]
+    fn test_song_origin_version() {
+        assert_eq!(
+            song(12004, 12).origin_version().map(|v| v.to_string()),
+            Some("HAPPY SKY".to_string())
+        );
+        assert!(song(1001, 0).origin_version().is_none());
+    }
+
+    #[test]
+    fn test_unknown_folders_are_counted() {
+        let song_db: HashMap<u32, SongInfo> = [
+            (12004, song(12004, 12)),
+            (34001, song(34001, 34)),
+            (34002, song(34002, 34)),
+            (1001, song(1001, 0)),
+            (1002, song(1002, 1)),
+            (80001, song(80001, 80)),
+        ]
+        .into_iter()
+        .collect();
+
+        let unknown = unknown_folders(&song_db);
+        assert_eq!(unknown, BTreeMap::from([(0, 1), (1, 1), (34, 2), (80, 1)]));
+    }
+
+    #[test]
+    fn test_table_is_consistent() {
+        let folders: Vec<i32> = OriginVersion::all().map(|v| v.folder).collect();
+        assert!(folders.windows(2).all(|w| w[0] < w[1]));
+        for version in OriginVersion::all() {
+            assert_eq!(OriginVersion::find(version.name), Some(version));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Clock abstraction for the tracker loop.
+//!
+//! The game loop waits between polls and on the result screen. Routing those
+//! waits through a [`Clock`] lets tests and embedders replace real sleeps with
+//! a [`ManualClock`] that advances virtual time instantly.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Source of monotonic time and sleeping
+pub trait Clock: Send + Sync {
+    /// Time elapsed since the clock's origin
+    fn elapsed(&self) -> Duration;
+
+    /// Block (or pretend to block) for the given duration
+    fn sleep(&self, duration: Duration);
+
+    /// Sleep for the given number of milliseconds
+    fn sleep_ms(&self, ms: u64) {
+        self.sleep(Duration::from_millis(ms));
+    }
+}
+
+/// Real clock backed by `Instant` and `thread::sleep`
+#[derive(Debug, Clone)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    /// Create a clock whose origin is now
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Virtual clock where sleeping advances time immediately
+///
+/// Time only moves when `sleep` or `advance` is called, which makes loops
+/// driven by it deterministic and instant.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    nanos: AtomicU64,
+}
+
+impl ManualClock {
+    /// Create a clock at time zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a shared clock at time zero
+    pub fn shared() -> Arc<Self> {
+        Arc::new(Self::new())
+    }
+
+    /// Move time forward without sleeping
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+/// Default clock used when none is injected
+pub(crate) fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_starts_at_zero() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_manual_clock_sleep_advances_time() {
+        let clock = ManualClock::new();
+        clock.sleep_ms(2000);
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(clock.elapsed(), Duration::from_millis(2050));
+    }
+
+    #[test]
+    fn test_system_clock_is_monotonic() {
+        let clock = SystemClock::new();
+        let first = clock.elapsed();
+        clock.sleep_ms(1);
+        assert!(clock.elapsed() > first);
+    }
+}
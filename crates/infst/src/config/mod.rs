@@ -2,7 +2,7 @@
 //!
 //! This module contains types for configuration and support files:
 //! - Version detection utilities
-//! - Polling, retry, and database configuration constants
+//! - Polling, retry, database, and offset health configuration constants
 
 mod version;
 
@@ -48,6 +48,20 @@ pub mod database {
     pub const EXTRA_DELAY: Duration = Duration::from_secs(1);
 }
 
+/// Runtime offset health check configuration.
+pub mod health {
+    use std::time::Duration;
+
+    /// Interval between offset re-validations while on song select.
+    pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Longest wait between checks after repeated failed re-searches.
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+    /// Number of stale-offset incidents kept; older ones are dropped.
+    pub const MAX_INCIDENTS: usize = 32;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,5 +1,5 @@
 use crate::error::Result;
-use crate::process::ReadMemory;
+use crate::process::{ChunkedMemoryIterator, ReadMemory, ScanReport};
 
 /// Version prefix for INFINITAS
 const VERSION_PREFIX: &str = "P2D:J:B:A:";
@@ -23,15 +23,28 @@ const EXPECTED_VERSION_OFFSET: usize = 4_000_000;
 /// 1. First, search in the expected region (around 4-5MB from base)
 /// 2. If not found, fall back to full 80MB scan
 ///
+/// Unreadable memory is skipped (see [`find_game_version_with_report`]).
+///
 /// Note: The first two occurrences are old 2016 builds, so we return the last found.
 pub fn find_game_version<R: ReadMemory>(reader: &R, base_address: u64) -> Result<Option<String>> {
+    let (version, report) = find_game_version_with_report(reader, base_address)?;
+    report.log_skipped("Version scan");
+    Ok(version)
+}
+
+/// Find the game version string, also returning what the final scan skipped
+pub fn find_game_version_with_report<R: ReadMemory>(
+    reader: &R,
+    base_address: u64,
+) -> Result<(Option<String>, ScanReport)> {
     // Phase 1: Quick search in expected region (4-8MB from base)
     // This covers most cases without a full 80MB scan
     let quick_search_start = base_address + EXPECTED_VERSION_OFFSET as u64;
     let quick_search_size = 4 * CHUNK_SIZE; // 4MB
 
-    if let Some(version) = search_version_in_range(reader, quick_search_start, quick_search_size) {
-        return Ok(Some(version));
+    let (version, report) = search_version_in_range(reader, quick_search_start, quick_search_size);
+    if version.is_some() {
+        return Ok((version, report));
     }
 
     // Phase 2: Full scan if quick search failed
@@ -47,22 +60,25 @@ fn search_version_in_range<R: ReadMemory>(
     reader: &R,
     start_addr: u64,
     max_size: usize,
-) -> Option<String> {
+) -> (Option<String>, ScanReport) {
     let end_addr = start_addr + max_size as u64;
-    let mut current_addr = start_addr;
+    let mut chunks = ChunkedMemoryIterator::new(reader, start_addr, end_addr, CHUNK_SIZE);
     let mut last_found: Option<String> = None;
     let mut overlap_buffer = String::new();
+    let mut expected_address = start_addr;
 
-    while current_addr < end_addr {
-        let remaining = (end_addr - current_addr) as usize;
-        let chunk_size = std::cmp::min(CHUNK_SIZE, remaining);
-
-        let chunk = match reader.read_bytes(current_addr, chunk_size) {
-            Ok(buf) => buf,
-            Err(_) => break,
+    for chunk in chunks.by_ref() {
+        let Ok(chunk) = chunk else {
+            continue;
         };
 
-        let text = decode_shift_jis(&chunk);
+        // A version string cannot span a skipped gap
+        if chunk.address != expected_address {
+            overlap_buffer.clear();
+        }
+        expected_address = chunk.end();
+
+        let text = decode_shift_jis(&chunk.data);
         let search_text = format!("{}{}", overlap_buffer, text);
 
         for i in 0..search_text.len().saturating_sub(VERSION_LENGTH) {
@@ -81,11 +97,9 @@ fn search_version_in_range<R: ReadMemory>(
         } else {
             overlap_buffer = text;
         }
-
-        current_addr += chunk_size as u64;
     }
 
-    last_found
+    (last_found, chunks.into_report())
 }
 
 /// Check if the game version matches the offsets version
@@ -133,6 +147,21 @@ fn decode_shift_jis(bytes: &[u8]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    #[test]
+    fn test_find_game_version_skips_unmapped_gap() {
+        let reader = MockMemoryBuilder::new()
+            .with_size(0x500000)
+            .unmapped(0x100000, 0x100000)
+            .write_utf8(0x300000, "P2D:J:B:A:2026012800")
+            .build();
+
+        let (version, report) = find_game_version_with_report(&reader, 0x1000).unwrap();
+        assert_eq!(version.as_deref(), Some("P2D:J:B:A:2026012800"));
+        assert_eq!(report.skipped[0], 0x101000..0x201000);
+        assert_eq!(report.scanned_bytes, 0x400000);
+    }
 
     #[test]
     fn test_is_valid_version() {
@@ -2,7 +2,7 @@
 
 use serde::Serialize;
 
-use crate::chart::SongInfo;
+use crate::chart::{SongInfo, song_layout_or_current};
 use crate::offset::OffsetsCollection;
 use crate::process::ReadMemory;
 
@@ -109,26 +109,23 @@ fn dump_song_entries<R: ReadMemory>(
     }
 
     let mut entries = Vec::new();
+    let layout = song_layout_or_current(reader, song_list_addr);
+    let entry_size = layout.size as u64;
     let metadata_base = song_list_addr + SongInfo::METADATA_TABLE_OFFSET as u64;
 
     for i in 0..count {
-        let entry_addr = song_list_addr + i as u64 * SongInfo::MEMORY_SIZE as u64;
-        let metadata_addr = metadata_base + i as u64 * SongInfo::MEMORY_SIZE as u64;
+        let entry_addr = song_list_addr + i as u64 * entry_size;
+        let metadata_addr = metadata_base + i as u64 * entry_size;
 
-        let (song_id, folder, title, levels) = match reader
-            .read_bytes(entry_addr, SongInfo::MEMORY_SIZE)
-        {
+        let (song_id, folder, title, levels) = match reader.read_bytes(entry_addr, layout.size) {
             Ok(bytes) => {
                 // Parse title (first 64 bytes, Shift-JIS)
                 let title = decode_shift_jis(&bytes[0..64]);
 
-                // Parse song_id and folder from main entry
-                let song_id = i32::from_le_bytes([bytes[624], bytes[625], bytes[626], bytes[627]]);
-                let folder = bytes[280] as i32;
-
-                // Parse levels
-                let mut levels = [0u8; 10];
-                levels.copy_from_slice(&bytes[288..298]);
+                // Parse song_id, folder and levels from main entry
+                let song_id = layout.song_id.decode(&bytes).unwrap_or(0);
+                let folder = layout.folder.decode(&bytes).map_or(0, i32::from);
+                let levels = layout.levels.decode(&bytes).unwrap_or_default();
 
                 (song_id, folder, title, levels)
             }
@@ -180,22 +177,22 @@ fn collect_detected_songs<R: ReadMemory>(reader: &R, song_list_addr: u64) -> Vec
     }
 
     let mut songs = Vec::new();
+    let layout = song_layout_or_current(reader, song_list_addr);
+    let entry_size = layout.size as u64;
     let metadata_base = song_list_addr + SongInfo::METADATA_TABLE_OFFSET as u64;
 
     // Scan up to 5000 entries
     for i in 0..5000u64 {
-        let entry_addr = song_list_addr + i * SongInfo::MEMORY_SIZE as u64;
-        let metadata_addr = metadata_base + i * SongInfo::MEMORY_SIZE as u64;
+        let entry_addr = song_list_addr + i * entry_size;
+        let metadata_addr = metadata_base + i * entry_size;
 
         // Try to read from main entry first
         if let Ok(bytes) = reader.read_bytes(entry_addr, 64) {
             let title = decode_shift_jis(&bytes);
             if !title.is_empty() {
                 // Read song_id from main entry
-                if let Ok(id_bytes) = reader.read_bytes(entry_addr + 624, 4) {
-                    let song_id =
-                        i32::from_le_bytes([id_bytes[0], id_bytes[1], id_bytes[2], id_bytes[3]]);
-                    let folder = reader.read_i32(entry_addr + 280).unwrap_or(0);
+                if let Ok(song_id) = layout.song_id.read(reader, entry_addr) {
+                    let folder = layout.folder.read(reader, entry_addr).map_or(0, i32::from);
 
                     if song_id > 0 {
                         songs.push(DetectedSong {
@@ -4,11 +4,14 @@
 //! - Checking game and offset status (`StatusInfo`)
 //! - Dumping memory structures (`DumpInfo`)
 //! - Scanning for song data (`ScanResult`)
+//! - Classifying song entry fields (`SongFieldReport`)
 
 mod dump;
 mod scan;
+mod song_fields;
 mod status;
 
 pub use dump::{DumpInfo, MemoryDump};
 pub use scan::{ScanResult, ScannedSong};
+pub use song_fields::{FieldClass, FieldStat, SongFieldReport};
 pub use status::{OffsetStatus, OffsetValidation, StatusInfo};
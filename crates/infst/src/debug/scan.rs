@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use serde::Serialize;
 
-use crate::chart::SongInfo;
+use crate::chart::{SongInfo, song_layout_or_current};
 use crate::process::ReadMemory;
 
 /// Information about a scanned song
@@ -108,13 +108,14 @@ fn scan_text_table<R: ReadMemory>(
         return songs;
     }
 
+    let layout = song_layout_or_current(reader, song_list_addr);
     let mut consecutive_failures = 0;
     const MAX_FAILURES: u32 = 10;
 
     for i in 0..5000u64 {
-        let entry_addr = song_list_addr + i * SongInfo::MEMORY_SIZE as u64;
+        let entry_addr = song_list_addr + i * layout.size as u64;
 
-        match SongInfo::read_from_memory(reader, entry_addr) {
+        match SongInfo::read_from_memory_with_layout(reader, entry_addr, layout) {
             Ok(Some(song)) if !song.title.is_empty() && song.id > 0 => {
                 songs.push(ScannedSong {
                     song_id: song.id,
@@ -152,18 +153,19 @@ fn scan_metadata_table<R: ReadMemory>(
     }
 
     // Entry structure:
-    // - text_entry[i] = song_list_addr + i * ENTRY_SIZE
-    // - meta_entry[i] = song_list_addr + METADATA_TABLE_OFFSET + i * ENTRY_SIZE
-    const ENTRY_SIZE: u64 = SongInfo::MEMORY_SIZE as u64; // 0x3F0 = 1008 bytes
+    // - text_entry[i] = song_list_addr + i * entry_size
+    // - meta_entry[i] = song_list_addr + METADATA_TABLE_OFFSET + i * entry_size
+    // entry_size is the stride of the detected song layout (0x4B0 or 0x3F0)
+    let entry_size = song_layout_or_current(reader, song_list_addr).size as u64;
     const METADATA_OFFSET: u64 = SongInfo::METADATA_TABLE_OFFSET as u64; // 0x7E0 = 2016 bytes
 
-    let max_entries = (scan_range as u64 / ENTRY_SIZE).min(5000);
+    let max_entries = (scan_range as u64 / entry_size).min(5000);
 
     // Note: With lazy loading, songs may be scattered across the entry table.
     // We scan all entries without early termination to find all loaded songs.
     // Approach: first check if title exists, then read metadata.
     for i in 0..max_entries {
-        let text_addr = song_list_addr + i * ENTRY_SIZE;
+        let text_addr = song_list_addr + i * entry_size;
         let meta_addr = text_addr + METADATA_OFFSET;
 
         // First, check if title exists at this entry
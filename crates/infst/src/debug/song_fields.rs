@@ -0,0 +1,460 @@
+//! Statistical classification of song entry fields
+//!
+//! Reads every loaded song entry and classifies each word (and the bytes of
+//! words that are not simple) across the population. Fields already known
+//! from the layout are labelled so the remaining ones stand out.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::chart::{SongInfo, song_layout_or_current};
+use crate::process::ReadMemory;
+use crate::process::layout::song::SongLayout;
+
+/// Maximum number of distinct values for a field to count as enum-like
+const MAX_ENUM_VALUES: usize = 16;
+
+/// Minimum absolute correlation for a field to count as correlated
+const MIN_CORRELATION: f64 = 0.9;
+
+/// Minimum share of increasing neighbours for a monotonic ID
+const MIN_MONOTONIC_RATIO: f64 = 0.9;
+
+/// Minimum share of non-zero values inside text runs for a text field
+const MIN_TEXT_RATIO: f64 = 0.9;
+
+/// Difficulty names in level/notes array order
+const DIFFICULTIES: [&str; 10] = [
+    "SPB", "SPN", "SPH", "SPA", "SPL", "DPB", "DPN", "DPH", "DPA", "DPL",
+];
+
+/// Classification of one field across all entries
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "class", rename_all = "snake_case")]
+pub enum FieldClass {
+    /// Same value in every entry
+    Constant { value: u32 },
+    /// Part of a Shift-JIS string
+    Text,
+    /// Unique values increasing with the entry index
+    MonotonicId,
+    /// Tracks a level or note count (Pearson correlation `r`)
+    Correlated { with: String, r: f64 },
+    /// Few distinct values
+    Enum { values: Vec<u32> },
+    /// Anything else
+    Varying { distinct: usize },
+}
+
+impl FieldClass {
+    fn describe(&self) -> String {
+        match self {
+            FieldClass::Constant { value } => format!("constant 0x{:X}", value),
+            FieldClass::Text => "text".to_string(),
+            FieldClass::MonotonicId => "monotonic id".to_string(),
+            FieldClass::Correlated { with, r } => format!("correlated with {} (r={:.2})", with, r),
+            FieldClass::Enum { values } => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                format!("enum {{{}}}", values.join(", "))
+            }
+            FieldClass::Varying { distinct } => format!("varying ({} distinct)", distinct),
+        }
+    }
+
+    /// Whether consecutive fields of this class can be shown as one range
+    fn mergeable(&self) -> bool {
+        matches!(self, FieldClass::Constant { .. } | FieldClass::Text)
+    }
+}
+
+/// Classification of one byte or word offset
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldStat {
+    pub offset: usize,
+    /// 1 for a byte, 4 for a little-endian word
+    pub width: usize,
+    #[serde(flatten)]
+    pub class: FieldClass,
+    /// Layout field covering this offset, if known
+    pub known: Option<&'static str>,
+}
+
+/// Field classification report for a song entry layout
+#[derive(Debug, Clone, Serialize)]
+pub struct SongFieldReport {
+    pub layout: &'static str,
+    pub entry_size: usize,
+    pub entries: usize,
+    pub fields: Vec<FieldStat>,
+}
+
+impl SongFieldReport {
+    /// Classify the fields of all loaded entries of the song list
+    ///
+    /// The entry layout is detected first; entries without a title or with a
+    /// song ID outside the valid range are skipped.
+    pub fn scan<R: ReadMemory>(reader: &R, song_list_addr: u64, max_entries: usize) -> Self {
+        let layout = song_layout_or_current(reader, song_list_addr);
+        let entries: Vec<Vec<u8>> = (0..max_entries as u64)
+            .filter_map(|i| {
+                reader
+                    .read_bytes(song_list_addr + i * layout.size as u64, layout.size)
+                    .ok()
+            })
+            .filter(|entry| {
+                matches!(
+                    SongInfo::parse_entry(entry, layout),
+                    Ok(Some(song)) if !song.title.is_empty() && SongInfo::ID_RANGE.contains(&song.id)
+                )
+            })
+            .collect();
+
+        Self::analyze(&entries, layout)
+    }
+
+    /// Classify the fields of entries laid out as `layout`, in list order
+    pub fn analyze(entries: &[Vec<u8>], layout: &'static SongLayout) -> Self {
+        let entries: Vec<&[u8]> = entries
+            .iter()
+            .filter(|entry| entry.len() >= layout.size)
+            .map(|entry| &entry[..layout.size])
+            .collect();
+
+        let features = song_features(&entries, layout);
+        let text_bytes = text_byte_offsets(&entries, layout.size);
+        let known = |offset: usize| {
+            layout
+                .layout
+                .fields
+                .iter()
+                .find(|field| (field.offset..field.end()).contains(&offset))
+                .map(|field| field.name)
+        };
+
+        let mut fields = Vec::new();
+        if !entries.is_empty() {
+            for offset in (0..layout.size).step_by(4) {
+                let words: Vec<u32> = entries
+                    .iter()
+                    .map(|entry| u32::from_le_bytes(entry[offset..offset + 4].try_into().unwrap()))
+                    .collect();
+                let bytes: Vec<Vec<u32>> = (offset..offset + 4)
+                    .map(|o| entries.iter().map(|entry| entry[o] as u32).collect())
+                    .collect();
+
+                let text = (offset..offset + 4).any(|o| text_bytes[o])
+                    && (offset..offset + 4)
+                        .zip(&bytes)
+                        .all(|(o, values)| text_bytes[o] || values.iter().all(|&v| v == 0));
+                let class = if text {
+                    FieldClass::Text
+                } else {
+                    classify(&words, &features)
+                };
+
+                let simple = matches!(
+                    class,
+                    FieldClass::Constant { .. } | FieldClass::Text | FieldClass::MonotonicId
+                );
+                fields.push(FieldStat {
+                    offset,
+                    width: 4,
+                    class,
+                    known: known(offset),
+                });
+
+                if !simple {
+                    for (o, values) in (offset..offset + 4).zip(&bytes) {
+                        fields.push(FieldStat {
+                            offset: o,
+                            width: 1,
+                            class: classify(values, &features),
+                            known: known(o),
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            layout: layout.name,
+            entry_size: layout.size,
+            entries: entries.len(),
+            fields,
+        }
+    }
+
+    /// Fields not covered by the layout
+    pub fn unknown_fields(&self) -> impl Iterator<Item = &FieldStat> {
+        self.fields.iter().filter(|field| field.known.is_none())
+    }
+
+    /// Human-readable report, merging runs of constant or text words
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Song field analysis (layout {}, {} bytes per entry, {} entries)\n\n",
+            self.layout, self.entry_size, self.entries
+        );
+        out.push_str(&format!(
+            "{:<15} {:<6} {:<44} {}\n",
+            "Offset", "Width", "Class", "Known field"
+        ));
+
+        let mut i = 0;
+        while i < self.fields.len() {
+            let field = &self.fields[i];
+            let mut end = field.offset + field.width;
+            let mut j = i + 1;
+            if field.width == 4 && field.class.mergeable() {
+                while let Some(next) = self.fields.get(j)
+                    && next.width == 4
+                    && next.offset == end
+                    && next.class == field.class
+                    && next.known == field.known
+                {
+                    end += 4;
+                    j += 1;
+                }
+            }
+
+            let offset = if end - field.offset > field.width {
+                format!("0x{:03X}-0x{:03X}", field.offset, end - 1)
+            } else {
+                format!("0x{:03X}", field.offset)
+            };
+            let width = if field.width == 1 { "u8" } else { "u32" };
+            out.push_str(&format!(
+                "{:<15} {:<6} {:<44} {}\n",
+                offset,
+                width,
+                field.class.describe(),
+                field.known.unwrap_or("-")
+            ));
+            i = j;
+        }
+        out
+    }
+}
+
+/// Level and note count features of every entry, named for the report
+fn song_features(entries: &[&[u8]], layout: &SongLayout) -> Vec<(String, Vec<f64>)> {
+    let levels: Vec<[u8; 10]> = entries
+        .iter()
+        .map(|entry| layout.levels.decode(entry).unwrap_or_default())
+        .collect();
+    let notes: Vec<[u32; 10]> = entries
+        .iter()
+        .map(|entry| layout.notes.decode(entry).unwrap_or_default())
+        .collect();
+
+    let mut features = Vec::new();
+    for (d, name) in DIFFICULTIES.iter().enumerate() {
+        features.push((
+            format!("level {}", name),
+            levels.iter().map(|l| l[d] as f64).collect(),
+        ));
+        features.push((
+            format!("notes {}", name),
+            notes.iter().map(|n| n[d] as f64).collect(),
+        ));
+    }
+    features
+}
+
+/// Classify the values of one field, in entry order
+fn classify(values: &[u32], features: &[(String, Vec<f64>)]) -> FieldClass {
+    let distinct: BTreeSet<u32> = values.iter().copied().collect();
+    if distinct.len() == 1 {
+        return FieldClass::Constant { value: values[0] };
+    }
+
+    if values.len() >= 3 && distinct.len() == values.len() {
+        let increasing = values.windows(2).filter(|w| w[1] > w[0]).count();
+        if increasing as f64 / (values.len() - 1) as f64 >= MIN_MONOTONIC_RATIO {
+            return FieldClass::MonotonicId;
+        }
+    }
+
+    let values_f: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+    let best = features
+        .iter()
+        .filter_map(|(name, feature)| correlation(&values_f, feature).map(|r| (name, r)))
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()));
+    if let Some((name, r)) = best
+        && r.abs() >= MIN_CORRELATION
+    {
+        return FieldClass::Correlated {
+            with: name.clone(),
+            r,
+        };
+    }
+
+    if distinct.len() <= MAX_ENUM_VALUES {
+        return FieldClass::Enum {
+            values: distinct.into_iter().collect(),
+        };
+    }
+    FieldClass::Varying {
+        distinct: distinct.len(),
+    }
+}
+
+/// Pearson correlation, `None` if either side has no variance
+fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+/// Byte offsets that are part of a Shift-JIS string in (almost) every entry
+/// where they are non-zero
+fn text_byte_offsets(entries: &[&[u8]], size: usize) -> Vec<bool> {
+    let mut in_text = vec![0usize; size];
+    let mut non_zero = vec![0usize; size];
+    for entry in entries {
+        let mask = text_mask(entry);
+        for (o, &byte) in entry.iter().enumerate().take(size) {
+            if byte != 0 {
+                non_zero[o] += 1;
+                if mask[o] {
+                    in_text[o] += 1;
+                }
+            }
+        }
+    }
+
+    (0..size)
+        .map(|o| non_zero[o] > 0 && in_text[o] as f64 / non_zero[o] as f64 >= MIN_TEXT_RATIO)
+        .collect()
+}
+
+/// Mark the bytes of NUL-terminated Shift-JIS runs of at least two bytes
+fn text_mask(entry: &[u8]) -> Vec<bool> {
+    let mut mask = vec![false; entry.len()];
+    let mut i = 0;
+    while i < entry.len() {
+        let start = i;
+        while i < entry.len() {
+            let b = entry[i];
+            let double = matches!(b, 0x81..=0x9F | 0xE0..=0xFC)
+                && entry
+                    .get(i + 1)
+                    .is_some_and(|&t| matches!(t, 0x40..=0x7E | 0x80..=0xFC));
+            if double {
+                i += 2;
+            } else if matches!(b, 0x20..=0x7E | 0xA1..=0xDF) {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+
+        let terminated = entry.get(i) == Some(&0);
+        if terminated && i - start >= 2 {
+            mask[start..i].fill(true);
+        }
+        i = if i == start { i + 1 } else { i };
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::layout::song;
+
+    fn entries() -> Vec<Vec<u8>> {
+        let layout = &song::CURRENT;
+        (0..20u32)
+            .map(|i| {
+                let mut entry = vec![0u8; layout.size];
+                let title = format!("Song number {}", i);
+                entry[..title.len()].copy_from_slice(title.as_bytes());
+                // Unknown fields: a constant, an enum, a level copy and an index
+                entry[0x100..0x104].copy_from_slice(&7u32.to_le_bytes());
+                entry[0x108] = (i % 3) as u8;
+                entry[0x10C] = (i % 12) as u8 + 1;
+                entry[0x110..0x114].copy_from_slice(&(100 + i * 2).to_le_bytes());
+
+                let levels = layout.levels.offset;
+                entry[levels + 2] = (i % 12) as u8 + 1;
+                entry[levels + 3] = (i % 5) as u8 + 8;
+                let notes = layout.notes.offset + 2 * 4;
+                entry[notes..notes + 4].copy_from_slice(&(300 + (i % 12) * 90).to_le_bytes());
+                let id = layout.song_id.offset;
+                entry[id..id + 4].copy_from_slice(&(1000 + i).to_le_bytes());
+                entry
+            })
+            .collect()
+    }
+
+    fn field(report: &SongFieldReport, offset: usize, width: usize) -> &FieldStat {
+        report
+            .fields
+            .iter()
+            .find(|f| f.offset == offset && f.width == width)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_classifies_fields() {
+        let report = SongFieldReport::analyze(&entries(), &song::CURRENT);
+        assert_eq!(report.entries, 20);
+
+        assert_eq!(field(&report, 0x000, 4).class, FieldClass::Text);
+        assert_eq!(field(&report, 0x000, 4).known, Some("title"));
+        assert_eq!(
+            field(&report, 0x100, 4).class,
+            FieldClass::Constant { value: 7 }
+        );
+        assert_eq!(
+            field(&report, 0x108, 1).class,
+            FieldClass::Enum {
+                values: vec![0, 1, 2]
+            }
+        );
+        assert_eq!(field(&report, 0x110, 4).class, FieldClass::MonotonicId);
+        assert_eq!(field(&report, 816, 4).class, FieldClass::MonotonicId);
+
+        match &field(&report, 0x10C, 1).class {
+            FieldClass::Correlated { with, r } => {
+                assert!(with == "level SPH" || with == "notes SPH");
+                assert!(*r > 0.99);
+            }
+            other => panic!("unexpected class {:?}", other),
+        }
+        assert!(report.unknown_fields().any(|f| f.offset == 0x10C));
+    }
+
+    #[test]
+    fn test_text_report_merges_runs() {
+        let report = SongFieldReport::analyze(&entries(), &song::CURRENT);
+        let text = report.to_text();
+        assert!(text.contains("20 entries"));
+        assert!(text.contains("text"));
+        assert!(text.contains("constant 0x0"));
+        assert!(text.lines().count() < report.fields.len());
+    }
+
+    #[test]
+    fn test_text_mask() {
+        let mask = text_mask(b"AB\0\x01C\0\x82\xA0x\0");
+        assert_eq!(
+            mask,
+            [
+                true, true, false, false, false, false, true, true, true, false
+            ]
+        );
+    }
+}
@@ -2,9 +2,10 @@
 
 use serde::Serialize;
 
-use crate::chart::SongInfo;
-use crate::offset::{OffsetSearcher, OffsetsCollection};
-use crate::process::ReadMemory;
+use crate::chart::{SongInfo, song_layout_or_current};
+use crate::offset::{OffsetRankings, OffsetSearcher, OffsetsCollection};
+use crate::play::RawPlayData;
+use crate::process::{FromMemory, ReadMemory};
 
 /// Validation result for an individual offset
 #[derive(Debug, Clone, Serialize)]
@@ -46,6 +47,9 @@ pub struct StatusInfo {
     pub current_song: Option<CurrentSongInfo>,
     /// Overall validation status
     pub all_valid: bool,
+    /// Ranked candidates per offset, when the offsets were searched for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rankings: Option<OffsetRankings>,
 }
 
 /// Information about the currently selected song
@@ -105,8 +109,15 @@ impl StatusInfo {
             song_count,
             current_song,
             all_valid,
+            rankings: None,
         }
     }
+
+    /// Attach the candidate rankings from the search that found the offsets
+    pub fn with_rankings(mut self, rankings: OffsetRankings) -> Self {
+        self.rankings = Some(rankings);
+        self
+    }
 }
 
 fn validate_song_list<R: ReadMemory>(reader: &R, addr: u64) -> OffsetValidation {
@@ -272,11 +283,15 @@ fn validate_play_data<R: ReadMemory>(reader: &R, addr: u64) -> OffsetValidation
         };
     }
 
-    use crate::process::layout::play;
-
-    let song_id = reader.read_i32(addr + play::SONG_ID).unwrap_or(-1);
-    let difficulty = reader.read_i32(addr + play::DIFFICULTY).unwrap_or(-1);
-    let lamp = reader.read_i32(addr + play::LAMP).unwrap_or(-1);
+    let RawPlayData {
+        song_id,
+        difficulty,
+        lamp,
+    } = RawPlayData::read(reader, addr).unwrap_or(RawPlayData {
+        song_id: -1,
+        difficulty: -1,
+        lamp: -1,
+    });
 
     // Accept initial state (all zeros)
     if song_id == 0 && difficulty == 0 && lamp == 0 {
@@ -448,9 +463,10 @@ fn count_songs_at_address<R: ReadMemory>(reader: &R, addr: u64) -> usize {
     const MAX_SONGS: usize = 5000;
     const MAX_FAILURES: u32 = 10;
 
+    let layout = song_layout_or_current(reader, addr);
     let mut current_addr = addr;
     while count < MAX_SONGS && consecutive_failures < MAX_FAILURES {
-        match SongInfo::read_from_memory(reader, current_addr) {
+        match SongInfo::read_from_memory_with_layout(reader, current_addr, layout) {
             Ok(Some(song)) if !song.title.is_empty() => {
                 count += 1;
                 consecutive_failures = 0;
@@ -459,7 +475,7 @@ fn count_songs_at_address<R: ReadMemory>(reader: &R, addr: u64) -> usize {
                 consecutive_failures += 1;
             }
         }
-        current_addr += SongInfo::MEMORY_SIZE as u64;
+        current_addr += layout.size as u64;
     }
 
     count
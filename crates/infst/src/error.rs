@@ -40,6 +40,12 @@ pub enum Error {
 
     #[error("Encoding error: {0}")]
     EncodingError(String),
+
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("Invalid BPM: {0}")]
+    InvalidBpm(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
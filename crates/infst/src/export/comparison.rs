@@ -68,7 +68,7 @@ mod tests {
     use super::*;
     use std::sync::Arc;
 
-    use crate::chart::{ChartInfo, Difficulty};
+    use crate::chart::{Bpm, ChartInfo, Difficulty};
     use crate::play::{PlayType, Settings};
     use crate::score::Judge;
 
@@ -80,7 +80,8 @@ mod tests {
                 title_english: Arc::from(""),
                 artist: Arc::from(""),
                 genre: Arc::from(""),
-                bpm: Arc::from("150"),
+                bpm: Bpm::fixed(150),
+                folder: 12,
                 difficulty: Difficulty::SpA,
                 level: 12,
                 total_notes: 1000, // max EX = 2000
@@ -17,19 +17,26 @@ use super::comparison::compare_with_personal_best;
 pub fn format_play_data_console(play_data: &PlayData, personal_best: Option<&ScoreData>) -> String {
     let mut output = String::new();
 
-    // Build title line: "冥 [SPA Lv.12]"
+    // Build title line: "冥 [SPA Lv.12] tricoro"
     let difficulty_label = format_colored_difficulty(&play_data.chart.difficulty);
+    let version = play_data
+        .chart
+        .origin_version()
+        .map(|v| format!(" {}", v.name))
+        .unwrap_or_default();
     let title_content = format!(
-        "  {} [{} Lv.{}]",
+        "  {} [{} Lv.{}]{}",
         play_data.chart.title.bold(),
         difficulty_label,
-        play_data.chart.level
+        play_data.chart.level,
+        version.dimmed()
     );
 
     // Calculate display width (approximate, accounting for ANSI codes)
     let content_width = play_data.chart.title.len()
         + play_data.chart.difficulty.short_name().len()
         + play_data.chart.level.to_string().len()
+        + version.len()
         + 12; // " [" + " Lv." + "]" + padding
     let border_width = content_width.max(50);
 
@@ -186,7 +193,7 @@ mod tests {
     use super::*;
     use std::sync::Arc;
 
-    use crate::chart::ChartInfo;
+    use crate::chart::{Bpm, ChartInfo};
     use crate::play::{PlayType, Settings};
     use crate::score::Judge;
 
@@ -199,7 +206,8 @@ mod tests {
                 title_english: Arc::from(""),
                 artist: Arc::from(""),
                 genre: Arc::from(""),
-                bpm: Arc::from("150"),
+                bpm: Bpm::fixed(150),
+                folder: 12,
                 difficulty: Difficulty::SpA,
                 level: 12,
                 total_notes: 1000,
@@ -232,5 +240,8 @@ mod tests {
         assert!(summary.contains("AAA"));
         assert!(summary.contains("1900"));
         assert!(!summary.contains("INVALID"));
+
+        let console = format_play_data_console(&play_data, None);
+        assert!(console.contains("HAPPY SKY"));
     }
 }
@@ -35,6 +35,9 @@ pub fn format_json_entry(play_data: &PlayData) -> JsonValue {
         "title": play_data.chart.title,
         "difficulty": play_data.chart.difficulty.short_name(),
         "level": play_data.chart.level,
+        "bpm": play_data.chart.bpm,
+        "soflan": play_data.chart.bpm.is_soflan(),
+        "origin_version": play_data.chart.origin_version().map(|v| v.name),
         "ex_score": play_data.ex_score,
         "grade": play_data.grade.short_name(),
         "lamp": play_data.lamp.expand_name(),
@@ -59,5 +59,6 @@ pub use comparison::{PersonalBestComparison, compare_with_personal_best};
 // Re-export tracker functions and types
 pub use tracker::{
     ChartDataJson, ExportDataJson, SongDataJson, export_song_list, export_tracker_json,
-    export_tracker_tsv, format_tracker_tsv_header, generate_tracker_json, generate_tracker_tsv,
+    export_tracker_tsv, filter_songs_by_bpm, filter_songs_by_origin, format_tracker_tsv_header,
+    generate_tracker_json, generate_tracker_tsv,
 };
@@ -6,7 +6,9 @@ use std::path::Path;
 
 use serde::Serialize;
 
-use crate::chart::{Difficulty, SongInfo, UnlockData, get_unlock_state_for_difficulty};
+use crate::chart::{
+    Bpm, BpmRange, Difficulty, OriginVersion, SongInfo, UnlockData, get_unlock_state_for_difficulty,
+};
 use crate::error::Result;
 use crate::play::{PlayData, UnlockType, calculate_dj_points};
 use crate::score::{Grade, Lamp, ScoreMap};
@@ -30,6 +32,12 @@ pub struct SongDataJson {
     pub song_id: u32,
     pub title: String,
     pub artist: String,
+    pub bpm: Bpm,
+    /// BPM changes during the song
+    pub soflan: bool,
+    pub folder: i32,
+    /// Version name, `None` for unknown folder values
+    pub origin_version: Option<&'static str>,
     pub charts: Vec<ChartDataJson>,
 }
 
@@ -231,6 +239,34 @@ fn generate_tracker_entry(
     Some(columns.join("\t"))
 }
 
+/// Songs whose BPM lies (at least partly) within `range`
+///
+/// Apply before generating an export to limit it to a BPM range.
+pub fn filter_songs_by_bpm(
+    song_db: &HashMap<u32, SongInfo>,
+    range: &BpmRange,
+) -> HashMap<u32, SongInfo> {
+    song_db
+        .iter()
+        .filter(|(_, song)| range.matches(&song.bpm))
+        .map(|(&song_id, song)| (song_id, song.clone()))
+        .collect()
+}
+
+/// Songs that first appeared in `version`
+///
+/// Apply before generating an export to limit it to one version.
+pub fn filter_songs_by_origin(
+    song_db: &HashMap<u32, SongInfo>,
+    version: OriginVersion,
+) -> HashMap<u32, SongInfo> {
+    song_db
+        .iter()
+        .filter(|(_, song)| song.origin_version() == Some(version))
+        .map(|(&song_id, song)| (song_id, song.clone()))
+        .collect()
+}
+
 /// Export song database to TSV for debugging
 ///
 /// Format: id, title, title2 (English), artist, genre
@@ -350,6 +386,10 @@ fn generate_song_json(
         song_id,
         title: song.title.to_string(),
         artist: song.artist.to_string(),
+        bpm: song.bpm,
+        soflan: song.bpm.is_soflan(),
+        folder: song.folder,
+        origin_version: song.origin_version().map(|v| v.name),
         charts,
     })
 }
@@ -387,7 +427,7 @@ mod tests {
             title_english: Arc::from(""),
             artist: Arc::from("Test Artist"),
             genre: Arc::from("Test Genre"),
-            bpm: Arc::from("150"),
+            bpm: Bpm::fixed(150),
             folder: 1,
             levels: [0, 5, 8, 10, 12, 0, 5, 8, 10, 12],
             total_notes: [0, 500, 800, 1000, 1200, 0, 500, 800, 1000, 1200],
@@ -443,6 +483,51 @@ mod tests {
         // Verify JSON structure contains expected data
         assert!(json.contains("\"song_id\": 1000"));
         assert!(json.contains("\"title\": \"Test Song\""));
+        assert!(json.contains("\"bpm\": \"150\""));
+        assert!(json.contains("\"soflan\": false"));
+        assert!(json.contains("\"origin_version\": null"));
+    }
+
+    #[test]
+    fn test_filter_songs_by_origin() {
+        let mut happy_sky = create_test_song(12004, "HAPPY SKY song");
+        happy_sky.folder = 12;
+        let mut unknown = create_test_song(99001, "Unknown folder");
+        unknown.folder = 99;
+        let song_db: HashMap<u32, SongInfo> = [
+            (1000, create_test_song(1000, "1st style song")),
+            (12004, happy_sky),
+            (99001, unknown),
+        ]
+        .into_iter()
+        .collect();
+
+        let version = OriginVersion::find("HAPPY SKY").unwrap();
+        let filtered = filter_songs_by_origin(&song_db, version);
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered.contains_key(&12004));
+    }
+
+    #[test]
+    fn test_filter_songs_by_bpm() {
+        let mut soflan = create_test_song(1001, "Soflan");
+        soflan.bpm = Bpm::new(100, 200);
+        let mut slow = create_test_song(1002, "Slow");
+        slow.bpm = Bpm::fixed(90);
+        let song_db: HashMap<u32, SongInfo> = [
+            (1000, create_test_song(1000, "Fixed")),
+            (1001, soflan),
+            (1002, slow),
+        ]
+        .into_iter()
+        .collect();
+
+        let filtered = filter_songs_by_bpm(&song_db, &BpmRange::new(Some(140), Some(160)).unwrap());
+        let mut ids: Vec<u32> = filtered.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, vec![1000, 1001]);
+
+        assert_eq!(filter_songs_by_bpm(&song_db, &BpmRange::default()).len(), 3);
     }
 
     #[test]
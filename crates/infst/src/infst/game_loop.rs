@@ -4,24 +4,27 @@
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::time::Duration;
 
 use chrono::Utc;
 use tracing::{debug, error, info, warn};
 
 use crate::chart::{
-    ChartInfo, Difficulty, fetch_song_by_id, fetch_song_database_from_memory_scan,
+    Bpm, ChartInfo, Difficulty, fetch_song_by_id, fetch_song_database_from_memory_scan,
     get_unlock_states,
 };
+use crate::clock::Clock;
 use crate::config::{check_version_match, find_game_version, polling, retry};
 use crate::error::Result;
 use crate::export::format_play_data_console;
-use crate::play::{AssistType, GameState, PlayData, PlayType, RawSettings, Settings};
-use crate::process::layout::{judge, play, settings, timing};
-use crate::process::{MemoryReader, ProcessHandle, ReadMemory};
-use crate::score::{Grade, Judge, Lamp, PlayerJudge, RawJudgeData, ScoreMap};
+use crate::play::{AssistType, GameState, PlayData, PlayType, RawPlayData, RawSettings, Settings};
+use crate::process::layout::{current_song, judge, settings, timing};
+use crate::process::{
+    CachingReader, FromMemory, MemoryReader, ProcessHandle, ProcessInfo, ReadMemory,
+};
+use crate::score::{Grade, Judge, Lamp, RawJudgeData, ScoreMap};
 
 use super::Infst;
+use super::health::OffsetResearch;
 
 /// Read a value from memory with a default on error.
 ///
@@ -41,10 +44,15 @@ where
 
 /// Check if memory is accessible with retry logic.
 ///
-/// Uses exponential backoff and checks process liveness between retries.
-fn verify_memory_access(reader: &MemoryReader, process: &ProcessHandle) -> bool {
+/// Uses exponential backoff (waiting on `clock`) and checks process liveness
+/// between retries.
+pub fn verify_memory_access<R: ReadMemory, P: ProcessInfo>(
+    reader: &R,
+    process: &P,
+    clock: &dyn Clock,
+) -> bool {
     for attempt in 0..retry::MAX_READ_RETRIES {
-        match reader.read_bytes(process.base_address, 4) {
+        match reader.read_bytes(process.base_address(), 4) {
             Ok(_) => return true,
             Err(e) => {
                 // Re-check process status before retrying
@@ -62,7 +70,7 @@ fn verify_memory_access(reader: &MemoryReader, process: &ProcessHandle) -> bool
                         delay,
                         e
                     );
-                    thread::sleep(Duration::from_millis(delay));
+                    clock.sleep_ms(delay);
                 } else {
                     debug!(
                         "Memory read failed after {} retries: {}",
@@ -83,18 +91,50 @@ impl Infst {
     /// When `shutdown_requested` is `true`, the loop exits.
     pub fn run(&mut self, process: &ProcessHandle, shutdown_requested: &AtomicBool) -> Result<()> {
         let reader = MemoryReader::new(process);
+        self.run_with_reader(&reader, process, shutdown_requested)
+    }
+
+    /// Run the main tracking loop over an arbitrary memory source
+    ///
+    /// `reader` supplies game memory and `process` is polled for liveness, so the
+    /// loop can be driven by a replay (e.g. `TimelineMemoryReader`) instead of a
+    /// live process. All waits go through the clock set with [`Infst::set_clock`].
+    pub fn run_with_reader<R: ReadMemory + Sync, P: ProcessInfo>(
+        &mut self,
+        reader: &R,
+        process: &P,
+        shutdown_requested: &AtomicBool,
+    ) -> Result<()> {
+        // Offset re-searches run on worker threads that end with the loop
+        thread::scope(|scope| self.poll(scope, reader, process, shutdown_requested))
+    }
+
+    fn poll<'scope, R: ReadMemory + Sync, P: ProcessInfo>(
+        &mut self,
+        scope: &'scope thread::Scope<'scope, '_>,
+        source: &'scope R,
+        process: &P,
+        shutdown_requested: &AtomicBool,
+    ) -> Result<()> {
+        // Small reads within a tick are served from one page load
+        let reader = &CachingReader::new(source);
         let mut last_state = GameState::Unknown;
+        let mut research = None;
 
         debug!("Starting tracker loop...");
 
         // Start TSV session
-        self.session_manager = crate::session::SessionManager::new("sessions");
+        self.session_manager = crate::session::SessionManager::new(&self.config.session_dir);
         match self.session_manager.start_tsv_session() {
             Ok(path) => debug!("Started TSV session at {:?}", path),
             Err(e) => warn!("Failed to start TSV session: {}", e),
         }
+        self.reset_offset_health_timer();
 
         loop {
+            // Memory may have changed since the last tick
+            reader.invalidate();
+
             // Check for shutdown signal
             if shutdown_requested.load(Ordering::SeqCst) {
                 debug!("Shutdown signal received, exiting tracker loop");
@@ -108,33 +148,51 @@ impl Infst {
             }
 
             // Step 2: Verify memory access with retry mechanism (exponential backoff)
-            if !verify_memory_access(&reader, process) {
+            if !verify_memory_access(reader, process, self.clock.as_ref()) {
                 break;
             }
 
             // Detect game state
-            let current_state = self.detect_game_state(&reader)?;
+            let current_state = self.detect_game_state(reader)?;
 
             if current_state != last_state {
                 debug!("State changed: {:?} -> {:?}", last_state, current_state);
-                self.handle_state_change(&reader, last_state, current_state)?;
+                self.handle_state_change(reader, last_state, current_state)?;
                 last_state = current_state;
             }
 
-            thread::sleep(Duration::from_millis(timing::GAME_STATE_POLL_INTERVAL_MS));
+            if let Some(done) = research.take_if(|r: &mut OffsetResearch| r.is_finished()) {
+                self.finish_offset_research(done);
+            }
+
+            // Offsets can go stale mid-session; re-check while idle
+            if current_state == GameState::SongSelect && research.is_none() {
+                research = self.maintain_offset_health(scope, source);
+            }
+
+            self.clock.sleep_ms(timing::GAME_STATE_POLL_INTERVAL_MS);
         }
 
+        let stats = reader.stats();
+        debug!(
+            "Read cache: {} hits, {} misses, {} bypassed ({:.0}% hit rate)",
+            stats.hits,
+            stats.misses,
+            stats.bypassed,
+            stats.hit_rate() * 100.0
+        );
+
         Ok(())
     }
 
-    fn detect_game_state(&mut self, reader: &MemoryReader) -> Result<GameState> {
+    fn detect_game_state<R: ReadMemory>(&mut self, reader: &R) -> Result<GameState> {
         let state_marker_1 = read_with_default(
-            || reader.read_i32(self.offsets.judge_data + judge::STATE_MARKER_1),
+            || judge::STATE_MARKER_1.read(reader, self.offsets.judge_data),
             0,
             "state_marker_1",
         );
         let state_marker_2 = read_with_default(
-            || reader.read_i32(self.offsets.judge_data + judge::STATE_MARKER_2),
+            || judge::STATE_MARKER_2.read(reader, self.offsets.judge_data),
             0,
             "state_marker_2",
         );
@@ -155,9 +213,9 @@ impl Infst {
             .detect(state_marker_1, state_marker_2, song_select_marker))
     }
 
-    fn handle_state_change(
+    fn handle_state_change<R: ReadMemory>(
         &mut self,
-        reader: &MemoryReader,
+        reader: &CachingReader<R>,
         _old_state: GameState,
         new_state: GameState,
     ) -> Result<()> {
@@ -171,16 +229,17 @@ impl Infst {
     }
 
     /// Handle transition to result screen
-    fn handle_result_screen(&mut self, reader: &MemoryReader) {
+    fn handle_result_screen<R: ReadMemory>(&mut self, reader: &CachingReader<R>) {
         info!("Detected result screen, waiting for data...");
 
         // Initial delay to allow game data to settle (matching C# implementation)
         // This prevents race conditions where judge data updates before play data
-        thread::sleep(Duration::from_millis(polling::RESULT_INITIAL_DELAY_MS));
+        self.clock.sleep_ms(polling::RESULT_INITIAL_DELAY_MS);
 
         // Poll until play data becomes available (exponential backoff)
         for (attempt, &delay) in polling::POLL_DELAYS_MS.iter().enumerate() {
-            thread::sleep(Duration::from_millis(delay));
+            self.clock.sleep_ms(delay);
+            reader.invalidate();
 
             match self.fetch_play_data(reader) {
                 Ok(play_data) => {
@@ -438,7 +497,7 @@ impl Infst {
     }
 
     /// Handle transition to song select screen
-    fn handle_song_select(&mut self, reader: &MemoryReader) {
+    fn handle_song_select<R: ReadMemory>(&mut self, reader: &R) {
         // Re-scan for newly loaded songs (handles lazy loading)
         self.rescan_song_database(reader);
 
@@ -460,7 +519,7 @@ impl Infst {
     ///
     /// Called when new songs are discovered to ensure score comparisons
     /// work for all known songs.
-    fn reload_score_map(&mut self, reader: &MemoryReader) {
+    fn reload_score_map<R: ReadMemory>(&mut self, reader: &R) {
         match ScoreMap::load_from_memory(reader, self.offsets.data_map, &self.game_data.song_db) {
             Ok(map) => {
                 info!("Reloaded score map: {} entries", map.len());
@@ -474,7 +533,7 @@ impl Infst {
     ///
     /// This handles lazy loading in newer INFINITAS versions where songs are
     /// only loaded into memory when scrolled to in the song select screen.
-    fn rescan_song_database(&mut self, reader: &MemoryReader) {
+    fn rescan_song_database<R: ReadMemory>(&mut self, reader: &R) {
         let scan_result =
             fetch_song_database_from_memory_scan(reader, self.offsets.song_list, 0x200000);
 
@@ -498,6 +557,7 @@ impl Infst {
                 new_songs,
                 self.game_data.song_db.len()
             );
+            self.persist_song_cache();
         }
     }
 
@@ -506,7 +566,7 @@ impl Infst {
     /// Captures current chart selection when entering Playing state.
     /// This is used for cross-validation on ResultScreen to ensure
     /// we're reading the correct play data.
-    fn handle_playing(&mut self, reader: &MemoryReader) {
+    fn handle_playing<R: ReadMemory>(&mut self, reader: &R) {
         match self.fetch_current_chart(reader) {
             Ok((song_id, difficulty)) => {
                 debug!(
@@ -523,7 +583,7 @@ impl Infst {
     }
 
     /// Poll for unlock state changes
-    fn poll_unlock_changes(&mut self, reader: &MemoryReader) {
+    fn poll_unlock_changes<R: ReadMemory>(&mut self, reader: &R) {
         if self.game_data.song_db.is_empty() {
             return;
         }
@@ -554,16 +614,18 @@ impl Infst {
     ///
     /// Used during Playing state to capture what chart is being played,
     /// enabling cross-validation when reading play data on ResultScreen.
-    fn fetch_current_chart(&self, reader: &MemoryReader) -> Result<(u32, Difficulty)> {
-        let song_id = reader.read_i32(self.offsets.current_song)? as u32;
-        let diff = reader.read_i32(self.offsets.current_song + 4)?;
+    pub fn fetch_current_chart<R: ReadMemory>(&self, reader: &R) -> Result<(u32, Difficulty)> {
+        let bytes = current_song::LAYOUT.read_bytes(reader, self.offsets.current_song)?;
+        let song_id = current_song::SONG_ID.decode(&bytes)? as u32;
+        let diff = current_song::DIFFICULTY.decode(&bytes)?;
 
         let difficulty = Difficulty::from_u8(diff as u8).unwrap_or(Difficulty::SpN);
 
         Ok((song_id, difficulty))
     }
 
-    fn fetch_play_data(&mut self, reader: &MemoryReader) -> Result<PlayData> {
+    /// Read the play result (judge, settings, chart and lamp) from memory
+    pub fn fetch_play_data<R: ReadMemory>(&mut self, reader: &R) -> Result<PlayData> {
         // Read data in same order as C# implementation:
         // 1. Judge data first (updates earliest on result screen)
         // 2. Settings
@@ -574,12 +636,11 @@ impl Infst {
         let settings = self.fetch_settings(reader, judge.play_type)?;
 
         // Read basic play data (after judge/settings to match C# timing)
-        let song_id = reader.read_i32(self.offsets.play_data + play::SONG_ID)? as u32;
-        let difficulty_val = reader.read_i32(self.offsets.play_data + play::DIFFICULTY)?;
-        let lamp_val = reader.read_i32(self.offsets.play_data + play::LAMP)?;
+        let raw = RawPlayData::read(reader, self.offsets.play_data)?;
+        let song_id = raw.song_id as u32;
 
-        let difficulty = Difficulty::from_u8(difficulty_val as u8).unwrap_or(Difficulty::SpN);
-        let lamp = Lamp::from_u8(lamp_val as u8).unwrap_or(Lamp::NoPlay);
+        let difficulty = Difficulty::from_u8(raw.difficulty as u8).unwrap_or(Difficulty::SpN);
+        let lamp = Lamp::from_u8(raw.lamp as u8).unwrap_or(Lamp::NoPlay);
 
         // Calculate EX score
         let ex_score = judge.ex_score();
@@ -608,9 +669,9 @@ impl Infst {
     }
 
     /// Create chart info from song database, dynamically loading from memory if not found
-    fn create_chart_info_dynamic(
+    fn create_chart_info_dynamic<R: ReadMemory>(
         &mut self,
-        reader: &MemoryReader,
+        reader: &R,
         song_id: u32,
         difficulty: Difficulty,
     ) -> ChartInfo {
@@ -625,6 +686,7 @@ impl Infst {
             let chart = ChartInfo::from_song_info(&song, difficulty, true);
             // Add to song database for future lookups
             self.game_data.song_db.insert(song_id, song);
+            self.persist_song_cache();
             return chart;
         }
 
@@ -636,7 +698,8 @@ impl Infst {
             title_english: format!("Song {:05}", song_id).into(),
             artist: "".into(),
             genre: "".into(),
-            bpm: "".into(),
+            bpm: Bpm::default(),
+            folder: 0,
             difficulty,
             level: 0,
             total_notes: 0,
@@ -644,80 +707,24 @@ impl Infst {
         }
     }
 
-    fn fetch_judge_data(&self, reader: &MemoryReader) -> Result<Judge> {
-        let base = self.offsets.judge_data;
-
-        let p1 = PlayerJudge {
-            pgreat: reader.read_u32(base + judge::P1_PGREAT)?,
-            great: reader.read_u32(base + judge::P1_GREAT)?,
-            good: reader.read_u32(base + judge::P1_GOOD)?,
-            bad: reader.read_u32(base + judge::P1_BAD)?,
-            poor: reader.read_u32(base + judge::P1_POOR)?,
-            combo_break: reader.read_u32(base + judge::P1_COMBO_BREAK)?,
-            fast: reader.read_u32(base + judge::P1_FAST)?,
-            slow: reader.read_u32(base + judge::P1_SLOW)?,
-            measure_end: reader.read_u32(base + judge::P1_MEASURE_END)?,
-        };
-
-        let p2 = PlayerJudge {
-            pgreat: reader.read_u32(base + judge::P2_PGREAT)?,
-            great: reader.read_u32(base + judge::P2_GREAT)?,
-            good: reader.read_u32(base + judge::P2_GOOD)?,
-            bad: reader.read_u32(base + judge::P2_BAD)?,
-            poor: reader.read_u32(base + judge::P2_POOR)?,
-            combo_break: reader.read_u32(base + judge::P2_COMBO_BREAK)?,
-            fast: reader.read_u32(base + judge::P2_FAST)?,
-            slow: reader.read_u32(base + judge::P2_SLOW)?,
-            measure_end: reader.read_u32(base + judge::P2_MEASURE_END)?,
-        };
-
-        Ok(Judge::from_raw_data(RawJudgeData { p1, p2 }))
+    /// Read judge counts for both players from memory
+    pub fn fetch_judge_data<R: ReadMemory>(&self, reader: &R) -> Result<Judge> {
+        let raw = RawJudgeData::read(reader, self.offsets.judge_data)?;
+        Ok(Judge::from_raw_data(raw))
     }
 
-    fn fetch_settings(&self, reader: &MemoryReader, play_type: PlayType) -> Result<Settings> {
-        let word: u64 = 4;
-        let base = self.offsets.play_settings;
-
-        let (style, assist, range, h_ran, style2) = match play_type {
-            PlayType::P1 | PlayType::Dp => {
-                let style = reader.read_i32(base)?;
-                let assist = reader.read_i32(base + word * 2)?;
-                let range = reader.read_i32(base + word * 4)?;
-                let h_ran = reader.read_i32(base + word * 9)?;
-                let style2 = if play_type == PlayType::Dp {
-                    reader.read_i32(base + word * 5)?
-                } else {
-                    0
-                };
-                (style, assist, range, h_ran, style2)
-            }
-            PlayType::P2 => {
-                let p2_offset = Settings::P2_OFFSET;
-                let style = reader.read_i32(base + p2_offset)?;
-                let assist = reader.read_i32(base + p2_offset + word * 2)?;
-                let range = reader.read_i32(base + p2_offset + word * 4)?;
-                let h_ran = reader.read_i32(base + p2_offset + word * 9)?;
-                (style, assist, range, h_ran, 0)
-            }
-        };
-
-        let flip = reader.read_i32(base + word * 3)?;
-        let battle = reader.read_i32(base + word * 8)?;
-
-        Ok(Settings::from_raw(RawSettings {
-            play_type,
-            style,
-            style2,
-            assist,
-            range,
-            flip,
-            battle,
-            h_ran,
-        }))
+    /// Read play options for the given play type from memory
+    pub fn fetch_settings<R: ReadMemory>(
+        &self,
+        reader: &R,
+        play_type: PlayType,
+    ) -> Result<Settings> {
+        let bytes = settings::LAYOUT.read_bytes(reader, self.offsets.play_settings)?;
+        Ok(Settings::from_raw(RawSettings::decode(&bytes, play_type)?))
     }
 
     /// Load current unlock state from memory
-    pub fn load_unlock_state(&mut self, reader: &MemoryReader) -> Result<()> {
+    pub fn load_unlock_state<R: ReadMemory>(&mut self, reader: &R) -> Result<()> {
         if self.game_data.song_db.is_empty() {
             warn!("Song database is empty, cannot load unlock state");
             return Ok(());
@@ -735,9 +742,9 @@ impl Infst {
     /// Check game version and compare with offsets version
     ///
     /// Returns (game_version, matches) where matches is true if versions match
-    pub fn check_game_version(
+    pub fn check_game_version<R: ReadMemory>(
         &self,
-        reader: &MemoryReader,
+        reader: &R,
         base_address: u64,
     ) -> Result<(Option<String>, bool)> {
         let game_version = find_game_version(reader, base_address)?;
@@ -0,0 +1,374 @@
+//! Offset health monitoring for INFST
+//!
+//! While the tracker idles on song select, the active offsets are
+//! re-validated every [`InfstConfig::offset_health_interval`]. When a check
+//! fails, only the failing offsets are searched again on a worker thread and
+//! the result is swapped in once it finishes; the session file, score map and
+//! song database are kept. Each failed re-search doubles the wait before the
+//! next check, up to [`MAX_BACKOFF`].
+//!
+//! [`InfstConfig::offset_health_interval`]: super::InfstConfig::offset_health_interval
+//! [`MAX_BACKOFF`]: crate::config::health::MAX_BACKOFF
+
+use std::thread::{Scope, ScopedJoinHandle};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, error, warn};
+
+use crate::config::health as health_config;
+use crate::error::{Error, Result};
+use crate::offset::{
+    OffsetHealth, OffsetSearcher, OffsetsCollection, RelationPriors, check_offset_health,
+};
+use crate::process::ReadMemory;
+
+use super::Infst;
+
+/// A failed offset health check and what the tracker did about it
+#[derive(Debug, Clone)]
+pub struct OffsetIncident {
+    /// When the check failed
+    pub detected_at: DateTime<Utc>,
+    /// Names of the offsets that failed validation
+    pub failed: Vec<&'static str>,
+    /// Offsets in use when the check failed
+    pub previous: OffsetsCollection,
+    /// Offsets swapped in, if the re-search succeeded
+    pub replacement: Option<OffsetsCollection>,
+    /// Why the re-search failed, if it did
+    pub error: Option<String>,
+}
+
+/// Offset re-search running on a worker thread
+pub(crate) struct OffsetResearch<'scope> {
+    incident: OffsetIncident,
+    handle: ScopedJoinHandle<'scope, Result<OffsetsCollection>>,
+}
+
+impl OffsetResearch<'_> {
+    /// Whether the worker has finished and the result can be applied
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Infst {
+    /// Stale-offset incidents detected during this run (the most recent
+    /// [`MAX_INCIDENTS`](crate::config::health::MAX_INCIDENTS))
+    pub fn offset_incidents(&self) -> &[OffsetIncident] {
+        &self.offset_incidents
+    }
+
+    /// Restart the health check interval from now
+    pub(crate) fn reset_offset_health_timer(&mut self) {
+        self.last_health_check = Some(self.clock.elapsed());
+    }
+
+    /// Start a re-search on a worker thread if a due health check fails
+    ///
+    /// The worker reads through `reader`; pass the result to
+    /// [`finish_offset_research`](Self::finish_offset_research) once it is
+    /// finished.
+    pub(crate) fn maintain_offset_health<'scope, R: ReadMemory + Sync>(
+        &mut self,
+        scope: &'scope Scope<'scope, '_>,
+        reader: &'scope R,
+    ) -> Option<OffsetResearch<'scope>> {
+        if !self.offset_health_check_due() {
+            return None;
+        }
+        let health = self.failed_offset_health(reader)?;
+        let incident = self.new_incident(&health);
+        let offsets = self.offsets.clone();
+        let handle = scope.spawn(move || research(reader, &offsets, &health));
+        Some(OffsetResearch { incident, handle })
+    }
+
+    /// Apply the result of a finished re-search
+    ///
+    /// Returns true if the offsets were replaced.
+    pub(crate) fn finish_offset_research(&mut self, research: OffsetResearch<'_>) -> bool {
+        let result = research
+            .handle
+            .join()
+            .unwrap_or_else(|_| Err(Error::offset_search_failed("Offset re-search panicked")));
+        self.apply_research(research.incident, result)
+    }
+
+    /// Whether the check interval (with backoff) has elapsed; restarts it if so
+    fn offset_health_check_due(&mut self) -> bool {
+        let Some(interval) = self.config.offset_health_interval else {
+            return false;
+        };
+        let interval = backoff(interval, self.research_failures);
+        let now = self.clock.elapsed();
+        if self
+            .last_health_check
+            .is_some_and(|last| now.saturating_sub(last) < interval)
+        {
+            return false;
+        }
+        self.last_health_check = Some(now);
+        true
+    }
+
+    /// Health of the active offsets, `None` if every offset passed
+    fn failed_offset_health<R: ReadMemory>(&mut self, reader: &R) -> Option<OffsetHealth> {
+        let health = check_offset_health(reader, &self.offsets);
+        if health.is_healthy() {
+            debug!("Offset health check passed");
+            self.research_failures = 0;
+            return None;
+        }
+
+        warn!(
+            "Offset health check failed for {}; re-searching",
+            health.failed.join(", ")
+        );
+        Some(health)
+    }
+
+    fn new_incident(&self, health: &OffsetHealth) -> OffsetIncident {
+        OffsetIncident {
+            detected_at: Utc::now(),
+            failed: health.failed.clone(),
+            previous: self.offsets.clone(),
+            replacement: None,
+            error: None,
+        }
+    }
+
+    fn apply_research(
+        &mut self,
+        mut incident: OffsetIncident,
+        result: Result<OffsetsCollection>,
+    ) -> bool {
+        let replaced = match result {
+            Ok(_) if self.offsets != incident.previous => {
+                debug!("Offsets changed during the re-search, discarding its result");
+                false
+            }
+            Ok(offsets) => {
+                for ((name, old), (_, new)) in
+                    incident.previous.named().into_iter().zip(offsets.named())
+                {
+                    if old != new {
+                        warn!("  {}: 0x{:X} -> 0x{:X}", name, old, new);
+                    }
+                }
+                incident.replacement = Some(offsets.clone());
+                self.update_offsets(offsets);
+                self.research_failures = 0;
+                true
+            }
+            Err(e) => {
+                self.research_failures = self.research_failures.saturating_add(1);
+                error!("Offset re-search failed, keeping current offsets: {}", e);
+                incident.error = Some(e.to_string());
+                false
+            }
+        };
+
+        if self.offset_incidents.len() >= health_config::MAX_INCIDENTS {
+            self.offset_incidents.remove(0);
+        }
+        self.offset_incidents.push(incident);
+        replaced
+    }
+}
+
+/// Search the failing offsets again, anchored on the ones that passed
+fn research<R: ReadMemory>(
+    reader: &R,
+    offsets: &OffsetsCollection,
+    health: &OffsetHealth,
+) -> Result<OffsetsCollection> {
+    OffsetSearcher::builder(reader)
+        .with_priors(RelationPriors::learned())
+        .build()
+        .research_offsets(offsets, health)
+}
+
+/// Check interval after `failures` consecutive failed re-searches
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    interval
+        .saturating_mul(1 << failures.min(16))
+        .min(health_config::MAX_BACKOFF.max(interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+    use crate::chart::SongInfo;
+    use crate::clock::ManualClock;
+    use crate::infst::InfstConfig;
+    use crate::process::layout::{judge, settings};
+    use crate::process::mock::{GAME_BASE, GAME_VERSION};
+    use crate::process::{MockMemoryBuilder, MockMemoryReader};
+
+    const SONG_LIST: u64 = 0xC00000;
+    // Relative layout used by the searcher
+    const JUDGE_DATA: u64 = SONG_LIST - 0x94E3C8;
+    const PLAY_SETTINGS: u64 = JUDGE_DATA - 0x2ACFA8;
+    const STALE_JUDGE_DATA: u64 = 0x300000;
+
+    fn game_image() -> MockMemoryReader {
+        let judge = JUDGE_DATA as usize;
+        let settings = PLAY_SETTINGS as usize;
+        let song = SONG_LIST as usize;
+        MockMemoryBuilder::game_image(0xC10000)
+            .write_song(song, 1001, "GAMBOL")
+            .write_song_select_judge(judge)
+            .write_i32(judge + 0x1E4, 1001) // currentSong
+            .write_i32(judge + 0x1E4 + 4, 3)
+            .write_i32(settings - settings::SONG_SELECT_MARKER as usize, 1)
+            .write_i32(settings + 0x2A0, 1001) // playData
+            .write_i32(settings + 0x2A0 + 4, 3)
+            .write_i32(settings + 0x2A0 + 24, 5)
+            // Whatever now lives at the stale judgeData address
+            .write_i32(
+                STALE_JUDGE_DATA as usize + judge::STATE_MARKER_1.offset,
+                5000,
+            )
+            .build()
+    }
+
+    fn offsets(judge_data: u64) -> OffsetsCollection {
+        OffsetsCollection {
+            version: GAME_VERSION.to_string(),
+            song_list: GAME_BASE + SONG_LIST,
+            judge_data: GAME_BASE + judge_data,
+            play_settings: GAME_BASE + PLAY_SETTINGS,
+            play_data: GAME_BASE + PLAY_SETTINGS + 0x2A0,
+            current_song: GAME_BASE + judge_data + 0x1E4,
+            ..Default::default()
+        }
+    }
+
+    /// Run a due health check and wait for its re-search, if one started
+    fn check(infst: &mut Infst, reader: &MockMemoryReader) -> Option<bool> {
+        thread::scope(|scope| {
+            let research = infst.maintain_offset_health(scope, reader)?;
+            Some(infst.finish_offset_research(research))
+        })
+    }
+
+    fn infst(dir: &Path, offsets: OffsetsCollection, clock: Arc<ManualClock>) -> Infst {
+        let config = InfstConfig::builder()
+            .session_dir(dir)
+            .offset_health_interval(Some(Duration::from_secs(30)))
+            .build();
+        let mut infst = Infst::with_config(offsets, config);
+        infst.set_clock(clock);
+        infst
+    }
+
+    #[test]
+    fn test_stale_offsets_are_researched_and_swapped() {
+        let reader = game_image();
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(STALE_JUDGE_DATA), ManualClock::shared());
+        let song_db = HashMap::from([(1001, SongInfo::default())]);
+        infst.set_song_db(song_db);
+
+        assert_eq!(check(&mut infst, &reader), Some(true));
+
+        assert_eq!(infst.offsets(), &offsets(JUDGE_DATA));
+        assert_eq!(infst.game_data.song_db.len(), 1);
+
+        let incident = &infst.offset_incidents()[0];
+        assert_eq!(incident.failed, ["judgeData"]);
+        assert_eq!(incident.previous, offsets(STALE_JUDGE_DATA));
+        assert_eq!(incident.replacement.as_ref(), Some(&offsets(JUDGE_DATA)));
+        assert!(incident.error.is_none());
+    }
+
+    #[test]
+    fn test_healthy_offsets_are_kept() {
+        let reader = game_image();
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(JUDGE_DATA), ManualClock::shared());
+
+        assert_eq!(check(&mut infst, &reader), None);
+        assert!(infst.offset_incidents().is_empty());
+    }
+
+    #[test]
+    fn test_checks_wait_for_interval() {
+        let reader = game_image();
+        let clock = ManualClock::shared();
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(STALE_JUDGE_DATA), clock.clone());
+        infst.reset_offset_health_timer();
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(check(&mut infst, &reader), None);
+        assert!(infst.offset_incidents().is_empty());
+
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(check(&mut infst, &reader), Some(true));
+        assert_eq!(infst.offset_incidents().len(), 1);
+    }
+
+    fn failed_research(infst: &mut Infst) {
+        let incident = infst.new_incident(&OffsetHealth {
+            failed: vec!["judgeData"],
+        });
+        infst.apply_research(incident, Err(Error::offset_search_failed("not found")));
+    }
+
+    #[test]
+    fn test_failed_research_backs_off() {
+        let clock = ManualClock::shared();
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(STALE_JUDGE_DATA), clock.clone());
+        infst.reset_offset_health_timer();
+
+        failed_research(&mut infst);
+        clock.advance(Duration::from_secs(30));
+        assert!(!infst.offset_health_check_due());
+        clock.advance(Duration::from_secs(30));
+        assert!(infst.offset_health_check_due());
+
+        failed_research(&mut infst);
+        clock.advance(Duration::from_secs(60));
+        assert!(!infst.offset_health_check_due());
+        clock.advance(Duration::from_secs(60));
+        assert!(infst.offset_health_check_due());
+
+        assert_eq!(
+            backoff(Duration::from_secs(30), 20),
+            health_config::MAX_BACKOFF
+        );
+    }
+
+    #[test]
+    fn test_incidents_are_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(STALE_JUDGE_DATA), ManualClock::shared());
+
+        for _ in 0..health_config::MAX_INCIDENTS + 5 {
+            failed_research(&mut infst);
+        }
+        assert_eq!(infst.offset_incidents().len(), health_config::MAX_INCIDENTS);
+    }
+
+    #[test]
+    fn test_result_is_discarded_if_offsets_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut infst = infst(dir.path(), offsets(STALE_JUDGE_DATA), ManualClock::shared());
+        let incident = infst.new_incident(&OffsetHealth {
+            failed: vec!["judgeData"],
+        });
+        infst.update_offsets(offsets(0x400000));
+
+        assert!(!infst.apply_research(incident, Ok(offsets(JUDGE_DATA))));
+        assert_eq!(infst.offsets(), &offsets(0x400000));
+    }
+}
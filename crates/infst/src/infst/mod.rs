@@ -31,13 +31,21 @@
 //! ```
 
 mod game_loop;
+mod health;
+
+pub use game_loop::verify_memory_access;
+pub use health::OffsetIncident;
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::chart::{Difficulty, SongInfo, UnlockData};
+use crate::chart::{Difficulty, SongCache, SongInfo, UnlockData};
+use crate::clock::{Clock, system_clock};
+use crate::config::health as health_config;
 use crate::error::Result;
 use crate::offset::OffsetsCollection;
 use crate::play::GameStateDetector;
@@ -73,6 +81,8 @@ pub struct InfstConfig {
     pub api_config: Option<ApiConfig>,
     /// Git integration configuration
     pub git_config: Option<GitConfig>,
+    /// Interval between offset health checks on song select (None disables them)
+    pub offset_health_interval: Option<Duration>,
 }
 
 impl Default for InfstConfig {
@@ -83,6 +93,7 @@ impl Default for InfstConfig {
             tracker_path: PathBuf::from("tracker.tsv"),
             api_config: None,
             git_config: None,
+            offset_health_interval: Some(health_config::CHECK_INTERVAL),
         }
     }
 }
@@ -102,6 +113,7 @@ pub struct InfstConfigBuilder {
     tracker_path: Option<PathBuf>,
     api_config: Option<ApiConfig>,
     git_config: Option<GitConfig>,
+    offset_health_interval: Option<Option<Duration>>,
 }
 
 impl InfstConfigBuilder {
@@ -135,6 +147,12 @@ impl InfstConfigBuilder {
         self
     }
 
+    /// Set the offset health check interval (None disables the checks)
+    pub fn offset_health_interval(mut self, interval: Option<Duration>) -> Self {
+        self.offset_health_interval = Some(interval);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> InfstConfig {
         let default = InfstConfig::default();
@@ -144,6 +162,9 @@ impl InfstConfigBuilder {
             tracker_path: self.tracker_path.unwrap_or(default.tracker_path),
             api_config: self.api_config,
             git_config: self.git_config,
+            offset_health_interval: self
+                .offset_health_interval
+                .unwrap_or(default.offset_health_interval),
         }
     }
 }
@@ -180,6 +201,16 @@ pub struct Infst {
     /// Currently playing chart (set during Playing state)
     /// Used for cross-validation when fetching play data on ResultScreen
     pub(crate) current_playing: Option<(u32, Difficulty)>,
+    /// Time source for polling delays
+    pub(crate) clock: Arc<dyn Clock>,
+    /// Clock time of the last offset health check
+    pub(crate) last_health_check: Option<Duration>,
+    /// Consecutive failed offset re-searches, for backing off the checks
+    pub(crate) research_failures: u32,
+    /// Stale-offset incidents detected during this run
+    pub(crate) offset_incidents: Vec<OffsetIncident>,
+    /// Persisted song database and its file, updated as songs are discovered
+    pub(crate) song_cache: Option<(SongCache, PathBuf)>,
 }
 
 impl Infst {
@@ -212,6 +243,11 @@ impl Infst {
             state_detector: GameStateDetector::new(),
             session_manager: SessionManager::new(&session_dir),
             current_playing: None,
+            clock: system_clock(),
+            last_health_check: None,
+            research_failures: 0,
+            offset_incidents: Vec::new(),
+            song_cache: None,
         }
     }
 
@@ -225,11 +261,37 @@ impl Infst {
         self.game_data.score_map = score_map;
     }
 
+    /// Set the clock used for polling delays
+    ///
+    /// Defaults to the system clock; inject a `ManualClock` to run the tracker
+    /// loop without real sleeps.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
     /// Set song database
     pub fn set_song_db(&mut self, song_db: HashMap<u32, SongInfo>) {
         self.game_data.song_db = song_db;
     }
 
+    /// Persist songs discovered during the session to a song cache file
+    pub fn set_song_cache<P: Into<PathBuf>>(&mut self, cache: SongCache, path: P) {
+        self.song_cache = Some((cache, path.into()));
+    }
+
+    /// Merge the song database into the song cache and save it if it changed
+    pub(crate) fn persist_song_cache(&mut self) {
+        let Some((cache, path)) = &mut self.song_cache else {
+            return;
+        };
+        if cache.merge(&self.game_data.song_db) == 0 {
+            return;
+        }
+        if let Err(e) = cache.save_to_path(path.as_path()) {
+            warn!("Failed to save song cache: {}", e);
+        }
+    }
+
     /// Get a reference to the offsets
     pub fn offsets(&self) -> &OffsetsCollection {
         &self.offsets
@@ -14,6 +14,7 @@
 //!   This feature is intended for CLI tools and development, not production use.
 
 pub mod chart;
+pub mod clock;
 pub mod config;
 #[cfg(feature = "debug-tools")]
 pub mod debug;
@@ -32,10 +33,14 @@ pub mod session;
 
 // Re-export from chart module
 pub use chart::{
-    Chart, ChartInfo, Difficulty, SongInfo, UnlockData, fetch_song_database,
-    fetch_song_database_bulk, get_unlock_state_for_difficulty, get_unlock_states,
+    Bpm, BpmRange, Chart, ChartInfo, Difficulty, OriginVersion, SongCache, SongInfo, UnlockData,
+    detect_song_layout, fetch_song_database, fetch_song_database_bulk,
+    get_unlock_state_for_difficulty, get_unlock_states, song_layout_or_current, unknown_folders,
 };
 
+// Re-export from clock module
+pub use clock::{Clock, ManualClock, SystemClock};
+
 // Re-export from config module
 pub use config::{check_version_match, extract_date_code, find_game_version};
 
@@ -44,16 +49,26 @@ pub use error::{Error, Result};
 
 // Re-export from process module
 pub use process::{
-    ByteBuffer, MemoryReader, ProcessHandle, ProcessInfo, ProcessProvider, ReadMemory,
+    ByteBuffer, MemoryReader, MemorySnapshot, ProcessHandle, ProcessInfo, ProcessProvider,
+    ReadMemory, SnapshotMemoryReader, SnapshotRecorder, TimelineMemoryReader, TimelineState,
     decode_shift_jis, decode_shift_jis_to_string,
 };
 
 // Re-export from offset module
 pub use offset::{
-    CodeSignature, InteractiveSearchResult, JudgeInput, OffsetCache, OffsetDump, OffsetSearcher,
-    OffsetSearcherBuilder, OffsetSignatureEntry, OffsetSignatureSet, OffsetsCollection,
-    SearchPrompter, SearchResult, builtin_signatures, load_offsets, load_signatures, save_offsets,
-    save_offsets_to_cache, save_signatures, try_load_cached_offsets,
+    CacheEntry, CachedValidation, CandidateRanking, CodeSignature, CoverageStatus,
+    InteractiveSearchResult, JudgeInput, OFFSETS_SCHEMA_VERSION, OffsetCache, OffsetChange,
+    OffsetCoverage, OffsetDatabase, OffsetDiff, OffsetDump, OffsetEntries, OffsetEntry,
+    OffsetHealth, OffsetMethod, OffsetRankings, OffsetSearcher, OffsetSearcherBuilder,
+    OffsetSignatureEntry, OffsetSignatureSet, OffsetSource, OffsetsCollection, OffsetsDocument,
+    OffsetsFormat, Relation, RelationChange, RelationHistory, RelationPriors, RelationStats,
+    ScoredCandidate, ScriptPlay, ScriptSettings, SearchPrompter, SearchReporter, SearchResult,
+    SearchScript, SearchWindow, SettingsInput, SignatureCoverage, SignatureCoverageReport,
+    SignatureGenerator, SignatureMatch, SignatureScanner, SignatureStatus, ValidationScore,
+    builtin_signatures, check_offset_health, load_offsets, load_offsets_document, load_signatures,
+    record_cached_validation, record_offset_relations, run_live_search_script, run_search_script,
+    save_offsets, save_offsets_document, save_offsets_to_cache, save_signatures,
+    try_load_cached_offsets, validate_offset,
 };
 
 // Re-export from play module
@@ -63,7 +78,9 @@ pub use play::{
 };
 
 // Re-export from infst module
-pub use infst::{ApiConfig, GameData, GitConfig, Infst, InfstConfig, InfstConfigBuilder};
+pub use infst::{
+    ApiConfig, GameData, GitConfig, Infst, InfstConfig, InfstConfigBuilder, OffsetIncident,
+};
 
 // Re-export from retry module
 pub use retry::{ExponentialBackoff, FixedDelay, NoRetry, RetryStrategy};
@@ -74,7 +91,8 @@ pub use score::{Grade, Judge, Lamp, ScoreData, ScoreMap};
 // Re-export from export module
 pub use export::{
     ExportFormat, JsonExporter, TsvExporter, TsvRowData, export_song_list, export_tracker_json,
-    export_tracker_tsv, format_tracker_tsv_header, generate_tracker_json, generate_tracker_tsv,
+    export_tracker_tsv, filter_songs_by_bpm, filter_songs_by_origin, format_tracker_tsv_header,
+    generate_tracker_json, generate_tracker_tsv,
 };
 
 // Re-export from session module
@@ -83,5 +101,6 @@ pub use session::SessionManager;
 // Debug utilities (requires debug-tools feature)
 #[cfg(feature = "debug-tools")]
 pub use debug::{
-    DumpInfo, MemoryDump, OffsetStatus, OffsetValidation, ScanResult, ScannedSong, StatusInfo,
+    DumpInfo, FieldClass, FieldStat, MemoryDump, OffsetStatus, OffsetValidation, ScanResult,
+    ScannedSong, SongFieldReport, StatusInfo,
 };
@@ -1,10 +1,16 @@
 //! Offset cache for faster startup
 //!
-//! Saves detected offsets to a file and reuses them on subsequent runs,
-//! skipping the expensive memory search when the game version matches.
+//! Saves detected offsets per game version and reuses them on subsequent runs,
+//! skipping the expensive memory search when the game version has been seen
+//! before. The cache lives in the user cache directory
+//! (`dirs::cache_dir()/infst/offsets.json`).
+//!
+//! Entries do not expire on a timer: an entry stays until validation against
+//! the running game fails. A new game build simply gets its own entry.
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
@@ -12,141 +18,298 @@ use tracing::{debug, info, warn};
 
 use super::OffsetsCollection;
 
-/// Cache file name
-const CACHE_FILE: &str = ".infst-cache.json";
+/// Cache file name inside the infst cache directory
+const CACHE_FILE: &str = "offsets.json";
 
-/// Maximum age for cache validity (24 hours)
-const MAX_CACHE_AGE_SECS: u64 = 24 * 60 * 60;
+/// How the offsets of a cache entry were obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetSource {
+    /// Signature-based offset search
+    Search,
+    /// Known offsets database
+    Database,
+    /// User-supplied offsets file
+    File,
+}
 
-/// Cached offset data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OffsetCache {
-    /// Game version string (e.g., "P2D:J:B:A:2026012800")
-    pub version: String,
+/// Results of the validation checks run against cached offsets
+///
+/// `None` means the check has not been run for the entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedValidation {
+    /// Every offset points to readable memory
+    pub memory_access: Option<bool>,
+    /// Offsets point to plausible game data (song count, judge markers, ...)
+    pub content: Option<bool>,
+}
+
+impl CachedValidation {
+    /// Result of a basic memory access check
+    pub fn memory_access(passed: bool) -> Self {
+        Self {
+            memory_access: Some(passed),
+            content: None,
+        }
+    }
+
+    /// Result of a content check
+    pub fn content(passed: bool) -> Self {
+        Self {
+            memory_access: None,
+            content: Some(passed),
+        }
+    }
+
+    /// Whether any check that was run failed
+    pub fn failed(&self) -> bool {
+        self.memory_access == Some(false) || self.content == Some(false)
+    }
+
+    /// Overwrite the checks that `other` ran
+    fn update(&mut self, other: Self) {
+        if other.memory_access.is_some() {
+            self.memory_access = other.memory_access;
+        }
+        if other.content.is_some() {
+            self.content = other.content;
+        }
+    }
+}
+
+/// Cached offsets for one game version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
     /// Detected offsets
     pub offsets: OffsetsCollection,
-    /// Cache creation timestamp (Unix seconds)
-    pub created_at: u64,
+    /// How the offsets were obtained
+    pub source: OffsetSource,
+    /// Latest validation results
+    pub validation: CachedValidation,
+    /// When the offsets were detected (Unix seconds)
+    pub detected_at: u64,
+    /// When the offsets last passed validation (Unix seconds)
+    pub last_verified: u64,
 }
 
-impl OffsetCache {
-    /// Create a new cache entry
-    pub fn new(version: String, offsets: OffsetsCollection) -> Self {
-        let created_at = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-
+impl CacheEntry {
+    /// Create an entry detected and verified now
+    pub fn new(
+        offsets: OffsetsCollection,
+        source: OffsetSource,
+        validation: CachedValidation,
+    ) -> Self {
+        let now = unix_now();
         Self {
-            version,
             offsets,
-            created_at,
+            source,
+            validation,
+            detected_at: now,
+            last_verified: now,
         }
     }
+}
 
-    /// Load cache from file
-    pub fn load() -> Option<Self> {
-        Self::load_from_path(CACHE_FILE)
+/// Cached offsets keyed by game version (e.g., "P2D:J:B:A:2026012800")
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffsetCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl OffsetCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default cache file path in the user cache directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("infst").join(CACHE_FILE))
+    }
+
+    /// Load the cache from the default path
+    ///
+    /// Returns an empty cache if the file is missing or unreadable.
+    pub fn load() -> Self {
+        match Self::default_path() {
+            Some(path) => Self::load_from_path(path),
+            None => {
+                debug!("No user cache directory available");
+                Self::new()
+            }
+        }
     }
 
-    /// Load cache from a specific path
-    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+    /// Load the cache from a specific path
+    ///
+    /// Returns an empty cache if the file is missing or unreadable.
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Self {
         let path = path.as_ref();
 
         let content = match fs::read_to_string(path) {
             Ok(c) => c,
             Err(e) => {
                 debug!("Cache file not found or unreadable: {}", e);
-                return None;
+                return Self::new();
             }
         };
 
         match serde_json::from_str::<OffsetCache>(&content) {
             Ok(cache) => {
-                debug!(
-                    "Loaded cache: version={}, created_at={}",
-                    cache.version, cache.created_at
-                );
-                Some(cache)
+                debug!("Loaded offset cache with {} entries", cache.len());
+                cache
             }
             Err(e) => {
                 warn!("Failed to parse cache file: {}", e);
-                None
+                Self::new()
             }
         }
     }
 
-    /// Save cache to file
+    /// Save the cache to the default path
     pub fn save(&self) -> Result<(), std::io::Error> {
-        self.save_to_path(CACHE_FILE)
+        let path = Self::default_path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no user cache directory available",
+            )
+        })?;
+        self.save_to_path(path)
     }
 
-    /// Save cache to a specific path
+    /// Save the cache to a specific path, creating the parent directory if needed
     pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
+        let path = path.as_ref();
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
-        fs::write(&path, content)?;
-        info!("Saved offset cache to {}", path.as_ref().display());
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        info!("Saved offset cache to {}", path.display());
         Ok(())
     }
 
-    /// Check if cache is valid for the given game version
-    pub fn is_valid_for(&self, game_version: &str) -> bool {
-        // Check version match
-        if self.version != game_version {
+    /// Usable entry for the given game version
+    ///
+    /// Entries whose offsets are incomplete or belong to another version are
+    /// ignored.
+    pub fn get(&self, game_version: &str) -> Option<&CacheEntry> {
+        let Some(entry) = self.entries.get(game_version) else {
+            debug!("No cached offsets for version {}", game_version);
+            return None;
+        };
+
+        if entry.offsets.version != game_version {
             debug!(
                 "Cache version mismatch: cached={}, current={}",
-                self.version, game_version
+                entry.offsets.version, game_version
             );
-            return false;
+            return None;
+        }
+
+        if !entry.offsets.is_valid() {
+            debug!("Cached offsets are invalid (some are zero)");
+            return None;
         }
 
-        // Check cache age
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
+        Some(entry)
+    }
+
+    /// Add or replace the entry for a game version
+    pub fn insert(&mut self, game_version: &str, entry: CacheEntry) {
+        self.entries.insert(game_version.to_string(), entry);
+    }
+
+    /// Remove the entry for a game version
+    pub fn remove(&mut self, game_version: &str) -> Option<CacheEntry> {
+        self.entries.remove(game_version)
+    }
 
-        let age = now.saturating_sub(self.created_at);
-        if age > MAX_CACHE_AGE_SECS {
-            debug!("Cache expired: age={} seconds", age);
+    /// Record validation results for a game version
+    ///
+    /// A failed check invalidates the entry; otherwise its results and
+    /// last-verified time are updated. Returns whether the entry is still
+    /// cached.
+    pub fn record_validation(&mut self, game_version: &str, validation: CachedValidation) -> bool {
+        if validation.failed() {
+            if self.entries.remove(game_version).is_some() {
+                info!("Invalidated cached offsets for {}", game_version);
+            }
             return false;
         }
 
-        // Check offsets are valid
-        if !self.offsets.is_valid() {
-            debug!("Cached offsets are invalid (some are zero)");
-            return false;
+        match self.entries.get_mut(game_version) {
+            Some(entry) => {
+                entry.validation.update(validation);
+                entry.last_verified = unix_now();
+                true
+            }
+            None => false,
         }
+    }
+
+    /// Cached game versions in order
+    pub fn versions(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-        true
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
 }
 
-/// Try to load cached offsets if valid for the given version
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Try to load cached offsets for the given version
 pub fn try_load_cached_offsets(game_version: &str) -> Option<OffsetsCollection> {
-    let cache = OffsetCache::load()?;
-
-    if cache.is_valid_for(game_version) {
-        info!(
-            "Using cached offsets (version: {}, age: {}s)",
-            cache.version,
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0)
-                .saturating_sub(cache.created_at)
-        );
-        Some(cache.offsets)
-    } else {
-        None
+    let cache = OffsetCache::load();
+    let entry = cache.get(game_version)?;
+
+    info!(
+        "Using cached offsets (version: {}, source: {:?}, last verified {}s ago)",
+        game_version,
+        entry.source,
+        unix_now().saturating_sub(entry.last_verified)
+    );
+    Some(entry.offsets.clone())
+}
+
+/// Save offsets to the cache entry for their game version
+pub fn save_offsets_to_cache(
+    game_version: &str,
+    offsets: &OffsetsCollection,
+    source: OffsetSource,
+    validation: CachedValidation,
+) {
+    let mut cache = OffsetCache::load();
+    cache.insert(
+        game_version,
+        CacheEntry::new(offsets.clone(), source, validation),
+    );
+    if let Err(e) = cache.save() {
+        warn!("Failed to save offset cache: {}", e);
     }
 }
 
-/// Save offsets to cache
-pub fn save_offsets_to_cache(version: &str, offsets: &OffsetsCollection) {
-    let cache = OffsetCache::new(version.to_string(), offsets.clone());
+/// Record validation results for cached offsets, invalidating them on failure
+pub fn record_cached_validation(game_version: &str, validation: CachedValidation) {
+    let mut cache = OffsetCache::load();
+    if cache.get(game_version).is_none() {
+        return;
+    }
+    cache.record_validation(game_version, validation);
     if let Err(e) = cache.save() {
         warn!("Failed to save offset cache: {}", e);
     }
@@ -155,15 +318,10 @@ pub fn save_offsets_to_cache(version: &str, offsets: &OffsetsCollection) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::NamedTempFile;
-
-    #[test]
-    fn test_cache_save_and_load() {
-        let temp_file = NamedTempFile::new().unwrap();
-        let path = temp_file.path().to_path_buf();
 
-        let offsets = OffsetsCollection {
-            version: "test".to_string(),
+    fn offsets(version: &str) -> OffsetsCollection {
+        OffsetsCollection {
+            version: version.to_string(),
             song_list: 0x1000,
             judge_data: 0x2000,
             play_settings: 0x3000,
@@ -171,38 +329,99 @@ mod tests {
             current_song: 0x5000,
             data_map: 0x6000,
             unlock_data: 0x7000,
-        };
+        }
+    }
+
+    fn entry(version: &str) -> CacheEntry {
+        CacheEntry::new(
+            offsets(version),
+            OffsetSource::Search,
+            CachedValidation::content(true),
+        )
+    }
+
+    #[test]
+    fn test_cache_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("infst").join(CACHE_FILE);
 
-        let cache = OffsetCache::new("P2D:J:B:A:2026012800".to_string(), offsets.clone());
+        let mut cache = OffsetCache::new();
+        cache.insert("P2D:J:B:A:2026012800", entry("P2D:J:B:A:2026012800"));
+        cache.insert("P2D:J:B:A:2025122400", entry("P2D:J:B:A:2025122400"));
         cache.save_to_path(&path).unwrap();
 
-        let loaded = OffsetCache::load_from_path(&path).unwrap();
-        assert_eq!(loaded.version, "P2D:J:B:A:2026012800");
-        assert_eq!(loaded.offsets.song_list, 0x1000);
+        let loaded = OffsetCache::load_from_path(&path);
+        assert_eq!(loaded, cache);
+        assert_eq!(
+            loaded.versions().collect::<Vec<_>>(),
+            ["P2D:J:B:A:2025122400", "P2D:J:B:A:2026012800"]
+        );
+        let loaded_entry = loaded.get("P2D:J:B:A:2026012800").unwrap();
+        assert_eq!(loaded_entry.offsets.song_list, 0x1000);
+        assert_eq!(loaded_entry.source, OffsetSource::Search);
+    }
+
+    #[test]
+    fn test_cache_load_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(OffsetCache::load_from_path(dir.path().join("missing.json")).is_empty());
     }
 
     #[test]
     fn test_cache_version_mismatch() {
-        let offsets = OffsetsCollection {
-            version: "test".to_string(),
-            song_list: 0x1000,
-            judge_data: 0x2000,
-            play_settings: 0x3000,
-            play_data: 0x4000,
-            current_song: 0x5000,
-            data_map: 0x6000,
-            unlock_data: 0x7000,
-        };
+        let mut cache = OffsetCache::new();
+        cache.insert("P2D:J:B:A:2026012800", entry("P2D:J:B:A:2026012800"));
+        cache.insert("P2D:J:B:A:2026020100", entry("P2D:J:B:A:2026012800"));
 
-        let cache = OffsetCache::new("P2D:J:B:A:2026012800".to_string(), offsets);
-        assert!(cache.is_valid_for("P2D:J:B:A:2026012800"));
-        assert!(!cache.is_valid_for("P2D:J:B:A:2025122400"));
+        assert!(cache.get("P2D:J:B:A:2026012800").is_some());
+        assert!(cache.get("P2D:J:B:A:2025122400").is_none());
+        assert!(cache.get("P2D:J:B:A:2026020100").is_none());
     }
 
     #[test]
     fn test_cache_invalid_offsets() {
-        let offsets = OffsetsCollection::default(); // All zeros
-        let cache = OffsetCache::new("P2D:J:B:A:2026012800".to_string(), offsets);
-        assert!(!cache.is_valid_for("P2D:J:B:A:2026012800"));
+        let mut cache = OffsetCache::new();
+        let mut invalid = entry("P2D:J:B:A:2026012800");
+        invalid.offsets.song_list = 0;
+        cache.insert("P2D:J:B:A:2026012800", invalid);
+        assert!(cache.get("P2D:J:B:A:2026012800").is_none());
+    }
+
+    #[test]
+    fn test_cache_old_entry_stays_valid() {
+        let mut cache = OffsetCache::new();
+        let mut old = entry("P2D:J:B:A:2026012800");
+        old.detected_at = 0;
+        old.last_verified = 0;
+        cache.insert("P2D:J:B:A:2026012800", old);
+        assert!(cache.get("P2D:J:B:A:2026012800").is_some());
+    }
+
+    #[test]
+    fn test_cache_record_validation() {
+        let mut cache = OffsetCache::new();
+        let mut stale = entry("P2D:J:B:A:2026012800");
+        stale.last_verified = 0;
+        cache.insert("P2D:J:B:A:2026012800", stale);
+
+        assert!(cache.record_validation(
+            "P2D:J:B:A:2026012800",
+            CachedValidation::memory_access(true)
+        ));
+        let updated = cache.get("P2D:J:B:A:2026012800").unwrap();
+        assert!(updated.last_verified > 0);
+        assert_eq!(
+            updated.validation,
+            CachedValidation {
+                memory_access: Some(true),
+                content: Some(true),
+            }
+        );
+
+        assert!(!cache.record_validation(
+            "P2D:J:B:A:2026012800",
+            CachedValidation::memory_access(false)
+        ));
+        assert!(cache.is_empty());
     }
 }
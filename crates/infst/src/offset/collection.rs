@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OffsetsCollection {
     pub version: String,
     pub song_list: u64,
@@ -25,6 +25,19 @@ impl OffsetsCollection {
             && self.current_song != 0
     }
 
+    /// Offsets with their offsets file names
+    pub fn named(&self) -> [(&'static str, u64); 7] {
+        [
+            ("songList", self.song_list),
+            ("dataMap", self.data_map),
+            ("judgeData", self.judge_data),
+            ("playData", self.play_data),
+            ("playSettings", self.play_settings),
+            ("unlockData", self.unlock_data),
+            ("currentSong", self.current_song),
+        ]
+    }
+
     /// Check if offsets required for state detection are valid
     pub fn has_state_detection_offsets(&self) -> bool {
         self.judge_data != 0 && self.play_settings != 0
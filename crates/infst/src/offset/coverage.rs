@@ -0,0 +1,390 @@
+//! Signature coverage of a build
+//!
+//! Evaluates every signature of an [`OffsetSignatureSet`] against a memory
+//! source: how often its pattern matches, which data addresses the matches
+//! resolve to, and whether those addresses pass the offset's validator. The
+//! per-offset summary shows which offsets signature detection can no longer
+//! be trusted for on this build.
+
+use std::fmt::Write;
+
+use serde::{Serialize, Serializer};
+
+use crate::error::Result;
+use crate::process::ReadMemory;
+
+use super::searcher::pattern::resolve_signature_matches;
+use super::{OffsetSignatureSet, OffsetsCollection, SignatureScanner, validate_offset};
+
+/// A data address a signature resolved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ResolvedTarget {
+    #[serde(serialize_with = "serialize_hex")]
+    pub address: u64,
+    /// Whether the address passes the offset's validator (None if the
+    /// offset has no validator)
+    pub valid: Option<bool>,
+}
+
+/// Evaluation of one signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureCoverage {
+    pub entry: String,
+    pub index: usize,
+    pub pattern: String,
+    /// Pattern matches in the code section
+    pub hits: usize,
+    /// Distinct addresses the matches resolve to after `deref`/`addend`
+    pub targets: Vec<ResolvedTarget>,
+}
+
+/// How well the signatures of an offset hold up on a build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageStatus {
+    /// The signatures agree on one valid address
+    Ok,
+    /// The signatures resolve to more than one valid address
+    Ambiguous,
+    /// Every resolved address fails validation
+    Invalid,
+    /// No signature resolved to an address
+    Unresolved,
+    /// The set has no signatures for the offset
+    NoSignatures,
+}
+
+impl CoverageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Ambiguous => "ambiguous",
+            Self::Invalid => "invalid",
+            Self::Unresolved => "unresolved",
+            Self::NoSignatures => "no signatures",
+        }
+    }
+}
+
+/// Coverage summary of one offset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OffsetCoverage {
+    pub name: String,
+    pub status: CoverageStatus,
+    /// Resolved addresses that passed (or have no) validation
+    #[serde(serialize_with = "serialize_hex_list")]
+    pub candidates: Vec<u64>,
+}
+
+impl OffsetCoverage {
+    /// Whether signature detection of this offset is unreliable
+    pub fn at_risk(&self) -> bool {
+        self.status != CoverageStatus::Ok
+    }
+}
+
+/// Coverage of a signature set on one build
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureCoverageReport {
+    /// Version the signature set is meant for
+    pub set_version: String,
+    pub signatures: Vec<SignatureCoverage>,
+    /// Every offset, followed by set entries that are not offsets
+    pub offsets: Vec<OffsetCoverage>,
+}
+
+impl SignatureCoverageReport {
+    /// Scan the code section once and evaluate every signature of the set
+    pub fn evaluate<R: ReadMemory>(reader: &R, signatures: &OffsetSignatureSet) -> Result<Self> {
+        let hits = SignatureScanner::new(signatures)?.scan(reader)?;
+        hits.report.log_skipped("Signature coverage scan");
+
+        let mut coverage = Vec::new();
+        for entry in &signatures.entries {
+            for (index, signature) in entry.signatures.iter().enumerate() {
+                let matches = hits.get(&entry.name, index).unwrap_or_default();
+                let pattern = signature.parsed_pattern()?;
+                let targets = resolve_signature_matches(reader, signature, &pattern, matches)
+                    .into_iter()
+                    .map(|address| ResolvedTarget {
+                        address,
+                        valid: validate_offset(reader, &entry.name, address),
+                    })
+                    .collect();
+                coverage.push(SignatureCoverage {
+                    entry: entry.name.clone(),
+                    index,
+                    pattern: signature.pattern.clone(),
+                    hits: matches.len(),
+                    targets,
+                });
+            }
+        }
+
+        let mut names: Vec<String> = OffsetsCollection::default()
+            .named()
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        for entry in &signatures.entries {
+            if !names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&entry.name))
+            {
+                names.push(entry.name.clone());
+            }
+        }
+        let offsets = names
+            .into_iter()
+            .map(|name| summarize(name, signatures, &coverage))
+            .collect();
+
+        Ok(Self {
+            set_version: signatures.version.clone(),
+            signatures: coverage,
+            offsets,
+        })
+    }
+
+    /// Offsets whose signature detection is unreliable on this build
+    pub fn at_risk(&self) -> impl Iterator<Item = &OffsetCoverage> {
+        self.offsets.iter().filter(|offset| offset.at_risk())
+    }
+
+    /// Render the report as plain text
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Signature set: {}", self.set_version);
+        for signature in &self.signatures {
+            let _ = writeln!(
+                out,
+                "\n{} #{}  {}",
+                signature.entry, signature.index, signature.pattern
+            );
+            let _ = writeln!(
+                out,
+                "  {} hit(s), {} target(s)",
+                signature.hits,
+                signature.targets.len()
+            );
+            for target in &signature.targets {
+                let validation = match target.valid {
+                    Some(true) => "valid",
+                    Some(false) => "INVALID",
+                    None => "not validated",
+                };
+                let _ = writeln!(out, "  -> 0x{:X} ({})", target.address, validation);
+            }
+        }
+
+        out.push_str("\nSummary:\n");
+        for offset in &self.offsets {
+            let candidates: Vec<String> = offset
+                .candidates
+                .iter()
+                .map(|address| format!("0x{:X}", address))
+                .collect();
+            let _ = writeln!(
+                out,
+                "  {:<14} {:<14} {}",
+                offset.name,
+                offset.status.as_str(),
+                candidates.join(", ")
+            );
+        }
+
+        let at_risk: Vec<&str> = self.at_risk().map(|o| o.name.as_str()).collect();
+        if at_risk.is_empty() {
+            out.push_str("\nAll offsets are covered by signatures on this build\n");
+        } else {
+            let _ = writeln!(out, "\nAt risk on this build: {}", at_risk.join(", "));
+        }
+        out
+    }
+}
+
+fn summarize(
+    name: String,
+    signatures: &OffsetSignatureSet,
+    coverage: &[SignatureCoverage],
+) -> OffsetCoverage {
+    if signatures
+        .entry(&name)
+        .is_none_or(|e| e.signatures.is_empty())
+    {
+        return OffsetCoverage {
+            name,
+            status: CoverageStatus::NoSignatures,
+            candidates: Vec::new(),
+        };
+    }
+
+    let targets: Vec<&ResolvedTarget> = coverage
+        .iter()
+        .filter(|signature| signature.entry.eq_ignore_ascii_case(&name))
+        .flat_map(|signature| &signature.targets)
+        .collect();
+    let mut candidates: Vec<u64> = targets
+        .iter()
+        .filter(|target| target.valid != Some(false))
+        .map(|target| target.address)
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let status = match candidates.len() {
+        _ if targets.is_empty() => CoverageStatus::Unresolved,
+        0 => CoverageStatus::Invalid,
+        1 => CoverageStatus::Ok,
+        _ => CoverageStatus::Ambiguous,
+    };
+    OffsetCoverage {
+        name,
+        status,
+        candidates,
+    }
+}
+
+fn serialize_hex<S: Serializer>(
+    value: &u64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{:X}", value))
+}
+
+fn serialize_hex_list<S: Serializer>(
+    values: &[u64],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_seq(values.iter().map(|value| format!("0x{:X}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::{CodeSignature, OffsetSignatureEntry};
+    use crate::process::layout::judge;
+    use crate::process::mock::GAME_BASE;
+    use crate::process::{MockMemoryBuilder, MockMemoryReader};
+
+    const JUDGE_DATA: usize = 0x1001000;
+    const STALE_JUDGE_DATA: usize = 0x1002000;
+    const CURRENT_SONG: usize = 0x1003000;
+    const EXTRA: usize = 0x1004000;
+
+    fn signature(pattern: &str) -> CodeSignature {
+        CodeSignature {
+            pattern: pattern.to_string(),
+            instr_offset: 0,
+            disp_offset: 3,
+            instr_len: 7,
+            deref: false,
+            addend: 0,
+        }
+    }
+
+    fn set() -> OffsetSignatureSet {
+        let entry = |name: &str, pattern: &str| OffsetSignatureEntry {
+            name: name.to_string(),
+            signatures: vec![signature(pattern)],
+        };
+        OffsetSignatureSet {
+            version: "*".to_string(),
+            entries: vec![
+                entry("judgeData", "48 8D 0D ?? ?? ?? ??"),
+                entry("playSettings", "89 2D ?? ?? ?? ?? EB"),
+                entry("currentSong", "48 8D 2D ?? ?? ?? ??"),
+                entry("extra", "4C 8D 3D ?? ?? ?? ??"),
+            ],
+        }
+    }
+
+    fn game_image() -> MockMemoryReader {
+        MockMemoryBuilder::game_image(0x1005000)
+            .write_song_select_judge(JUDGE_DATA)
+            .write_i32(STALE_JUDGE_DATA + judge::STATE_MARKER_1.offset, 5000)
+            .write_i32(CURRENT_SONG, 77) // song_id below the valid range
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], JUDGE_DATA)
+            .write_rip_relative(0x200, &[0x48, 0x8D, 0x0D], STALE_JUDGE_DATA)
+            .write_rip_relative(0x300, &[0x48, 0x8D, 0x2D], CURRENT_SONG)
+            .write_rip_relative(0x400, &[0x4C, 0x8D, 0x3D], EXTRA)
+            .build()
+    }
+
+    fn status(report: &SignatureCoverageReport, name: &str) -> CoverageStatus {
+        report
+            .offsets
+            .iter()
+            .find(|offset| offset.name == name)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn test_signatures_report_hits_and_validated_targets() {
+        let report = SignatureCoverageReport::evaluate(&game_image(), &set()).unwrap();
+
+        let judge = &report.signatures[0];
+        assert_eq!(judge.hits, 2);
+        assert_eq!(
+            judge.targets,
+            [
+                ResolvedTarget {
+                    address: GAME_BASE + JUDGE_DATA as u64,
+                    valid: Some(true)
+                },
+                ResolvedTarget {
+                    address: GAME_BASE + STALE_JUDGE_DATA as u64,
+                    valid: Some(false)
+                },
+            ]
+        );
+        assert_eq!(report.signatures[1].hits, 0);
+        assert_eq!(report.signatures[3].targets[0].valid, None);
+    }
+
+    #[test]
+    fn test_summary_flags_offsets_at_risk() {
+        let report = SignatureCoverageReport::evaluate(&game_image(), &set()).unwrap();
+
+        assert_eq!(status(&report, "judgeData"), CoverageStatus::Ok);
+        assert_eq!(status(&report, "playSettings"), CoverageStatus::Unresolved);
+        assert_eq!(status(&report, "currentSong"), CoverageStatus::Invalid);
+        assert_eq!(status(&report, "dataMap"), CoverageStatus::NoSignatures);
+        assert_eq!(status(&report, "extra"), CoverageStatus::Ok);
+        assert_eq!(report.offsets.len(), 8);
+
+        let at_risk: Vec<_> = report.at_risk().map(|o| o.name.as_str()).collect();
+        assert_eq!(
+            at_risk,
+            [
+                "songList",
+                "dataMap",
+                "playData",
+                "playSettings",
+                "unlockData",
+                "currentSong"
+            ]
+        );
+        assert!(report.to_text().contains(
+            "At risk on this build: songList, dataMap, playData, playSettings, unlockData, currentSong"
+        ));
+    }
+
+    #[test]
+    fn test_conflicting_valid_targets_are_ambiguous() {
+        let reader = MockMemoryBuilder::game_image(0x1005000)
+            .write_song_select_judge(JUDGE_DATA)
+            .write_song_select_judge(STALE_JUDGE_DATA)
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], JUDGE_DATA)
+            .write_rip_relative(0x200, &[0x48, 0x8D, 0x0D], STALE_JUDGE_DATA)
+            .build();
+
+        let report = SignatureCoverageReport::evaluate(&reader, &set()).unwrap();
+        assert_eq!(status(&report, "judgeData"), CoverageStatus::Ambiguous);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["offsets"][2]["status"], "ambiguous");
+        assert_eq!(json["offsets"][2]["candidates"][0], "0x141001000");
+    }
+}
@@ -0,0 +1,237 @@
+//! Versioned offsets database
+//!
+//! Maps game version strings (`P2D:J:B:A:YYYYMMDDNN`) to known offsets, so a
+//! build whose offsets were already found does not need a search. A database
+//! is bundled into the binary and can be extended or overridden by a user file.
+//!
+//! The file format is a sequence of offsets files: each entry starts with a
+//! version line followed by `key = value` lines. Lines starting with `#` or
+//! `;` are comments.
+//!
+//! ```text
+//! P2D:J:B:A:2026012800
+//! songList = 0x1431865a0
+//! judgeData = 0x1428380ec
+//! ...
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+use super::OffsetsCollection;
+use super::loader::{format_offsets, parse_offsets};
+use crate::error::{Error, Result};
+
+/// Database bundled into the binary
+const BUNDLED_DATABASE: &str = include_str!("../../data/offsets.txt");
+
+/// Known offsets keyed by game version
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OffsetDatabase {
+    entries: BTreeMap<String, OffsetsCollection>,
+}
+
+impl OffsetDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The database bundled into the binary
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_DATABASE).expect("bundled offsets database is valid")
+    }
+
+    /// The bundled database with the entries of a user file layered on top
+    ///
+    /// A missing user file is not an error; an unreadable or malformed one is
+    /// logged and ignored.
+    pub fn with_overrides<P: AsRef<Path>>(user_path: P) -> Self {
+        let mut db = Self::bundled();
+        let path = user_path.as_ref();
+        if !path.exists() {
+            return db;
+        }
+        match Self::load(path) {
+            Ok(user) => {
+                debug!(
+                    "Loaded {} offset entries from {}",
+                    user.len(),
+                    path.display()
+                );
+                db.merge(user);
+            }
+            Err(e) => warn!("Ignoring offsets database {}: {}", path.display(), e),
+        }
+        db
+    }
+
+    /// Parse a database file
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut db = Self::new();
+        for block in split_entries(content) {
+            db.insert(parse_offsets(&block)?)?;
+        }
+        Ok(db)
+    }
+
+    /// Load a database file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Save the database, creating the parent directory if needed
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    /// Format the database in its file format
+    pub fn to_text(&self) -> String {
+        let entries: Vec<String> = self.entries.values().map(format_offsets).collect();
+        let mut text = entries.join("\n\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Offsets for an exact game version
+    pub fn get(&self, version: &str) -> Option<&OffsetsCollection> {
+        self.entries.get(version)
+    }
+
+    /// Add or replace the entry for `offsets.version`
+    ///
+    /// Only complete entries are accepted. Returns the replaced entry.
+    pub fn insert(&mut self, offsets: OffsetsCollection) -> Result<Option<OffsetsCollection>> {
+        if !offsets.is_valid() {
+            return Err(Error::InvalidOffset(format!(
+                "Offsets for '{}' are incomplete (version and all seven offsets are required)",
+                offsets.version
+            )));
+        }
+        Ok(self.entries.insert(offsets.version.clone(), offsets))
+    }
+
+    /// Remove the entry for a version
+    pub fn remove(&mut self, version: &str) -> Option<OffsetsCollection> {
+        self.entries.remove(version)
+    }
+
+    /// Add every entry of `other`, replacing entries with the same version
+    pub fn merge(&mut self, other: Self) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Entries in version order
+    pub fn entries(&self) -> impl Iterator<Item = &OffsetsCollection> {
+        self.entries.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Split a database file into per-entry offsets file blocks
+///
+/// A line that is neither a comment nor a `key = value` pair starts a new
+/// entry.
+fn split_entries(content: &str) -> Vec<String> {
+    let mut blocks: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if !trimmed.contains('=') {
+            blocks.push(String::new());
+        }
+        if let Some(block) = blocks.last_mut() {
+            block.push_str(trimmed);
+            block.push('\n');
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(version: &str, base: u64) -> OffsetsCollection {
+        OffsetsCollection {
+            version: version.to_string(),
+            song_list: base + 0x1000,
+            data_map: base + 0x2000,
+            judge_data: base + 0x3000,
+            play_data: base + 0x4000,
+            play_settings: base + 0x5000,
+            unlock_data: base + 0x6000,
+            current_song: base + 0x7000,
+        }
+    }
+
+    #[test]
+    fn test_bundled_database_parses() {
+        let db = OffsetDatabase::bundled();
+        assert!(db.entries().all(OffsetsCollection::is_valid));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut db = OffsetDatabase::new();
+        db.insert(offsets("P2D:J:B:A:2026012800", 0x1420000000))
+            .unwrap();
+        db.insert(offsets("P2D:J:B:A:2025122400", 0x1410000000))
+            .unwrap();
+
+        let parsed = OffsetDatabase::parse(&format!("# header\n\n{}", db.to_text())).unwrap();
+        assert_eq!(parsed, db);
+        assert_eq!(
+            parsed.get("P2D:J:B:A:2026012800").unwrap().judge_data,
+            0x1420003000
+        );
+        assert!(parsed.get("P2D:J:B:A:2026020100").is_none());
+    }
+
+    #[test]
+    fn test_incomplete_entry_is_rejected() {
+        let content = "P2D:J:B:A:2026012800\nsongList = 0x1431865a0\n";
+        assert!(OffsetDatabase::parse(content).is_err());
+    }
+
+    #[test]
+    fn test_user_file_overrides_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("offsets.txt");
+
+        let mut user = OffsetDatabase::new();
+        user.insert(offsets("P2D:J:B:A:2026012800", 0x1430000000))
+            .unwrap();
+        user.save(&path).unwrap();
+
+        let db = OffsetDatabase::with_overrides(&path);
+        assert_eq!(
+            db.get("P2D:J:B:A:2026012800").unwrap().song_list,
+            0x1430001000
+        );
+        assert_eq!(
+            OffsetDatabase::with_overrides(dir.path().join("missing.txt")),
+            OffsetDatabase::bundled()
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Offset differences between game builds
+//!
+//! Compares the offsets of two builds and, given a scan of the newer build,
+//! which builtin signatures still resolve there. The result renders as text
+//! for release notes or serializes to JSON with hex addresses.
+
+use std::fmt::Write;
+
+use serde::{Serialize, Serializer};
+
+use crate::offset::{OffsetRelations, OffsetsCollection, SignatureHits};
+
+use super::searcher::IMAGE_BASE;
+
+/// How often a signature matched in the scanned build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureMatch {
+    Unique,
+    Multiple,
+    None,
+}
+
+impl SignatureMatch {
+    pub fn from_count(count: usize) -> Self {
+        match count {
+            0 => Self::None,
+            1 => Self::Unique,
+            _ => Self::Multiple,
+        }
+    }
+}
+
+/// One offset in both builds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OffsetChange {
+    pub name: &'static str,
+    #[serde(serialize_with = "hex")]
+    pub from: u64,
+    #[serde(serialize_with = "hex")]
+    pub to: u64,
+    #[serde(serialize_with = "signed_hex")]
+    pub delta: i64,
+}
+
+/// A relation between offsets that differs between the builds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelationChange {
+    pub name: &'static str,
+    #[serde(serialize_with = "signed_hex")]
+    pub from: i64,
+    #[serde(serialize_with = "signed_hex")]
+    pub to: i64,
+    #[serde(serialize_with = "signed_hex")]
+    pub delta: i64,
+}
+
+/// Match status of one builtin signature in the scanned build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureStatus {
+    pub entry: String,
+    pub index: usize,
+    pub pattern: String,
+    pub matches: usize,
+    pub status: SignatureMatch,
+}
+
+/// Differences between the offsets of two builds
+#[derive(Debug, Clone, Serialize)]
+pub struct OffsetDiff {
+    pub from_version: String,
+    pub to_version: String,
+    /// Every offset, changed or not
+    pub offsets: Vec<OffsetChange>,
+    /// Relations that changed
+    pub relations: Vec<RelationChange>,
+    /// Signature status in the newer build, when it was scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<SignatureStatus>>,
+}
+
+impl OffsetDiff {
+    /// Compare two builds loaded at the default image base
+    pub fn new(from: &OffsetsCollection, to: &OffsetsCollection) -> Self {
+        Self::with_base(from, to, IMAGE_BASE)
+    }
+
+    /// Compare two builds, measuring SongList from `base`
+    pub fn with_base(from: &OffsetsCollection, to: &OffsetsCollection, base: u64) -> Self {
+        let offsets = from
+            .named()
+            .into_iter()
+            .zip(to.named())
+            .map(|((name, from), (_, to))| OffsetChange {
+                name,
+                from,
+                to,
+                delta: to as i64 - from as i64,
+            })
+            .collect();
+
+        let relations = OffsetRelations::from_offsets(from, base)
+            .named()
+            .into_iter()
+            .zip(OffsetRelations::from_offsets(to, base).named())
+            .filter(|((_, from), (_, to))| from != to)
+            .map(|((name, from), (_, to))| RelationChange {
+                name,
+                from,
+                to,
+                delta: to - from,
+            })
+            .collect();
+
+        Self {
+            from_version: from.version.clone(),
+            to_version: to.version.clone(),
+            offsets,
+            relations,
+            signatures: None,
+        }
+    }
+
+    /// Attach the signature matches from a scan of the newer build
+    pub fn with_signature_hits(mut self, hits: &SignatureHits) -> Self {
+        self.signatures = Some(
+            hits.hits
+                .iter()
+                .map(|hit| SignatureStatus {
+                    entry: hit.entry.clone(),
+                    index: hit.index,
+                    pattern: hit.pattern.clone(),
+                    matches: hit.matches.len(),
+                    status: SignatureMatch::from_count(hit.matches.len()),
+                })
+                .collect(),
+        );
+        self
+    }
+
+    /// Offsets whose address changed
+    pub fn changed_offsets(&self) -> impl Iterator<Item = &OffsetChange> {
+        self.offsets.iter().filter(|change| change.delta != 0)
+    }
+
+    /// Render the diff as plain text
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Offsets: {} -> {}", self.from_version, self.to_version);
+        for change in &self.offsets {
+            if change.delta == 0 {
+                let _ = writeln!(
+                    out,
+                    "  {:<14} {:>13}  (unchanged)",
+                    change.name,
+                    format!("0x{:X}", change.from)
+                );
+            } else {
+                let _ = writeln!(
+                    out,
+                    "  {:<14} {:>13} -> {:>13}  ({})",
+                    change.name,
+                    format!("0x{:X}", change.from),
+                    format!("0x{:X}", change.to),
+                    format_signed_hex(change.delta)
+                );
+            }
+        }
+
+        out.push_str("\nRelations:\n");
+        if self.relations.is_empty() {
+            out.push_str("  (unchanged)\n");
+        }
+        for change in &self.relations {
+            let _ = writeln!(
+                out,
+                "  {:<32} {:>12} -> {:>12}  ({})",
+                change.name,
+                format_signed_hex(change.from),
+                format_signed_hex(change.to),
+                format_signed_hex(change.delta)
+            );
+        }
+
+        if let Some(signatures) = &self.signatures {
+            out.push_str("\nSignatures:\n");
+            for signature in signatures {
+                let status = match signature.status {
+                    SignatureMatch::Unique => "unique".to_string(),
+                    SignatureMatch::Multiple => format!("multiple ({} matches)", signature.matches),
+                    SignatureMatch::None => "no match".to_string(),
+                };
+                let _ = writeln!(
+                    out,
+                    "  {:<14} #{:<3} {}",
+                    signature.entry, signature.index, status
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Format a signed distance as `+0x10` / `-0x10`, or `0`
+fn format_signed_hex(value: i64) -> String {
+    match value {
+        0 => "0".to_string(),
+        v if v < 0 => format!("-0x{:X}", v.unsigned_abs()),
+        v => format!("+0x{:X}", v),
+    }
+}
+
+fn hex<S: Serializer>(value: &u64, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{:X}", value))
+}
+
+fn signed_hex<S: Serializer>(value: &i64, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_signed_hex(*value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::SignatureHit;
+
+    fn offsets(version: &str, shift: u64) -> OffsetsCollection {
+        OffsetsCollection {
+            version: version.to_string(),
+            song_list: 0x1431B08A0 + shift,
+            data_map: 0x142FE5758 + shift,
+            judge_data: 0x1428624D8 + shift,
+            play_data: 0x1425B5800,
+            play_settings: 0x1425B5560 + shift,
+            unlock_data: 0x1431BC180 + shift,
+            current_song: 0x1425B5744 + shift,
+        }
+    }
+
+    const PATTERN: &str = "48 8B 05 <disp> ?? ?? ?? ?? <next>";
+
+    fn hit(entry: &str, matches: Vec<u64>) -> SignatureHit {
+        SignatureHit {
+            entry: entry.to_string(),
+            index: 0,
+            pattern: PATTERN.to_string(),
+            matches,
+        }
+    }
+
+    #[test]
+    fn test_hit_pattern_is_valid() {
+        assert!(PATTERN.parse::<crate::offset::SignaturePattern>().is_ok());
+    }
+
+    #[test]
+    fn test_offset_deltas() {
+        let diff = OffsetDiff::new(&offsets("A", 0), &offsets("B", 0x2C220));
+
+        assert_eq!(diff.from_version, "A");
+        assert_eq!(diff.to_version, "B");
+        assert_eq!(diff.offsets.len(), 7);
+        assert_eq!(diff.offsets[0].name, "songList");
+        assert_eq!(diff.offsets[0].delta, 0x2C220);
+
+        let changed: Vec<_> = diff.changed_offsets().map(|c| c.name).collect();
+        assert!(!changed.contains(&"playData"));
+        assert_eq!(changed.len(), 6);
+    }
+
+    #[test]
+    fn test_only_changed_relations_are_listed() {
+        let diff = OffsetDiff::new(&offsets("A", 0), &offsets("B", 0x2C220));
+
+        // Everything but playData moved together
+        let names: Vec<_> = diff.relations.iter().map(|r| r.name).collect();
+        assert_eq!(
+            names,
+            ["song_list_from_base", "play_data_from_play_settings"]
+        );
+        assert_eq!(diff.relations[1].delta, -0x2C220);
+
+        let same = OffsetDiff::new(&offsets("A", 0), &offsets("A", 0));
+        assert!(same.relations.is_empty());
+        assert_eq!(same.changed_offsets().count(), 0);
+    }
+
+    #[test]
+    fn test_signature_status() {
+        let hits = SignatureHits {
+            hits: vec![
+                hit("songList", vec![0x140001000]),
+                hit("judgeData", vec![0x140002000, 0x140003000]),
+                hit("dataMap", vec![]),
+            ],
+            ..Default::default()
+        };
+        let diff = OffsetDiff::new(&offsets("A", 0), &offsets("B", 0)).with_signature_hits(&hits);
+
+        let statuses: Vec<_> = diff
+            .signatures
+            .unwrap()
+            .iter()
+            .map(|s| (s.status, s.matches))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                (SignatureMatch::Unique, 1),
+                (SignatureMatch::Multiple, 2),
+                (SignatureMatch::None, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_uses_hex() {
+        let diff = OffsetDiff::new(&offsets("A", 0), &offsets("B", 0x2C220));
+        let json = serde_json::to_value(&diff).unwrap();
+
+        assert_eq!(json["offsets"][0]["from"], "0x1431B08A0");
+        assert_eq!(json["offsets"][0]["delta"], "+0x2C220");
+        assert_eq!(json["offsets"][3]["delta"], "0");
+        assert_eq!(json["relations"][1]["delta"], "-0x2C220");
+        assert!(json.get("signatures").is_none());
+
+        let hits = SignatureHits {
+            hits: vec![hit("dataMap", vec![])],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(diff.with_signature_hits(&hits)).unwrap();
+        assert_eq!(json["signatures"][0]["status"], "none");
+    }
+
+    #[test]
+    fn test_text_output() {
+        let hits = SignatureHits {
+            hits: vec![hit("judgeData", vec![0x140002000, 0x140003000])],
+            ..Default::default()
+        };
+        let text = OffsetDiff::new(&offsets("A", 0), &offsets("B", 0x2C220))
+            .with_signature_hits(&hits)
+            .to_text();
+
+        assert!(text.starts_with("Offsets: A -> B\n"));
+        assert!(text.contains("songList         0x1431B08A0 ->   0x1431DCAC0  (+0x2C220)"));
+        assert!(text.contains("playData"));
+        assert!(text.contains("(unchanged)"));
+        assert!(text.contains("play_data_from_play_settings"));
+        assert!(text.contains("judgeData      #0   multiple (2 matches)"));
+    }
+}
@@ -0,0 +1,358 @@
+//! Structured offsets file format
+//!
+//! A JSON alternative to the legacy `version` + `key = hex` offsets file that
+//! records, for each offset, how it was found and how confident the finder
+//! was. Offsets this version of infst does not know about are kept in
+//! [`OffsetEntries::extra`] so newer files survive a round trip.
+//!
+//! ```json
+//! {
+//!   "schemaVersion": 1,
+//!   "version": "P2D:J:B:A:2026012800",
+//!   "offsets": {
+//!     "songList": { "value": "0x1431865a0", "method": "relative", "confidence": 0.9 },
+//!     "judgeData": { "value": "0x1428380ec", "method": "signature", "confidence": 1.0 }
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tracing::debug;
+
+use super::OffsetsCollection;
+use super::loader::{format_offsets, parse_offsets};
+use crate::error::{Error, Result};
+
+/// Current schema version of the structured format
+pub const OFFSETS_SCHEMA_VERSION: u32 = 1;
+
+/// On-disk offsets file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetsFormat {
+    /// Version line followed by `key = hex` lines
+    Legacy,
+    /// JSON document with schema version and provenance
+    Json,
+}
+
+impl OffsetsFormat {
+    /// Detect the format of an offsets file from its content
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with('{') {
+            Self::Json
+        } else {
+            Self::Legacy
+        }
+    }
+}
+
+/// How an offset was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetMethod {
+    /// Code signature (RIP-relative reference)
+    Signature,
+    /// Data pattern search
+    Pattern,
+    /// Relative to another known offset
+    Relative,
+    /// Entered or edited by hand
+    Manual,
+}
+
+/// A single offset with its provenance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OffsetEntry {
+    /// Absolute address
+    #[serde(with = "hex_address")]
+    pub value: u64,
+    /// How the offset was found
+    pub method: OffsetMethod,
+    /// Confidence in the offset, from 0.0 to 1.0
+    pub confidence: f32,
+}
+
+impl OffsetEntry {
+    pub fn new(value: u64, method: OffsetMethod, confidence: f32) -> Self {
+        Self {
+            value,
+            method,
+            confidence,
+        }
+    }
+}
+
+/// Offsets of a structured file, keyed by their legacy names
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OffsetEntries {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub song_list: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_map: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_data: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_data: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_settings: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unlock_data: Option<OffsetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_song: Option<OffsetEntry>,
+    /// Offsets for structures not known to this version of infst
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, OffsetEntry>,
+}
+
+impl OffsetEntries {
+    /// Known offsets with their legacy names
+    fn known(&self) -> [(&'static str, Option<&OffsetEntry>); 7] {
+        [
+            ("songList", self.song_list.as_ref()),
+            ("dataMap", self.data_map.as_ref()),
+            ("judgeData", self.judge_data.as_ref()),
+            ("playData", self.play_data.as_ref()),
+            ("playSettings", self.play_settings.as_ref()),
+            ("unlockData", self.unlock_data.as_ref()),
+            ("currentSong", self.current_song.as_ref()),
+        ]
+    }
+
+    /// All entries with their names, known offsets first
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &OffsetEntry)> {
+        self.known()
+            .into_iter()
+            .filter_map(|(name, entry)| entry.map(|entry| (name, entry)))
+            .chain(
+                self.extra
+                    .iter()
+                    .map(|(name, entry)| (name.as_str(), entry)),
+            )
+    }
+}
+
+/// Structured offsets file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OffsetsDocument {
+    pub schema_version: u32,
+    /// Game version string (e.g., "P2D:J:B:A:2026012800")
+    pub version: String,
+    pub offsets: OffsetEntries,
+}
+
+impl OffsetsDocument {
+    /// Build a document giving every non-zero offset the same provenance
+    pub fn from_collection(
+        offsets: &OffsetsCollection,
+        method: OffsetMethod,
+        confidence: f32,
+    ) -> Self {
+        let entry = |value: u64| (value != 0).then(|| OffsetEntry::new(value, method, confidence));
+        Self {
+            schema_version: OFFSETS_SCHEMA_VERSION,
+            version: offsets.version.clone(),
+            offsets: OffsetEntries {
+                song_list: entry(offsets.song_list),
+                data_map: entry(offsets.data_map),
+                judge_data: entry(offsets.judge_data),
+                play_data: entry(offsets.play_data),
+                play_settings: entry(offsets.play_settings),
+                unlock_data: entry(offsets.unlock_data),
+                current_song: entry(offsets.current_song),
+                extra: BTreeMap::new(),
+            },
+        }
+    }
+
+    /// Offsets known to this version of infst; missing ones are zero
+    pub fn to_collection(&self) -> OffsetsCollection {
+        let value = |entry: Option<OffsetEntry>| entry.map_or(0, |e| e.value);
+        for name in self.offsets.extra.keys() {
+            debug!("Ignoring offset not used by this version: '{}'", name);
+        }
+        OffsetsCollection {
+            version: self.version.clone(),
+            song_list: value(self.offsets.song_list),
+            data_map: value(self.offsets.data_map),
+            judge_data: value(self.offsets.judge_data),
+            play_data: value(self.offsets.play_data),
+            play_settings: value(self.offsets.play_settings),
+            unlock_data: value(self.offsets.unlock_data),
+            current_song: value(self.offsets.current_song),
+        }
+    }
+
+    /// Parse an offsets file in either format
+    ///
+    /// Legacy files carry no provenance, so their offsets are recorded as
+    /// manual with full confidence.
+    pub fn parse(content: &str) -> Result<Self> {
+        match OffsetsFormat::detect(content) {
+            OffsetsFormat::Legacy => Ok(Self::from_collection(
+                &parse_offsets(content)?,
+                OffsetMethod::Manual,
+                1.0,
+            )),
+            OffsetsFormat::Json => {
+                let document: Self = serde_json::from_str(content)?;
+                document.validate()?;
+                Ok(document)
+            }
+        }
+    }
+
+    /// Format the document as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Format the known offsets in the legacy format
+    pub fn to_legacy(&self) -> String {
+        format_offsets(&self.to_collection())
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.schema_version == 0 || self.schema_version > OFFSETS_SCHEMA_VERSION {
+            return Err(Error::InvalidOffset(format!(
+                "Unsupported offsets schema version {} (supported: 1..={})",
+                self.schema_version, OFFSETS_SCHEMA_VERSION
+            )));
+        }
+        for (name, entry) in self.offsets.iter() {
+            if !(0.0..=1.0).contains(&entry.confidence) {
+                return Err(Error::InvalidOffset(format!(
+                    "Confidence of '{}' must be between 0 and 1, got {}",
+                    name, entry.confidence
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load an offsets file in either format as a structured document
+pub fn load_offsets_document<P: AsRef<Path>>(path: P) -> Result<OffsetsDocument> {
+    OffsetsDocument::parse(&fs::read_to_string(path)?)
+}
+
+/// Save a structured offsets document as JSON
+pub fn save_offsets_document<P: AsRef<Path>>(path: P, document: &OffsetsDocument) -> Result<()> {
+    fs::write(path, document.to_json()?)?;
+    Ok(())
+}
+
+/// Serialize addresses as `0x`-prefixed hex strings, accepting plain numbers too
+mod hex_address {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Address {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(
+        value: &u64,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<u64, D::Error> {
+        match Address::deserialize(deserializer)? {
+            Address::Number(value) => Ok(value),
+            Address::Text(text) => {
+                let digits = text
+                    .strip_prefix("0x")
+                    .or_else(|| text.strip_prefix("0X"))
+                    .unwrap_or(&text);
+                u64::from_str_radix(digits, 16).map_err(|e| {
+                    serde::de::Error::custom(format!("invalid address '{}': {}", text, e))
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = "P2D:J:B:A:2026012800\nsongList = 0x1431865a0\njudgeData = 0x1428380ec\n";
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(OffsetsFormat::detect(LEGACY), OffsetsFormat::Legacy);
+        assert_eq!(
+            OffsetsFormat::detect("\n  {\"schemaVersion\": 1}"),
+            OffsetsFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_legacy_converts_to_manual_entries() {
+        let document = OffsetsDocument::parse(LEGACY).unwrap();
+        assert_eq!(document.schema_version, OFFSETS_SCHEMA_VERSION);
+        assert_eq!(
+            document.offsets.song_list,
+            Some(OffsetEntry::new(0x1431865a0, OffsetMethod::Manual, 1.0))
+        );
+        assert_eq!(document.offsets.play_data, None);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut document = OffsetsDocument::parse(LEGACY).unwrap();
+        document.offsets.judge_data =
+            Some(OffsetEntry::new(0x1428380ec, OffsetMethod::Signature, 0.75));
+        document.offsets.extra.insert(
+            "arenaData".to_string(),
+            OffsetEntry::new(0x142900000, OffsetMethod::Pattern, 0.5),
+        );
+
+        let json = document.to_json().unwrap();
+        assert!(json.contains("\"value\": \"0x1428380ec\""));
+        assert!(json.contains("\"arenaData\""));
+
+        let parsed = OffsetsDocument::parse(&json).unwrap();
+        assert_eq!(parsed, document);
+        assert_eq!(parsed.to_collection().judge_data, 0x1428380ec);
+    }
+
+    #[test]
+    fn test_json_accepts_numeric_values() {
+        let json = r#"{
+            "schemaVersion": 1,
+            "version": "P2D:J:B:A:2026012800",
+            "offsets": { "songList": { "value": 4096, "method": "relative", "confidence": 1.0 } }
+        }"#;
+        let offsets = OffsetsDocument::parse(json).unwrap().to_collection();
+        assert_eq!(offsets.song_list, 0x1000);
+        assert_eq!(offsets.judge_data, 0);
+    }
+
+    #[test]
+    fn test_json_rejects_unsupported_schema() {
+        let json = r#"{"schemaVersion": 2, "version": "x", "offsets": {}}"#;
+        assert!(OffsetsDocument::parse(json).is_err());
+    }
+
+    #[test]
+    fn test_json_rejects_out_of_range_confidence() {
+        let json = r#"{
+            "schemaVersion": 1,
+            "version": "x",
+            "offsets": { "songList": { "value": "0x1000", "method": "manual", "confidence": 1.5 } }
+        }"#;
+        assert!(OffsetsDocument::parse(json).is_err());
+    }
+}
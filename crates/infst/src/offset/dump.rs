@@ -1,7 +1,7 @@
-use crate::offset::OffsetsCollection;
+use crate::offset::{OffsetRankings, OffsetsCollection};
 use crate::process::ReadMemory;
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
@@ -14,6 +14,9 @@ pub struct OffsetDump {
     pub relations: OffsetRelations,
     pub memory_samples: MemorySamples,
     pub data_map_diagnostics: Option<DataMapDiagnostics>,
+    /// Ranked candidates per offset, when the offsets were searched for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rankings: Option<OffsetRankings>,
 }
 
 /// Offset values in hex string format
@@ -29,7 +32,7 @@ pub struct OffsetValues {
 }
 
 /// Relative distances between offsets (signed, in bytes)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OffsetRelations {
     pub song_list_from_base: i64,
     pub unlock_data_from_song_list: i64,
@@ -40,6 +43,57 @@ pub struct OffsetRelations {
     pub current_song_from_play_settings: i64,
 }
 
+impl OffsetRelations {
+    /// Distances between the given offsets, with SongList measured from `base`
+    pub fn from_offsets(offsets: &OffsetsCollection, base: u64) -> Self {
+        Self {
+            song_list_from_base: offsets.song_list as i64 - base as i64,
+            unlock_data_from_song_list: offsets.unlock_data as i64 - offsets.song_list as i64,
+            data_map_from_song_list: offsets.data_map as i64 - offsets.song_list as i64,
+            judge_data_from_data_map: offsets.judge_data as i64 - offsets.data_map as i64,
+            play_settings_from_judge_data: offsets.play_settings as i64 - offsets.judge_data as i64,
+            play_data_from_play_settings: offsets.play_data as i64 - offsets.play_settings as i64,
+            current_song_from_play_settings: offsets.current_song as i64
+                - offsets.play_settings as i64,
+        }
+    }
+
+    /// Relations with their dump field names
+    pub fn named(&self) -> [(&'static str, i64); 7] {
+        [
+            ("song_list_from_base", self.song_list_from_base),
+            (
+                "unlock_data_from_song_list",
+                self.unlock_data_from_song_list,
+            ),
+            ("data_map_from_song_list", self.data_map_from_song_list),
+            ("judge_data_from_data_map", self.judge_data_from_data_map),
+            (
+                "play_settings_from_judge_data",
+                self.play_settings_from_judge_data,
+            ),
+            (
+                "play_data_from_play_settings",
+                self.play_data_from_play_settings,
+            ),
+            (
+                "current_song_from_play_settings",
+                self.current_song_from_play_settings,
+            ),
+        ]
+    }
+
+    /// JudgeData relative to SongList
+    pub fn judge_data_from_song_list(&self) -> i64 {
+        self.data_map_from_song_list + self.judge_data_from_data_map
+    }
+
+    /// CurrentSong relative to JudgeData
+    pub fn current_song_from_judge_data(&self) -> i64 {
+        self.current_song_from_play_settings + self.play_settings_from_judge_data
+    }
+}
+
 /// Memory samples at each offset location
 #[derive(Debug, Clone, Serialize)]
 pub struct MemorySamples {
@@ -75,16 +129,7 @@ impl OffsetDump {
             current_song: format!("0x{:X}", offsets.current_song),
         };
 
-        let relations = OffsetRelations {
-            song_list_from_base: offsets.song_list as i64 - base as i64,
-            unlock_data_from_song_list: offsets.unlock_data as i64 - offsets.song_list as i64,
-            data_map_from_song_list: offsets.data_map as i64 - offsets.song_list as i64,
-            judge_data_from_data_map: offsets.judge_data as i64 - offsets.data_map as i64,
-            play_settings_from_judge_data: offsets.play_settings as i64 - offsets.judge_data as i64,
-            play_data_from_play_settings: offsets.play_data as i64 - offsets.play_settings as i64,
-            current_song_from_play_settings: offsets.current_song as i64
-                - offsets.play_settings as i64,
-        };
+        let relations = OffsetRelations::from_offsets(offsets, base);
 
         let memory_samples = MemorySamples {
             play_data_32bytes: Self::read_memory_hex(reader, offsets.play_data, 32),
@@ -100,9 +145,16 @@ impl OffsetDump {
             relations,
             memory_samples,
             data_map_diagnostics: Self::data_map_diagnostics(reader, offsets.data_map, base),
+            rankings: None,
         }
     }
 
+    /// Attach the candidate rankings from the search that found the offsets
+    pub fn with_rankings(mut self, rankings: OffsetRankings) -> Self {
+        self.rankings = Some(rankings);
+        self
+    }
+
     fn read_memory_hex<R: ReadMemory>(reader: &R, address: u64, size: usize) -> String {
         if address == 0 {
             return "(address is 0)".to_string();
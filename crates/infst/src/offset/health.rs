@@ -0,0 +1,131 @@
+//! Runtime offset health checks
+//!
+//! Offsets that were valid at startup can go stale mid-session (e.g. the game
+//! reallocates a structure). The tracker re-checks the active offsets while
+//! idle on song select with the searcher's validators, relaxed where song
+//! select legitimately holds initial (all-zero) data.
+
+use super::OffsetsCollection;
+use super::searcher::validation::{
+    count_songs_at_address, validate_current_song_address, validate_data_map_address,
+    validate_judge_data_candidate, validate_play_data_address, validate_play_settings_at,
+    validate_unlock_data_address,
+};
+use crate::process::ReadMemory;
+
+/// Result of re-validating a set of offsets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OffsetHealth {
+    /// Names of the offsets that failed validation (e.g. "judgeData")
+    pub failed: Vec<&'static str>,
+}
+
+impl OffsetHealth {
+    /// Whether every offset passed
+    pub fn is_healthy(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Whether the named offset failed
+    pub fn has_failed(&self, name: &str) -> bool {
+        self.failed.contains(&name)
+    }
+}
+
+/// Re-validate the offsets against current memory
+///
+/// Zero offsets are skipped, since they were never detected.
+pub fn check_offset_health<R: ReadMemory>(reader: &R, offsets: &OffsetsCollection) -> OffsetHealth {
+    OffsetHealth {
+        failed: offsets
+            .named()
+            .into_iter()
+            .filter(|&(name, address)| {
+                address != 0 && validate_offset(reader, name, address) == Some(false)
+            })
+            .map(|(name, _)| name)
+            .collect(),
+    }
+}
+
+/// Check an address with the validator of the named offset
+///
+/// PlayData and CurrentSong may still hold their initial zeros before the
+/// first play. Returns None for names without a validator.
+pub fn validate_offset<R: ReadMemory>(reader: &R, name: &str, address: u64) -> Option<bool> {
+    let is_zeroed = |size: usize| {
+        reader
+            .read_bytes(address, size)
+            .is_ok_and(|bytes| bytes.iter().all(|&b| b == 0))
+    };
+
+    let valid = match name.to_ascii_lowercase().as_str() {
+        "songlist" => count_songs_at_address(reader, address) >= 1,
+        "datamap" => validate_data_map_address(reader, address),
+        "judgedata" => validate_judge_data_candidate(reader, address),
+        "playdata" => validate_play_data_address(reader, address) || is_zeroed(8),
+        "playsettings" => validate_play_settings_at(reader, address).is_some(),
+        "unlockdata" => validate_unlock_data_address(reader, address),
+        "currentsong" => validate_current_song_address(reader, address) || is_zeroed(8),
+        _ => return None,
+    };
+    Some(valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+    use crate::process::layout::judge;
+    use crate::process::mock::{GAME_BASE, GAME_VERSION};
+
+    fn offsets() -> OffsetsCollection {
+        OffsetsCollection {
+            version: GAME_VERSION.to_string(),
+            judge_data: GAME_BASE,
+            play_settings: GAME_BASE + 0x200,
+            play_data: GAME_BASE + 0x300,
+            current_song: GAME_BASE + 0x400,
+            ..Default::default()
+        }
+    }
+
+    fn builder() -> MockMemoryBuilder {
+        MockMemoryBuilder::game_image(0x1000).write_song_select_judge(0)
+    }
+
+    #[test]
+    fn test_initial_song_select_state_is_healthy() {
+        let reader = builder().build();
+        let health = check_offset_health(&reader, &offsets());
+        assert!(health.is_healthy(), "failed: {:?}", health.failed);
+    }
+
+    #[test]
+    fn test_garbage_is_reported_per_offset() {
+        let reader = builder()
+            .write_i32(judge::STATE_MARKER_1.offset, 5000)
+            .write_i32(0x400, 77) // currentSong song_id below the valid range
+            .build();
+        let health = check_offset_health(&reader, &offsets());
+        assert_eq!(health.failed, ["judgeData", "currentSong"]);
+        assert!(health.has_failed("judgeData"));
+        assert!(!health.has_failed("playSettings"));
+    }
+
+    #[test]
+    fn test_validate_offset_by_name() {
+        let reader = builder().build();
+        assert_eq!(validate_offset(&reader, "judgeData", GAME_BASE), Some(true));
+        assert_eq!(validate_offset(&reader, "JUDGEDATA", GAME_BASE), Some(true));
+        assert_eq!(validate_offset(&reader, "unknown", GAME_BASE), None);
+
+        let reader = builder()
+            .write_i32(judge::STATE_MARKER_1.offset, 5000)
+            .build();
+        assert_eq!(
+            validate_offset(&reader, "judgeData", GAME_BASE),
+            Some(false)
+        );
+    }
+}
@@ -1,13 +1,17 @@
 use tracing::warn;
 
 use crate::error::{Error, Result};
-use crate::offset::OffsetsCollection;
+use crate::offset::{OffsetsCollection, OffsetsDocument, OffsetsFormat};
 use std::fs;
 use std::path::Path;
 
+/// Load an offsets file, detecting the legacy or structured format
 pub fn load_offsets<P: AsRef<Path>>(path: P) -> Result<OffsetsCollection> {
     let content = fs::read_to_string(&path)?;
-    parse_offsets(&content)
+    match OffsetsFormat::detect(&content) {
+        OffsetsFormat::Legacy => parse_offsets(&content),
+        OffsetsFormat::Json => Ok(OffsetsDocument::parse(&content)?.to_collection()),
+    }
 }
 
 pub fn save_offsets<P: AsRef<Path>>(path: P, offsets: &OffsetsCollection) -> Result<()> {
@@ -16,7 +20,7 @@ pub fn save_offsets<P: AsRef<Path>>(path: P, offsets: &OffsetsCollection) -> Res
     Ok(())
 }
 
-fn parse_offsets(content: &str) -> Result<OffsetsCollection> {
+pub(super) fn parse_offsets(content: &str) -> Result<OffsetsCollection> {
     let mut offsets = OffsetsCollection::default();
     let mut lines = content.lines();
 
@@ -68,7 +72,7 @@ fn parse_hex_value(value: &str) -> Result<u64> {
         .map_err(|e| Error::InvalidOffset(format!("Failed to parse '{}': {}", value, e)))
 }
 
-fn format_offsets(offsets: &OffsetsCollection) -> String {
+pub(super) fn format_offsets(offsets: &OffsetsCollection) -> String {
     let mut lines = Vec::new();
 
     lines.push(offsets.version.clone());
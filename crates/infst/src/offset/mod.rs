@@ -4,7 +4,12 @@
 //!
 //! - **Signature scanning**: Find code patterns to derive data addresses
 //! - **Offset collection**: Store and validate detected offsets
-//! - **Persistence**: Save/load offsets to files for faster startup
+//! - **Persistence**: Save/load offsets to files for faster startup, in the
+//!   legacy `key = hex` format or a structured JSON format with provenance
+//! - **Database**: Known offsets per game version, bundled and user-extended
+//! - **Health checks**: Re-validation of active offsets during a session
+//! - **Diffs**: What moved between two game builds
+//! - **Coverage**: Which signatures still resolve to valid data on a build
 //!
 //! ## Architecture
 //!
@@ -16,19 +21,34 @@
 //! ## Key Types
 //!
 //! - [`OffsetsCollection`]: All detected memory offsets
+//! - [`OffsetDatabase`]: Known offsets per game version
+//! - [`OffsetsDocument`]: Structured offsets file with per-offset provenance
 //! - [`OffsetSearcher`]: Signature-based offset finder
 //! - [`CodeSignature`]: Pattern definition for code scanning
+//! - [`SignaturePattern`]: Parsed signature pattern (wildcards, gaps, captures)
 
 mod cache;
 mod collection;
+mod coverage;
+mod database;
+mod diff;
+mod document;
 mod dump;
+mod health;
 mod loader;
 mod searcher;
 mod signature;
+mod signature_pattern;
 
 pub use cache::*;
 pub use collection::*;
+pub use coverage::*;
+pub use database::*;
+pub use diff::*;
+pub use document::*;
 pub use dump::*;
+pub use health::*;
 pub use loader::*;
 pub use searcher::*;
 pub use signature::*;
+pub use signature_pattern::*;
@@ -126,23 +126,20 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         )))
     }
 
-    /// Like fetch_and_search, but returns the LAST match instead of first.
-    /// Expands search area progressively and uses the last match found.
-    /// This avoids false positives from earlier memory regions (e.g., 2016-build data).
-    pub(crate) fn fetch_and_search_last(
-        &mut self,
-        hint: u64,
-        pattern: &[u8],
-        offset_from_match: i64,
-    ) -> Result<u64> {
+    /// Find every match of a pattern, expanding the search area progressively.
+    ///
+    /// Matches come from the largest area that could be read, in address
+    /// order. Callers that want to avoid false positives from earlier memory
+    /// regions (e.g., 2016-build data) use the last match.
+    pub(crate) fn fetch_all_matches(&mut self, hint: u64, pattern: &[u8]) -> Vec<u64> {
         let mut search_size = INITIAL_SEARCH_SIZE;
-        let mut last_matches: Vec<u64> = Vec::new();
+        let mut matches: Vec<u64> = Vec::new();
 
         // Keep expanding to find all matches across the readable memory area
         while search_size <= MAX_SEARCH_SIZE {
             match self.load_buffer_around(hint, search_size) {
                 Ok(()) => {
-                    last_matches = self.find_all_matches(pattern);
+                    matches = self.find_all_matches(pattern);
                 }
                 Err(_) => {
                     // Memory read failed, use results from previous size
@@ -152,17 +149,7 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             search_size *= 2;
         }
 
-        if last_matches.is_empty() {
-            return Err(Error::offset_search_failed(format!(
-                "Pattern not found within +/-{} MB",
-                MAX_SEARCH_SIZE / 1024 / 1024
-            )));
-        }
-
-        // Use last match to avoid false positives from earlier regions
-        let last_match = *last_matches.last().expect("matches is non-empty");
-        let address = last_match.wrapping_add_signed(offset_from_match);
-        Ok(address)
+        matches
     }
 
     /// Search for multiple patterns, returning the first match and its index
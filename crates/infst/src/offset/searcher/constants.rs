@@ -37,6 +37,8 @@
 //! These values are derived from analysis of 9 game versions and remain
 //! remarkably stable across updates.
 
+use crate::chart::SongInfo;
+
 /// Initial buffer size for memory search (2MB)
 pub const INITIAL_SEARCH_SIZE: usize = 2 * 1024 * 1024;
 /// Maximum half-window size for memory search (total read size is 2x)
@@ -57,11 +59,11 @@ pub const CODE_SCAN_LIMIT: usize = 128 * 1024 * 1024;
 /// Minimum number of songs expected in INFINITAS (for validation)
 pub const MIN_EXPECTED_SONGS: usize = 1000;
 
-/// Minimum valid song ID in IIDX (song IDs start from 1000)
-pub const MIN_SONG_ID: i32 = 1000;
+/// Minimum valid song ID in IIDX (see [`SongInfo::ID_RANGE`])
+pub const MIN_SONG_ID: i32 = *SongInfo::ID_RANGE.start() as i32;
 
-/// Maximum valid song ID in IIDX (reasonable upper bound)
-pub const MAX_SONG_ID: i32 = 50000;
+/// Maximum valid song ID in IIDX (see [`SongInfo::ID_RANGE`])
+pub const MAX_SONG_ID: i32 = *SongInfo::ID_RANGE.end() as i32;
 
 // ============================================================================
 // Relative Offsets (derived from historical analysis of 9 versions)
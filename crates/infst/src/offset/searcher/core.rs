@@ -1,19 +1,31 @@
 //! Core offset searcher structure and basic methods
 
+use std::cell::OnceCell;
+
 use tracing::{debug, info};
 
 use crate::error::{Error, Result};
-use crate::offset::{OffsetSignatureSet, OffsetsCollection};
-use crate::process::ReadMemory;
+use crate::offset::{OffsetHealth, OffsetSignatureSet, OffsetsCollection, check_offset_health};
+use crate::process::{RangePlan, ReadMemory};
 
 use super::constants::*;
-use super::validation::{validate_basic_memory_access, validate_signature_offsets};
+use super::pattern::{code_section_plan, resolve_signature_matches};
+use super::priors::RelationPriors;
+use super::ranking::OffsetRankings;
+use super::relative_search::{score_judge_data, score_play_settings};
+use super::signature_scan::{SignatureHits, SignatureScanner};
+use super::validation::{
+    ValidationScore, score_current_song_address, score_data_map_address, score_play_data_address,
+    score_song_list, score_unlock_data_address, validate_basic_memory_access,
+    validate_signature_offsets,
+};
 
 /// Builder for creating OffsetSearcher with optional configuration
 pub struct OffsetSearcherBuilder<'a, R: ReadMemory> {
     reader: &'a R,
     initial_buffer_size: usize,
     song_list_hint: Option<u64>,
+    priors: RelationPriors,
 }
 
 impl<'a, R: ReadMemory> OffsetSearcherBuilder<'a, R> {
@@ -23,6 +35,7 @@ impl<'a, R: ReadMemory> OffsetSearcherBuilder<'a, R> {
             reader,
             initial_buffer_size: INITIAL_SEARCH_SIZE,
             song_list_hint: None,
+            priors: RelationPriors::default(),
         }
     }
 
@@ -38,6 +51,12 @@ impl<'a, R: ReadMemory> OffsetSearcherBuilder<'a, R> {
         self
     }
 
+    /// Set the search windows for the relative searches
+    pub fn with_priors(mut self, priors: RelationPriors) -> Self {
+        self.priors = priors;
+        self
+    }
+
     /// Build the OffsetSearcher
     pub fn build(self) -> OffsetSearcher<'a, R> {
         OffsetSearcher {
@@ -45,6 +64,10 @@ impl<'a, R: ReadMemory> OffsetSearcherBuilder<'a, R> {
             buffer: Vec::with_capacity(self.initial_buffer_size),
             buffer_base: 0,
             song_list_hint: self.song_list_hint,
+            signature_hits: None,
+            code_plan: OnceCell::new(),
+            rankings: OffsetRankings::default(),
+            priors: self.priors,
         }
     }
 }
@@ -55,6 +78,14 @@ pub struct OffsetSearcher<'a, R: ReadMemory> {
     pub(crate) buffer: Vec<u8>,
     pub(crate) buffer_base: u64,
     pub(crate) song_list_hint: Option<u64>,
+    /// Result of the last [`scan_signatures`](Self::scan_signatures) call
+    pub(crate) signature_hits: Option<SignatureHits>,
+    /// Readable spans of the code section, enumerated on first use
+    pub(crate) code_plan: OnceCell<RangePlan>,
+    /// Scored candidates from the searches run so far
+    pub(crate) rankings: OffsetRankings,
+    /// Search windows for the relative searches
+    pub(crate) priors: RelationPriors,
 }
 
 impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
@@ -65,6 +96,10 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             buffer: Vec::new(),
             buffer_base: 0,
             song_list_hint: None,
+            signature_hits: None,
+            code_plan: OnceCell::new(),
+            rankings: OffsetRankings::default(),
+            priors: RelationPriors::default(),
         }
     }
 
@@ -78,10 +113,19 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         self.reader
     }
 
-    /// Search for all offsets using code signatures (AOB scan)
+    /// Readable spans of the code section, shared by every code scan of this searcher
+    pub(crate) fn code_plan(&self) -> RangePlan {
+        self.code_plan
+            .get_or_init(|| code_section_plan(self.reader))
+            .clone()
+    }
+
+    /// Search for all offsets, falling back to code signatures (AOB scan)
     ///
-    /// This method relies on RIP-relative code references instead of data patterns,
-    /// making it more resilient to data layout changes.
+    /// The pattern and relative searches are authoritative. A signature target
+    /// is only used for an offset whose search failed, and only when it is the
+    /// single target that validates. The code section is scanned at most once,
+    /// the first time a search fails.
     pub fn search_all_with_signatures(
         &mut self,
         signatures: &OffsetSignatureSet,
@@ -98,47 +142,76 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         };
 
         // Phase 1: SongList (anchor)
-        debug!("Phase 1: Searching SongList via pattern search...");
+        debug!("Phase 1: Searching SongList...");
         let base = self.reader.base_address();
         let song_list_hint = self
             .song_list_hint
             .unwrap_or(base + EXPECTED_SONG_LIST_OFFSET);
-        offsets.song_list = self.search_song_list_offset(song_list_hint)?;
+        let searched = self.search_song_list_offset(song_list_hint);
+        offsets.song_list =
+            self.resolve_with_signatures(signatures, "songList", searched, score_song_list)?;
         debug!("  SongList: 0x{:X}", offsets.song_list);
 
         // Phase 2: JudgeData (relative search from SongList)
-        info!("Phase 2: Searching JudgeData via relative offset from SongList...");
-        offsets.judge_data = self.search_judge_data_near_song_list(offsets.song_list)?;
+        info!("Phase 2: Searching JudgeData...");
+        let searched = self.search_judge_data_near_song_list(offsets.song_list);
+        offsets.judge_data =
+            self.resolve_with_signatures(signatures, "judgeData", searched, score_judge_data)?;
         info!("  JudgeData: 0x{:X}", offsets.judge_data);
 
         // Phase 3: PlaySettings (relative search from JudgeData)
-        info!("Phase 3: Searching PlaySettings via relative offset from JudgeData...");
-        offsets.play_settings = self.search_play_settings_near_judge_data(offsets.judge_data)?;
+        info!("Phase 3: Searching PlaySettings...");
+        let searched = self.search_play_settings_near_judge_data(offsets.judge_data);
+        offsets.play_settings = self.resolve_with_signatures(
+            signatures,
+            "playSettings",
+            searched,
+            score_play_settings,
+        )?;
         info!("  PlaySettings: 0x{:X}", offsets.play_settings);
 
         // Phase 4: PlayData (relative search from PlaySettings)
-        info!("Phase 4: Searching PlayData via relative offset from PlaySettings...");
-        offsets.play_data = self.search_play_data_near_play_settings(offsets.play_settings)?;
+        info!("Phase 4: Searching PlayData...");
+        let searched = self.search_play_data_near_play_settings(offsets.play_settings);
+        offsets.play_data = self.resolve_with_signatures(
+            signatures,
+            "playData",
+            searched,
+            score_play_data_address,
+        )?;
         info!("  PlayData: 0x{:X}", offsets.play_data);
 
         // Phase 5: CurrentSong (relative search from JudgeData)
-        info!("Phase 5: Searching CurrentSong via relative offset from JudgeData...");
-        offsets.current_song = self.search_current_song_near_judge_data(offsets.judge_data)?;
+        info!("Phase 5: Searching CurrentSong...");
+        let searched = self.search_current_song_near_judge_data(offsets.judge_data);
+        offsets.current_song = self.resolve_with_signatures(
+            signatures,
+            "currentSong",
+            searched,
+            score_current_song_address,
+        )?;
         info!("  CurrentSong: 0x{:X}", offsets.current_song);
 
         // Phase 6: DataMap / UnlockData (pattern search, using SongList as hint)
-        debug!("Phase 6: Searching remaining offsets with patterns...");
-        let base = self.reader.base_address();
-        offsets.data_map = self.search_data_map_offset(base).or_else(|e| {
+        debug!("Phase 6: Searching remaining offsets...");
+        let searched = self.search_data_map_offset(base).or_else(|e| {
             debug!(
                 "  DataMap search from base failed: {}, trying from SongList",
                 e
             );
             self.search_data_map_offset(offsets.song_list)
-        })?;
+        });
+        offsets.data_map =
+            self.resolve_with_signatures(signatures, "dataMap", searched, score_data_map_address)?;
         debug!("  DataMap: 0x{:X}", offsets.data_map);
 
-        offsets.unlock_data = self.search_unlock_data_offset(offsets.song_list)?;
+        let searched = self.search_unlock_data_offset(offsets.song_list);
+        offsets.unlock_data = self.resolve_with_signatures(
+            signatures,
+            "unlockData",
+            searched,
+            score_unlock_data_address,
+        )?;
         debug!("  UnlockData: 0x{:X}", offsets.unlock_data);
 
         if !offsets.is_valid() {
@@ -233,6 +306,201 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         Ok(offsets)
     }
 
+    /// Re-search only the offsets that failed a health check
+    ///
+    /// Offsets that passed are kept and used as anchors. An offset whose
+    /// anchor moved is searched again too, since its relative position came
+    /// from the old anchor. The result must pass a fresh health check.
+    pub fn research_offsets(
+        &mut self,
+        offsets: &OffsetsCollection,
+        health: &OffsetHealth,
+    ) -> Result<OffsetsCollection> {
+        let mut new = offsets.clone();
+
+        if health.has_failed("songList") {
+            new.song_list = self.search_song_list_offset(offsets.song_list)?;
+        }
+        if health.has_failed("judgeData") || new.song_list != offsets.song_list {
+            new.judge_data = self.search_judge_data_near_song_list(new.song_list)?;
+        }
+        if health.has_failed("playSettings") || new.judge_data != offsets.judge_data {
+            new.play_settings = self.search_play_settings_near_judge_data(new.judge_data)?;
+        }
+        // PlayData and CurrentSong can only be found once they hold data; if
+        // just their anchor moved, keep them at the same distance from it
+        if health.has_failed("playData") {
+            new.play_data = self.search_play_data_near_play_settings(new.play_settings)?;
+        } else if new.play_settings != offsets.play_settings {
+            new.play_data = self
+                .search_play_data_near_play_settings(new.play_settings)
+                .unwrap_or_else(|_| {
+                    let delta = offsets.play_data.wrapping_sub(offsets.play_settings);
+                    new.play_settings.wrapping_add(delta)
+                });
+        }
+        if health.has_failed("currentSong") {
+            new.current_song = self.search_current_song_near_judge_data(new.judge_data)?;
+        } else if new.judge_data != offsets.judge_data {
+            new.current_song = self
+                .search_current_song_near_judge_data(new.judge_data)
+                .unwrap_or_else(|_| {
+                    let delta = offsets.current_song.wrapping_sub(offsets.judge_data);
+                    new.judge_data.wrapping_add(delta)
+                });
+        }
+        if health.has_failed("dataMap") {
+            new.data_map = self.search_data_map_offset(offsets.data_map)?;
+        }
+        if health.has_failed("unlockData") {
+            new.unlock_data = self.search_unlock_data_offset(new.song_list)?;
+        }
+
+        let recheck = check_offset_health(self.reader, &new);
+        if !recheck.is_healthy() {
+            return Err(Error::offset_search_failed(format!(
+                "Re-searched offsets still invalid: {}",
+                recheck.failed.join(", ")
+            )));
+        }
+        Ok(new)
+    }
+
+    /// Scan the code section once for every signature in the set
+    ///
+    /// The hits are kept on the searcher so later signature lookups reuse
+    /// them instead of rescanning per pattern.
+    pub fn scan_signatures(&mut self, signatures: &OffsetSignatureSet) -> Result<&SignatureHits> {
+        self.signature_hits = None;
+        let hits = SignatureScanner::new(signatures)?.scan_plan(self.reader, self.code_plan())?;
+        hits.report.log_skipped("Signature scan");
+        debug!(
+            "Signature scan: {} matches for {} signatures",
+            hits.total_matches(),
+            hits.hits.len()
+        );
+        Ok(self.signature_hits.insert(hits))
+    }
+
+    /// Validated targets of an entry's signatures, from the last signature scan
+    ///
+    /// Empty when the hits were not scanned for this signature set. Targets
+    /// are 4-byte aligned and deduplicated, best score first (lowest address
+    /// among equal scores).
+    pub(crate) fn signature_candidates<F>(
+        &self,
+        signatures: &OffsetSignatureSet,
+        name: &str,
+        score: F,
+    ) -> Vec<(u64, ValidationScore)>
+    where
+        F: Fn(&R, u64) -> ValidationScore,
+    {
+        let Some(hits) = self
+            .signature_hits
+            .as_ref()
+            .filter(|hits| hits.is_for(signatures))
+        else {
+            return Vec::new();
+        };
+        let Some(entry) = signatures.entry(name) else {
+            return Vec::new();
+        };
+
+        let mut targets: Vec<u64> = entry
+            .signatures
+            .iter()
+            .enumerate()
+            .filter_map(|(index, signature)| {
+                let matches = hits.get(&entry.name, index)?;
+                let pattern = signature.parsed_pattern().ok()?;
+                Some(resolve_signature_matches(
+                    self.reader,
+                    signature,
+                    &pattern,
+                    matches,
+                ))
+            })
+            .flatten()
+            .filter(|addr| addr.is_multiple_of(4))
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+
+        let mut candidates: Vec<(u64, ValidationScore)> = targets
+            .into_iter()
+            .map(|addr| (addr, score(self.reader, addr)))
+            .filter(|(_, score)| score.is_valid())
+            .collect();
+        candidates.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.score.total_cmp(&a_score.score).then(a.cmp(b))
+        });
+        candidates
+    }
+
+    /// Take the search result, or the single validated signature target when
+    /// the search failed
+    ///
+    /// Signatures are scanned here on first use, so a detection where every
+    /// search succeeds never scans the code section.
+    fn resolve_with_signatures<F>(
+        &mut self,
+        signatures: &OffsetSignatureSet,
+        name: &str,
+        searched: Result<u64>,
+        score: F,
+    ) -> Result<u64>
+    where
+        F: Fn(&R, u64) -> ValidationScore,
+    {
+        let error = match searched {
+            Ok(addr) => return Ok(addr),
+            Err(e) => e,
+        };
+        if signatures.entry(name).is_none() {
+            return Err(error);
+        }
+        if !self
+            .signature_hits
+            .as_ref()
+            .is_some_and(|hits| hits.is_for(signatures))
+            && let Err(e) = self.scan_signatures(signatures)
+        {
+            debug!("Signature scan failed: {}", e);
+            return Err(error);
+        }
+
+        match self
+            .signature_candidates(signatures, name, score)
+            .as_slice()
+        {
+            [(addr, _)] => {
+                debug!("  {}: search failed, 0x{:X} from signatures", name, addr);
+                Ok(*addr)
+            }
+            [] => Err(error),
+            ranked => {
+                debug!(
+                    "  {}: {} signature targets validate (best 0x{:X}), none used",
+                    name,
+                    ranked.len(),
+                    ranked[0].0
+                );
+                Err(error)
+            }
+        }
+    }
+
+    /// Hits from the last signature scan, if any
+    pub fn signature_hits(&self) -> Option<&SignatureHits> {
+        self.signature_hits.as_ref()
+    }
+
+    /// Ranked candidates for each offset searched so far
+    pub fn rankings(&self) -> &OffsetRankings {
+        &self.rankings
+    }
+
     /// Validate all offsets in a collection (delegates to validation module)
     #[inline]
     pub fn validate_signature_offsets(&self, offsets: &OffsetsCollection) -> bool {
@@ -265,3 +533,124 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::{CodeSignature, OffsetSignatureEntry};
+    use crate::process::MockMemoryBuilder;
+    use crate::process::layout::judge;
+    use crate::process::mock::GAME_BASE;
+
+    const JUDGE_DATA: usize = 0x1001000;
+    const OTHER: usize = 0x1002000;
+
+    fn set(pattern: &str) -> OffsetSignatureSet {
+        OffsetSignatureSet {
+            version: "*".to_string(),
+            entries: vec![OffsetSignatureEntry {
+                name: "judgeData".to_string(),
+                signatures: vec![CodeSignature {
+                    pattern: pattern.to_string(),
+                    instr_offset: 0,
+                    disp_offset: 3,
+                    instr_len: 7,
+                    deref: false,
+                    addend: 0,
+                }],
+            }],
+        }
+    }
+
+    fn image() -> MockMemoryBuilder {
+        // lea rcx, [JUDGE_DATA] and lea rcx, [OTHER]; only JUDGE_DATA validates
+        MockMemoryBuilder::game_image(0x1003000)
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], JUDGE_DATA)
+            .write_rip_relative(0x200, &[0x48, 0x8D, 0x0D], OTHER)
+            .write_song_select_judge(JUDGE_DATA)
+            .write_i32(OTHER + judge::STATE_MARKER_1.offset, 5000)
+    }
+
+    fn targets(candidates: Vec<(u64, ValidationScore)>) -> Vec<u64> {
+        candidates.into_iter().map(|(addr, _)| addr).collect()
+    }
+
+    #[test]
+    fn test_signature_targets_come_from_one_scan_of_the_same_set() {
+        let reader = image().build();
+        let signatures = set("48 8D 0D ?? ?? ?? ??");
+        let mut searcher = OffsetSearcher::new(&reader);
+
+        // Nothing is resolved before the set has been scanned
+        assert!(
+            searcher
+                .signature_candidates(&signatures, "judgeData", score_judge_data)
+                .is_empty()
+        );
+
+        searcher.scan_signatures(&signatures).unwrap();
+        assert_eq!(
+            targets(searcher.signature_candidates(&signatures, "judgeData", score_judge_data)),
+            [GAME_BASE + JUDGE_DATA as u64]
+        );
+
+        // Hits from another set are not reused
+        let other = set("48 8D 0D ?? ?? ?? ?? 90");
+        assert!(
+            searcher
+                .signature_candidates(&other, "judgeData", score_judge_data)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_signature_candidates_rank_by_score() {
+        // A zeroed region below JUDGE_DATA validates too, but scores lower
+        const ZEROED: usize = 0x1000800;
+        let reader = image()
+            .write_rip_relative(0x300, &[0x48, 0x8D, 0x0D], ZEROED)
+            .build();
+        let signatures = set("48 8D 0D ?? ?? ?? ??");
+        let mut searcher = OffsetSearcher::new(&reader);
+        searcher.scan_signatures(&signatures).unwrap();
+
+        assert_eq!(
+            targets(searcher.signature_candidates(&signatures, "judgeData", score_judge_data)),
+            [GAME_BASE + JUDGE_DATA as u64, GAME_BASE + ZEROED as u64]
+        );
+
+        // Two validating targets are ambiguous, so the failed search stands
+        let failed = Err(Error::offset_search_failed("not found".to_string()));
+        assert!(
+            searcher
+                .resolve_with_signatures(&signatures, "judgeData", failed, score_judge_data)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_result_is_authoritative() {
+        let reader = image().build();
+        let signatures = set("48 8D 0D ?? ?? ?? ??");
+        let mut searcher = OffsetSearcher::new(&reader);
+
+        // A successful search is kept and the code section is never scanned
+        assert_eq!(
+            searcher
+                .resolve_with_signatures(&signatures, "judgeData", Ok(0x1234), score_judge_data)
+                .unwrap(),
+            0x1234
+        );
+        assert!(searcher.signature_hits().is_none());
+
+        // A failed search falls back to the single validating target
+        let failed = Err(Error::offset_search_failed("not found".to_string()));
+        assert_eq!(
+            searcher
+                .resolve_with_signatures(&signatures, "judgeData", failed, score_judge_data)
+                .unwrap(),
+            GAME_BASE + JUDGE_DATA as u64
+        );
+        assert!(searcher.signature_hits().is_some());
+    }
+}
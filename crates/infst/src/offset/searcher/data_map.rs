@@ -7,8 +7,9 @@ use crate::process::{ByteBuffer, ReadMemory};
 
 use super::OffsetSearcher;
 use super::constants::*;
+use super::ranking::CandidateRanking;
 use super::utils::merge_byte_representations;
-use super::validation::OffsetValidation;
+use super::validation::{OffsetValidation, ValidationScore, score_unlock_data_address};
 
 /// Probe result for DataMap candidate validation
 ///
@@ -28,6 +29,28 @@ pub(crate) struct DataMapProbe {
 }
 
 impl DataMapProbe {
+    /// Score the probe by how many sampled nodes look like score entries
+    pub fn score(&self) -> ValidationScore {
+        let sampled = self.non_null_entries.min(DATA_MAP_NODE_SAMPLES);
+        let mut score = ValidationScore::new();
+        score.require(
+            true,
+            1.0,
+            format!("table size 0x{:X} in range", self.table_size),
+        );
+        score.prefer(
+            self.valid_nodes > 0,
+            1.0,
+            format!("{}/{} sampled nodes valid", self.valid_nodes, sampled),
+        );
+        score.prefer(
+            sampled > 0 && self.valid_nodes * 2 >= sampled,
+            2.0,
+            "at least half of sampled nodes valid",
+        );
+        score
+    }
+
     pub fn is_better_than(&self, other: &Self) -> bool {
         (
             self.valid_nodes,
@@ -48,7 +71,22 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
     pub fn search_unlock_data_offset(&mut self, base_hint: u64) -> Result<u64> {
         // Pattern: 1000 (first song ID), 1 (type), 462 (unlocks)
         let pattern = merge_byte_representations(&[1000, 1, 462]);
-        self.fetch_and_search_last(base_hint, &pattern, 0)
+        let matches = self.fetch_all_matches(base_hint, &pattern);
+        let selected = matches.last().copied();
+
+        // Later matches are preferred, so rank them first
+        self.rank_candidates(
+            "unlockData",
+            matches.into_iter().rev(),
+            selected,
+            score_unlock_data_address,
+        );
+        selected.ok_or_else(|| {
+            Error::offset_search_failed(format!(
+                "Pattern not found within +/-{} MB",
+                MAX_SEARCH_SIZE / 1024 / 1024
+            ))
+        })
     }
 
     /// Search for data map offset
@@ -58,6 +96,7 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         let mut search_size = INITIAL_SEARCH_SIZE;
         let mut best: Option<DataMapProbe> = None;
         let mut fallback: Option<u64> = None;
+        let mut probes: Vec<DataMapProbe> = Vec::new();
 
         while search_size <= MAX_SEARCH_SIZE {
             if self.load_buffer_around(base_hint, search_size).is_err() {
@@ -74,6 +113,7 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
                 let Some(probe) = self.probe_data_map_candidate(candidate) else {
                     continue;
                 };
+                probes.push(probe.clone());
 
                 let is_better = match &best {
                     None => true,
@@ -88,6 +128,11 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             search_size *= 2;
         }
 
+        let scores = probes.iter().map(|probe| (probe.addr, probe.score()));
+        let selected = best.as_ref().map(|probe| (probe.addr, probe.score()));
+        self.rankings
+            .insert("dataMap", CandidateRanking::from_scores(scores, selected));
+
         if let Some(probe) = best {
             debug!(
                 "  DataMap: selected 0x{:X} (valid_nodes={}, non_null_entries={}, table_size={})",
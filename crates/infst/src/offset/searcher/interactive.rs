@@ -1,18 +1,47 @@
 //! Interactive offset search functionality
 //!
 //! This module provides the interactive offset discovery process that guides
-//! users through finding game data structures in memory.
+//! users through finding game data structures in memory. The steps are shared
+//! with the scripted search in [`scripted`](super::scripted).
 
 use crate::error::Result;
 use crate::offset::OffsetsCollection;
 use crate::play::PlayType;
 use crate::process::ReadMemory;
+use crate::process::layout::settings;
 
 use super::OffsetSearcher;
 use super::constants::*;
-use super::types::{InteractiveSearchResult, JudgeInput, SearchPrompter};
+use super::types::{
+    InteractiveSearchResult, JudgeInput, SearchPrompter, SearchReporter, SettingsInput,
+};
 use super::utils::merge_byte_representations;
 
+/// Song played for the judge step (Sleepless Days SPA)
+pub const DISCOVERY_SONG_ID: u32 = 25094;
+/// Difficulty played for the judge step (SPA)
+pub const DISCOVERY_DIFFICULTY: u32 = 3;
+
+/// Settings of the two play settings steps
+///
+/// C# prompts: "RANDOM EXHARD OFF SUDDEN+" and "MIRROR EASY AUTO-SCRATCH HIDDEN+"
+pub const DISCOVERY_SETTINGS: [SettingsInput; 2] = [
+    // RANDOM=1, EXHARD=4 (C# value), OFF=0, SUDDEN+=1
+    SettingsInput {
+        style: 1,
+        gauge: 4,
+        assist: 0,
+        range: 1,
+    },
+    // MIRROR=4, EASY=2, AUTO-SCRATCH=1, HIDDEN+=2
+    SettingsInput {
+        style: 4,
+        gauge: 2,
+        assist: 1,
+        range: 2,
+    },
+];
+
 impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
     /// Run interactive offset search with user prompts
     ///
@@ -34,11 +63,71 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             ..Default::default()
         };
 
+        // Phase 1: Static patterns
+        self.discover_static_offsets(prompter, old_offsets, &mut new_offsets)?;
+
+        // Phase 2: Judge data (requires playing a song)
+        prompter.prompt_continue(
+            "Play Sleepless Days SPA, either fully or exit after hitting 50-ish notes or more, then press ENTER"
+        );
+
+        prompter.display_message("Enter your judge data:");
+        let judge = JudgeInput {
+            pgreat: prompter.prompt_number("Enter pgreat count: "),
+            great: prompter.prompt_number("Enter great count: "),
+            good: prompter.prompt_number("Enter good count: "),
+            bad: prompter.prompt_number("Enter bad count: "),
+            poor: prompter.prompt_number("Enter poor count: "),
+            combo_break: prompter.prompt_number("Enter combobreak count: "),
+            fast: prompter.prompt_number("Enter fast count: "),
+            slow: prompter.prompt_number("Enter slow count: "),
+        };
+
+        // Phase 3: Judge data, play data and current song
+        let play_type = self.discover_play_offsets(
+            prompter,
+            old_offsets,
+            &mut new_offsets,
+            &judge,
+            DISCOVERY_SONG_ID,
+            DISCOVERY_DIFFICULTY,
+        )?;
+
+        // Phase 4: Play settings (requires user to set specific options)
+        prompter.prompt_continue(
+            "Set the following settings and then press ENTER: RANDOM EXHARD OFF SUDDEN+",
+        );
+        let settings_addr1 =
+            self.discover_play_settings(prompter, old_offsets, &DISCOVERY_SETTINGS[0])?;
+
+        prompter.prompt_continue(
+            "Now set the following settings and then press ENTER: MIRROR EASY AUTO-SCRATCH HIDDEN+",
+        );
+        let settings_addr2 =
+            self.discover_play_settings(prompter, old_offsets, &DISCOVERY_SETTINGS[1])?;
+
+        new_offsets.play_settings =
+            resolve_play_settings(prompter, &[settings_addr1, settings_addr2], play_type);
+
+        prompter.display_message("Offset search complete!");
+
+        Ok(InteractiveSearchResult {
+            offsets: new_offsets,
+            play_type,
+        })
+    }
+
+    /// Search SongList, UnlockData and DataMap, which need no particular game state
+    pub(crate) fn discover_static_offsets<P: SearchReporter>(
+        &mut self,
+        prompter: &P,
+        old_offsets: &OffsetsCollection,
+        new_offsets: &mut OffsetsCollection,
+    ) -> Result<()> {
         // Use base address as default hint if old offsets are invalid
         let base = self.reader.base_address();
         let hint = |offset: u64| if offset == 0 { base } else { offset };
 
-        // Phase 1: Static patterns
         prompter.display_message("Searching for SongList...");
         new_offsets.song_list = self.search_song_list_offset(hint(old_offsets.song_list))?;
         prompter.display_message(&format!("Found SongList at 0x{:X}", new_offsets.song_list));
@@ -59,28 +148,26 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         };
         new_offsets.data_map = self.search_data_map_offset(data_map_hint)?;
         prompter.display_message(&format!("Found DataMap at 0x{:X}", new_offsets.data_map));
+        Ok(())
+    }
 
-        // Phase 2: Judge data (requires playing a song)
-        prompter.prompt_continue(
-            "Play Sleepless Days SPA, either fully or exit after hitting 50-ish notes or more, then press ENTER"
-        );
-
-        prompter.display_message("Enter your judge data:");
-        let judge = JudgeInput {
-            pgreat: prompter.prompt_number("Enter pgreat count: "),
-            great: prompter.prompt_number("Enter great count: "),
-            good: prompter.prompt_number("Enter good count: "),
-            bad: prompter.prompt_number("Enter bad count: "),
-            poor: prompter.prompt_number("Enter poor count: "),
-            combo_break: prompter.prompt_number("Enter combobreak count: "),
-            fast: prompter.prompt_number("Enter fast count: "),
-            slow: prompter.prompt_number("Enter slow count: "),
-        };
+    /// Search JudgeData, PlayData and CurrentSong after a play of the given chart
+    pub(crate) fn discover_play_offsets<P: SearchReporter>(
+        &mut self,
+        prompter: &P,
+        old_offsets: &OffsetsCollection,
+        new_offsets: &mut OffsetsCollection,
+        judge: &JudgeInput,
+        song_id: u32,
+        difficulty: u32,
+    ) -> Result<PlayType> {
+        let base = self.reader.base_address();
+        let hint = |offset: u64| if offset == 0 { base } else { offset };
 
         // Try P1 pattern first, then P2
         prompter.display_message("Searching for JudgeData...");
         let (judge_address, play_type) =
-            self.search_judge_data_with_playtype(hint(old_offsets.judge_data), &judge)?;
+            self.search_judge_data_with_playtype(hint(old_offsets.judge_data), judge)?;
         new_offsets.judge_data = judge_address;
         prompter.display_message(&format!(
             "Found JudgeData at 0x{:X} ({})",
@@ -88,22 +175,25 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             play_type.short_name()
         ));
 
-        // Phase 3: Play data and current song (Sleepless Days SPA = 25094, difficulty 3)
         let ex_score = judge.pgreat * 2 + judge.great;
         prompter.display_message("Searching for PlayData...");
-        new_offsets.play_data =
-            self.search_play_data_offset(hint(old_offsets.play_data), 25094, 3, ex_score)?;
+        new_offsets.play_data = self.search_play_data_offset(
+            hint(old_offsets.play_data),
+            song_id,
+            difficulty,
+            ex_score,
+        )?;
         prompter.display_message(&format!("Found PlayData at 0x{:X}", new_offsets.play_data));
 
         prompter.display_message("Searching for CurrentSong...");
         let current_song_addr =
-            self.search_current_song_offset(hint(old_offsets.current_song), 25094, 3)?;
+            self.search_current_song_offset(hint(old_offsets.current_song), song_id, difficulty)?;
         // Verify it's different from PlayData
         new_offsets.current_song = if current_song_addr == new_offsets.play_data {
             self.search_current_song_offset_excluding(
                 hint(old_offsets.current_song),
-                25094,
-                3,
+                song_id,
+                difficulty,
                 Some(new_offsets.play_data),
             )?
         } else {
@@ -113,59 +203,32 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             "Found CurrentSong at 0x{:X}",
             new_offsets.current_song
         ));
+        Ok(play_type)
+    }
 
-        // Phase 4: Play settings (requires user to set specific options)
-        // C# prompts: "RANDOM EXHARD OFF SUDDEN+" and "MIRROR EASY AUTO-SCRATCH HIDDEN+"
-        prompter.prompt_continue(
-            "Set the following settings and then press ENTER: RANDOM EXHARD OFF SUDDEN+",
-        );
-
-        prompter.display_message("Searching for PlaySettings...");
-        // RANDOM=1, EXHARD=4, OFF=0, SUDDEN+=1 (C# values)
-        let settings_addr1 = self.search_play_settings_offset(
-            hint(old_offsets.play_settings),
-            1, // RANDOM (style)
-            4, // EXHARD (gauge) - C# uses 4 for EXHARD
-            0, // OFF (assist)
-            1, // SUDDEN+ (range)
-        )?;
-
-        prompter.prompt_continue(
-            "Now set the following settings and then press ENTER: MIRROR EASY AUTO-SCRATCH HIDDEN+",
-        );
-
-        // MIRROR=4, EASY=2, AUTO-SCRATCH=1, HIDDEN+=2
-        let settings_addr2 = self.search_play_settings_offset(
-            hint(old_offsets.play_settings),
-            4, // MIRROR (style)
-            2, // EASY (gauge)
-            1, // AUTO-SCRATCH (assist)
-            2, // HIDDEN+ (range)
-        )?;
-
-        if settings_addr1 != settings_addr2 {
-            prompter
-                .display_warning("Warning: Settings addresses don't match between two searches!");
-        }
-
-        // Adjust for P2 offset if needed
-        new_offsets.play_settings = if play_type == PlayType::P2 {
-            use crate::play::Settings;
-            settings_addr1 - Settings::P2_OFFSET
+    /// Search PlaySettings while the given options are set
+    ///
+    /// Returns the address of the matching settings, which for P2 is offset
+    /// from the PlaySettings base (see [`resolve_play_settings`]).
+    pub(crate) fn discover_play_settings<P: SearchReporter>(
+        &mut self,
+        prompter: &P,
+        old_offsets: &OffsetsCollection,
+        settings: &SettingsInput,
+    ) -> Result<u64> {
+        let hint = if old_offsets.play_settings == 0 {
+            self.reader.base_address()
         } else {
-            settings_addr1
+            old_offsets.play_settings
         };
-        prompter.display_message(&format!(
-            "Found PlaySettings at 0x{:X}",
-            new_offsets.play_settings
-        ));
-
-        prompter.display_message("Offset search complete!");
-
-        Ok(InteractiveSearchResult {
-            offsets: new_offsets,
-            play_type,
-        })
+        prompter.display_message("Searching for PlaySettings...");
+        self.search_play_settings_offset(
+            hint,
+            settings.style,
+            settings.gauge,
+            settings.assist,
+            settings.range,
+        )
     }
 
     /// Search for judge data and determine play type
@@ -204,3 +267,25 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
         self.fetch_and_search(base_hint, &pattern, 0, exclude)
     }
 }
+
+/// PlaySettings base from the addresses found by each settings step
+///
+/// Warns if the steps disagree and uses the first one.
+pub(crate) fn resolve_play_settings<P: SearchReporter>(
+    prompter: &P,
+    addresses: &[u64],
+    play_type: PlayType,
+) -> u64 {
+    if addresses.windows(2).any(|pair| pair[0] != pair[1]) {
+        prompter.display_warning("Warning: Settings addresses don't match between two searches!");
+    }
+
+    // Adjust for P2 offset if needed
+    let address = if play_type == PlayType::P2 {
+        addresses[0] - settings::P2_OFFSET as u64
+    } else {
+        addresses[0]
+    };
+    prompter.display_message(&format!("Found PlaySettings at 0x{:X}", address));
+    address
+}
@@ -6,13 +6,12 @@
 use tracing::debug;
 
 use crate::error::{Error, Result};
-use crate::offset::{CodeSignature, OffsetSignatureSet};
-use crate::process::{ByteBuffer, ReadMemory};
+use crate::offset::{CodeSignature, OffsetSignatureSet, SignaturePattern};
+use crate::process::ReadMemory;
 
 use super::OffsetSearcher;
-use super::constants::{
-    CODE_SCAN_CHUNK_SIZE, CODE_SCAN_LIMIT, MIN_EXPECTED_SONGS, MIN_VALID_DATA_ADDRESS,
-};
+use super::constants::MIN_EXPECTED_SONGS;
+use super::pattern::{resolve_signature_matches, scan_plan_for_pattern};
 use super::validation::OffsetValidation;
 
 impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
@@ -78,6 +77,15 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
             Error::offset_search_failed("Signature entry 'songList' not found".to_string())
         })?;
 
+        // One pass for the whole set; later entries reuse the hits
+        if !self
+            .signature_hits
+            .as_ref()
+            .is_some_and(|hits| hits.is_for(signatures))
+        {
+            self.scan_signatures(signatures)?;
+        }
+
         for signature in &entry.signatures {
             let candidates = self.resolve_signature_targets(signature)?;
             let mut best: Option<(u64, usize)> = None;
@@ -173,138 +181,31 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
 
     /// Resolve signature to target addresses
     pub fn resolve_signature_targets(&self, signature: &CodeSignature) -> Result<Vec<u64>> {
-        let pattern = signature.pattern_bytes()?;
-        let matches = self.scan_code_for_pattern(&pattern)?;
-        let mut targets = Vec::new();
-
-        for match_addr in matches {
-            let instr_addr = match_addr + signature.instr_offset as u64;
-            let disp_addr = instr_addr + signature.disp_offset as u64;
-
-            let disp_bytes = match self.reader.read_bytes(disp_addr, 4) {
-                Ok(bytes) => bytes,
-                Err(_) => continue,
-            };
-
-            let disp = ByteBuffer::new(&disp_bytes).read_i32_at(0).unwrap_or(0);
-            let next_ip = instr_addr + signature.instr_len as u64;
-            let mut target = next_ip.wrapping_add_signed(disp as i64);
-
-            if signature.deref {
-                match self.reader.read_u64(target) {
-                    Ok(ptr) => target = ptr,
-                    Err(_) => continue,
-                }
-            }
-
-            if signature.addend != 0 {
-                target = target.wrapping_add_signed(signature.addend);
-            }
-
-            // Validate address is within expected range (above ImageBase)
-            if target < MIN_VALID_DATA_ADDRESS {
-                debug!(
-                    "  Rejecting invalid address 0x{:X} (below MIN_VALID_DATA_ADDRESS 0x{:X})",
-                    target, MIN_VALID_DATA_ADDRESS
-                );
-                continue;
-            }
-
-            if target != 0 {
-                targets.push(target);
-            }
-        }
-
-        targets.sort_unstable();
-        targets.dedup();
-        Ok(targets)
+        let cached = self
+            .signature_hits
+            .as_ref()
+            .and_then(|hits| hits.for_pattern(&signature.pattern))
+            .map(<[u64]>::to_vec);
+        let pattern = signature.parsed_pattern()?;
+        let matches = match cached {
+            Some(matches) => matches,
+            None => self.scan_code_for_pattern(&pattern)?,
+        };
+        Ok(resolve_signature_matches(
+            self.reader,
+            signature,
+            &pattern,
+            &matches,
+        ))
     }
 
-    /// Scan code section for a pattern with wildcards
-    pub fn scan_code_for_pattern(&self, pattern: &[Option<u8>]) -> Result<Vec<u64>> {
-        let base = self.reader.base_address();
-        let mut results: Vec<u64> = Vec::new();
-        let mut offset: u64 = 0;
-        let mut scanned: usize = 0;
-        let mut tail: Vec<u8> = Vec::new();
-
-        while scanned < CODE_SCAN_LIMIT {
-            let remaining = CODE_SCAN_LIMIT - scanned;
-            let read_size = remaining.min(CODE_SCAN_CHUNK_SIZE);
-            let addr = base + offset;
-
-            let chunk = match self.reader.read_bytes(addr, read_size) {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    if scanned == 0 {
-                        return Err(Error::offset_search_failed(format!(
-                            "Failed to read code section: {}",
-                            e
-                        )));
-                    }
-                    debug!(
-                        "Code scan stopped at offset {:#x} (scanned {:#x} bytes): {}",
-                        offset, scanned, e
-                    );
-                    break;
-                }
-            };
-
-            let mut data = Vec::with_capacity(tail.len() + chunk.len());
-            data.extend_from_slice(&tail);
-            data.extend_from_slice(&chunk);
-
-            let data_base = addr.saturating_sub(tail.len() as u64);
-            results.extend(self.find_matches_with_wildcards(&data, data_base, pattern));
-
-            if pattern.len() > 1 {
-                let keep = pattern.len() - 1;
-                if data.len() >= keep {
-                    tail = data[data.len() - keep..].to_vec();
-                } else {
-                    tail = data;
-                }
-            } else {
-                tail.clear();
-            }
-
-            scanned += read_size;
-            offset += read_size as u64;
-        }
-
-        results.sort_unstable();
-        results.dedup();
+    /// Scan code section for a signature pattern
+    pub fn scan_code_for_pattern(&self, pattern: &SignaturePattern) -> Result<Vec<u64>> {
+        let (results, report) = scan_plan_for_pattern(self.reader, self.code_plan(), pattern)?;
+        report.log_skipped("Code scan");
         Ok(results)
     }
 
-    /// Find all matches of a pattern with wildcards in a buffer
-    fn find_matches_with_wildcards(
-        &self,
-        buffer: &[u8],
-        base_addr: u64,
-        pattern: &[Option<u8>],
-    ) -> Vec<u64> {
-        if pattern.is_empty() || buffer.len() < pattern.len() {
-            return Vec::new();
-        }
-
-        let mut results = Vec::new();
-        let last = buffer.len() - pattern.len();
-
-        'outer: for i in 0..=last {
-            for (j, byte) in pattern.iter().enumerate() {
-                if let Some(value) = byte
-                    && buffer[i + j] != *value
-                {
-                    continue 'outer;
-                }
-            }
-            results.push(base_addr + i as u64);
-        }
-
-        results
-    }
-
     /// Search for code that references a specific data address
     ///
     /// Looks for x64 RIP-relative LEA/MOV instructions.
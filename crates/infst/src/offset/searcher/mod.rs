@@ -13,8 +13,13 @@
 //! - [`data_map`]: DataMap and UnlockData detection
 //! - [`buffer`]: Buffer management and pattern search helpers
 //! - [`interactive`]: User-guided offset discovery workflow
+//! - [`scripted`]: The same workflow driven by a script of expected values
 //! - [`validation`]: Offset validation functions
 //! - [`pattern`]: Pattern search utilities
+//! - [`ranking`]: Scored candidates kept alongside each search
+//! - [`priors`]: Search windows learned from offset relations of past versions
+//! - [`signature_scan`]: Single-pass scan for a whole signature set
+//! - [`signature_gen`]: Signature generation from known-good offsets
 //! - [`legacy`]: Legacy signature-based search (feature-gated)
 //!
 //! ## Search Strategy
@@ -36,9 +41,14 @@ mod interactive;
 #[cfg(feature = "legacy-signatures")]
 pub mod legacy;
 pub mod pattern;
+mod priors;
+mod ranking;
 mod relative_search;
+mod scripted;
 #[cfg(feature = "legacy-signatures")]
 pub mod search;
+pub mod signature_gen;
+pub mod signature_scan;
 mod song_list;
 mod types;
 mod utils;
@@ -46,11 +56,23 @@ pub mod validation;
 
 // Re-export core types
 pub use core::{OffsetSearcher, OffsetSearcherBuilder};
+pub use interactive::{DISCOVERY_DIFFICULTY, DISCOVERY_SETTINGS, DISCOVERY_SONG_ID};
+pub use priors::{
+    Relation, RelationHistory, RelationPriors, RelationStats, SearchWindow, record_offset_relations,
+};
+pub use ranking::{CandidateRanking, OffsetRankings, ScoredCandidate, TOP_CANDIDATES};
+pub use scripted::{
+    ScriptPlay, ScriptSettings, SearchScript, run_live_search_script, run_search_script,
+};
+pub use signature_gen::SignatureGenerator;
+pub use signature_scan::{SignatureHit, SignatureHits, SignatureScanner};
 pub use types::*;
 pub use utils::merge_byte_representations;
 
+pub(crate) use constants::IMAGE_BASE;
+
 // Re-export validation functions and trait
 pub use validation::{
-    OffsetValidation, validate_basic_memory_access, validate_new_version_text_table,
-    validate_signature_offsets,
+    OffsetValidation, ScoreCheck, ValidationScore, validate_basic_memory_access,
+    validate_new_version_text_table, validate_signature_offsets,
 };
@@ -6,8 +6,8 @@
 use tracing::debug;
 
 use crate::error::{Error, Result};
-use crate::offset::CodeSignature;
-use crate::process::ReadMemory;
+use crate::offset::{CodeSignature, SignaturePattern};
+use crate::process::{ChunkedMemoryIterator, MultiPattern, RangePlan, ReadMemory, ScanReport};
 
 use super::constants::*;
 use super::types::SearchResult;
@@ -77,25 +77,7 @@ impl<'a, R: ReadMemory> PatternSearcher<'a, R> {
         base_addr: u64,
         pattern: &[Option<u8>],
     ) -> Vec<u64> {
-        if pattern.is_empty() || buffer.len() < pattern.len() {
-            return Vec::new();
-        }
-
-        let mut results = Vec::new();
-        let last = buffer.len() - pattern.len();
-
-        'outer: for i in 0..=last {
-            for (j, byte) in pattern.iter().enumerate() {
-                if let Some(value) = byte
-                    && buffer[i + j] != *value
-                {
-                    continue 'outer;
-                }
-            }
-            results.push(base_addr + i as u64);
-        }
-
-        results
+        find_matches_with_wildcards(buffer, base_addr, pattern)
     }
 
     /// Search for a pattern with progressive buffer expansion
@@ -196,112 +178,188 @@ impl<'a, R: ReadMemory> PatternSearcher<'a, R> {
         )))
     }
 
-    /// Scan code section for a byte pattern with wildcards
-    pub fn scan_code_for_pattern(&self, pattern: &[Option<u8>]) -> Result<Vec<u64>> {
-        let base = self.reader.base_address();
-        let mut results: Vec<u64> = Vec::new();
-        let mut offset: u64 = 0;
-        let mut scanned: usize = 0;
-        let mut tail: Vec<u8> = Vec::new();
-
-        while scanned < CODE_SCAN_LIMIT {
-            let remaining = CODE_SCAN_LIMIT - scanned;
-            let read_size = remaining.min(CODE_SCAN_CHUNK_SIZE);
-            let addr = base + offset;
-
-            let chunk = match self.reader.read_bytes(addr, read_size) {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    if scanned == 0 {
-                        return Err(Error::offset_search_failed(format!(
-                            "Failed to read code section: {}",
-                            e
-                        )));
-                    }
-                    debug!(
-                        "Code scan stopped at offset {:#x} (scanned {:#x} bytes): {}",
-                        offset, scanned, e
-                    );
-                    break;
-                }
-            };
-
-            let mut data = Vec::with_capacity(tail.len() + chunk.len());
-            data.extend_from_slice(&tail);
-            data.extend_from_slice(&chunk);
-
-            let data_base = addr.saturating_sub(tail.len() as u64);
-            results.extend(self.find_matches_with_wildcards(&data, data_base, pattern));
-
-            if pattern.len() > 1 {
-                let keep = pattern.len() - 1;
-                if data.len() >= keep {
-                    tail = data[data.len() - keep..].to_vec();
-                } else {
-                    tail = data;
-                }
-            } else {
-                tail.clear();
-            }
-
-            scanned += read_size;
-            offset += read_size as u64;
-        }
-
-        results.sort_unstable();
-        results.dedup();
+    /// Scan code section for a signature pattern
+    pub fn scan_code_for_pattern(&self, pattern: &SignaturePattern) -> Result<Vec<u64>> {
+        let (results, report) = scan_code_for_pattern_with_report(self.reader, pattern)?;
+        report.log_skipped("Code scan");
         Ok(results)
     }
 
     /// Resolve signature targets from code references
     pub fn resolve_signature_targets(&self, signature: &CodeSignature) -> Result<Vec<u64>> {
-        let pattern = signature.pattern_bytes()?;
+        let pattern = signature.parsed_pattern()?;
         let matches = self.scan_code_for_pattern(&pattern)?;
-        let mut targets = Vec::new();
-
-        for match_addr in matches {
-            let instr_addr = match_addr + signature.instr_offset as u64;
-            let disp_addr = instr_addr + signature.disp_offset as u64;
+        Ok(resolve_signature_matches(
+            self.reader,
+            signature,
+            &pattern,
+            &matches,
+        ))
+    }
+}
 
-            let disp_bytes = match self.reader.read_bytes(disp_addr, 4) {
-                Ok(bytes) => bytes,
+/// Resolve pattern matches of a signature to the data addresses they reference
+///
+/// Each match is re-read and re-matched to find its capture points, then the
+/// RIP-relative displacement is applied (plus `deref` and `addend`). Targets
+/// below [`MIN_VALID_DATA_ADDRESS`] are rejected.
+pub fn resolve_signature_matches<R: ReadMemory + ?Sized>(
+    reader: &R,
+    signature: &CodeSignature,
+    pattern: &SignaturePattern,
+    matches: &[u64],
+) -> Vec<u64> {
+    let mut targets = Vec::new();
+
+    for &match_addr in matches {
+        let Some(bytes) = reader
+            .read_bytes(match_addr, pattern.max_len())
+            .or_else(|_| reader.read_bytes(match_addr, pattern.min_len()))
+            .ok()
+        else {
+            continue;
+        };
+        let Some(found) = pattern.match_at(&bytes) else {
+            continue;
+        };
+        let location = signature.disp_location(&found);
+
+        let disp_addr = match_addr + location.disp as u64;
+        let disp = match reader.read_i32(disp_addr) {
+            Ok(disp) => disp,
+            Err(_) => continue,
+        };
+        let next_ip = match_addr + location.next as u64;
+        let mut target = next_ip.wrapping_add_signed(disp as i64);
+
+        if signature.deref {
+            match reader.read_u64(target) {
+                Ok(ptr) => target = ptr,
                 Err(_) => continue,
-            };
+            }
+        }
 
-            let disp =
-                i32::from_le_bytes([disp_bytes[0], disp_bytes[1], disp_bytes[2], disp_bytes[3]]);
-            let next_ip = instr_addr + signature.instr_len as u64;
-            let mut target = next_ip.wrapping_add_signed(disp as i64);
+        if signature.addend != 0 {
+            target = target.wrapping_add_signed(signature.addend);
+        }
 
-            if signature.deref {
-                match self.reader.read_u64(target) {
-                    Ok(ptr) => target = ptr,
-                    Err(_) => continue,
-                }
-            }
+        // Validate address is within expected range (above ImageBase)
+        if target < MIN_VALID_DATA_ADDRESS {
+            debug!(
+                "  Rejecting invalid address 0x{:X} (below MIN_VALID_DATA_ADDRESS 0x{:X})",
+                target, MIN_VALID_DATA_ADDRESS
+            );
+            continue;
+        }
 
-            if signature.addend != 0 {
-                target = target.wrapping_add_signed(signature.addend);
-            }
+        if target != 0 {
+            targets.push(target);
+        }
+    }
+
+    targets.sort_unstable();
+    targets.dedup();
+    targets
+}
+
+/// Readable spans of the code section (from the module base)
+pub fn code_section_plan<R: ReadMemory + ?Sized>(reader: &R) -> RangePlan {
+    let base = reader.base_address();
+    RangePlan::for_reader(reader, base, base + CODE_SCAN_LIMIT as u64)
+}
+
+/// Scan the code section (from the module base) for a signature pattern
+///
+/// Unreadable regions are skipped and listed in the report; a match never
+/// spans a skipped gap. Fails only if nothing could be read at all.
+pub fn scan_code_for_pattern_with_report<R: ReadMemory + ?Sized>(
+    reader: &R,
+    pattern: &SignaturePattern,
+) -> Result<(Vec<u64>, ScanReport)> {
+    scan_plan_for_pattern(reader, code_section_plan(reader), pattern)
+}
 
-            // Validate address is within expected range (above ImageBase)
-            if target < MIN_VALID_DATA_ADDRESS {
-                debug!(
-                    "  Rejecting invalid address 0x{:X} (below MIN_VALID_DATA_ADDRESS 0x{:X})",
-                    target, MIN_VALID_DATA_ADDRESS
-                );
+/// Scan the readable spans of a code section plan for a signature pattern
+pub fn scan_plan_for_pattern<R: ReadMemory + ?Sized>(
+    reader: &R,
+    plan: RangePlan,
+    pattern: &SignaturePattern,
+) -> Result<(Vec<u64>, ScanReport)> {
+    let matcher = MultiPattern::new(vec![pattern.clone()]);
+    let mut expected_address = plan.readable.first().map_or(0, |span| span.start);
+    let mut chunks = ChunkedMemoryIterator::with_plan(reader, plan, CODE_SCAN_CHUNK_SIZE);
+    let mut results: Vec<u64> = Vec::new();
+    let mut tail: Vec<u8> = Vec::new();
+    let mut last_error = None;
+
+    for chunk in chunks.by_ref() {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                last_error = Some(e);
                 continue;
             }
+        };
 
-            if target != 0 {
-                targets.push(target);
-            }
+        if chunk.address != expected_address {
+            tail.clear();
         }
+        expected_address = chunk.end();
+
+        let mut data = Vec::with_capacity(tail.len() + chunk.data.len());
+        data.extend_from_slice(&tail);
+        data.extend_from_slice(&chunk.data);
+
+        let data_base = chunk.address - tail.len() as u64;
+        let matches = matcher.find_all(&data).pop().unwrap_or_default();
+        results.extend(matches.into_iter().map(|pos| data_base + pos as u64));
+
+        let keep = pattern.max_len().saturating_sub(1);
+        tail = data[data.len().saturating_sub(keep)..].to_vec();
+    }
+
+    let report = chunks.into_report();
+    if report.scanned_bytes == 0 {
+        let reason = match last_error {
+            Some(e) => e.to_string(),
+            None => "no readable memory".to_string(),
+        };
+        return Err(Error::offset_search_failed(format!(
+            "Failed to read code section: {}",
+            reason
+        )));
+    }
+
+    results.sort_unstable();
+    results.dedup();
+    Ok((results, report))
+}
 
-        targets.sort_unstable();
-        targets.dedup();
-        Ok(targets)
+/// Find all matches of a pattern with wildcards in a buffer
+pub fn find_matches_with_wildcards(
+    buffer: &[u8],
+    base_addr: u64,
+    pattern: &[Option<u8>],
+) -> Vec<u64> {
+    if pattern.is_empty() || buffer.len() < pattern.len() {
+        return Vec::new();
     }
+
+    let mut results = Vec::new();
+    let last = buffer.len() - pattern.len();
+
+    'outer: for i in 0..=last {
+        for (j, byte) in pattern.iter().enumerate() {
+            if let Some(value) = byte
+                && buffer[i + j] != *value
+            {
+                continue 'outer;
+            }
+        }
+        results.push(base_addr + i as u64);
+    }
+
+    results
 }
 
 #[cfg(test)]
@@ -357,4 +415,57 @@ mod tests {
         assert_eq!(matches[1], 0x1003);
         assert_eq!(matches[2], 0x1006);
     }
+
+    #[test]
+    fn test_scan_code_skips_unmapped_gap() {
+        let reader = MockMemoryBuilder::new()
+            .base(0x1000)
+            .write_bytes(0x10, &[0x48, 0x8D, 0x0D])
+            .unmapped(0x100, 0x100)
+            .write_bytes(0x300, &[0x48, 0x8D, 0x0D])
+            .build();
+
+        let pattern = "48 8D ??".parse().unwrap();
+        let (matches, report) = scan_code_for_pattern_with_report(&reader, &pattern).unwrap();
+
+        assert_eq!(matches, vec![0x1010, 0x1300]);
+        assert_eq!(report.skipped[0], 0x1100..0x1200);
+    }
+
+    #[test]
+    fn test_scan_code_fails_without_readable_memory() {
+        let reader = MockMemoryBuilder::new().build();
+        let pattern = "48".parse().unwrap();
+        assert!(scan_code_for_pattern_with_report(&reader, &pattern).is_err());
+    }
+
+    #[test]
+    fn test_resolve_signature_with_gap_and_captures() {
+        const BASE: u64 = 0x140000000;
+        // lea rcx/rax, [rip+disp] behind a variable-length prefix
+        let reader = MockMemoryBuilder::new()
+            .base(BASE)
+            .with_size(0x4000)
+            .write_bytes(0x100, &[0x33, 0xC0, 0x90, 0x48, 0x8D, 0x0D])
+            .write_i32(0x106, 0x100_1000)
+            .write_bytes(0x200, &[0x33, 0xC0, 0x48, 0x8D, 0x05])
+            .write_i32(0x205, 0x100_2000)
+            .build();
+        let signature = CodeSignature {
+            pattern: "33 C0 [0-2] 48 8D (05|0D) <disp> ?? ?? ?? ??".to_string(),
+            instr_offset: 0,
+            disp_offset: 0,
+            instr_len: 0,
+            deref: false,
+            addend: 0,
+        };
+
+        let searcher = PatternSearcher::new(&reader);
+        let targets = searcher.resolve_signature_targets(&signature).unwrap();
+
+        assert_eq!(
+            targets,
+            vec![BASE + 0x10A + 0x100_1000, BASE + 0x209 + 0x100_2000]
+        );
+    }
 }
@@ -0,0 +1,474 @@
+//! Learned relative-offset priors
+//!
+//! The relative searches start from hard-coded distances between structures
+//! (see [`constants`](super::constants)). Every successful search also records
+//! the [`OffsetRelations`] of its game version in a history file
+//! (`dirs::cache_dir()/infst/relations.json`). The spread of those relations
+//! across versions gives each relative search a likely window, searched
+//! first, before falling back to the full hard-coded range.
+//!
+//! Without history the priors are the hard-coded values.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::constants::*;
+use super::relative_search::addresses_near;
+use crate::offset::{OffsetRelations, OffsetsCollection};
+
+/// History file name inside the infst cache directory
+const HISTORY_FILE: &str = "relations.json";
+
+/// z-score of the prediction interval around the mean drift (95%)
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// Slack added around the observed drift when sizing the likely window
+const LIKELY_WINDOW_MARGIN: u64 = 0x20;
+
+/// A distance the relative search relies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relation {
+    /// JudgeData relative to SongList
+    JudgeDataFromSongList,
+    /// PlaySettings relative to JudgeData
+    PlaySettingsFromJudgeData,
+    /// PlayData relative to PlaySettings
+    PlayDataFromPlaySettings,
+    /// CurrentSong relative to JudgeData
+    CurrentSongFromJudgeData,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 4] = [
+        Relation::JudgeDataFromSongList,
+        Relation::PlaySettingsFromJudgeData,
+        Relation::PlayDataFromPlaySettings,
+        Relation::CurrentSongFromJudgeData,
+    ];
+
+    /// Distance of this relation in the recorded relations
+    pub fn delta(self, relations: &OffsetRelations) -> i64 {
+        match self {
+            Relation::JudgeDataFromSongList => relations.judge_data_from_song_list(),
+            Relation::PlaySettingsFromJudgeData => relations.play_settings_from_judge_data,
+            Relation::PlayDataFromPlaySettings => relations.play_data_from_play_settings,
+            Relation::CurrentSongFromJudgeData => relations.current_song_from_judge_data(),
+        }
+    }
+
+    /// Hard-coded search window used without history
+    pub fn default_window(self) -> SearchWindow {
+        let (expected, range) = match self {
+            Relation::JudgeDataFromSongList => {
+                (-(JUDGE_TO_SONG_LIST as i64), JUDGE_DATA_SEARCH_RANGE)
+            }
+            Relation::PlaySettingsFromJudgeData => {
+                (-(JUDGE_TO_PLAY_SETTINGS as i64), PLAY_SETTINGS_SEARCH_RANGE)
+            }
+            Relation::PlayDataFromPlaySettings => {
+                (PLAY_SETTINGS_TO_PLAY_DATA as i64, PLAY_DATA_SEARCH_RANGE)
+            }
+            Relation::CurrentSongFromJudgeData => {
+                (JUDGE_TO_CURRENT_SONG as i64, CURRENT_SONG_SEARCH_RANGE)
+            }
+        };
+        SearchWindow {
+            expected,
+            likely: range as u64,
+            range: range as u64,
+        }
+    }
+}
+
+/// Drift of a relation across recorded versions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelationStats {
+    pub samples: usize,
+    pub mean: f64,
+    /// Sample standard deviation (0 with a single sample)
+    pub std_dev: f64,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl RelationStats {
+    /// Statistics of the given deltas, or None without samples
+    pub fn from_deltas(deltas: &[i64]) -> Option<Self> {
+        let samples = deltas.len();
+        let min = *deltas.iter().min()?;
+        let max = *deltas.iter().max()?;
+        let mean = deltas.iter().map(|&d| d as f64).sum::<f64>() / samples as f64;
+        let std_dev = if samples > 1 {
+            let variance = deltas
+                .iter()
+                .map(|&d| (d as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (samples - 1) as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+        Some(Self {
+            samples,
+            mean,
+            std_dev,
+            min,
+            max,
+        })
+    }
+
+    /// Half-width of the 95% prediction interval for the next version
+    pub fn prediction_half_width(&self) -> f64 {
+        CONFIDENCE_Z * self.std_dev * (1.0 + 1.0 / self.samples as f64).sqrt()
+    }
+}
+
+/// Where to look for a structure relative to its anchor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchWindow {
+    /// Most probable distance from the anchor
+    pub expected: i64,
+    /// Radius around the expected position that is searched first
+    pub likely: u64,
+    /// Radius searched when nothing valid lies within `likely`
+    pub range: u64,
+}
+
+impl SearchWindow {
+    /// Window centred on the observed drift of a relation
+    ///
+    /// The likely region covers the observed min/max and the prediction
+    /// interval; the full range still covers the hard-coded window.
+    pub fn from_stats(stats: &RelationStats, default: SearchWindow) -> Self {
+        let expected = (stats.mean / 4.0).round() as i64 * 4;
+        let spread = (stats.max - expected)
+            .unsigned_abs()
+            .max((expected - stats.min).unsigned_abs())
+            .max(stats.prediction_half_width().ceil() as u64);
+        let likely = (spread + LIKELY_WINDOW_MARGIN).next_multiple_of(4);
+        let range = (default.range + expected.abs_diff(default.expected))
+            .max(likely)
+            .next_multiple_of(4);
+        Self {
+            expected,
+            likely: likely.min(range),
+            range,
+        }
+    }
+
+    /// Expected address for the given anchor
+    pub fn center(&self, anchor: u64) -> u64 {
+        anchor.wrapping_add_signed(self.expected)
+    }
+
+    /// Whether an address lies in the likely region
+    pub fn is_likely(&self, anchor: u64, addr: u64) -> bool {
+        addr.abs_diff(self.center(anchor)) <= self.likely
+    }
+
+    /// Aligned candidates, the likely region first, each stage nearest first
+    pub fn addresses(&self, anchor: u64) -> impl Iterator<Item = u64> + use<> {
+        let center = self.center(anchor);
+        let likely = self.likely;
+        addresses_near(center, likely as usize).chain(
+            addresses_near(center, self.range as usize)
+                .filter(move |addr| addr.abs_diff(center) > likely),
+        )
+    }
+}
+
+/// Search windows for every relation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationPriors {
+    windows: BTreeMap<Relation, SearchWindow>,
+}
+
+impl Default for RelationPriors {
+    /// The hard-coded windows
+    fn default() -> Self {
+        Self {
+            windows: Relation::ALL
+                .into_iter()
+                .map(|relation| (relation, relation.default_window()))
+                .collect(),
+        }
+    }
+}
+
+impl RelationPriors {
+    /// Windows learned from the given history
+    ///
+    /// Relations without samples keep their hard-coded window.
+    pub fn from_history(history: &RelationHistory) -> Self {
+        let mut priors = Self::default();
+        for relation in Relation::ALL {
+            if let Some(stats) = history.stats(relation) {
+                let window = SearchWindow::from_stats(&stats, relation.default_window());
+                priors.windows.insert(relation, window);
+            }
+        }
+        priors
+    }
+
+    /// Windows learned from the history in the user cache directory
+    pub fn learned() -> Self {
+        let history = RelationHistory::load();
+        debug!(
+            "Deriving relative search windows from {} recorded versions",
+            history.len()
+        );
+        Self::from_history(&history)
+    }
+
+    /// Search window for a relation
+    pub fn window(&self, relation: Relation) -> SearchWindow {
+        self.windows
+            .get(&relation)
+            .copied()
+            .unwrap_or_else(|| relation.default_window())
+    }
+}
+
+/// Offset relations keyed by game version
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationHistory {
+    entries: BTreeMap<String, OffsetRelations>,
+}
+
+impl RelationHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default history file path in the user cache directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("infst").join(HISTORY_FILE))
+    }
+
+    /// Load the history from the default path
+    ///
+    /// Returns an empty history if the file is missing or unreadable.
+    pub fn load() -> Self {
+        match Self::default_path() {
+            Some(path) => Self::load_from_path(path),
+            None => {
+                debug!("No user cache directory available");
+                Self::new()
+            }
+        }
+    }
+
+    /// Load the history from a specific path
+    ///
+    /// Returns an empty history if the file is missing or unreadable.
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("Relation history not found or unreadable: {}", e);
+                return Self::new();
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(history) => history,
+            Err(e) => {
+                warn!("Failed to parse relation history: {}", e);
+                Self::new()
+            }
+        }
+    }
+
+    /// Save the history to the default path
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = Self::default_path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no user cache directory available",
+            )
+        })?;
+        self.save_to_path(path)
+    }
+
+    /// Save the history to a specific path, creating the parent directory if needed
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
+        let path = path.as_ref();
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        debug!("Saved relation history to {}", path.display());
+        Ok(())
+    }
+
+    /// Record the relations of a game version, replacing earlier ones
+    pub fn insert(&mut self, game_version: &str, relations: OffsetRelations) {
+        self.entries.insert(game_version.to_string(), relations);
+    }
+
+    pub fn get(&self, game_version: &str) -> Option<&OffsetRelations> {
+        self.entries.get(game_version)
+    }
+
+    /// Drift statistics of a relation across recorded versions
+    pub fn stats(&self, relation: Relation) -> Option<RelationStats> {
+        let deltas: Vec<i64> = self
+            .entries
+            .values()
+            .map(|relations| relation.delta(relations))
+            .collect();
+        RelationStats::from_deltas(&deltas)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Record the relations of complete offsets in the user history
+///
+/// Incomplete offsets or offsets without a game version are not recorded.
+pub fn record_offset_relations(offsets: &OffsetsCollection, base: u64) {
+    if !offsets.is_valid() || offsets.version == "unknown" {
+        return;
+    }
+    let mut history = RelationHistory::load();
+    let relations = OffsetRelations::from_offsets(offsets, base);
+    if history.get(&offsets.version) == Some(&relations) {
+        return;
+    }
+    history.insert(&offsets.version, relations);
+    match history.save() {
+        Ok(()) => info!(
+            "Recorded offset relations for {} ({} versions known)",
+            offsets.version,
+            history.len()
+        ),
+        Err(e) => warn!("Failed to save relation history: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::mock::{GAME_BASE, GAME_VERSION};
+
+    /// Offsets with the given relative layout
+    fn offsets(judge_from_song_list: u64, play_settings_from_judge: u64) -> OffsetsCollection {
+        let song_list = 0x1431865A0;
+        let judge_data = song_list - judge_from_song_list;
+        let play_settings = judge_data - play_settings_from_judge;
+        OffsetsCollection {
+            version: GAME_VERSION.to_string(),
+            song_list,
+            data_map: song_list + 0x1000,
+            unlock_data: song_list + 0x2000,
+            judge_data,
+            play_settings,
+            play_data: play_settings + 0x2A0,
+            current_song: judge_data + 0x1E4,
+        }
+    }
+
+    fn history(layouts: &[(u64, u64)]) -> RelationHistory {
+        let mut history = RelationHistory::new();
+        for (i, &(a, b)) in layouts.iter().enumerate() {
+            let relations = OffsetRelations::from_offsets(&offsets(a, b), GAME_BASE);
+            history.insert(&format!("P2D:J:B:A:20260101{:02}", i), relations);
+        }
+        history
+    }
+
+    #[test]
+    fn test_relations_derive_search_deltas() {
+        let relations = OffsetRelations::from_offsets(&offsets(0x94E4B4, 0x2ACFA8), GAME_BASE);
+        assert_eq!(Relation::JudgeDataFromSongList.delta(&relations), -0x94E4B4);
+        assert_eq!(
+            Relation::PlaySettingsFromJudgeData.delta(&relations),
+            -0x2ACFA8
+        );
+        assert_eq!(Relation::PlayDataFromPlaySettings.delta(&relations), 0x2A0);
+        assert_eq!(Relation::CurrentSongFromJudgeData.delta(&relations), 0x1E4);
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = RelationStats::from_deltas(&[-0x2ACEE8, -0x2ACFA8]).unwrap();
+        assert_eq!(stats.samples, 2);
+        assert_eq!(stats.min, -0x2ACFA8);
+        assert_eq!(stats.max, -0x2ACEE8);
+        assert_eq!(stats.mean, -0x2ACF48 as f64);
+        assert!((stats.std_dev - 135.76).abs() < 0.01);
+        assert!(RelationStats::from_deltas(&[]).is_none());
+    }
+
+    #[test]
+    fn test_priors_without_history_are_hard_coded() {
+        let priors = RelationPriors::from_history(&RelationHistory::new());
+        assert_eq!(priors, RelationPriors::default());
+        let window = priors.window(Relation::JudgeDataFromSongList);
+        assert_eq!(window.expected, -(JUDGE_TO_SONG_LIST as i64));
+        assert_eq!(window.likely, JUDGE_DATA_SEARCH_RANGE as u64);
+    }
+
+    #[test]
+    fn test_learned_window_covers_observed_drift() {
+        let history = history(&[(0x94E374, 0x2ACEE8), (0x94E4B4, 0x2ACFA8)]);
+        let priors = RelationPriors::from_history(&history);
+
+        let window = priors.window(Relation::PlaySettingsFromJudgeData);
+        assert_eq!(window.expected, -0x2ACF48);
+        // Prediction interval (~326 bytes) exceeds the observed min/max
+        assert!(window.likely > 0x60 && window.likely < PLAY_SETTINGS_SEARCH_RANGE as u64);
+        assert!(window.range >= PLAY_SETTINGS_SEARCH_RANGE as u64);
+
+        let window = priors.window(Relation::JudgeDataFromSongList);
+        assert_eq!(window.expected, -0x94E414);
+        assert!(window.likely < 0x400);
+        // Still covers the hard-coded window when widening
+        let default = Relation::JudgeDataFromSongList.default_window();
+        assert!(window.range >= default.range + window.expected.abs_diff(default.expected));
+    }
+
+    #[test]
+    fn test_window_searches_likely_region_first() {
+        let window = SearchWindow {
+            expected: 0x100,
+            likely: 0x8,
+            range: 0x10,
+        };
+        let addresses: Vec<u64> = window.addresses(0x1000).collect();
+        assert_eq!(
+            addresses,
+            [
+                0x1100, 0x10FC, 0x1104, 0x10F8, 0x1108, 0x10F4, 0x110C, 0x10F0, 0x1110
+            ]
+        );
+        assert!(window.is_likely(0x1000, 0x1108));
+        assert!(!window.is_likely(0x1000, 0x110C));
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("infst").join(HISTORY_FILE);
+        let history = history(&[(0x94E374, 0x2ACEE8)]);
+
+        history.save_to_path(&path).unwrap();
+        assert_eq!(RelationHistory::load_from_path(&path), history);
+        assert!(RelationHistory::load_from_path(dir.path().join("missing.json")).is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Ranked offset candidates
+//!
+//! The searcher still picks one address per offset using its own rules
+//! (first valid candidate nearest the expected position, most songs, ...).
+//! Alongside that choice it scores the other candidates it came across, so a
+//! wrong pick can be diagnosed by comparing it against the runner-up.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::process::{CachingReader, ReadMemory};
+
+use super::OffsetSearcher;
+use super::validation::ValidationScore;
+
+/// Number of candidates kept per offset
+pub const TOP_CANDIDATES: usize = 5;
+
+/// A candidate address with its validation score
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoredCandidate {
+    #[serde(serialize_with = "serialize_hex")]
+    pub address: u64,
+    /// Score from 0.0 to 1.0
+    pub score: f32,
+    /// Checks behind the score, failed checks first
+    pub reasons: Vec<String>,
+}
+
+impl ScoredCandidate {
+    pub fn new(address: u64, score: &ValidationScore) -> Self {
+        Self {
+            address,
+            score: score.score,
+            reasons: score.reasons(),
+        }
+    }
+}
+
+/// Top valid candidates for a single offset
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CandidateRanking {
+    /// Candidate the searcher picked, if any
+    pub selected: Option<ScoredCandidate>,
+    /// Valid candidates, best first; ties keep the order they were found in
+    pub candidates: Vec<ScoredCandidate>,
+    /// Score difference between the best and second-best candidates
+    pub margin: Option<f32>,
+}
+
+impl CandidateRanking {
+    /// Rank scored candidates, keeping the best [`TOP_CANDIDATES`] valid ones
+    ///
+    /// Candidates should be given in the searcher's order of preference so
+    /// that equal scores rank the way the searcher would pick them.
+    pub fn from_scores<I>(scores: I, selected: Option<(u64, ValidationScore)>) -> Self
+    where
+        I: IntoIterator<Item = (u64, ValidationScore)>,
+    {
+        let mut ranking = Self::default();
+        for (address, score) in scores {
+            ranking.consider(address, &score);
+        }
+        ranking.selected = selected.map(|(address, score)| ScoredCandidate::new(address, &score));
+        ranking
+    }
+
+    /// Add a candidate if it is valid and scores within the top N
+    pub fn consider(&mut self, address: u64, score: &ValidationScore) {
+        if !score.is_valid() || self.candidates.iter().any(|c| c.address == address) {
+            return;
+        }
+        let position = self
+            .candidates
+            .iter()
+            .position(|c| c.score < score.score)
+            .unwrap_or(self.candidates.len());
+        if position >= TOP_CANDIDATES {
+            return;
+        }
+        self.candidates
+            .insert(position, ScoredCandidate::new(address, score));
+        self.candidates.truncate(TOP_CANDIDATES);
+        self.margin = self
+            .runner_up()
+            .map(|runner_up| self.candidates[0].score - runner_up.score);
+    }
+
+    /// Highest-scoring candidate
+    pub fn best(&self) -> Option<&ScoredCandidate> {
+        self.candidates.first()
+    }
+
+    /// Second-highest-scoring candidate
+    pub fn runner_up(&self) -> Option<&ScoredCandidate> {
+        self.candidates.get(1)
+    }
+}
+
+/// Candidate rankings keyed by offset name (e.g. "judgeData")
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct OffsetRankings {
+    rankings: BTreeMap<String, CandidateRanking>,
+}
+
+impl OffsetRankings {
+    /// Ranking for an offset, if its search ran
+    pub fn get(&self, name: &str) -> Option<&CandidateRanking> {
+        self.rankings.get(name)
+    }
+
+    /// Store the ranking of an offset, replacing any earlier search
+    pub fn insert(&mut self, name: &str, ranking: CandidateRanking) {
+        self.rankings.insert(name.to_string(), ranking);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CandidateRanking)> {
+        self.rankings
+            .iter()
+            .map(|(name, ranking)| (name.as_str(), ranking))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rankings.is_empty()
+    }
+}
+
+impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
+    /// Score candidate addresses and store the ranking under `name`
+    ///
+    /// Reads go through a page cache, since neighbouring candidates share
+    /// most of their memory.
+    pub(crate) fn rank_candidates<I, F>(
+        &mut self,
+        name: &str,
+        addresses: I,
+        selected: Option<u64>,
+        score: F,
+    ) where
+        I: IntoIterator<Item = u64>,
+        F: Fn(&CachingReader<&'a R>, u64) -> ValidationScore,
+    {
+        let cache = CachingReader::new(self.reader);
+        let scores = addresses
+            .into_iter()
+            .map(|address| (address, score(&cache, address)));
+        let ranking = CandidateRanking::from_scores(
+            scores,
+            selected.map(|address| (address, score(&cache, address))),
+        );
+        self.rankings.insert(name, ranking);
+    }
+}
+
+fn serialize_hex<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{:X}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(value: f32) -> ValidationScore {
+        let mut score = ValidationScore::new();
+        score.require(true, value, "required");
+        score.prefer(false, 1.0 - value, "preferred");
+        score
+    }
+
+    fn invalid() -> ValidationScore {
+        let mut score = ValidationScore::new();
+        score.require(false, 1.0, "required");
+        score
+    }
+
+    #[test]
+    fn test_ranking_orders_by_score_then_discovery() {
+        let ranking = CandidateRanking::from_scores(
+            [
+                (0x1000, score(0.5)),
+                (0x1004, score(0.75)),
+                (0x1008, invalid()),
+                (0x100C, score(0.5)),
+            ],
+            Some((0x1004, score(0.75))),
+        );
+
+        let addresses: Vec<u64> = ranking.candidates.iter().map(|c| c.address).collect();
+        assert_eq!(addresses, [0x1004, 0x1000, 0x100C]);
+        assert_eq!(ranking.best().unwrap().address, 0x1004);
+        assert_eq!(ranking.runner_up().unwrap().address, 0x1000);
+        assert_eq!(ranking.margin, Some(0.25));
+        assert_eq!(ranking.selected.unwrap().address, 0x1004);
+    }
+
+    #[test]
+    fn test_ranking_keeps_top_candidates() {
+        let scores = (0..10u64).map(|i| (0x1000 + i * 4, score(i as f32 / 10.0)));
+        let ranking = CandidateRanking::from_scores(scores, None);
+
+        assert_eq!(ranking.candidates.len(), TOP_CANDIDATES);
+        assert_eq!(ranking.best().unwrap().address, 0x1000 + 9 * 4);
+        assert_eq!(ranking.candidates[4].address, 0x1000 + 5 * 4);
+    }
+
+    #[test]
+    fn test_rankings_serialize_addresses_as_hex() {
+        let mut rankings = OffsetRankings::default();
+        rankings.insert(
+            "judgeData",
+            CandidateRanking::from_scores([(0x1428380EC, score(1.0))], None),
+        );
+        let json = serde_json::to_value(&rankings).unwrap();
+        assert_eq!(json["judgeData"]["candidates"][0]["address"], "0x1428380EC");
+        assert!(json["judgeData"]["margin"].is_null());
+    }
+}
@@ -2,80 +2,135 @@
 //!
 //! This module implements the relative offset search strategy, which finds
 //! game data structures by searching near expected positions calculated from
-//! known anchor points.
+//! known anchor points. The search windows come from
+//! [`RelationPriors`](super::RelationPriors): the
+//! likely region learned from earlier versions is tried first, then the full
+//! range.
+
+use tracing::debug;
 
 use crate::error::{Error, Result};
-use crate::process::ReadMemory;
+use crate::process::{CachingReader, ReadMemory};
 
 use super::OffsetSearcher;
 use super::constants::*;
-use super::validation::OffsetValidation;
+use super::priors::{Relation, SearchWindow};
+use super::validation::{
+    OffsetValidation, ValidationScore, score_current_song_address, score_judge_data_candidate,
+    score_play_data_address, score_play_settings_at,
+};
+
+/// Aligned addresses within `range` of `expected`, nearest first
+///
+/// Below is tried before above at each distance.
+pub(crate) fn addresses_near(expected: u64, range: usize) -> impl Iterator<Item = u64> {
+    (0..=range as u64)
+        .step_by(4)
+        .flat_map(move |delta| {
+            let below = (delta != 0 && expected >= delta).then(|| expected - delta);
+            [below, Some(expected + delta)]
+        })
+        .flatten()
+        .filter(|addr| addr.is_multiple_of(4))
+}
+
+/// Score JudgeData, preferring candidates whose inferred CurrentSong is valid
+pub(crate) fn score_judge_data<R: ReadMemory>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = score_judge_data_candidate(reader, addr);
+    if score.is_valid() {
+        let current_song = addr.wrapping_add(JUDGE_TO_CURRENT_SONG);
+        let cross = score_current_song_address(reader, current_song);
+        score.prefer(
+            cross.is_valid(),
+            2.0,
+            format!("currentSong at 0x{:X} valid", current_song),
+        );
+    }
+    score
+}
 
-impl<R: ReadMemory> OffsetSearcher<'_, R> {
-    /// Search for an address near an expected location with validation
-    pub(crate) fn search_near_expected<F>(
+/// Score PlaySettings, preferring candidates whose inferred PlayData is valid
+pub(crate) fn score_play_settings<R: ReadMemory>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = score_play_settings_at(reader, addr);
+    if score.is_valid() {
+        let play_data = addr.wrapping_add(PLAY_SETTINGS_TO_PLAY_DATA);
+        let cross = score_play_data_address(reader, play_data);
+        score.prefer(
+            cross.is_valid(),
+            2.0,
+            format!("playData at 0x{:X} valid", play_data),
+        );
+    }
+    score
+}
+
+impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
+    /// Search a window around an anchor, likely region first
+    pub(crate) fn search_in_window<F>(
         &self,
-        expected: u64,
-        range: usize,
+        anchor: u64,
+        window: SearchWindow,
         validate: F,
     ) -> Option<u64>
     where
         F: Fn(&Self, u64) -> bool,
     {
-        let range = range as u64;
-        let step = 4u64;
-        let mut delta = 0u64;
-
-        while delta <= range {
-            if delta == 0 {
-                if expected.is_multiple_of(4) && validate(self, expected) {
-                    return Some(expected);
-                }
-            } else {
-                if expected >= delta {
-                    let addr = expected - delta;
-                    if addr.is_multiple_of(4) && validate(self, addr) {
-                        return Some(addr);
-                    }
-                }
-
-                let addr = expected + delta;
-                if addr.is_multiple_of(4) && validate(self, addr) {
-                    return Some(addr);
-                }
-            }
-
-            delta += step;
+        let found = window
+            .addresses(anchor)
+            .find(|&addr| validate(self, addr))?;
+        if !window.is_likely(anchor, found) {
+            debug!(
+                "  0x{:X} is outside the likely window (expected 0x{:X} ± 0x{:X})",
+                found,
+                window.center(anchor),
+                window.likely
+            );
         }
+        Some(found)
+    }
 
-        None
+    /// Rank every aligned address in a window around an anchor
+    fn rank_in_window<F>(
+        &mut self,
+        name: &str,
+        anchor: u64,
+        window: SearchWindow,
+        selected: Option<u64>,
+        score: F,
+    ) where
+        F: Fn(&CachingReader<&'a R>, u64) -> ValidationScore,
+    {
+        self.rank_candidates(name, window.addresses(anchor), selected, score);
     }
 
     /// Search for JudgeData near SongList using relative offset
-    pub(crate) fn search_judge_data_near_song_list(&self, song_list: u64) -> Result<u64> {
-        let expected = song_list.wrapping_sub(JUDGE_TO_SONG_LIST);
+    pub(crate) fn search_judge_data_near_song_list(&mut self, song_list: u64) -> Result<u64> {
+        let window = self.priors.window(Relation::JudgeDataFromSongList);
 
         // First, try to find a candidate where both JudgeData and the inferred
         // CurrentSong position are valid. This cross-validation is more reliable.
-        let result = self.search_near_expected(expected, JUDGE_DATA_SEARCH_RANGE, |this, addr| {
-            if !this.reader.validate_judge_data_candidate(addr) {
-                return false;
-            }
-            // Cross-validate: check if CurrentSong at expected relative position is valid
-            let inferred_current_song = addr.wrapping_add(JUDGE_TO_CURRENT_SONG);
-            this.reader
-                .validate_current_song_address(inferred_current_song)
-        });
-
-        if let Some(addr) = result {
-            return Ok(addr);
-        }
+        let selected = self
+            .search_in_window(song_list, window, |this, addr| {
+                if !this.reader.validate_judge_data_candidate(addr) {
+                    return false;
+                }
+                // Cross-validate: check if CurrentSong at expected relative position is valid
+                let inferred_current_song = this
+                    .priors
+                    .window(Relation::CurrentSongFromJudgeData)
+                    .center(addr);
+                this.reader
+                    .validate_current_song_address(inferred_current_song)
+            })
+            // Fallback: just validate JudgeData structure itself
+            .or_else(|| {
+                self.search_in_window(song_list, window, |this, addr| {
+                    this.reader.validate_judge_data_candidate(addr)
+                })
+            });
 
-        // Fallback: just validate JudgeData structure itself
-        self.search_near_expected(expected, JUDGE_DATA_SEARCH_RANGE, |this, addr| {
-            this.reader.validate_judge_data_candidate(addr)
-        })
-        .ok_or_else(|| {
+        self.rank_in_window("judgeData", song_list, window, selected, score_judge_data);
+        selected.ok_or_else(|| {
             Error::offset_search_failed(
                 "No valid candidates found for judgeData near SongList".to_string(),
             )
@@ -83,30 +138,38 @@ impl<R: ReadMemory> OffsetSearcher<'_, R> {
     }
 
     /// Search for PlaySettings near JudgeData using relative offset
-    pub(crate) fn search_play_settings_near_judge_data(&self, judge_data: u64) -> Result<u64> {
-        let expected = judge_data.wrapping_sub(JUDGE_TO_PLAY_SETTINGS);
+    pub(crate) fn search_play_settings_near_judge_data(&mut self, judge_data: u64) -> Result<u64> {
+        let window = self.priors.window(Relation::PlaySettingsFromJudgeData);
 
         // First, try to find a candidate where both PlaySettings and the inferred
         // PlayData position are valid. This cross-validation is more reliable.
-        let result =
-            self.search_near_expected(expected, PLAY_SETTINGS_SEARCH_RANGE, |this, addr| {
+        let selected = self
+            .search_in_window(judge_data, window, |this, addr| {
                 if this.reader.validate_play_settings_at(addr).is_none() {
                     return false;
                 }
                 // Cross-validate: check if PlayData at expected relative position is valid
-                let inferred_play_data = addr.wrapping_add(PLAY_SETTINGS_TO_PLAY_DATA);
+                let inferred_play_data = this
+                    .priors
+                    .window(Relation::PlayDataFromPlaySettings)
+                    .center(addr);
                 this.reader.validate_play_data_address(inferred_play_data)
+            })
+            // Fallback: just validate PlaySettings structure itself
+            .or_else(|| {
+                self.search_in_window(judge_data, window, |this, addr| {
+                    this.reader.validate_play_settings_at(addr).is_some()
+                })
             });
 
-        if let Some(addr) = result {
-            return Ok(addr);
-        }
-
-        // Fallback: just validate PlaySettings structure itself
-        self.search_near_expected(expected, PLAY_SETTINGS_SEARCH_RANGE, |this, addr| {
-            this.reader.validate_play_settings_at(addr).is_some()
-        })
-        .ok_or_else(|| {
+        self.rank_in_window(
+            "playSettings",
+            judge_data,
+            window,
+            selected,
+            score_play_settings,
+        );
+        selected.ok_or_else(|| {
             Error::offset_search_failed(
                 "No valid candidates found for playSettings near JudgeData".to_string(),
             )
@@ -114,12 +177,23 @@ impl<R: ReadMemory> OffsetSearcher<'_, R> {
     }
 
     /// Search for PlayData near PlaySettings using relative offset
-    pub(crate) fn search_play_data_near_play_settings(&self, play_settings: u64) -> Result<u64> {
-        let expected = play_settings.wrapping_add(PLAY_SETTINGS_TO_PLAY_DATA);
-        self.search_near_expected(expected, PLAY_DATA_SEARCH_RANGE, |this, addr| {
+    pub(crate) fn search_play_data_near_play_settings(
+        &mut self,
+        play_settings: u64,
+    ) -> Result<u64> {
+        let window = self.priors.window(Relation::PlayDataFromPlaySettings);
+        let selected = self.search_in_window(play_settings, window, |this, addr| {
             this.reader.validate_play_data_address(addr)
-        })
-        .ok_or_else(|| {
+        });
+
+        self.rank_in_window(
+            "playData",
+            play_settings,
+            window,
+            selected,
+            score_play_data_address,
+        );
+        selected.ok_or_else(|| {
             Error::offset_search_failed(
                 "No valid candidates found for playData near PlaySettings".to_string(),
             )
@@ -127,12 +201,20 @@ impl<R: ReadMemory> OffsetSearcher<'_, R> {
     }
 
     /// Search for CurrentSong near JudgeData using relative offset
-    pub(crate) fn search_current_song_near_judge_data(&self, judge_data: u64) -> Result<u64> {
-        let expected = judge_data.wrapping_add(JUDGE_TO_CURRENT_SONG);
-        self.search_near_expected(expected, CURRENT_SONG_SEARCH_RANGE, |this, addr| {
+    pub(crate) fn search_current_song_near_judge_data(&mut self, judge_data: u64) -> Result<u64> {
+        let window = self.priors.window(Relation::CurrentSongFromJudgeData);
+        let selected = self.search_in_window(judge_data, window, |this, addr| {
             this.reader.validate_current_song_address(addr)
-        })
-        .ok_or_else(|| {
+        });
+
+        self.rank_in_window(
+            "currentSong",
+            judge_data,
+            window,
+            selected,
+            score_current_song_address,
+        );
+        selected.ok_or_else(|| {
             Error::offset_search_failed(
                 "No valid candidates found for currentSong near JudgeData".to_string(),
             )
@@ -143,6 +225,8 @@ impl<R: ReadMemory> OffsetSearcher<'_, R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::offset::OffsetRelations;
+    use crate::offset::searcher::priors::{RelationHistory, RelationPriors};
     use crate::process::MockMemoryBuilder;
     use crate::process::layout::judge;
 
@@ -272,8 +356,16 @@ mod tests {
     // Search function tests
     // ========================================================================
 
+    fn window(range: u64) -> SearchWindow {
+        SearchWindow {
+            expected: 0,
+            likely: range,
+            range,
+        }
+    }
+
     #[test]
-    fn test_search_near_expected_exact_match() {
+    fn test_search_in_window_exact_match() {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
@@ -281,7 +373,7 @@ mod tests {
             .build();
         let searcher = OffsetSearcher::new(&reader);
 
-        let result = searcher.search_near_expected(0x1000, 0x100, |this, addr| {
+        let result = searcher.search_in_window(0x1000, window(0x100), |this, addr| {
             this.reader.read_i32(addr).unwrap_or(-1) == 42
         });
 
@@ -289,7 +381,7 @@ mod tests {
     }
 
     #[test]
-    fn test_search_near_expected_offset() {
+    fn test_search_in_window_offset() {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
@@ -297,7 +389,7 @@ mod tests {
             .build();
         let searcher = OffsetSearcher::new(&reader);
 
-        let result = searcher.search_near_expected(0x1000, 0x100, |this, addr| {
+        let result = searcher.search_in_window(0x1000, window(0x100), |this, addr| {
             this.reader.read_i32(addr).unwrap_or(-1) == 42
         });
 
@@ -305,14 +397,14 @@ mod tests {
     }
 
     #[test]
-    fn test_search_near_expected_not_found() {
+    fn test_search_in_window_not_found() {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
             .build();
         let searcher = OffsetSearcher::new(&reader);
 
-        let result = searcher.search_near_expected(0x1000, 0x50, |_, _| false);
+        let result = searcher.search_in_window(0x1000, window(0x50), |_, _| false);
 
         assert!(result.is_none());
     }
@@ -325,13 +417,53 @@ mod tests {
         let reader = MockMemoryBuilder::new()
             .base(expected_judge)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, 50)
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 50)
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
-        let searcher = OffsetSearcher::new(&reader);
+        let mut searcher = OffsetSearcher::new(&reader);
 
         let result = searcher.search_judge_data_near_song_list(song_list);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), expected_judge);
+
+        let ranking = searcher.rankings().get("judgeData").unwrap();
+        assert_eq!(ranking.selected.as_ref().unwrap().address, expected_judge);
+        assert_eq!(ranking.best().unwrap().address, expected_judge);
+    }
+
+    #[test]
+    fn test_search_play_data_prefers_learned_window() {
+        let play_settings = 0x1000u64;
+        let reader = MockMemoryBuilder::new()
+            .base(play_settings)
+            .with_size(0x400)
+            .write_i32(0x2A0, 1001)
+            .write_i32(0x2A4, 3)
+            .write_i32(0x2C0, 1002)
+            .write_i32(0x2C4, 3)
+            .build();
+
+        let mut searcher = OffsetSearcher::new(&reader);
+        let result = searcher.search_play_data_near_play_settings(play_settings);
+        assert_eq!(result.unwrap(), play_settings + 0x2A0);
+
+        let mut history = RelationHistory::new();
+        history.insert(
+            "P2D:J:B:A:2025122400",
+            OffsetRelations {
+                song_list_from_base: 0x301A380,
+                unlock_data_from_song_list: 0,
+                data_map_from_song_list: 0,
+                judge_data_from_data_map: -0x94E374,
+                play_settings_from_judge_data: -0x2ACEE8,
+                play_data_from_play_settings: 0x2C0,
+                current_song_from_play_settings: 0x2AD0CC,
+            },
+        );
+        let mut searcher = OffsetSearcher::builder(&reader)
+            .with_priors(RelationPriors::from_history(&history))
+            .build();
+        let result = searcher.search_play_data_near_play_settings(play_settings);
+        assert_eq!(result.unwrap(), play_settings + 0x2C0);
     }
 }
@@ -0,0 +1,442 @@
+//! Scripted offset discovery
+//!
+//! Runs the steps of [`OffsetSearcher::interactive_search`] without asking
+//! for judge counts. A script lists the judge counts of a recorded play and
+//! the options set for each PlaySettings step, optionally with a memory
+//! snapshot taken at each step, so discovery can be repeated for every new
+//! build. Without snapshots the steps run against the live game, which only
+//! holds one step's options at a time, so the user is prompted before each.
+//!
+//! ```json
+//! {
+//!   "play": {
+//!     "snapshot": "play.snap",
+//!     "song_id": 25094,
+//!     "difficulty": 3,
+//!     "judge": { "pgreat": 812, "great": 120, "good": 4, "bad": 1, "poor": 3,
+//!                "combo_break": 2, "fast": 61, "slow": 40 }
+//!   },
+//!   "settings": [
+//!     { "snapshot": "random.snap", "style": 1, "gauge": 4, "assist": 0, "range": 1 },
+//!     { "snapshot": "mirror.snap", "style": 4, "gauge": 2, "assist": 1, "range": 2 }
+//!   ]
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::offset::OffsetsCollection;
+use crate::process::ReadMemory;
+
+use super::OffsetSearcher;
+use super::interactive::{
+    DISCOVERY_DIFFICULTY, DISCOVERY_SETTINGS, DISCOVERY_SONG_ID, resolve_play_settings,
+};
+use super::types::{
+    InteractiveSearchResult, JudgeInput, SearchPrompter, SearchReporter, SettingsInput,
+};
+
+/// Times a live settings step is prompted before its search fails
+const LIVE_STEP_ATTEMPTS: usize = 3;
+
+/// The play step of a search script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptPlay {
+    /// Snapshot taken on the result screen of the play
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PathBuf>,
+    #[serde(default = "default_song_id")]
+    pub song_id: u32,
+    #[serde(default = "default_difficulty")]
+    pub difficulty: u32,
+    /// Judge counts shown on the result screen
+    pub judge: JudgeInput,
+}
+
+/// A PlaySettings step of a search script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptSettings {
+    /// Snapshot taken while the options were set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PathBuf>,
+    #[serde(flatten)]
+    pub settings: SettingsInput,
+}
+
+/// Expected values for a non-interactive offset search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchScript {
+    pub play: ScriptPlay,
+    /// Defaults to the two option sets of the interactive search
+    #[serde(default = "default_settings")]
+    pub settings: Vec<ScriptSettings>,
+}
+
+fn default_song_id() -> u32 {
+    DISCOVERY_SONG_ID
+}
+
+fn default_difficulty() -> u32 {
+    DISCOVERY_DIFFICULTY
+}
+
+fn default_settings() -> Vec<ScriptSettings> {
+    DISCOVERY_SETTINGS
+        .iter()
+        .map(|&settings| ScriptSettings {
+            snapshot: None,
+            settings,
+        })
+        .collect()
+}
+
+impl SearchScript {
+    /// Parse a script from JSON
+    pub fn from_json(content: &str) -> Result<Self> {
+        let script: Self = serde_json::from_str(content)?;
+        if script.settings.is_empty() {
+            return Err(Error::offset_search_failed(
+                "Search script has no settings steps",
+            ));
+        }
+        Ok(script)
+    }
+
+    /// Load a script, resolving snapshot paths relative to the script file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut script = Self::from_json(&fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            script.resolve_snapshots(dir);
+        }
+        Ok(script)
+    }
+
+    fn resolve_snapshots(&mut self, dir: &Path) {
+        let snapshots = std::iter::once(&mut self.play.snapshot)
+            .chain(self.settings.iter_mut().map(|step| &mut step.snapshot));
+        for snapshot in snapshots.flatten() {
+            if snapshot.is_relative() {
+                *snapshot = dir.join(&*snapshot);
+            }
+        }
+    }
+
+    /// Snapshot of every step, or None if any step has no snapshot
+    pub fn snapshots(&self) -> Option<Vec<&Path>> {
+        std::iter::once(&self.play.snapshot)
+            .chain(self.settings.iter().map(|step| &step.snapshot))
+            .map(|snapshot| snapshot.as_deref())
+            .collect()
+    }
+
+    /// Whether any step names a snapshot
+    pub fn has_snapshots(&self) -> bool {
+        self.play.snapshot.is_some() || self.settings.iter().any(|s| s.snapshot.is_some())
+    }
+}
+
+/// Reports search progress through tracing
+struct LogReporter;
+
+impl SearchReporter for LogReporter {
+    fn display_message(&self, message: &str) {
+        info!("{}", message);
+    }
+
+    fn display_warning(&self, message: &str) {
+        warn!("{}", message);
+    }
+}
+
+/// Run the offset discovery steps of a script over recorded memory
+///
+/// `play` is the memory for the play step (it is also used for the offsets
+/// that need no particular game state) and `settings` the memory for each
+/// settings step, in script order.
+pub fn run_search_script<P: ReadMemory, S: ReadMemory>(
+    script: &SearchScript,
+    play: &P,
+    settings: &[S],
+    old_offsets: &OffsetsCollection,
+    new_version: &str,
+) -> Result<InteractiveSearchResult> {
+    if settings.len() != script.settings.len() {
+        return Err(Error::offset_search_failed(format!(
+            "Search script has {} settings steps but {} memory sources were given",
+            script.settings.len(),
+            settings.len()
+        )));
+    }
+
+    run_steps(
+        script,
+        play,
+        old_offsets,
+        new_version,
+        &LogReporter,
+        |index, step| {
+            OffsetSearcher::new(&settings[index]).discover_play_settings(
+                &LogReporter,
+                old_offsets,
+                &step.settings,
+            )
+        },
+    )
+}
+
+/// Run the offset discovery steps of a script against the running game
+///
+/// The game holds the options of one settings step at a time, so the user is
+/// prompted to set them before each step is searched. A step whose options
+/// are not found is prompted again, up to three times.
+pub fn run_live_search_script<R: ReadMemory, P: SearchPrompter>(
+    script: &SearchScript,
+    reader: &R,
+    prompter: &P,
+    old_offsets: &OffsetsCollection,
+    new_version: &str,
+) -> Result<InteractiveSearchResult> {
+    prompter.prompt_continue("Open the result screen of the scripted play, then press ENTER");
+
+    let steps = script.settings.len();
+    run_steps(
+        script,
+        reader,
+        old_offsets,
+        new_version,
+        prompter,
+        |index, step| {
+            let mut attempt = 1;
+            loop {
+                prompter.prompt_continue(&format!(
+                    "Set the options of step {}/{} ({}) and then press ENTER",
+                    index + 1,
+                    steps,
+                    step.settings
+                ));
+                match OffsetSearcher::new(reader).discover_play_settings(
+                    prompter,
+                    old_offsets,
+                    &step.settings,
+                ) {
+                    Ok(address) => return Ok(address),
+                    Err(e) if attempt < LIVE_STEP_ATTEMPTS => {
+                        prompter.display_warning(&format!(
+                            "Options not found in memory ({}); check them and try again",
+                            e
+                        ));
+                        attempt += 1;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        },
+    )
+}
+
+/// Search the static and play offsets in `play`, then PlaySettings with
+/// `settings_step` for each settings step of the script
+fn run_steps<P, Rp, F>(
+    script: &SearchScript,
+    play: &P,
+    old_offsets: &OffsetsCollection,
+    new_version: &str,
+    reporter: &Rp,
+    mut settings_step: F,
+) -> Result<InteractiveSearchResult>
+where
+    P: ReadMemory,
+    Rp: SearchReporter,
+    F: FnMut(usize, &ScriptSettings) -> Result<u64>,
+{
+    let mut new_offsets = OffsetsCollection {
+        version: new_version.to_string(),
+        ..Default::default()
+    };
+
+    let mut searcher = OffsetSearcher::new(play);
+    searcher.discover_static_offsets(reporter, old_offsets, &mut new_offsets)?;
+    let play_type = searcher.discover_play_offsets(
+        reporter,
+        old_offsets,
+        &mut new_offsets,
+        &script.play.judge,
+        script.play.song_id,
+        script.play.difficulty,
+    )?;
+
+    let addresses = script
+        .settings
+        .iter()
+        .enumerate()
+        .map(|(index, step)| settings_step(index, step))
+        .collect::<Result<Vec<u64>>>()?;
+    new_offsets.play_settings = resolve_play_settings(reporter, &addresses, play_type);
+
+    Ok(InteractiveSearchResult {
+        offsets: new_offsets,
+        play_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::searcher::utils::merge_byte_representations;
+    use crate::play::PlayType;
+    use crate::process::layout::settings;
+    use crate::process::mock::GAME_BASE;
+    use crate::process::{MockMemoryBuilder, MockMemoryReader};
+
+    const SIZE: usize = 0x400000;
+
+    fn script() -> SearchScript {
+        SearchScript::from_json(
+            r#"{
+                "play": { "judge": { "pgreat": 500, "great": 100, "combo_break": 3 } },
+                "settings": [{ "style": 1, "gauge": 4, "assist": 0, "range": 1 }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn settings_memory(offset: usize, values: &SettingsInput) -> MockMemoryReader {
+        MockMemoryBuilder::game_image(SIZE)
+            .write_bytes(
+                offset,
+                &merge_byte_representations(&[
+                    values.style,
+                    values.gauge,
+                    values.assist,
+                    0,
+                    values.range,
+                ]),
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_script_defaults() {
+        let script = script();
+        assert_eq!(script.play.song_id, DISCOVERY_SONG_ID);
+        assert_eq!(script.play.difficulty, DISCOVERY_DIFFICULTY);
+        assert_eq!(script.play.judge.pgreat, 500);
+        assert_eq!(script.play.judge.slow, 0);
+        assert!(!script.has_snapshots());
+        assert!(script.snapshots().is_none());
+
+        let script = SearchScript::from_json(r#"{ "play": { "judge": {} } }"#).unwrap();
+        let settings: Vec<SettingsInput> = script.settings.iter().map(|s| s.settings).collect();
+        assert_eq!(settings, DISCOVERY_SETTINGS);
+
+        assert!(SearchScript::from_json(r#"{ "play": { "judge": {} }, "settings": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_paths_resolve_against_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.json");
+        fs::write(
+            &path,
+            r#"{
+                "play": { "snapshot": "play.snap", "judge": {} },
+                "settings": [{ "snapshot": "random.snap", "style": 1 }]
+            }"#,
+        )
+        .unwrap();
+
+        let script = SearchScript::load(&path).unwrap();
+        assert_eq!(
+            script.snapshots().unwrap(),
+            [dir.path().join("play.snap"), dir.path().join("random.snap")]
+        );
+    }
+
+    #[test]
+    fn test_play_settings_steps_read_their_own_memory() {
+        let script = script();
+        let play = MockMemoryBuilder::game_image(SIZE).build();
+        let reader = settings_memory(0x1000, &script.settings[0].settings);
+
+        let address = OffsetSearcher::new(&reader)
+            .discover_play_settings(
+                &LogReporter,
+                &OffsetsCollection::default(),
+                &script.settings[0].settings,
+            )
+            .unwrap();
+        assert_eq!(address, GAME_BASE + 0x1000);
+
+        // The settings pattern is not in the play memory
+        let result = OffsetSearcher::new(&play).discover_play_settings(
+            &LogReporter,
+            &OffsetsCollection::default(),
+            &script.settings[0].settings,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_play_step_finds_judge_play_and_current_song() {
+        let script = script();
+        let judge = &script.play.judge;
+        let searcher_memory = {
+            let (p1, _) =
+                OffsetSearcher::new(&MockMemoryReader::new(Vec::new())).build_judge_patterns(judge);
+            MockMemoryBuilder::game_image(SIZE)
+                .write_bytes(0x2000, &p1)
+                .write_bytes(
+                    0x3000,
+                    &merge_byte_representations(&[25094, 3, 1100]), // PlayData
+                )
+                .write_bytes(0x4000, &merge_byte_representations(&[25094, 3])) // CurrentSong
+                .build()
+        };
+
+        let mut offsets = OffsetsCollection::default();
+        let play_type = OffsetSearcher::new(&searcher_memory)
+            .discover_play_offsets(
+                &LogReporter,
+                &OffsetsCollection::default(),
+                &mut offsets,
+                judge,
+                script.play.song_id,
+                script.play.difficulty,
+            )
+            .unwrap();
+
+        assert_eq!(play_type, PlayType::P1);
+        assert_eq!(offsets.judge_data, GAME_BASE + 0x2000);
+        assert_eq!(offsets.play_data, GAME_BASE + 0x3000);
+        assert_eq!(offsets.current_song, GAME_BASE + 0x4000);
+    }
+
+    #[test]
+    fn test_p2_play_settings_are_rebased() {
+        let address = GAME_BASE + 0x1000 + settings::P2_OFFSET as u64;
+        assert_eq!(
+            resolve_play_settings(&LogReporter, &[address, address], PlayType::P2),
+            GAME_BASE + 0x1000
+        );
+    }
+
+    #[test]
+    fn test_settings_sources_must_match_script() {
+        let script = script();
+        let play = MockMemoryBuilder::game_image(0x100).build();
+        let result = run_search_script::<_, MockMemoryReader>(
+            &script,
+            &play,
+            &[],
+            &OffsetsCollection::default(),
+            "unknown",
+        );
+        assert!(result.is_err());
+    }
+}
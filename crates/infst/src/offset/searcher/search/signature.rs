@@ -8,12 +8,13 @@ use tracing::debug;
 
 use crate::error::{Error, Result};
 use crate::offset::searcher::validation::OffsetValidation;
-use crate::offset::{CodeSignature, OffsetSignatureSet};
-use crate::process::{ByteBuffer, ReadMemory};
+use crate::offset::{CodeSignature, OffsetSignatureSet, SignaturePattern};
+use crate::process::ReadMemory;
 
-use super::super::constants::{
-    CODE_SCAN_CHUNK_SIZE, CODE_SCAN_LIMIT, MIN_EXPECTED_SONGS, MIN_VALID_DATA_ADDRESS,
-};
+use super::super::constants::MIN_EXPECTED_SONGS;
+use super::super::pattern::{resolve_signature_matches, scan_code_for_pattern_with_report};
+
+pub use super::super::pattern::find_matches_with_wildcards;
 
 /// Signature search functionality for OffsetSearcher
 pub trait SignatureSearch<R: ReadMemory> {
@@ -41,7 +42,7 @@ pub trait SignatureSearch<R: ReadMemory> {
     fn resolve_signature_targets(&self, signature: &CodeSignature) -> Result<Vec<u64>>;
 
     /// Scan code section for a pattern
-    fn scan_code_for_pattern(&self, pattern: &[Option<u8>]) -> Result<Vec<u64>>;
+    fn scan_code_for_pattern(&self, pattern: &SignaturePattern) -> Result<Vec<u64>>;
 
     /// Find matches with wildcards in buffer
     fn find_matches_with_wildcards(
@@ -56,140 +57,23 @@ pub fn resolve_signature_targets<R: ReadMemory>(
     reader: &R,
     signature: &CodeSignature,
 ) -> Result<Vec<u64>> {
-    let pattern = signature.pattern_bytes()?;
+    let pattern = signature.parsed_pattern()?;
     let matches = scan_code_for_pattern(reader, &pattern)?;
-    let mut targets = Vec::new();
-
-    for match_addr in matches {
-        let instr_addr = match_addr + signature.instr_offset as u64;
-        let disp_addr = instr_addr + signature.disp_offset as u64;
-
-        let disp_bytes = match reader.read_bytes(disp_addr, 4) {
-            Ok(bytes) => bytes,
-            Err(_) => continue,
-        };
-
-        let disp = ByteBuffer::new(&disp_bytes).read_i32_at(0).unwrap_or(0);
-        let next_ip = instr_addr + signature.instr_len as u64;
-        let mut target = next_ip.wrapping_add_signed(disp as i64);
-
-        if signature.deref {
-            match reader.read_u64(target) {
-                Ok(ptr) => target = ptr,
-                Err(_) => continue,
-            }
-        }
-
-        if signature.addend != 0 {
-            target = target.wrapping_add_signed(signature.addend);
-        }
-
-        // Validate address is within expected range (above ImageBase)
-        if target < MIN_VALID_DATA_ADDRESS {
-            debug!(
-                "  Rejecting invalid address 0x{:X} (below MIN_VALID_DATA_ADDRESS 0x{:X})",
-                target, MIN_VALID_DATA_ADDRESS
-            );
-            continue;
-        }
-
-        if target != 0 {
-            targets.push(target);
-        }
-    }
-
-    targets.sort_unstable();
-    targets.dedup();
-    Ok(targets)
+    Ok(resolve_signature_matches(
+        reader, signature, &pattern, &matches,
+    ))
 }
 
-/// Scan code section for a pattern with wildcards
+/// Scan code section for a signature pattern
 pub fn scan_code_for_pattern<R: ReadMemory>(
     reader: &R,
-    pattern: &[Option<u8>],
+    pattern: &SignaturePattern,
 ) -> Result<Vec<u64>> {
-    let base = reader.base_address();
-    let mut results: Vec<u64> = Vec::new();
-    let mut offset: u64 = 0;
-    let mut scanned: usize = 0;
-    let mut tail: Vec<u8> = Vec::new();
-
-    while scanned < CODE_SCAN_LIMIT {
-        let remaining = CODE_SCAN_LIMIT - scanned;
-        let read_size = remaining.min(CODE_SCAN_CHUNK_SIZE);
-        let addr = base + offset;
-
-        let chunk = match reader.read_bytes(addr, read_size) {
-            Ok(bytes) => bytes,
-            Err(e) => {
-                if scanned == 0 {
-                    return Err(Error::offset_search_failed(format!(
-                        "Failed to read code section: {}",
-                        e
-                    )));
-                }
-                debug!(
-                    "Code scan stopped at offset {:#x} (scanned {:#x} bytes): {}",
-                    offset, scanned, e
-                );
-                break;
-            }
-        };
-
-        let mut data = Vec::with_capacity(tail.len() + chunk.len());
-        data.extend_from_slice(&tail);
-        data.extend_from_slice(&chunk);
-
-        let data_base = addr.saturating_sub(tail.len() as u64);
-        results.extend(find_matches_with_wildcards(&data, data_base, pattern));
-
-        if pattern.len() > 1 {
-            let keep = pattern.len() - 1;
-            if data.len() >= keep {
-                tail = data[data.len() - keep..].to_vec();
-            } else {
-                tail = data;
-            }
-        } else {
-            tail.clear();
-        }
-
-        scanned += read_size;
-        offset += read_size as u64;
-    }
-
-    results.sort_unstable();
-    results.dedup();
+    let (results, report) = scan_code_for_pattern_with_report(reader, pattern)?;
+    report.log_skipped("Code scan");
     Ok(results)
 }
 
-/// Find all matches of a pattern with wildcards in a buffer
-pub fn find_matches_with_wildcards(
-    buffer: &[u8],
-    base_addr: u64,
-    pattern: &[Option<u8>],
-) -> Vec<u64> {
-    if pattern.is_empty() || buffer.len() < pattern.len() {
-        return Vec::new();
-    }
-
-    let mut results = Vec::new();
-    let last = buffer.len() - pattern.len();
-
-    'outer: for i in 0..=last {
-        for (j, byte) in pattern.iter().enumerate() {
-            if let Some(value) = byte
-                && buffer[i + j] != *value
-            {
-                continue 'outer;
-            }
-        }
-        results.push(base_addr + i as u64);
-    }
-
-    results
-}
-
 /// Search for song list offset using signature
 ///
 /// NOTE: Currently unused because signature search doesn't work on Version 2.
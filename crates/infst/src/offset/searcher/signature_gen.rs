@@ -0,0 +1,346 @@
+//! Code signature generation from known-good offsets
+//!
+//! Given offsets that are known to be correct for the running build, finds the
+//! RIP-relative instructions referencing each offset and grows a byte window
+//! around every reference until it matches only once in the code section. The
+//! displacement is wildcarded and marked with a `<disp>` capture, so the
+//! resulting [`OffsetSignatureSet`] can be saved with `save_signatures` and
+//! used for later builds.
+
+use std::collections::{HashMap, HashSet};
+
+use memchr::memmem;
+use tracing::{debug, warn};
+
+use crate::error::{Error, Result};
+use crate::offset::{CodeSignature, OffsetSignatureEntry, OffsetSignatureSet, OffsetsCollection};
+use crate::process::{ChunkedMemoryIterator, ReadMemory};
+
+use super::constants::{CODE_SCAN_CHUNK_SIZE, CODE_SCAN_LIMIT};
+
+/// Longest signature window tried before giving up on a reference
+pub const MAX_SIGNATURE_LEN: usize = 48;
+
+/// Signatures kept per offset (shortest first)
+pub const MAX_SIGNATURES_PER_OFFSET: usize = 3;
+
+/// Bytes before the displacement that start every window (opcode + ModRM)
+const INITIAL_PREFIX_LEN: usize = 2;
+
+/// Size of a RIP-relative displacement
+const DISP_LEN: usize = 4;
+
+/// Generator of code signatures for known offsets
+pub struct SignatureGenerator<'a, R: ReadMemory + ?Sized> {
+    reader: &'a R,
+    max_len: usize,
+    max_per_offset: usize,
+}
+
+impl<'a, R: ReadMemory + ?Sized> SignatureGenerator<'a, R> {
+    pub fn new(reader: &'a R) -> Self {
+        Self {
+            reader,
+            max_len: MAX_SIGNATURE_LEN,
+            max_per_offset: MAX_SIGNATURES_PER_OFFSET,
+        }
+    }
+
+    /// Set the longest signature window to try
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.max(INITIAL_PREFIX_LEN + DISP_LEN);
+        self
+    }
+
+    /// Set how many signatures to keep per offset
+    pub fn max_per_offset(mut self, max_per_offset: usize) -> Self {
+        self.max_per_offset = max_per_offset.max(1);
+        self
+    }
+
+    /// Generate signatures for every non-zero offset
+    ///
+    /// Offsets without a unique reference are left out of the set.
+    pub fn generate(&self, offsets: &OffsetsCollection) -> Result<OffsetSignatureSet> {
+        let image = CodeImage::read(self.reader)?;
+        let targets: HashSet<u64> = offsets
+            .named()
+            .into_iter()
+            .map(|(_, addr)| addr)
+            .filter(|&addr| addr != 0)
+            .collect();
+        let references = image.find_references(&targets);
+
+        let mut entries = Vec::new();
+        for (name, addr) in offsets.named() {
+            if addr == 0 {
+                continue;
+            }
+
+            let disp_addrs = references.get(&addr).map(Vec::as_slice).unwrap_or(&[]);
+            let mut patterns: Vec<String> = disp_addrs
+                .iter()
+                .filter_map(|&disp_addr| image.unique_pattern(disp_addr, self.max_len))
+                .collect();
+            patterns.sort_by_key(|pattern| pattern.split_whitespace().count());
+            patterns.truncate(self.max_per_offset);
+
+            debug!(
+                "{}: {} references, {} unique signatures",
+                name,
+                disp_addrs.len(),
+                patterns.len()
+            );
+            if patterns.is_empty() {
+                warn!("No unique code signature found for {} (0x{:X})", name, addr);
+                continue;
+            }
+
+            entries.push(OffsetSignatureEntry {
+                name: name.to_string(),
+                signatures: patterns
+                    .into_iter()
+                    .map(|pattern| CodeSignature {
+                        pattern,
+                        instr_offset: 0,
+                        disp_offset: 0,
+                        instr_len: 0,
+                        deref: false,
+                        addend: 0,
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(OffsetSignatureSet {
+            version: offsets.version.clone(),
+            entries,
+        })
+    }
+}
+
+/// Readable part of the code section, merged into contiguous segments
+struct CodeImage {
+    segments: Vec<(u64, Vec<u8>)>,
+}
+
+impl CodeImage {
+    fn read<R: ReadMemory + ?Sized>(reader: &R) -> Result<Self> {
+        let base = reader.base_address();
+        let end = base + CODE_SCAN_LIMIT as u64;
+        let mut chunks = ChunkedMemoryIterator::new(reader, base, end, CODE_SCAN_CHUNK_SIZE);
+        let mut segments: Vec<(u64, Vec<u8>)> = Vec::new();
+
+        for chunk in chunks.by_ref().flatten() {
+            match segments.last_mut() {
+                Some((start, data)) if *start + data.len() as u64 == chunk.address => {
+                    data.extend_from_slice(&chunk.data);
+                }
+                _ => segments.push((chunk.address, chunk.data)),
+            }
+        }
+
+        let report = chunks.into_report();
+        report.log_skipped("Signature generation");
+        if segments.is_empty() {
+            return Err(Error::offset_search_failed(
+                "Failed to read code section: no readable memory".to_string(),
+            ));
+        }
+        Ok(Self { segments })
+    }
+
+    fn byte_at(&self, addr: u64) -> Option<u8> {
+        let index = self
+            .segments
+            .partition_point(|(start, _)| *start <= addr)
+            .checked_sub(1)?;
+        let (start, data) = &self.segments[index];
+        data.get((addr - start) as usize).copied()
+    }
+
+    /// Displacement addresses of RIP-relative operands pointing at a target
+    ///
+    /// Only operands whose displacement ends the instruction are considered
+    /// (ModRM `mod = 00, r/m = 101` directly before it), so the target is
+    /// relative to the address right after the displacement.
+    fn find_references(&self, targets: &HashSet<u64>) -> HashMap<u64, Vec<u64>> {
+        let mut references: HashMap<u64, Vec<u64>> = HashMap::new();
+
+        for (start, data) in &self.segments {
+            for pos in 1..data.len().saturating_sub(DISP_LEN - 1) {
+                if data[pos - 1] & 0xC7 != 0x05 {
+                    continue;
+                }
+                let disp =
+                    i32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+                let disp_addr = start + pos as u64;
+                let target = (disp_addr + DISP_LEN as u64).wrapping_add_signed(disp as i64);
+                if targets.contains(&target) {
+                    references.entry(target).or_default().push(disp_addr);
+                }
+            }
+        }
+
+        references
+    }
+
+    /// Shortest window around a reference that matches only at that reference
+    ///
+    /// The window starts at the opcode and ModRM bytes plus the wildcarded
+    /// displacement, then grows one byte at a time, alternating after and
+    /// before, until no other location matches.
+    fn unique_pattern(&self, disp_addr: u64, max_len: usize) -> Option<String> {
+        let mut before = INITIAL_PREFIX_LEN as u64;
+        let mut after = 0u64;
+        let prefix: Vec<u8> = (disp_addr.checked_sub(before)?..disp_addr)
+            .map(|addr| self.byte_at(addr))
+            .collect::<Option<_>>()?;
+
+        // Candidates are the displacement addresses of every match
+        let finder = memmem::Finder::new(&prefix);
+        let mut candidates: Vec<u64> = Vec::new();
+        for (start, data) in &self.segments {
+            candidates.extend(
+                finder
+                    .find_iter(data)
+                    .map(|pos| pos + prefix.len())
+                    .filter(|disp| disp + DISP_LEN <= data.len())
+                    .map(|disp| start + disp as u64),
+            );
+        }
+
+        let mut prefer_after = true;
+        let (mut can_grow_after, mut can_grow_before) = (true, true);
+        while candidates.len() > 1 {
+            if before + after + DISP_LEN as u64 >= max_len as u64
+                || !(can_grow_after || can_grow_before)
+            {
+                return None;
+            }
+
+            if (prefer_after && can_grow_after) || !can_grow_before {
+                let offset = DISP_LEN as u64 + after;
+                let Some(expected) = self.byte_at(disp_addr + offset) else {
+                    can_grow_after = false;
+                    continue;
+                };
+                candidates.retain(|&c| self.byte_at(c + offset) == Some(expected));
+                after += 1;
+            } else {
+                let offset = before + 1;
+                let Some(expected) = disp_addr
+                    .checked_sub(offset)
+                    .and_then(|addr| self.byte_at(addr))
+                else {
+                    can_grow_before = false;
+                    continue;
+                };
+                candidates.retain(|&c| {
+                    c.checked_sub(offset).and_then(|addr| self.byte_at(addr)) == Some(expected)
+                });
+                before += 1;
+            }
+            prefer_after = !prefer_after;
+        }
+
+        if candidates != [disp_addr] {
+            return None;
+        }
+
+        let after_start = disp_addr + DISP_LEN as u64;
+        let mut tokens = Vec::new();
+        for addr in disp_addr - before..disp_addr {
+            tokens.push(format!("{:02X}", self.byte_at(addr)?));
+        }
+        tokens.push("<disp>".to_string());
+        tokens.extend(std::iter::repeat_n("??".to_string(), DISP_LEN));
+        for addr in after_start..after_start + after {
+            tokens.push(format!("{:02X}", self.byte_at(addr)?));
+        }
+        Some(tokens.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::searcher::pattern::{
+        resolve_signature_matches, scan_code_for_pattern_with_report,
+    };
+    use crate::process::MockMemoryBuilder;
+    use crate::process::mock::{GAME_BASE, GAME_VERSION};
+
+    const JUDGE_DATA: usize = 0x1200000;
+    const PLAY_DATA: usize = 0x1300000;
+
+    fn offsets() -> OffsetsCollection {
+        OffsetsCollection {
+            version: GAME_VERSION.to_string(),
+            judge_data: GAME_BASE + JUDGE_DATA as u64,
+            play_data: GAME_BASE + PLAY_DATA as u64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generated_signatures_resolve_to_offsets() {
+        let reader = MockMemoryBuilder::game_image(0x1000)
+            // Same instruction bytes before and after the displacement at
+            // 0x100 and 0x200; only the preceding byte differs
+            .write_bytes(0xFF, &[0x90])
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], JUDGE_DATA)
+            .write_bytes(0x107, &[0xE8, 0x11])
+            .write_bytes(0x1FF, &[0xCC])
+            .write_rip_relative(0x200, &[0x48, 0x8D, 0x0D], PLAY_DATA)
+            .write_bytes(0x207, &[0xE8, 0x11])
+            .build();
+
+        let set = SignatureGenerator::new(&reader)
+            .generate(&offsets())
+            .unwrap();
+        assert_eq!(set.version, "P2D:J:B:A:2026012800");
+        assert_eq!(set.entries.len(), 2);
+
+        for (name, target) in [("judgeData", JUDGE_DATA), ("playData", PLAY_DATA)] {
+            let signature = &set.entry(name).unwrap().signatures[0];
+            assert!(signature.pattern.contains("<disp> ?? ?? ?? ??"));
+
+            let pattern = signature.parsed_pattern().unwrap();
+            let (matches, _) = scan_code_for_pattern_with_report(&reader, &pattern).unwrap();
+            assert_eq!(matches.len(), 1, "{} is not unique", signature.pattern);
+            assert_eq!(
+                resolve_signature_matches(&reader, signature, &pattern, &matches),
+                [GAME_BASE + target as u64]
+            );
+        }
+    }
+
+    #[test]
+    fn test_unreferenced_offset_is_skipped() {
+        let reader = MockMemoryBuilder::game_image(0x1000)
+            .write_rip_relative(0x100, &[0x48, 0x8B, 0x05], JUDGE_DATA)
+            .build();
+
+        let set = SignatureGenerator::new(&reader)
+            .generate(&offsets())
+            .unwrap();
+        assert!(set.entry("judgeData").is_some());
+        assert!(set.entry("playData").is_none());
+    }
+
+    #[test]
+    fn test_ambiguous_reference_gives_up_at_max_len() {
+        let reader = MockMemoryBuilder::game_image(0x1000)
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], JUDGE_DATA)
+            // Same bytes, different displacement, identical surroundings
+            .write_bytes(0x200, &[0x48, 0x8D, 0x0D, 0x00, 0x00, 0x00, 0x00])
+            .build();
+
+        let set = SignatureGenerator::new(&reader)
+            .max_len(16)
+            .generate(&offsets())
+            .unwrap();
+        assert!(set.entries.is_empty());
+    }
+}
@@ -0,0 +1,319 @@
+//! Single-pass scan for every signature of an `OffsetSignatureSet`
+//!
+//! Scanning the code section once per pattern reads up to 128MB for each
+//! signature. `SignatureScanner` compiles all patterns into one
+//! [`MultiPattern`] and reports the hits of every signature from a single
+//! traversal, splitting each chunk across worker threads.
+
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::offset::OffsetSignatureSet;
+use crate::process::{ChunkedMemoryIterator, MultiPattern, RangePlan, ReadMemory, ScanReport};
+
+use super::constants::CODE_SCAN_CHUNK_SIZE;
+use super::pattern::code_section_plan;
+
+/// Raw pattern matches of one signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHit {
+    /// Signature entry name (e.g. "judgeData")
+    pub entry: String,
+    /// Index of the signature within its entry
+    pub index: usize,
+    pub pattern: String,
+    /// Addresses where the pattern matched, sorted
+    pub matches: Vec<u64>,
+}
+
+/// Matches of every signature in a set
+#[derive(Debug, Clone, Default)]
+pub struct SignatureHits {
+    pub hits: Vec<SignatureHit>,
+    pub report: ScanReport,
+}
+
+impl SignatureHits {
+    /// Matches of the `index`-th signature of an entry
+    pub fn get(&self, entry: &str, index: usize) -> Option<&[u64]> {
+        self.hits
+            .iter()
+            .find(|hit| hit.index == index && hit.entry.eq_ignore_ascii_case(entry))
+            .map(|hit| hit.matches.as_slice())
+    }
+
+    /// Matches of the first signature with the given pattern string
+    pub fn for_pattern(&self, pattern: &str) -> Option<&[u64]> {
+        self.hits
+            .iter()
+            .find(|hit| hit.pattern == pattern)
+            .map(|hit| hit.matches.as_slice())
+    }
+
+    /// Total number of matches across all signatures
+    pub fn total_matches(&self) -> usize {
+        self.hits.iter().map(|hit| hit.matches.len()).sum()
+    }
+
+    /// Whether these hits come from a scan of exactly this signature set
+    pub fn is_for(&self, signatures: &OffsetSignatureSet) -> bool {
+        let mut keys = signatures.entries.iter().flat_map(|entry| {
+            entry
+                .signatures
+                .iter()
+                .enumerate()
+                .map(move |(index, signature)| (&entry.name, index, &signature.pattern))
+        });
+        self.hits.iter().all(|hit| {
+            keys.next().is_some_and(|(entry, index, pattern)| {
+                hit.entry == *entry && hit.index == index && hit.pattern == *pattern
+            })
+        }) && keys.next().is_none()
+    }
+}
+
+/// Scanner matching all signatures of a set in one pass
+pub struct SignatureScanner {
+    keys: Vec<(String, usize, String)>,
+    patterns: MultiPattern,
+    threads: usize,
+}
+
+impl SignatureScanner {
+    /// Compile every signature of the set
+    ///
+    /// Fails if any signature pattern is malformed.
+    pub fn new(signatures: &OffsetSignatureSet) -> Result<Self> {
+        let mut keys = Vec::new();
+        let mut patterns = Vec::new();
+        for entry in &signatures.entries {
+            for (index, signature) in entry.signatures.iter().enumerate() {
+                patterns.push(signature.parsed_pattern()?);
+                keys.push((entry.name.clone(), index, signature.pattern.clone()));
+            }
+        }
+
+        Ok(Self {
+            keys,
+            patterns: MultiPattern::new(patterns),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        })
+    }
+
+    /// Set the number of worker threads (defaults to the available parallelism)
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Number of compiled signatures
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Match every signature against an in-memory buffer starting at `base`
+    pub fn scan_buffer(&self, buffer: &[u8], base: u64) -> SignatureHits {
+        let matches = self.patterns.find_all_parallel(buffer, self.threads);
+        let mut hits = self.empty_hits();
+        for (hit, offsets) in hits.hits.iter_mut().zip(matches) {
+            hit.matches = offsets.into_iter().map(|o| base + o as u64).collect();
+        }
+        hits.report.scanned_bytes = buffer.len() as u64;
+        hits
+    }
+
+    /// Scan the code section (from the module base) once for every signature
+    ///
+    /// Unreadable regions are skipped and listed in the report. Fails only if
+    /// nothing could be read at all.
+    pub fn scan<R: ReadMemory + ?Sized>(&self, reader: &R) -> Result<SignatureHits> {
+        self.scan_plan(reader, code_section_plan(reader))
+    }
+
+    /// Scan the readable spans of a code section plan once for every signature
+    pub fn scan_plan<R: ReadMemory + ?Sized>(
+        &self,
+        reader: &R,
+        plan: RangePlan,
+    ) -> Result<SignatureHits> {
+        let mut expected_address = plan.readable.first().map_or(0, |span| span.start);
+        let mut chunks = ChunkedMemoryIterator::with_plan(reader, plan, CODE_SCAN_CHUNK_SIZE);
+        let mut hits = self.empty_hits();
+        let mut tail: Vec<u8> = Vec::new();
+        let mut last_error = None;
+
+        for chunk in chunks.by_ref() {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            if chunk.address != expected_address {
+                tail.clear();
+            }
+            expected_address = chunk.end();
+
+            let mut data = Vec::with_capacity(tail.len() + chunk.data.len());
+            data.extend_from_slice(&tail);
+            data.extend_from_slice(&chunk.data);
+
+            let data_base = chunk.address - tail.len() as u64;
+            let matches = self.patterns.find_all_parallel(&data, self.threads);
+            for (hit, offsets) in hits.hits.iter_mut().zip(matches) {
+                hit.matches
+                    .extend(offsets.into_iter().map(|o| data_base + o as u64));
+            }
+
+            let keep = self.patterns.max_len().saturating_sub(1);
+            tail = data[data.len().saturating_sub(keep)..].to_vec();
+        }
+
+        hits.report = chunks.into_report();
+        if hits.report.scanned_bytes == 0 {
+            let reason = match last_error {
+                Some(e) => e.to_string(),
+                None => "no readable memory".to_string(),
+            };
+            return Err(Error::offset_search_failed(format!(
+                "Failed to read code section: {}",
+                reason
+            )));
+        }
+
+        // Matches inside the carried-over tail are found twice
+        for hit in &mut hits.hits {
+            hit.matches.sort_unstable();
+            hit.matches.dedup();
+        }
+        Ok(hits)
+    }
+
+    fn empty_hits(&self) -> SignatureHits {
+        let hits = self
+            .keys
+            .iter()
+            .map(|(entry, index, pattern)| SignatureHit {
+                entry: entry.clone(),
+                index: *index,
+                pattern: pattern.clone(),
+                matches: Vec::new(),
+            })
+            .collect();
+        SignatureHits {
+            hits,
+            report: ScanReport::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset::{CodeSignature, OffsetSignatureEntry, builtin_signatures};
+    use crate::process::MockMemoryBuilder;
+
+    fn signature(pattern: &str) -> CodeSignature {
+        CodeSignature {
+            pattern: pattern.to_string(),
+            instr_offset: 0,
+            disp_offset: 3,
+            instr_len: 7,
+            deref: false,
+            addend: 0,
+        }
+    }
+
+    fn set(entries: &[(&str, &[&str])]) -> OffsetSignatureSet {
+        OffsetSignatureSet {
+            version: "*".to_string(),
+            entries: entries
+                .iter()
+                .map(|(name, patterns)| OffsetSignatureEntry {
+                    name: name.to_string(),
+                    signatures: patterns.iter().map(|p| signature(p)).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_scan_reports_hits_per_signature() {
+        let reader = MockMemoryBuilder::new()
+            .base(0x140000000)
+            .write_bytes(0x100, &[0x48, 0x8D, 0x0D, 0x10, 0x20, 0x30, 0x40])
+            .write_bytes(0x200, &[0x44, 0x89, 0x25, 0x01, 0x02, 0x03, 0x04])
+            .write_bytes(0x300, &[0x48, 0x8D, 0x0D, 0x50, 0x60, 0x70, 0x80])
+            .unmapped(0x400, 0x100)
+            .write_bytes(0x600, &[0x44, 0x89, 0x25, 0xAA])
+            .build();
+        let signatures = set(&[
+            ("judgeData", &["48 8D 0D ?? ?? ?? ??"]),
+            ("playData", &["11 22 33", "44 89 25 ??"]),
+        ]);
+
+        let hits = SignatureScanner::new(&signatures)
+            .unwrap()
+            .scan(&reader)
+            .unwrap();
+
+        assert_eq!(
+            hits.get("judgeData", 0),
+            Some(&[0x140000100, 0x140000300][..])
+        );
+        assert_eq!(hits.get("playData", 0), Some(&[][..]));
+        assert_eq!(
+            hits.for_pattern("44 89 25 ??"),
+            Some(&[0x140000200, 0x140000600][..])
+        );
+        assert_eq!(hits.total_matches(), 4);
+        assert_eq!(hits.report.skipped[0], 0x140000400..0x140000500);
+    }
+
+    #[test]
+    fn test_scan_buffer_matches_builtin_signatures() {
+        let scanner = SignatureScanner::new(&builtin_signatures()).unwrap();
+        let mut buffer = vec![0xCCu8; 0x1000];
+        let pattern = [
+            0x89, 0x2D, 0x01, 0x02, 0x03, 0x04, 0xEB, 0x0C, 0x48, 0x8D, 0x0D,
+        ];
+        buffer[0x800..0x800 + pattern.len()].copy_from_slice(&pattern);
+
+        let hits = scanner.scan_buffer(&buffer, 0x1000);
+        assert_eq!(hits.get("playSettings", 0), Some(&[0x1800][..]));
+        assert_eq!(hits.total_matches(), 1);
+    }
+
+    #[test]
+    fn test_hits_are_keyed_to_their_signature_set() {
+        let reader = MockMemoryBuilder::new()
+            .base(0x140000000)
+            .with_size(0x100)
+            .build();
+        let signatures = set(&[("judgeData", &["48 8D 0D ?? ?? ?? ??"])]);
+        let hits = SignatureScanner::new(&signatures)
+            .unwrap()
+            .scan(&reader)
+            .unwrap();
+
+        assert!(hits.is_for(&signatures));
+        assert!(!hits.is_for(&set(&[("judgeData", &["48 8D 15 ?? ?? ?? ??"])])));
+        assert!(!hits.is_for(&set(&[
+            ("judgeData", &["48 8D 0D ?? ?? ?? ??"]),
+            ("playData", &["44 89 25 ??"]),
+        ])));
+        assert!(!hits.is_for(&set(&[])));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let signatures = set(&[("songList", &["4C 8D ZZ"])]);
+        assert!(SignatureScanner::new(&signatures).is_err());
+    }
+}
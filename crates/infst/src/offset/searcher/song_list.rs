@@ -2,14 +2,18 @@
 
 use tracing::{debug, info, warn};
 
-use crate::chart::SongInfo;
 use crate::error::{Error, Result};
+use crate::process::layout::song;
 use crate::process::{ByteBuffer, ReadMemory, decode_shift_jis_to_string};
 
 use super::OffsetSearcher;
 use super::constants::*;
+use super::ranking::CandidateRanking;
 use super::utils::merge_byte_representations;
-use super::validation::{OffsetValidation, validate_new_version_text_table};
+use super::validation::{
+    OffsetValidation, ValidationScore, score_song_list, song_list_score,
+    validate_new_version_text_table,
+};
 
 impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
     /// Search for song list offset using version string pattern
@@ -21,6 +25,29 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
     /// populated due to lazy loading. In this case, we validate by checking the metadata
     /// table at text_base + 0x7E0.
     pub fn search_song_list_offset(&mut self, base_hint: u64) -> Result<u64> {
+        let mut scored = Vec::new();
+        let result = self.find_song_list_offset(base_hint, &mut scored);
+
+        let selected = result.as_ref().ok().map(|&addr| {
+            let score = scored
+                .iter()
+                .find(|(candidate, _)| *candidate == addr)
+                .map(|(_, score)| score.clone())
+                .unwrap_or_else(|| score_song_list(self.reader, addr));
+            (addr, score)
+        });
+        self.rankings
+            .insert("songList", CandidateRanking::from_scores(scored, selected));
+        result
+    }
+
+    /// Pattern search behind [`search_song_list_offset`](Self::search_song_list_offset),
+    /// collecting the score of every candidate it checks
+    fn find_song_list_offset(
+        &mut self,
+        base_hint: u64,
+        scored: &mut Vec<(u64, ValidationScore)>,
+    ) -> Result<u64> {
         // Pattern: "5.1.1." (version string marker)
         let pattern = b"5.1.1.";
         let mut search_size = INITIAL_SEARCH_SIZE;
@@ -47,14 +74,14 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
 
                 // Try the address itself and nearby offsets
                 // "5.1.1." might be a header, with actual song list starting after it
-                let offsets_to_try: &[i64] = &[
-                    0,                                // Direct match
-                    SongInfo::MEMORY_SIZE as i64,     // One entry after (0x3F0)
-                    SongInfo::MEMORY_SIZE as i64 * 2, // Two entries after
-                    -(SongInfo::MEMORY_SIZE as i64),  // One entry before
-                ];
-
-                for &offset in offsets_to_try {
+                // (one or two entries after, or one before, for each known entry size)
+                let offsets_to_try =
+                    std::iter::once(0).chain(song::KNOWN.iter().flat_map(|layout| {
+                        let size = layout.size as i64;
+                        [size, size * 2, -size]
+                    }));
+
+                for offset in offsets_to_try {
                     let candidate_addr = addr.wrapping_add_signed(offset);
                     if !candidate_addr.is_multiple_of(4) {
                         continue;
@@ -71,11 +98,12 @@ impl<'a, R: ReadMemory> OffsetSearcher<'a, R> {
 
                     // Check for new version structure (song_id in metadata table)
                     // If direct match and at least 1 song with valid title exists
-                    if offset == 0
+                    let is_new_version = offset == 0
                         && song_count >= 1
-                        && new_version_candidate.is_none()
-                        && validate_new_version_text_table(self.reader, candidate_addr)
-                    {
+                        && validate_new_version_text_table(self.reader, candidate_addr);
+                    scored.push((candidate_addr, song_list_score(song_count, is_new_version)));
+
+                    if is_new_version && new_version_candidate.is_none() {
                         info!(
                             "  New version text table detected at 0x{:X} ({} title entries)",
                             candidate_addr, song_count
@@ -1,10 +1,15 @@
 //! Types for offset searching
 
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
 use crate::offset::OffsetsCollection;
 use crate::play::PlayType;
 
 /// Judge data for interactive offset searching
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct JudgeInput {
     pub pgreat: u32,
     pub great: u32,
@@ -16,6 +21,28 @@ pub struct JudgeInput {
     pub slow: u32,
 }
 
+/// Play settings set while searching for PlaySettings
+///
+/// Values are the raw in-memory values (e.g. style 1 = RANDOM, gauge 4 = EXHARD).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsInput {
+    pub style: i32,
+    pub gauge: i32,
+    pub assist: i32,
+    pub range: i32,
+}
+
+impl fmt::Display for SettingsInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "style {}, gauge {}, assist {}, range {}",
+            self.style, self.gauge, self.assist, self.range
+        )
+    }
+}
+
 /// Search result with address and matching pattern index
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -23,19 +50,22 @@ pub struct SearchResult {
     pub pattern_index: usize,
 }
 
+/// Trait for reporting offset search progress
+pub trait SearchReporter {
+    /// Display a message to the user
+    fn display_message(&self, message: &str);
+
+    /// Display a warning message
+    fn display_warning(&self, message: &str);
+}
+
 /// Trait for interactive user prompts during offset search
-pub trait SearchPrompter {
+pub trait SearchPrompter: SearchReporter {
     /// Prompt user to press enter to continue
     fn prompt_continue(&self, message: &str);
 
     /// Prompt user to enter a number
     fn prompt_number(&self, prompt: &str) -> u32;
-
-    /// Display a message to the user
-    fn display_message(&self, message: &str);
-
-    /// Display a warning message
-    fn display_warning(&self, message: &str);
 }
 
 /// Interactive offset search result
@@ -3,35 +3,67 @@
 use crate::process::ReadMemory;
 
 use super::super::utils::is_power_of_two;
+use super::ValidationScore;
 
 /// Validate if an address contains valid CurrentSong data.
 ///
 /// Initial state (all zeros) is NOT accepted during offset search.
 /// We need actual song selection data to verify the offset is correct.
 pub fn validate_current_song_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> bool {
+    score_current_song_address(reader, addr).is_valid()
+}
+
+/// Score a CurrentSong candidate, recording each check of
+/// [`validate_current_song_address`].
+pub fn score_current_song_address<R: ReadMemory + ?Sized>(
+    reader: &R,
+    addr: u64,
+) -> ValidationScore {
+    let mut score = ValidationScore::new();
     let song_id = reader.read_i32(addr).unwrap_or(-1);
     let difficulty = reader.read_i32(addr + 4).unwrap_or(-1);
 
     // Do NOT accept initial state (zeros) during offset search.
     // Zero values can appear at wrong addresses - we need actual data to validate.
     // The game should have a song selected when we're searching for offsets.
-    if song_id == 0 && difficulty == 0 {
-        return false;
+    if !score.require(
+        song_id != 0 || difficulty != 0,
+        1.0,
+        "song_id and difficulty are not both zero",
+    ) {
+        return score;
     }
 
     // song_id must be in realistic range (IIDX song IDs start from ~1000)
-    if !(1000..=50000).contains(&song_id) {
-        return false;
+    if !score.require(
+        (1000..=50000).contains(&song_id),
+        2.0,
+        format!("song_id {} in 1000..=50000", song_id),
+    ) {
+        return score;
     }
     // Filter out powers of 2 which are likely memory artifacts
-    if is_power_of_two(song_id as u32) {
-        return false;
+    if !score.require(
+        !is_power_of_two(song_id as u32),
+        1.0,
+        format!("song_id {} is not a power of two", song_id),
+    ) {
+        return score;
     }
-    if !(0..=9).contains(&difficulty) {
-        return false;
+    if !score.require(
+        (0..=9).contains(&difficulty),
+        1.0,
+        format!("difficulty {} in 0..=9", difficulty),
+    ) {
+        return score;
     }
 
     // Additional validation: check that the third field is reasonable
     let field3 = reader.read_i32(addr + 8).unwrap_or(-1);
-    (0..=10000).contains(&field3)
+    score.require(
+        (0..=10000).contains(&field3),
+        1.0,
+        format!("field3 {} in 0..=10000", field3),
+    );
+    score
 }
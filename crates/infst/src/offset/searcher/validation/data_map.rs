@@ -3,26 +3,43 @@
 use crate::process::ReadMemory;
 
 use super::super::constants::*;
+use super::ValidationScore;
 
 /// Validate data_map address.
 pub fn validate_data_map_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> bool {
+    score_data_map_address(reader, addr).is_valid()
+}
+
+/// Score a data_map candidate, recording each check of
+/// [`validate_data_map_address`].
+pub fn score_data_map_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = ValidationScore::new();
     // DataMap structure: table_start at addr, table_end at addr+8
-    let table_start = match reader.read_u64(addr) {
-        Ok(v) => v,
-        Err(_) => return false,
-    };
-    let table_end = match reader.read_u64(addr + 8) {
-        Ok(v) => v,
-        Err(_) => return false,
+    let (Ok(table_start), Ok(table_end)) = (reader.read_u64(addr), reader.read_u64(addr + 8))
+    else {
+        score.require(false, 1.0, "table pointers readable");
+        return score;
     };
 
-    if table_end <= table_start {
-        return false;
+    if !score.require(
+        table_end > table_start,
+        1.0,
+        format!(
+            "table_end 0x{:X} after table_start 0x{:X}",
+            table_end, table_start
+        ),
+    ) {
+        return score;
     }
 
     let size = table_end - table_start;
     // Valid size range: 8KB to 16MB
-    (0x2000..=0x1000000).contains(&size)
+    score.require(
+        (0x2000..=0x1000000).contains(&size),
+        2.0,
+        format!("table size 0x{:X} in 0x2000..=0x1000000", size),
+    );
+    score
 }
 
 /// Validate a data map node.
@@ -3,6 +3,8 @@
 use crate::process::ReadMemory;
 use crate::process::layout::judge;
 
+use super::ValidationScore;
+
 /// Validate if the given address contains valid JudgeData.
 ///
 /// Checks:
@@ -13,37 +15,62 @@ use crate::process::layout::judge;
 /// fast/slow counts, measure end markers). In song select state, these are
 /// all zeros. During/after play, they contain valid counts.
 pub fn validate_judge_data_candidate<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> bool {
-    if !addr.is_multiple_of(4) {
-        return false;
+    score_judge_data_candidate(reader, addr).is_valid()
+}
+
+/// Score a JudgeData candidate, recording each check of
+/// [`validate_judge_data_candidate`].
+pub fn score_judge_data_candidate<R: ReadMemory + ?Sized>(
+    reader: &R,
+    addr: u64,
+) -> ValidationScore {
+    let mut score = ValidationScore::new();
+    if !score.require(addr.is_multiple_of(4), 1.0, "address is 4-byte aligned") {
+        return score;
     }
 
     // Check state markers (must be 0-100)
-    let marker1 = reader.read_i32(addr + judge::STATE_MARKER_1).unwrap_or(-1);
-    let marker2 = reader.read_i32(addr + judge::STATE_MARKER_2).unwrap_or(-1);
-    if !(0..=100).contains(&marker1) || !(0..=100).contains(&marker2) {
-        return false;
+    let marker1 = judge::STATE_MARKER_1.read(reader, addr).unwrap_or(-1);
+    let marker2 = judge::STATE_MARKER_2.read(reader, addr).unwrap_or(-1);
+    if !score.require(
+        (0..=100).contains(&marker1) && (0..=100).contains(&marker2),
+        2.0,
+        format!("state markers {}/{} in 0..=100", marker1, marker2),
+    ) {
+        return score;
     }
+    // Zeroed memory passes the checks above; live judgeData has its markers set
+    score.prefer(
+        marker1 != 0 || marker2 != 0,
+        1.0,
+        format!("state markers {}/{} set", marker1, marker2),
+    );
 
     // Read the judgment region (first 72 bytes = 18 i32 values)
-    let Ok(bytes) = reader.read_bytes(addr, judge::INITIAL_ZERO_SIZE) else {
-        return false;
+    let bytes = reader.read_bytes(addr, judge::INITIAL_ZERO_SIZE);
+    let Ok(bytes) = bytes else {
+        score.require(false, 1.0, "judgment region readable");
+        return score;
     };
 
-    // Check if all bytes are zero (song select state)
+    // All zeros is the song select state; otherwise every i32 value must be
+    // a plausible count (0..=MAX_NOTES)
     let all_zeros = bytes.iter().all(|&b| b == 0);
-    if all_zeros {
-        return true;
-    }
-
-    // If not all zeros, verify each i32 value is in valid range
-    // Each judgment value should be 0-3000 (MAX_NOTES), combo breaks 0-500, etc.
-    for chunk in bytes.chunks_exact(4) {
-        let value = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-        // All values should be non-negative and reasonably bounded
-        if !(0..=judge::MAX_NOTES).contains(&value) {
-            return false;
-        }
-    }
+    let out_of_range = bytes
+        .chunks_exact(4)
+        .map(|chunk| i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .filter(|value| !(0..=judge::MAX_NOTES).contains(value))
+        .count();
+    let detail = if all_zeros {
+        "judgment region is all zeros".to_string()
+    } else {
+        format!(
+            "{} judgment values outside 0..={}",
+            out_of_range,
+            judge::MAX_NOTES
+        )
+    };
+    score.require(all_zeros || out_of_range == 0, 2.0, detail);
 
-    true
+    score
 }
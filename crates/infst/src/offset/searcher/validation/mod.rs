@@ -7,6 +7,7 @@ mod current_song;
 mod data_map;
 mod judge;
 mod play;
+mod score;
 mod song_list;
 mod unlock;
 
@@ -17,12 +18,18 @@ use crate::process::ReadMemory;
 
 use super::constants::*;
 
-pub use current_song::validate_current_song_address;
-pub use data_map::{validate_data_map_address, validate_data_map_node};
-pub use judge::validate_judge_data_candidate;
-pub use play::{validate_play_data_address, validate_play_settings_at};
-pub use song_list::{count_songs_at_address, validate_new_version_text_table};
-pub use unlock::validate_unlock_data_address;
+pub use current_song::{score_current_song_address, validate_current_song_address};
+pub use data_map::{score_data_map_address, validate_data_map_address, validate_data_map_node};
+pub use judge::{score_judge_data_candidate, validate_judge_data_candidate};
+pub use play::{
+    score_play_data_address, score_play_settings_at, validate_play_data_address,
+    validate_play_settings_at,
+};
+pub use score::{ScoreCheck, ValidationScore};
+pub use song_list::{
+    count_songs_at_address, score_song_list, song_list_score, validate_new_version_text_table,
+};
+pub use unlock::{score_unlock_data_address, validate_unlock_data_address};
 
 /// Validation helper methods for OffsetSearcher.
 pub trait OffsetValidation: ReadMemory {
@@ -337,8 +344,8 @@ mod tests {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, 50)
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 50)
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         assert!(validate_judge_data_candidate(&reader, 0x1000));
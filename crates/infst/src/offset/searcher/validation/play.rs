@@ -1,9 +1,11 @@
 //! Play settings and play data validation.
 
-use crate::process::ReadMemory;
+use crate::play::RawPlayData;
 use crate::process::layout::settings;
+use crate::process::{FromMemory, ReadMemory};
 
 use super::super::constants::*;
+use super::ValidationScore;
 
 /// Validate if the given address contains valid PlaySettings.
 ///
@@ -14,31 +16,58 @@ use super::super::constants::*;
 /// - 0x0C: flip (4 bytes, 0 or 1)
 /// - 0x10: range (4 bytes, range 0-5)
 pub fn validate_play_settings_at<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> Option<u64> {
-    let style = reader.read_i32(addr).ok()?;
-    let gauge = reader.read_i32(addr + 4).ok()?;
-    let assist = reader.read_i32(addr + 8).ok()?;
-    let flip = reader.read_i32(addr + 12).ok()?;
-    let range = reader.read_i32(addr + 16).ok()?;
+    score_play_settings_at(reader, addr)
+        .is_valid()
+        .then_some(addr)
+}
+
+/// Score a PlaySettings candidate, recording each check of
+/// [`validate_play_settings_at`].
+///
+/// An all-zero settings block passes validation but scores lower, since
+/// zeroed memory matches it anywhere.
+pub fn score_play_settings_at<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = ValidationScore::new();
+    let fields: Option<Vec<i32>> = (0..5).map(|i| reader.read_i32(addr + i * 4).ok()).collect();
+    let Some(fields) = fields else {
+        score.require(false, 1.0, "settings readable");
+        return score;
+    };
 
     // Valid ranges check (aligned with C# implementation)
-    if !(0..=6).contains(&style)
-        || !(0..=4).contains(&gauge)
-        || !(0..=5).contains(&assist)
-        || !(0..=1).contains(&flip)
-        || !(0..=5).contains(&range)
-    {
-        return None;
+    let ranges = [
+        ("style", 0..=6),
+        ("gauge", 0..=4),
+        ("assist", 0..=5),
+        ("flip", 0..=1),
+        ("range", 0..=5),
+    ];
+    for ((name, range), value) in ranges.into_iter().zip(&fields) {
+        let detail = format!("{} {} in {}..={}", name, value, range.start(), range.end());
+        score.require(range.contains(value), 1.0, detail);
+    }
+    if !score.is_valid() {
+        return score;
     }
 
     // Additional validation: song_select_marker should be 0 or 1
-    let song_select_marker = reader
-        .read_i32(addr.wrapping_sub(settings::SONG_SELECT_MARKER))
-        .ok()?;
-    if !(0..=1).contains(&song_select_marker) {
-        return None;
-    }
+    let Ok(song_select_marker) = reader.read_i32(addr.wrapping_sub(settings::SONG_SELECT_MARKER))
+    else {
+        score.require(false, 1.0, "song_select_marker readable");
+        return score;
+    };
+    score.require(
+        (0..=1).contains(&song_select_marker),
+        1.0,
+        format!("song_select_marker {} in 0..=1", song_select_marker),
+    );
 
-    Some(addr)
+    score.prefer(
+        fields.iter().any(|&value| value != 0),
+        1.0,
+        "settings are not all zero",
+    );
+    score
 }
 
 /// Validate if an address contains valid PlayData.
@@ -52,21 +81,49 @@ pub fn validate_play_settings_at<R: ReadMemory + ?Sized>(reader: &R, addr: u64)
 /// Initial state (all zeros) is NOT accepted during offset search.
 /// We need actual play data with valid song_id to verify the offset is correct.
 pub fn validate_play_data_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> bool {
-    use crate::process::layout::play;
+    score_play_data_address(reader, addr).is_valid()
+}
 
-    let song_id = reader.read_i32(addr + play::SONG_ID).unwrap_or(-1);
-    let difficulty = reader.read_i32(addr + play::DIFFICULTY).unwrap_or(-1);
-    let lamp = reader.read_i32(addr + play::LAMP).unwrap_or(-1);
+/// Score a PlayData candidate, recording each check of
+/// [`validate_play_data_address`].
+pub fn score_play_data_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = ValidationScore::new();
+    let RawPlayData {
+        song_id,
+        difficulty,
+        lamp,
+    } = RawPlayData::read(reader, addr).unwrap_or(RawPlayData {
+        song_id: -1,
+        difficulty: -1,
+        lamp: -1,
+    });
 
     // Do NOT accept initial state (all zeros) during offset search.
     // Zero values can appear at wrong addresses - we need actual data to validate.
     // The game should have play data populated when we're searching for offsets.
-    if song_id == 0 && difficulty == 0 && lamp == 0 {
-        return false;
+    if !score.require(
+        song_id != 0 || difficulty != 0 || lamp != 0,
+        1.0,
+        "play data is not all zero",
+    ) {
+        return score;
     }
 
     // Require song_id in valid IIDX range (>= 1000)
-    (MIN_SONG_ID..=MAX_SONG_ID).contains(&song_id)
-        && (0..=9).contains(&difficulty)
-        && (0..=7).contains(&lamp)
+    score.require(
+        (MIN_SONG_ID..=MAX_SONG_ID).contains(&song_id),
+        2.0,
+        format!("song_id {} in {}..={}", song_id, MIN_SONG_ID, MAX_SONG_ID),
+    );
+    score.require(
+        (0..=9).contains(&difficulty),
+        1.0,
+        format!("difficulty {} in 0..=9", difficulty),
+    );
+    score.require(
+        (0..=7).contains(&lamp),
+        1.0,
+        format!("lamp {} in 0..=7", lamp),
+    );
+    score
 }
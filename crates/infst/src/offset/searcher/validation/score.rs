@@ -0,0 +1,111 @@
+//! Scored validation results.
+//!
+//! Validators record each check they make with a weight and a short
+//! description, so a rejected or low-ranked candidate shows why.
+
+use serde::Serialize;
+
+/// A single check made by a validator
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreCheck {
+    /// Human-readable description, including the values read
+    pub detail: String,
+    pub passed: bool,
+    /// Contribution to the score when passed
+    pub weight: f32,
+    /// A failed required check rejects the candidate
+    pub required: bool,
+}
+
+/// Result of validating a candidate address
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValidationScore {
+    /// Passed weight over total weight, from 0.0 to 1.0
+    pub score: f32,
+    /// Whether every required check passed
+    pub valid: bool,
+    /// Checks in the order they were made
+    pub checks: Vec<ScoreCheck>,
+}
+
+impl ValidationScore {
+    pub fn new() -> Self {
+        Self {
+            score: 0.0,
+            valid: true,
+            checks: Vec::new(),
+        }
+    }
+
+    /// Record a check the candidate must pass
+    pub fn require(&mut self, passed: bool, weight: f32, detail: impl Into<String>) -> bool {
+        self.record(passed, weight, true, detail.into());
+        passed
+    }
+
+    /// Record a check that only raises the score
+    pub fn prefer(&mut self, passed: bool, weight: f32, detail: impl Into<String>) -> bool {
+        self.record(passed, weight, false, detail.into());
+        passed
+    }
+
+    /// Whether every required check passed
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Reasons for the score, failed checks first
+    pub fn reasons(&self) -> Vec<String> {
+        let mut checks: Vec<&ScoreCheck> = self.checks.iter().collect();
+        checks.sort_by_key(|check| check.passed);
+        checks
+            .into_iter()
+            .map(|check| format!("{} {}", if check.passed { "+" } else { "-" }, check.detail))
+            .collect()
+    }
+
+    fn record(&mut self, passed: bool, weight: f32, required: bool, detail: String) {
+        if required && !passed {
+            self.valid = false;
+        }
+        self.checks.push(ScoreCheck {
+            detail,
+            passed,
+            weight,
+            required,
+        });
+
+        let total: f32 = self.checks.iter().map(|c| c.weight).sum();
+        let passed: f32 = self
+            .checks
+            .iter()
+            .filter(|c| c.passed)
+            .map(|c| c.weight)
+            .sum();
+        self.score = if total > 0.0 { passed / total } else { 0.0 };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_is_weighted_fraction() {
+        let mut score = ValidationScore::new();
+        score.require(true, 3.0, "marker in range");
+        score.prefer(false, 1.0, "non-zero counts");
+        assert!(score.is_valid());
+        assert_eq!(score.score, 0.75);
+        assert_eq!(score.reasons(), ["- non-zero counts", "+ marker in range"]);
+    }
+
+    #[test]
+    fn test_failed_required_check_rejects() {
+        let mut score = ValidationScore::new();
+        score.require(false, 1.0, "readable");
+        score.prefer(true, 1.0, "aligned");
+        assert!(!score.is_valid());
+        assert_eq!(score.score, 0.5);
+    }
+}
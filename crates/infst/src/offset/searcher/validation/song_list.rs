@@ -2,14 +2,16 @@
 
 use tracing::debug;
 
-use crate::chart::SongInfo;
+use crate::chart::{SongInfo, song_layout_or_current};
 use crate::process::ReadMemory;
 
 use super::super::constants::MIN_EXPECTED_SONGS;
+use super::ValidationScore;
 
 /// Count how many songs can be read from a given song list address.
 ///
-/// This function counts songs until:
+/// Entries are read with the song layout detected at the address. This
+/// function counts songs until:
 /// - MIN_EXPECTED_SONGS (1000) is reached (early termination for performance)
 /// - MAX_SONGS_TO_CHECK (5000) is reached
 /// - Too many consecutive failures occur
@@ -17,6 +19,7 @@ pub fn count_songs_at_address<R: ReadMemory>(reader: &R, song_list_addr: u64) ->
     let mut count = 0;
     let mut consecutive_failures = 0;
     let mut current_position: u64 = 0;
+    let layout = song_layout_or_current(reader, song_list_addr);
 
     const MAX_SONGS_TO_CHECK: usize = 5000;
     const MAX_CONSECUTIVE_FAILURES: u32 = 10;
@@ -32,12 +35,12 @@ pub fn count_songs_at_address<R: ReadMemory>(reader: &R, song_list_addr: u64) ->
         }
         let address = song_list_addr + current_position;
 
-        match SongInfo::read_from_memory(reader, address) {
+        match SongInfo::read_from_memory_with_layout(reader, address, layout) {
             Ok(Some(song)) if !song.title.is_empty() => {
                 if count < 3
-                    && let Ok(full_buffer) = reader.read_bytes(address, SongInfo::MEMORY_SIZE)
+                    && let Ok(full_buffer) = reader.read_bytes(address, layout.size)
                 {
-                    let id_offset = 256 + 368; // SONG_ID_OFFSET
+                    let id_offset = layout.song_id.offset;
                     debug!(
                         "    Song {}: id={}, title={:?} at 0x{:X}",
                         count, song.id, song.title, address
@@ -87,7 +90,7 @@ pub fn count_songs_at_address<R: ReadMemory>(reader: &R, song_list_addr: u64) ->
             }
         }
 
-        current_position += SongInfo::MEMORY_SIZE as u64;
+        current_position += layout.size as u64;
     }
 
     count
@@ -120,3 +123,74 @@ pub fn validate_new_version_text_table<R: ReadMemory>(reader: &R, text_base: u64
 
     false
 }
+
+/// Score a song list from its readable song count and whether it carries a
+/// new-version text table.
+///
+/// A list is valid with at least [`MIN_EXPECTED_SONGS`] songs, or with any
+/// songs if the new-version metadata table checks out.
+pub fn song_list_score(song_count: usize, is_new_version: bool) -> ValidationScore {
+    let mut score = ValidationScore::new();
+    let has_enough_songs = song_count >= MIN_EXPECTED_SONGS;
+    score.require(
+        has_enough_songs || is_new_version,
+        1.0,
+        format!(
+            "{} songs readable (need {} or a new-version text table)",
+            song_count, MIN_EXPECTED_SONGS
+        ),
+    );
+    score.prefer(
+        has_enough_songs,
+        2.0,
+        format!("at least {} songs readable", MIN_EXPECTED_SONGS),
+    );
+    score.prefer(is_new_version, 1.0, "new-version text table present");
+    score
+}
+
+/// Score a song list candidate address
+pub fn score_song_list<R: ReadMemory>(reader: &R, song_list_addr: u64) -> ValidationScore {
+    let song_count = count_songs_at_address(reader, song_list_addr);
+    let is_new_version = song_count >= 1 && validate_new_version_text_table(reader, song_list_addr);
+    song_list_score(song_count, is_new_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+    use crate::process::layout::song::{self, SongLayout};
+
+    fn song_list(layout: &SongLayout, count: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        for i in 0..count {
+            let mut entry = vec![0u8; layout.size];
+            let title = format!("Song {}", i);
+            entry[..title.len()].copy_from_slice(title.as_bytes());
+            let id = layout.song_id.offset;
+            entry[id..id + 4].copy_from_slice(&(1001 + i as i32).to_le_bytes());
+            entry[layout.levels.offset + 1] = 5;
+            buffer.extend_from_slice(&entry);
+        }
+        buffer.extend_from_slice(&vec![0u8; 16 * layout.size]);
+        buffer
+    }
+
+    #[test]
+    fn test_count_songs_uses_detected_layout() {
+        for layout in song::KNOWN {
+            let reader = MockMemoryBuilder::new()
+                .base(0x1000)
+                .write_bytes(0, &song_list(layout, 40))
+                .build();
+
+            assert_eq!(
+                count_songs_at_address(&reader, 0x1000),
+                40,
+                "{}",
+                layout.name
+            );
+        }
+    }
+}
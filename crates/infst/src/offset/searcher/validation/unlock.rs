@@ -3,28 +3,35 @@
 use crate::process::ReadMemory;
 
 use super::super::constants::*;
+use super::ValidationScore;
 
 /// Validate unlock_data address.
 pub fn validate_unlock_data_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> bool {
+    score_unlock_data_address(reader, addr).is_valid()
+}
+
+/// Score an unlock_data candidate, recording each check of
+/// [`validate_unlock_data_address`].
+pub fn score_unlock_data_address<R: ReadMemory + ?Sized>(reader: &R, addr: u64) -> ValidationScore {
+    let mut score = ValidationScore::new();
     // First entry should have song_id around 1000, reasonable type and unlocks
-    let song_id = match reader.read_i32(addr) {
-        Ok(v) => v,
-        Err(_) => return false,
-    };
-    let unlock_type = match reader.read_i32(addr + 4) {
-        Ok(v) => v,
-        Err(_) => return false,
+    let (Ok(song_id), Ok(unlock_type)) = (reader.read_i32(addr), reader.read_i32(addr + 4)) else {
+        score.require(false, 1.0, "first entry readable");
+        return score;
     };
 
     // song_id should be in valid range
-    if !(MIN_SONG_ID..=MAX_SONG_ID).contains(&song_id) {
-        return false;
-    }
+    score.require(
+        (MIN_SONG_ID..=MAX_SONG_ID).contains(&song_id),
+        2.0,
+        format!("song_id {} in {}..={}", song_id, MIN_SONG_ID, MAX_SONG_ID),
+    );
 
     // unlock_type should be 0-3
-    if !(0..=3).contains(&unlock_type) {
-        return false;
-    }
-
-    true
+    score.require(
+        (0..=3).contains(&unlock_type),
+        1.0,
+        format!("unlock_type {} in 0..=3", unlock_type),
+    );
+    score
 }
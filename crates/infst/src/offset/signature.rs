@@ -2,13 +2,23 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
-use crate::error::{Error, Result};
+use crate::error::Result;
 
+use super::signature_pattern::{PatternMatch, SignaturePattern};
+
+/// Code pattern whose RIP-relative displacement points at a data structure
+///
+/// The displacement is located by the `<disp>` (and optional `<next>`)
+/// capture points of the pattern. Patterns without captures fall back to the
+/// fixed `instr_offset`/`disp_offset`/`instr_len` fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeSignature {
     pub pattern: String,
+    #[serde(default)]
     pub instr_offset: usize,
+    #[serde(default)]
     pub disp_offset: usize,
+    #[serde(default)]
     pub instr_len: usize,
     #[serde(default)]
     pub deref: bool,
@@ -16,10 +26,35 @@ pub struct CodeSignature {
     pub addend: i64,
 }
 
+/// Where a signature's displacement sits within a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispLocation {
+    /// Offset of the 32-bit displacement from the match start
+    pub disp: usize,
+    /// Offset of the next instruction (the RIP the displacement is relative to)
+    pub next: usize,
+}
+
 impl CodeSignature {
-    pub fn pattern_bytes(&self) -> Result<Vec<Option<u8>>> {
+    pub fn parsed_pattern(&self) -> Result<SignaturePattern> {
         parse_pattern(&self.pattern)
     }
+
+    /// Locate the displacement in a match
+    ///
+    /// `<disp>` marks the displacement; `<next>` defaults to just after it.
+    pub fn disp_location(&self, found: &PatternMatch) -> DispLocation {
+        match found.capture("disp") {
+            Some(disp) => DispLocation {
+                disp,
+                next: found.capture("next").unwrap_or(disp + 4),
+            },
+            None => DispLocation {
+                disp: self.instr_offset + self.disp_offset,
+                next: self.instr_offset + self.instr_len,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,38 +89,14 @@ pub fn save_signatures<P: AsRef<Path>>(path: P, signatures: &OffsetSignatureSet)
     Ok(())
 }
 
-pub fn parse_pattern(pattern: &str) -> Result<Vec<Option<u8>>> {
-    let mut bytes = Vec::new();
-    for token in pattern.split_whitespace() {
-        if token == "??" || token == "?" {
-            bytes.push(None);
-            continue;
-        }
-
-        let value = u8::from_str_radix(token, 16).map_err(|e| {
-            Error::InvalidOffset(format!("Invalid signature token '{}': {}", token, e))
-        })?;
-        bytes.push(Some(value));
-    }
-
-    if bytes.is_empty() {
-        return Err(Error::InvalidOffset(
-            "Signature pattern is empty".to_string(),
-        ));
-    }
-
-    Ok(bytes)
+/// Parse a signature pattern (see [`SignaturePattern`] for the grammar)
+pub fn parse_pattern(pattern: &str) -> Result<SignaturePattern> {
+    pattern.parse()
 }
 
-pub fn format_pattern(bytes: &[Option<u8>]) -> String {
-    bytes
-        .iter()
-        .map(|b| match b {
-            Some(value) => format!("{:02X}", value),
-            None => "??".to_string(),
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Format a pattern in the canonical form accepted by [`parse_pattern`]
+pub fn format_pattern(pattern: &SignaturePattern) -> String {
+    pattern.to_string()
 }
 
 pub fn builtin_signatures() -> OffsetSignatureSet {
@@ -162,7 +173,10 @@ mod tests {
 
     #[test]
     fn test_parse_pattern_with_wildcards() {
-        let bytes = parse_pattern("48 8D 0D ?? ?? ?? ??").unwrap();
+        let bytes = parse_pattern("48 8D 0D ?? ?? ?? ??")
+            .unwrap()
+            .to_bytes()
+            .unwrap();
         assert_eq!(bytes.len(), 7);
         assert_eq!(bytes[0], Some(0x48));
         assert_eq!(bytes[1], Some(0x8D));
@@ -173,9 +187,53 @@ mod tests {
     #[test]
     fn test_format_pattern_roundtrip() {
         let pattern = vec![Some(0x48), Some(0x8D), Some(0x0D), None, Some(0xFF)];
-        let formatted = format_pattern(&pattern);
+        let formatted = format_pattern(&SignaturePattern::from_bytes(&pattern));
         assert_eq!(formatted, "48 8D 0D ?? FF");
         let parsed = parse_pattern(&formatted).unwrap();
-        assert_eq!(parsed, pattern);
+        assert_eq!(parsed.to_bytes(), Some(pattern));
+    }
+
+    #[test]
+    fn test_legacy_json_still_loads() {
+        let json = r#"{
+            "version": "*",
+            "entries": [{
+                "name": "judgeData",
+                "signatures": [{
+                    "pattern": "48 8D 0D ? ? ? ?",
+                    "instr_offset": 0,
+                    "disp_offset": 3,
+                    "instr_len": 7
+                }]
+            }]
+        }"#;
+        let set: OffsetSignatureSet = serde_json::from_str(json).unwrap();
+        let signature = &set.entry("judgeData").unwrap().signatures[0];
+
+        let found = signature
+            .parsed_pattern()
+            .unwrap()
+            .match_at(&[0x48, 0x8D, 0x0D, 1, 2, 3, 4])
+            .unwrap();
+        assert_eq!(
+            signature.disp_location(&found),
+            DispLocation { disp: 3, next: 7 }
+        );
+    }
+
+    #[test]
+    fn test_captures_replace_fixed_offsets() {
+        let json = r#"{"pattern": "48 [0-2] 8D (05|0D) <disp> ?? ?? ?? ?? <next> 89"}"#;
+        let signature: CodeSignature = serde_json::from_str(json).unwrap();
+
+        let found = signature
+            .parsed_pattern()
+            .unwrap()
+            .match_at(&[0x48, 0x90, 0x8D, 0x05, 1, 2, 3, 4, 0x89])
+            .unwrap();
+        assert_eq!(
+            signature.disp_location(&found),
+            DispLocation { disp: 4, next: 8 }
+        );
     }
 }
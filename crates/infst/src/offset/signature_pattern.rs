@@ -0,0 +1,491 @@
+//! Signature pattern language
+//!
+//! Patterns are whitespace-separated tokens:
+//!
+//! | Token       | Meaning                                           |
+//! |-------------|---------------------------------------------------|
+//! | `4C`        | exact byte                                        |
+//! | `??` / `?`  | any byte                                          |
+//! | `4?` / `?D` | nibble wildcard (high or low nibble fixed)        |
+//! | `(05\|0D)`  | one of several bytes (nibble wildcards allowed)   |
+//! | `[4-8]`     | gap of 4 to 8 arbitrary bytes (`[4]` = exactly 4) |
+//! | `<disp>`    | named capture point (zero width)                  |
+//!
+//! Capture points mark positions inside a match. For code signatures, `<disp>`
+//! marks the RIP-relative displacement and `<next>` the end of the instruction,
+//! replacing the fixed `instr_offset`/`disp_offset`/`instr_len` fields.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Largest allowed gap, keeping backtracking bounded
+pub const MAX_GAP: usize = 64;
+
+/// A byte compared under a mask (`0xFF` exact, `0xF0`/`0x0F` nibble, `0` any)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaskedByte {
+    pub value: u8,
+    pub mask: u8,
+}
+
+impl MaskedByte {
+    pub const ANY: Self = Self { value: 0, mask: 0 };
+
+    pub const fn exact(value: u8) -> Self {
+        Self { value, mask: 0xFF }
+    }
+
+    pub fn matches(&self, byte: u8) -> bool {
+        byte & self.mask == self.value
+    }
+
+    /// The byte value, if the whole byte is fixed
+    pub fn exact_value(&self) -> Option<u8> {
+        (self.mask == 0xFF).then_some(self.value)
+    }
+
+    fn parse(token: &str) -> Result<Self> {
+        if token == "?" || token == "??" {
+            return Ok(Self::ANY);
+        }
+
+        let chars: Vec<char> = token.chars().collect();
+        if chars.len() != 2 {
+            return Err(invalid_token(token, "expected two hex digits"));
+        }
+
+        let nibble = |c: char| -> Result<Option<u8>> {
+            if c == '?' {
+                return Ok(None);
+            }
+            c.to_digit(16)
+                .map(|d| Some(d as u8))
+                .ok_or_else(|| invalid_token(token, "invalid hex digit"))
+        };
+
+        let (high, low) = (nibble(chars[0])?, nibble(chars[1])?);
+        Ok(Self {
+            value: (high.unwrap_or(0) << 4) | low.unwrap_or(0),
+            mask: if high.is_some() { 0xF0 } else { 0 } | if low.is_some() { 0x0F } else { 0 },
+        })
+    }
+}
+
+impl fmt::Display for MaskedByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nibble = |fixed: bool, value: u8| {
+            if fixed {
+                char::from_digit(value as u32, 16)
+                    .expect("nibble is a hex digit")
+                    .to_ascii_uppercase()
+            } else {
+                '?'
+            }
+        };
+        write!(
+            f,
+            "{}{}",
+            nibble(self.mask & 0xF0 != 0, self.value >> 4),
+            nibble(self.mask & 0x0F != 0, self.value & 0x0F)
+        )
+    }
+}
+
+/// One element of a signature pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternToken {
+    Byte(MaskedByte),
+    /// Any one of the alternatives
+    Alt(Vec<MaskedByte>),
+    /// Between `min` and `max` arbitrary bytes
+    Gap {
+        min: usize,
+        max: usize,
+    },
+    /// Named zero-width position
+    Capture(String),
+}
+
+impl PatternToken {
+    fn parse(token: &str) -> Result<Self> {
+        if let Some(inner) = token.strip_prefix('(') {
+            let inner = inner
+                .strip_suffix(')')
+                .ok_or_else(|| invalid_token(token, "unclosed alternation"))?;
+            let options = inner
+                .split('|')
+                .map(MaskedByte::parse)
+                .collect::<Result<Vec<_>>>()?;
+            if options.len() < 2 {
+                return Err(invalid_token(token, "alternation needs two or more bytes"));
+            }
+            return Ok(Self::Alt(options));
+        }
+
+        if let Some(inner) = token.strip_prefix('[') {
+            let inner = inner
+                .strip_suffix(']')
+                .ok_or_else(|| invalid_token(token, "unclosed gap"))?;
+            let parse = |s: &str| {
+                s.parse::<usize>()
+                    .map_err(|_| invalid_token(token, "invalid gap length"))
+            };
+            let (min, max) = match inner.split_once('-') {
+                Some((min, max)) => (parse(min)?, parse(max)?),
+                None => (parse(inner)?, parse(inner)?),
+            };
+            if min > max || max == 0 || max > MAX_GAP {
+                return Err(invalid_token(
+                    token,
+                    &format!("gap must satisfy min <= max and 0 < max <= {}", MAX_GAP),
+                ));
+            }
+            return Ok(Self::Gap { min, max });
+        }
+
+        if let Some(inner) = token.strip_prefix('<') {
+            let name = inner
+                .strip_suffix('>')
+                .ok_or_else(|| invalid_token(token, "unclosed capture"))?;
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(invalid_token(token, "invalid capture name"));
+            }
+            return Ok(Self::Capture(name.to_string()));
+        }
+
+        MaskedByte::parse(token).map(Self::Byte)
+    }
+
+    /// Width of a fixed-size token (`None` for gaps)
+    fn fixed_width(&self) -> Option<usize> {
+        match self {
+            Self::Byte(_) | Self::Alt(_) => Some(1),
+            Self::Capture(_) => Some(0),
+            Self::Gap { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PatternToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Byte(byte) => write!(f, "{}", byte),
+            Self::Alt(options) => {
+                let options: Vec<String> = options.iter().map(ToString::to_string).collect();
+                write!(f, "({})", options.join("|"))
+            }
+            Self::Gap { min, max } if min == max => write!(f, "[{}]", min),
+            Self::Gap { min, max } => write!(f, "[{}-{}]", min, max),
+            Self::Capture(name) => write!(f, "<{}>", name),
+        }
+    }
+}
+
+/// A successful pattern match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Matched length in bytes
+    pub len: usize,
+    /// Capture positions relative to the match start
+    pub captures: Vec<(String, usize)>,
+}
+
+impl PatternMatch {
+    /// Position of a named capture, relative to the match start
+    pub fn capture(&self, name: &str) -> Option<usize> {
+        self.captures
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, pos)| *pos)
+    }
+}
+
+/// Parsed signature pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePattern {
+    tokens: Vec<PatternToken>,
+}
+
+impl SignaturePattern {
+    /// Build a pattern of exact and wildcard bytes
+    pub fn from_bytes(bytes: &[Option<u8>]) -> Self {
+        let tokens = bytes
+            .iter()
+            .map(|b| PatternToken::Byte(b.map_or(MaskedByte::ANY, MaskedByte::exact)))
+            .collect();
+        Self { tokens }
+    }
+
+    pub fn tokens(&self) -> &[PatternToken] {
+        &self.tokens
+    }
+
+    /// Shortest possible match length
+    pub fn min_len(&self) -> usize {
+        self.tokens
+            .iter()
+            .map(|t| match t {
+                PatternToken::Gap { min, .. } => *min,
+                other => other.fixed_width().unwrap_or(0),
+            })
+            .sum()
+    }
+
+    /// Longest possible match length
+    pub fn max_len(&self) -> usize {
+        self.tokens
+            .iter()
+            .map(|t| match t {
+                PatternToken::Gap { max, .. } => *max,
+                other => other.fixed_width().unwrap_or(0),
+            })
+            .sum()
+    }
+
+    /// Names of the capture points, in pattern order
+    pub fn capture_names(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().filter_map(|t| match t {
+            PatternToken::Capture(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Exact bytes at fixed offsets before the first gap
+    ///
+    /// Used to pick prefilter anchors; nibble wildcards and alternations
+    /// are not included.
+    pub fn fixed_head(&self) -> Vec<(usize, u8)> {
+        let mut offset = 0;
+        let mut head = Vec::new();
+        for token in &self.tokens {
+            match token {
+                PatternToken::Byte(byte) => {
+                    if let Some(value) = byte.exact_value() {
+                        head.push((offset, value));
+                    }
+                    offset += 1;
+                }
+                PatternToken::Alt(_) => offset += 1,
+                PatternToken::Capture(_) => {}
+                PatternToken::Gap { .. } => break,
+            }
+        }
+        head
+    }
+
+    /// Plain byte form, if the pattern only uses exact bytes and `??`
+    pub fn to_bytes(&self) -> Option<Vec<Option<u8>>> {
+        self.tokens
+            .iter()
+            .filter(|t| !matches!(t, PatternToken::Capture(_)))
+            .map(|t| match t {
+                PatternToken::Byte(b) if b.mask == 0 => Some(None),
+                PatternToken::Byte(b) => b.exact_value().map(Some),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Try to match at the start of `data`
+    ///
+    /// Gaps prefer the shortest length that lets the rest of the pattern match.
+    /// An empty pattern never matches.
+    pub fn match_at(&self, data: &[u8]) -> Option<PatternMatch> {
+        if self.tokens.is_empty() {
+            return None;
+        }
+        let mut positions = vec![0; self.tokens.len()];
+        let len = self.match_from(data, 0, 0, &mut positions)?;
+        let captures = self
+            .tokens
+            .iter()
+            .zip(&positions)
+            .filter_map(|(token, pos)| match token {
+                PatternToken::Capture(name) => Some((name.clone(), *pos)),
+                _ => None,
+            })
+            .collect();
+        Some(PatternMatch { len, captures })
+    }
+
+    /// Whether the pattern matches at the start of `data`
+    pub fn is_match(&self, data: &[u8]) -> bool {
+        if self.tokens.is_empty() {
+            return false;
+        }
+        let mut positions = vec![0; self.tokens.len()];
+        self.match_from(data, 0, 0, &mut positions).is_some()
+    }
+
+    fn match_from(
+        &self,
+        data: &[u8],
+        index: usize,
+        pos: usize,
+        positions: &mut [usize],
+    ) -> Option<usize> {
+        let Some(token) = self.tokens.get(index) else {
+            return Some(pos);
+        };
+        positions[index] = pos;
+
+        match token {
+            PatternToken::Byte(byte) => {
+                let actual = *data.get(pos)?;
+                byte.matches(actual).then_some(())?;
+                self.match_from(data, index + 1, pos + 1, positions)
+            }
+            PatternToken::Alt(options) => {
+                let actual = *data.get(pos)?;
+                options.iter().any(|o| o.matches(actual)).then_some(())?;
+                self.match_from(data, index + 1, pos + 1, positions)
+            }
+            PatternToken::Capture(_) => self.match_from(data, index + 1, pos, positions),
+            PatternToken::Gap { min, max } => {
+                (*min..=*max).find_map(|len| self.match_from(data, index + 1, pos + len, positions))
+            }
+        }
+    }
+}
+
+impl FromStr for SignaturePattern {
+    type Err = Error;
+
+    fn from_str(pattern: &str) -> Result<Self> {
+        let tokens = pattern
+            .split_whitespace()
+            .map(PatternToken::parse)
+            .collect::<Result<Vec<_>>>()?;
+
+        let is_byte = |t: &PatternToken| matches!(t, PatternToken::Byte(_) | PatternToken::Alt(_));
+        if !tokens.iter().any(is_byte) {
+            return Err(Error::InvalidOffset(
+                "Signature pattern is empty".to_string(),
+            ));
+        }
+
+        let first_byte = tokens.iter().position(is_byte).unwrap_or(0);
+        let last_byte = tokens.iter().rposition(is_byte).unwrap_or(0);
+        let gap_outside = tokens.iter().enumerate().any(|(i, t)| {
+            matches!(t, PatternToken::Gap { .. }) && (i < first_byte || i > last_byte)
+        });
+        if gap_outside {
+            return Err(Error::InvalidOffset(format!(
+                "Gaps must be between bytes in signature pattern '{}'",
+                pattern
+            )));
+        }
+
+        let mut names: Vec<&str> = tokens
+            .iter()
+            .filter_map(|t| match t {
+                PatternToken::Capture(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        names.sort_unstable();
+        if let Some(pair) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Error::InvalidOffset(format!(
+                "Duplicate capture '<{}>' in signature pattern",
+                pair[0]
+            )));
+        }
+
+        Ok(Self { tokens })
+    }
+}
+
+impl fmt::Display for SignaturePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self.tokens.iter().map(ToString::to_string).collect();
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+impl From<Vec<Option<u8>>> for SignaturePattern {
+    fn from(bytes: Vec<Option<u8>>) -> Self {
+        Self::from_bytes(&bytes)
+    }
+}
+
+fn invalid_token(token: &str, reason: &str) -> Error {
+    Error::InvalidOffset(format!("Invalid signature token '{}': {}", token, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> SignaturePattern {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_all_token_kinds() {
+        for text in [
+            "48 8D 0D ?? ?? ?? ??",
+            "4? 8B (05|0D|1?) <disp> ?? ?? ?? ?? <next> [4-8] ?D E8",
+            "89 [3] 05",
+        ] {
+            assert_eq!(pattern(text).to_string(), text);
+        }
+        // Single `?` and lowercase hex are normalised
+        assert_eq!(pattern("4c ? 8d").to_string(), "4C ?? 8D");
+    }
+
+    #[test]
+    fn test_nibble_and_alternation_matching() {
+        let p = pattern("4? 8D (05|0D)");
+        assert!(p.is_match(&[0x48, 0x8D, 0x05]));
+        assert!(p.is_match(&[0x4C, 0x8D, 0x0D]));
+        assert!(!p.is_match(&[0x58, 0x8D, 0x05]));
+        assert!(!p.is_match(&[0x48, 0x8D, 0x15]));
+        assert!(pattern("?C").is_match(&[0x4C]));
+    }
+
+    #[test]
+    fn test_gap_shifts_captures() {
+        let p = pattern("E8 [1-3] <disp> 8D ?? <next>");
+        let m = p.match_at(&[0xE8, 0x00, 0x00, 0x8D, 0x01]).unwrap();
+        assert_eq!(m.len, 5);
+        assert_eq!(m.capture("disp"), Some(3));
+        assert_eq!(m.capture("next"), Some(5));
+        assert!(p.match_at(&[0xE8, 0, 0, 0, 0, 0x8D, 0x01]).is_none());
+        assert_eq!((p.min_len(), p.max_len()), (4, 6));
+    }
+
+    #[test]
+    fn test_fixed_head_and_plain_bytes() {
+        let p = pattern("48 (05|0D) ?? 8B [2] 89");
+        assert_eq!(p.fixed_head(), vec![(0, 0x48), (3, 0x8B)]);
+        assert_eq!(p.to_bytes(), None);
+        assert_eq!(
+            pattern("48 ?? <disp> 8B").to_bytes(),
+            Some(vec![Some(0x48), None, Some(0x8B)])
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        for text in [
+            "",
+            "<disp>",
+            "[4] 48",
+            "48 [4]",
+            "48 [8-4] 89",
+            "48 [100] 89",
+            "48 (05) 89",
+            "48 (05|0D",
+            "48 <1x>",
+            "48 <a> 89 <a>",
+            "4G",
+            "488D",
+        ] {
+            assert!(text.parse::<SignaturePattern>().is_err(), "{:?}", text);
+        }
+    }
+}
@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::chart::ChartInfo;
+use crate::error::Result;
 use crate::play::{AssistType, Settings};
+use crate::process::layout::play;
+use crate::process::{FromMemory, StructLayout};
 use crate::score::{Grade, Judge, Lamp};
 
 /// Complete play data for a single play
@@ -42,6 +45,26 @@ impl PlayData {
     }
 }
 
+/// Raw play result values read directly from memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RawPlayData {
+    pub song_id: i32,
+    pub difficulty: i32,
+    pub lamp: i32,
+}
+
+impl FromMemory for RawPlayData {
+    const LAYOUT: &'static StructLayout = &play::LAYOUT;
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            song_id: play::SONG_ID.decode(bytes)?,
+            difficulty: play::DIFFICULTY.decode(bytes)?,
+            lamp: play::LAMP.decode(bytes)?,
+        })
+    }
+}
+
 // DJ Points calculation constants
 // Based on the official DJ Points formula from beatmania IIDX
 const DJ_POINTS_GRADE_A_BASE_BONUS: i32 = 10;
@@ -3,7 +3,9 @@ use strum::{Display, IntoStaticStr};
 use thiserror::Error;
 use tracing::warn;
 
+use crate::error;
 use crate::play::PlayType;
+use crate::process::layout::settings;
 
 /// Error for invalid enum value conversion
 #[derive(Debug, Error)]
@@ -44,9 +46,50 @@ pub struct RawSettings {
     pub h_ran: i32,
 }
 
+impl RawSettings {
+    /// Decode the options for the given play type from the settings structure
+    ///
+    /// `bytes` must cover [`settings::LAYOUT`]. Flip and battle are shared;
+    /// the other options are read from the P2 block for [`PlayType::P2`].
+    pub fn decode(bytes: &[u8], play_type: PlayType) -> error::Result<Self> {
+        let (style, assist, range, h_ran, style2) = match play_type {
+            PlayType::P1 | PlayType::Dp => {
+                let style2 = if play_type == PlayType::Dp {
+                    settings::STYLE2.decode(bytes)?
+                } else {
+                    0
+                };
+                (
+                    settings::STYLE.decode(bytes)?,
+                    settings::ASSIST.decode(bytes)?,
+                    settings::RANGE.decode(bytes)?,
+                    settings::H_RAN.decode(bytes)?,
+                    style2,
+                )
+            }
+            PlayType::P2 => (
+                settings::P2_STYLE.decode(bytes)?,
+                settings::P2_ASSIST.decode(bytes)?,
+                settings::P2_RANGE.decode(bytes)?,
+                settings::P2_H_RAN.decode(bytes)?,
+                0,
+            ),
+        };
+
+        Ok(Self {
+            play_type,
+            style,
+            style2,
+            assist,
+            range,
+            flip: settings::FLIP.decode(bytes)?,
+            battle: settings::BATTLE.decode(bytes)?,
+            h_ran,
+        })
+    }
+}
+
 impl Settings {
-    /// P2 settings offset (4 * 15 = 60 bytes)
-    pub const P2_OFFSET: u64 = 60;
     pub const WORD_SIZE: u64 = 4;
 
     /// Build settings from raw memory values.
@@ -264,6 +307,25 @@ mod tests {
         assert!(RangeType::try_from(-1).is_err());
     }
 
+    #[test]
+    fn test_raw_settings_decode_p2_block() {
+        let mut bytes = vec![0u8; settings::LAYOUT.size];
+        let mut put = |offset: usize, value: i32| {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        put(settings::STYLE.offset, 1);
+        put(settings::P2_STYLE.offset, 4);
+        put(settings::P2_RANGE.offset, 2);
+        put(settings::FLIP.offset, 1);
+
+        let p1 = RawSettings::decode(&bytes, PlayType::P1).unwrap();
+        assert_eq!((p1.style, p1.range, p1.flip), (1, 0, 1));
+
+        let p2 = RawSettings::decode(&bytes, PlayType::P2).unwrap();
+        assert_eq!((p2.style, p2.range, p2.flip), (4, 2, 1));
+        assert!(RawSettings::decode(&bytes[..40], PlayType::P2).is_err());
+    }
+
     #[test]
     fn test_settings_from_raw_p1() {
         let settings = Settings::from_raw(RawSettings {
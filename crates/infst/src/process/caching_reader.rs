@@ -0,0 +1,324 @@
+//! Per-tick read cache.
+//!
+//! The tracker loop issues many small reads against the same few structures
+//! (judge data, play settings, play data) on every poll. `CachingReader` loads
+//! the surrounding page once and serves those reads from memory until it is
+//! invalidated, turning ~30 `ReadProcessMemory` calls into one or two.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+use super::ReadMemory;
+use super::regions::MemoryRegions;
+use crate::error::{Error, Result};
+
+/// Default cache page size (4KB, the x86-64 page size).
+pub const DEFAULT_PAGE_SIZE: usize = 4096;
+
+/// Read counters for a [`CachingReader`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads served entirely from cached data.
+    pub hits: u64,
+    /// Reads that had to load data from the inner reader.
+    pub misses: u64,
+    /// Reads larger than a page, passed straight to the inner reader.
+    pub bypassed: u64,
+}
+
+impl CacheStats {
+    /// Fraction of cacheable reads served from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Memory reader that coalesces small reads into page-sized loads.
+///
+/// Cached data is never refreshed implicitly: call [`invalidate`](Self::invalidate)
+/// whenever the underlying memory may have changed (once per tick in the
+/// tracker loop, and before each result screen poll).
+///
+/// Reads larger than the page size bypass the cache. If loading a whole page
+/// fails (e.g. it straddles an unmapped region), the exact range is read
+/// instead and the result is not cached.
+pub struct CachingReader<R: ReadMemory> {
+    inner: R,
+    page_size: usize,
+    blocks: RefCell<BTreeMap<u64, Vec<u8>>>,
+    stats: Cell<CacheStats>,
+}
+
+impl<R: ReadMemory> CachingReader<R> {
+    /// Create a cache with the default page size.
+    pub fn new(inner: R) -> Self {
+        Self::with_page_size(inner, DEFAULT_PAGE_SIZE)
+    }
+
+    /// Create a cache with a custom page size (must be a power of two).
+    pub fn with_page_size(inner: R, page_size: usize) -> Self {
+        assert!(
+            page_size.is_power_of_two(),
+            "page size must be a power of two"
+        );
+        Self {
+            inner,
+            page_size,
+            blocks: RefCell::new(BTreeMap::new()),
+            stats: Cell::new(CacheStats::default()),
+        }
+    }
+
+    /// Drop all cached data.
+    pub fn invalidate(&self) {
+        self.blocks.borrow_mut().clear();
+    }
+
+    /// Load a whole structure into the cache ahead of the reads that use it.
+    pub fn prefetch(&self, address: u64, size: usize) -> Result<()> {
+        let data = self.inner.read_bytes(address, size)?;
+        self.blocks.borrow_mut().insert(address, data);
+        Ok(())
+    }
+
+    /// Current read counters.
+    pub fn stats(&self) -> CacheStats {
+        self.stats.get()
+    }
+
+    /// Reset the read counters to zero.
+    pub fn reset_stats(&self) {
+        self.stats.set(CacheStats::default());
+    }
+
+    /// Get the wrapped reader.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Number of cached blocks (pages and prefetched structures).
+    pub fn cached_blocks(&self) -> usize {
+        self.blocks.borrow().len()
+    }
+
+    fn record(&self, update: impl FnOnce(&mut CacheStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    /// Copy `size` bytes at `address` out of a cached block, if one covers it.
+    ///
+    /// Blocks may overlap (a prefetched structure inside a loaded page), so
+    /// every block starting at or below `address` is considered, nearest first.
+    fn lookup(&self, address: u64, size: usize) -> Option<Vec<u8>> {
+        let blocks = self.blocks.borrow();
+        blocks.range(..=address).rev().find_map(|(&start, data)| {
+            let offset = (address - start) as usize;
+            data.get(offset..offset.checked_add(size)?)
+                .map(<[u8]>::to_vec)
+        })
+    }
+
+    /// Load the page-aligned span covering the read.
+    fn load_pages(&self, address: u64, size: usize) -> Result<()> {
+        let mask = !(self.page_size as u64 - 1);
+        let start = address & mask;
+        let end = address
+            .checked_add(size as u64)
+            .and_then(|end| end.checked_add(self.page_size as u64 - 1))
+            .ok_or_else(|| Error::MemoryReadFailed {
+                address,
+                message: "page span exceeds the address space".to_string(),
+            })?
+            & mask;
+        let data = self.inner.read_bytes(start, (end - start) as usize)?;
+        self.blocks.borrow_mut().insert(start, data);
+        Ok(())
+    }
+}
+
+impl<R: ReadMemory> ReadMemory for CachingReader<R> {
+    fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        if size > self.page_size {
+            self.record(|s| s.bypassed += 1);
+            return self.inner.read_bytes(address, size);
+        }
+
+        if let Some(bytes) = self.lookup(address, size) {
+            self.record(|s| s.hits += 1);
+            return Ok(bytes);
+        }
+
+        self.record(|s| s.misses += 1);
+        if self.load_pages(address, size).is_ok()
+            && let Some(bytes) = self.lookup(address, size)
+        {
+            return Ok(bytes);
+        }
+        self.inner.read_bytes(address, size)
+    }
+
+    fn base_address(&self) -> u64 {
+        self.inner.base_address()
+    }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        self.inner.region_map()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{MockMemoryBuilder, MockMemoryReader};
+
+    /// Counts reads reaching the inner reader.
+    struct CountingReader {
+        inner: MockMemoryReader,
+        reads: Cell<usize>,
+    }
+
+    impl ReadMemory for CountingReader {
+        fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.read_bytes(address, size)
+        }
+
+        fn base_address(&self) -> u64 {
+            self.inner.base_address()
+        }
+    }
+
+    fn counting(size: usize) -> CountingReader {
+        let inner = MockMemoryBuilder::new()
+            .base(0x10000)
+            .with_size(size)
+            .write_u32(0x100, 11)
+            .write_u32(0x104, 22)
+            .build();
+        CountingReader {
+            inner,
+            reads: Cell::new(0),
+        }
+    }
+
+    #[test]
+    fn test_small_reads_share_one_page_load() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        assert_eq!(cache.read_u32(0x10100).unwrap(), 11);
+        assert_eq!(cache.read_u32(0x10104).unwrap(), 22);
+        assert_eq!(cache.read_i32(0x10108).unwrap(), 0);
+
+        assert_eq!(cache.inner().reads.get(), 1);
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 2,
+                misses: 1,
+                bypassed: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_straddling_pages() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        let bytes = cache.read_bytes(0x10FFE, 4).unwrap();
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(cache.inner().reads.get(), 1);
+        assert!(cache.read_bytes(0x11000, 8).is_ok());
+        assert_eq!(cache.inner().reads.get(), 1);
+    }
+
+    #[test]
+    fn test_invalidate_forces_reload() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        cache.read_u32(0x10100).unwrap();
+        cache.invalidate();
+        assert_eq!(cache.cached_blocks(), 0);
+        cache.read_u32(0x10100).unwrap();
+
+        assert_eq!(cache.inner().reads.get(), 2);
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn test_large_reads_bypass_cache() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        cache.read_bytes(0x10000, 0x2000).unwrap();
+        cache.read_bytes(0x10000, 0x2000).unwrap();
+
+        assert_eq!(cache.inner().reads.get(), 2);
+        assert_eq!(cache.stats().bypassed, 2);
+        assert_eq!(cache.cached_blocks(), 0);
+    }
+
+    #[test]
+    fn test_falls_back_to_exact_read_at_image_end() {
+        // Image ends mid-page, so the page load fails
+        let inner = MockMemoryBuilder::new()
+            .base(0x10000)
+            .write_u32(0x1700, 33)
+            .build();
+        let cache = CachingReader::new(inner);
+
+        assert_eq!(cache.read_u32(0x11700).unwrap(), 33);
+        assert_eq!(cache.stats().misses, 1);
+        assert_eq!(cache.cached_blocks(), 0);
+        assert!(cache.read_u32(0x20000).is_err());
+    }
+
+    #[test]
+    fn test_read_near_address_space_end_skips_page_load() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        // The page span would wrap past u64::MAX; the exact read still runs
+        assert!(cache.read_u32(u64::MAX - 2).is_err());
+        assert_eq!(cache.cached_blocks(), 0);
+        assert_eq!(cache.inner().reads.get(), 1);
+    }
+
+    #[test]
+    fn test_prefetch_serves_structure() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        cache.prefetch(0x10100, 8).unwrap();
+        assert_eq!(cache.read_u32(0x10104).unwrap(), 22);
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.inner().reads.get(), 1);
+    }
+
+    #[test]
+    fn test_prefetched_block_does_not_shadow_page() {
+        let cache = CachingReader::new(counting(0x4000));
+
+        cache.read_u32(0x10100).unwrap();
+        cache.prefetch(0x10100, 4).unwrap();
+
+        // 0x10104 lies past the small block but inside the page loaded first
+        assert_eq!(cache.read_u32(0x10104).unwrap(), 22);
+        assert_eq!(cache.inner().reads.get(), 2);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_hit_rate() {
+        let stats = CacheStats {
+            hits: 3,
+            misses: 1,
+            bypassed: 5,
+        };
+        assert!((stats.hit_rate() - 0.75).abs() < f64::EPSILON);
+        assert_eq!(CacheStats::default().hit_rate(), 0.0);
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! This module provides an iterator-based approach to reading large memory regions
 //! in fixed-size chunks, avoiding memory pressure from loading entire regions at once.
+//! When the reader exposes a region map, unreadable gaps are skipped up front.
+
+use std::collections::VecDeque;
+use std::ops::Range;
 
 use super::ReadMemory;
+use super::regions::{RangePlan, ScanReport};
 use crate::error::Result;
 
 /// Default chunk size for memory reading (4MB).
@@ -18,10 +23,21 @@ pub struct MemoryChunk {
     pub data: Vec<u8>,
 }
 
+impl MemoryChunk {
+    /// End address of this chunk (exclusive).
+    pub fn end(&self) -> u64 {
+        self.address + self.data.len() as u64
+    }
+}
+
 /// Iterator that reads memory in fixed-size chunks.
 ///
 /// This is useful for searching large memory regions without loading
-/// everything into memory at once.
+/// everything into memory at once. Chunks never span an unreadable gap, so
+/// consecutive chunks are only adjacent when `chunk.address` equals the
+/// previous chunk's end. Gaps and failed reads are recorded in [`report`].
+///
+/// [`report`]: ChunkedMemoryIterator::report
 ///
 /// # Example
 ///
@@ -35,14 +51,14 @@ pub struct MemoryChunk {
 ///     }
 /// }
 /// ```
-pub struct ChunkedMemoryIterator<'a, R: ReadMemory> {
+pub struct ChunkedMemoryIterator<'a, R: ReadMemory + ?Sized> {
     reader: &'a R,
-    current: u64,
-    end: u64,
+    spans: VecDeque<Range<u64>>,
     chunk_size: usize,
+    report: ScanReport,
 }
 
-impl<'a, R: ReadMemory> ChunkedMemoryIterator<'a, R> {
+impl<'a, R: ReadMemory + ?Sized> ChunkedMemoryIterator<'a, R> {
     /// Create a new chunked memory iterator.
     ///
     /// # Arguments
@@ -52,11 +68,27 @@ impl<'a, R: ReadMemory> ChunkedMemoryIterator<'a, R> {
     /// * `end` - Ending address (exclusive)
     /// * `chunk_size` - Size of each chunk to read
     pub fn new(reader: &'a R, start: u64, end: u64, chunk_size: usize) -> Self {
+        Self::with_plan(
+            reader,
+            RangePlan::for_reader(reader, start, end),
+            chunk_size,
+        )
+    }
+
+    /// Create a chunked memory iterator over a precomputed range plan.
+    ///
+    /// Lets callers that scan the same range repeatedly enumerate the region
+    /// map once instead of on every scan.
+    pub fn with_plan(reader: &'a R, plan: RangePlan, chunk_size: usize) -> Self {
+        let mut report = ScanReport::default();
+        for gap in plan.skipped {
+            report.skip(gap);
+        }
         Self {
             reader,
-            current: start,
-            end,
+            spans: plan.readable.into(),
             chunk_size,
+            report,
         }
     }
 
@@ -64,31 +96,49 @@ impl<'a, R: ReadMemory> ChunkedMemoryIterator<'a, R> {
     pub fn with_default_chunk_size(reader: &'a R, start: u64, end: u64) -> Self {
         Self::new(reader, start, end, DEFAULT_CHUNK_SIZE)
     }
+
+    /// Bytes read and ranges skipped so far.
+    ///
+    /// Gaps known from the region map are listed from the start; failed reads
+    /// are added as they happen.
+    pub fn report(&self) -> &ScanReport {
+        &self.report
+    }
+
+    /// Consume the iterator, returning its report.
+    pub fn into_report(self) -> ScanReport {
+        self.report
+    }
 }
 
-impl<R: ReadMemory> Iterator for ChunkedMemoryIterator<'_, R> {
+impl<R: ReadMemory + ?Sized> Iterator for ChunkedMemoryIterator<'_, R> {
     type Item = Result<MemoryChunk>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.end {
-            return None;
+        let span = self.spans.front_mut()?;
+        let address = span.start;
+        let read_size = self.chunk_size.min((span.end - address) as usize);
+        span.start += read_size as u64;
+        if span.is_empty() {
+            self.spans.pop_front();
         }
 
-        let read_size = self.chunk_size.min((self.end - self.current) as usize);
-        let address = self.current;
-        self.current += read_size as u64;
-
-        Some(
-            self.reader
-                .read_bytes(address, read_size)
-                .map(|data| MemoryChunk { address, data }),
-        )
+        let result = self.reader.read_bytes(address, read_size);
+        match &result {
+            Ok(_) => self.report.scanned_bytes += read_size as u64,
+            Err(_) => {
+                self.report.skip(address..address + read_size as u64);
+                self.report.skipped.sort_by_key(|r| r.start);
+            }
+        }
+        Some(result.map(|data| MemoryChunk { address, data }))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::MemoryRegions;
     use crate::process::mock::MockMemoryBuilder;
 
     #[test]
@@ -151,4 +201,39 @@ mod tests {
         assert_eq!(chunks[1].data.len(), 1);
         assert_eq!(chunks[1].data, vec![5]);
     }
+
+    #[test]
+    fn test_chunked_iterator_skips_unmapped_gap() {
+        let reader = MockMemoryBuilder::new()
+            .with_size(0x30)
+            .unmapped(0x10, 0x10)
+            .build();
+
+        let mut iter = ChunkedMemoryIterator::new(&reader, 0x1000, 0x1040, 0x20);
+        let chunks: Vec<_> = iter.by_ref().map(|c| c.unwrap()).collect();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].address, chunks[0].end()), (0x1000, 0x1010));
+        assert_eq!((chunks[1].address, chunks[1].end()), (0x1020, 0x1030));
+        assert_eq!(iter.report().skipped, vec![0x1010..0x1020, 0x1030..0x1040]);
+        assert_eq!(iter.report().scanned_bytes, 0x20);
+    }
+
+    #[test]
+    fn test_chunked_iterator_with_plan_uses_given_spans() {
+        let reader = MockMemoryBuilder::new().with_size(0x30).build();
+        let plan = RangePlan::from_regions(&reader.memory_regions().unwrap(), 0x1000, 0x1010);
+
+        let chunks: Vec<_> = ChunkedMemoryIterator::with_plan(&reader, plan.clone(), 0x20)
+            .map(|c| c.unwrap())
+            .collect();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!((chunks[0].address, chunks[0].end()), (0x1000, 0x1010));
+
+        // The plan is reusable across scans of the same range
+        assert_eq!(
+            ChunkedMemoryIterator::with_plan(&reader, plan, 0x20).count(),
+            1
+        );
+    }
 }
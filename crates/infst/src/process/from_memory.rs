@@ -0,0 +1,366 @@
+//! Declarative struct layouts.
+//!
+//! A [`StructLayout`] lists the named, typed fields of a game structure at
+//! fixed offsets. Types implementing [`FromMemory`] are decoded from a single
+//! `read_bytes` call covering the whole layout, and debug tools reuse the same
+//! descriptors to label raw bytes.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::{ByteBuffer, ReadMemory, decode_shift_jis};
+use crate::error::Result;
+
+/// Type of a field, as needed to size and display it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    U8,
+    I32,
+    U32,
+    U64,
+    /// Raw bytes of the given length
+    Bytes(usize),
+    /// Array of the given number of u32 values
+    U32Array(usize),
+    /// Null-terminated Shift-JIS string in a fixed-size slot
+    ShiftJis(usize),
+}
+
+impl FieldKind {
+    /// Size of the field in bytes
+    pub const fn size(self) -> usize {
+        match self {
+            FieldKind::U8 => 1,
+            FieldKind::I32 | FieldKind::U32 => 4,
+            FieldKind::U64 => 8,
+            FieldKind::Bytes(n) | FieldKind::ShiftJis(n) => n,
+            FieldKind::U32Array(n) => n * 4,
+        }
+    }
+}
+
+/// Rust type that a field decodes to
+pub trait FieldType {
+    type Value;
+    const KIND: FieldKind;
+
+    /// Decode from a slice of exactly `KIND.size()` bytes
+    fn decode(bytes: &[u8]) -> Self::Value;
+}
+
+impl FieldType for u8 {
+    type Value = u8;
+    const KIND: FieldKind = FieldKind::U8;
+
+    fn decode(bytes: &[u8]) -> u8 {
+        bytes[0]
+    }
+}
+
+impl FieldType for i32 {
+    type Value = i32;
+    const KIND: FieldKind = FieldKind::I32;
+
+    fn decode(bytes: &[u8]) -> i32 {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl FieldType for u32 {
+    type Value = u32;
+    const KIND: FieldKind = FieldKind::U32;
+
+    fn decode(bytes: &[u8]) -> u32 {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl FieldType for u64 {
+    type Value = u64;
+    const KIND: FieldKind = FieldKind::U64;
+
+    fn decode(bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl<const N: usize> FieldType for [u8; N] {
+    type Value = [u8; N];
+    const KIND: FieldKind = FieldKind::Bytes(N);
+
+    fn decode(bytes: &[u8]) -> [u8; N] {
+        let mut values = [0u8; N];
+        values.copy_from_slice(bytes);
+        values
+    }
+}
+
+impl<const N: usize> FieldType for [u32; N] {
+    type Value = [u32; N];
+    const KIND: FieldKind = FieldKind::U32Array(N);
+
+    fn decode(bytes: &[u8]) -> [u32; N] {
+        let mut values = [0u32; N];
+        for (value, chunk) in values.iter_mut().zip(bytes.chunks_exact(4)) {
+            *value = u32::decode(chunk);
+        }
+        values
+    }
+}
+
+/// Marker for a Shift-JIS string field stored in an `N`-byte slot
+pub struct ShiftJis<const N: usize>;
+
+impl<const N: usize> FieldType for ShiftJis<N> {
+    type Value = Arc<str>;
+    const KIND: FieldKind = FieldKind::ShiftJis(N);
+
+    fn decode(bytes: &[u8]) -> Arc<str> {
+        decode_shift_jis(bytes)
+    }
+}
+
+/// Untyped description of a field, used for labelling bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    pub name: &'static str,
+    pub offset: usize,
+    pub kind: FieldKind,
+}
+
+impl FieldDescriptor {
+    /// Offset one past the last byte of the field
+    pub const fn end(&self) -> usize {
+        self.offset + self.kind.size()
+    }
+
+    /// Whether the field covers the given struct offset
+    pub fn contains(&self, offset: usize) -> bool {
+        (self.offset..self.end()).contains(&offset)
+    }
+
+    /// Format the field's value from the bytes of the whole structure
+    ///
+    /// Returns `None` if `bytes` does not cover the field.
+    pub fn format_value(&self, bytes: &[u8]) -> Option<String> {
+        let data = bytes.get(self.offset..self.end())?;
+        let text = match self.kind {
+            FieldKind::U8 => u8::decode(data).to_string(),
+            FieldKind::I32 => i32::decode(data).to_string(),
+            FieldKind::U32 => u32::decode(data).to_string(),
+            FieldKind::U64 => format!("{:#x}", u64::decode(data)),
+            FieldKind::Bytes(_) => format!("{:?}", data),
+            FieldKind::U32Array(_) => {
+                let values: Vec<u32> = data.chunks_exact(4).map(u32::decode).collect();
+                format!("{:?}", values)
+            }
+            FieldKind::ShiftJis(_) => format!("{:?}", decode_shift_jis(data)),
+        };
+        Some(text)
+    }
+}
+
+/// Typed field at a fixed offset within a structure
+pub struct Field<T: FieldType> {
+    pub name: &'static str,
+    pub offset: usize,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: FieldType> Clone for Field<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: FieldType> Copy for Field<T> {}
+
+impl<T: FieldType> std::fmt::Debug for Field<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Field")
+            .field("name", &self.name)
+            .field("offset", &self.offset)
+            .field("kind", &T::KIND)
+            .finish()
+    }
+}
+
+impl<T: FieldType> Field<T> {
+    pub const fn new(name: &'static str, offset: usize) -> Self {
+        Self {
+            name,
+            offset,
+            _type: PhantomData,
+        }
+    }
+
+    /// Size of the field in bytes
+    pub const fn size(&self) -> usize {
+        T::KIND.size()
+    }
+
+    /// Absolute address of the field in a structure at `base`
+    pub const fn at(&self, base: u64) -> u64 {
+        base + self.offset as u64
+    }
+
+    /// Untyped descriptor for this field
+    pub const fn descriptor(&self) -> FieldDescriptor {
+        FieldDescriptor {
+            name: self.name,
+            offset: self.offset,
+            kind: T::KIND,
+        }
+    }
+
+    /// Decode the field from the bytes of the whole structure
+    pub fn decode(&self, bytes: &[u8]) -> Result<T::Value> {
+        let data = ByteBuffer::new(bytes).slice_at(self.offset, self.size())?;
+        Ok(T::decode(data))
+    }
+
+    /// Read only this field of a structure at `base`
+    pub fn read<R: ReadMemory + ?Sized>(&self, reader: &R, base: u64) -> Result<T::Value> {
+        let data = reader.read_bytes(self.at(base), self.size())?;
+        Ok(T::decode(&data))
+    }
+}
+
+/// Named list of fields making up a memory structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructLayout {
+    pub name: &'static str,
+    /// Bytes read to decode the structure (covers every field)
+    pub size: usize,
+    pub fields: &'static [FieldDescriptor],
+}
+
+impl StructLayout {
+    /// Look up a field by name
+    pub fn field(&self, name: &str) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Field covering the given struct offset, if any
+    pub fn field_at(&self, offset: usize) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|f| f.contains(offset))
+    }
+
+    /// Read the bytes of a structure at `address` in one call
+    pub fn read_bytes<R: ReadMemory + ?Sized>(&self, reader: &R, address: u64) -> Result<Vec<u8>> {
+        reader.read_bytes(address, self.size)
+    }
+}
+
+/// Type decoded from a memory structure described by a [`StructLayout`]
+pub trait FromMemory: Sized {
+    const LAYOUT: &'static StructLayout;
+
+    /// Decode from the bytes of the structure (at least `LAYOUT.size` long)
+    fn decode(bytes: &[u8]) -> Result<Self>;
+
+    /// Read and decode the structure at `address` with a single read
+    fn read<R: ReadMemory + ?Sized>(reader: &R, address: u64) -> Result<Self> {
+        let bytes = Self::LAYOUT.read_bytes(reader, address)?;
+        Self::decode(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    const COUNT: Field<u32> = Field::new("count", 4);
+    const NEXT: Field<u64> = Field::new("next", 8);
+    const TITLE: Field<ShiftJis<8>> = Field::new("title", 16);
+    const NOTES: Field<[u32; 2]> = Field::new("notes", 24);
+
+    const LAYOUT: StructLayout = StructLayout {
+        name: "sample",
+        size: 32,
+        fields: &[
+            COUNT.descriptor(),
+            NEXT.descriptor(),
+            TITLE.descriptor(),
+            NOTES.descriptor(),
+        ],
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Sample {
+        count: u32,
+        next: u64,
+        title: Arc<str>,
+        notes: [u32; 2],
+    }
+
+    impl FromMemory for Sample {
+        const LAYOUT: &'static StructLayout = &LAYOUT;
+
+        fn decode(bytes: &[u8]) -> Result<Self> {
+            Ok(Self {
+                count: COUNT.decode(bytes)?,
+                next: NEXT.decode(bytes)?,
+                title: TITLE.decode(bytes)?,
+                notes: NOTES.decode(bytes)?,
+            })
+        }
+    }
+
+    fn sample_reader() -> crate::process::MockMemoryReader {
+        MockMemoryBuilder::new()
+            .base(0x1000)
+            .with_size(0x100)
+            .write_u32(0x14, 7)
+            .write_u64(0x18, 0xDEADBEEF)
+            .write_shift_jis(0x20, "GAMBOL")
+            .write_u32(0x28, 400)
+            .write_u32(0x2C, 700)
+            .build()
+    }
+
+    #[test]
+    fn test_from_memory_single_read() {
+        let sample = Sample::read(&sample_reader(), 0x1010).unwrap();
+        assert_eq!(
+            sample,
+            Sample {
+                count: 7,
+                next: 0xDEADBEEF,
+                title: "GAMBOL".into(),
+                notes: [400, 700],
+            }
+        );
+    }
+
+    #[test]
+    fn test_field_read_and_address() {
+        let reader = sample_reader();
+        assert_eq!(COUNT.at(0x1010), 0x1014);
+        assert_eq!(COUNT.read(&reader, 0x1010).unwrap(), 7);
+        assert_eq!(NOTES.size(), 8);
+    }
+
+    #[test]
+    fn test_decode_short_buffer_fails() {
+        assert!(Sample::decode(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_layout_lookup_and_format() {
+        let bytes = LAYOUT.read_bytes(&sample_reader(), 0x1010).unwrap();
+
+        assert_eq!(LAYOUT.field("next").unwrap().offset, 8);
+        assert_eq!(LAYOUT.field_at(11).unwrap().name, "next");
+        assert!(LAYOUT.field_at(2).is_none());
+
+        let format = |name| LAYOUT.field(name).unwrap().format_value(&bytes).unwrap();
+        assert_eq!(format("count"), "7");
+        assert_eq!(format("next"), "0xdeadbeef");
+        assert_eq!(format("title"), "\"GAMBOL\"");
+        assert_eq!(format("notes"), "[400, 700]");
+    }
+}
@@ -1,11 +1,21 @@
-#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#![cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(dead_code))]
 
 use crate::error::{Error, Result};
 use crate::process::provider::ProcessInfo;
+use crate::process::regions::{MemoryRegion, MemoryRegions};
 
 #[cfg(target_os = "windows")]
 use tracing::warn;
 
+#[cfg(target_os = "linux")]
+use crate::process::procfs;
+#[cfg(target_os = "linux")]
+use crate::process::provider::ProcessProvider;
+#[cfg(target_os = "linux")]
+use std::fs::File;
+
+#[cfg(target_os = "windows")]
+use crate::process::regions::Protection;
 #[cfg(target_os = "windows")]
 use std::ffi::OsString;
 #[cfg(target_os = "windows")]
@@ -17,6 +27,12 @@ use windows::Win32::System::Diagnostics::ToolHelp::{
     CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
 };
 #[cfg(target_os = "windows")]
+use windows::Win32::System::Memory::{
+    MEM_COMMIT, MEMORY_BASIC_INFORMATION, PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE,
+    PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_NOACCESS, PAGE_PROTECTION_FLAGS, PAGE_READONLY,
+    PAGE_READWRITE, PAGE_WRITECOPY, VirtualQueryEx,
+};
+#[cfg(target_os = "windows")]
 use windows::Win32::System::ProcessStatus::{
     EnumProcessModulesEx, GetModuleInformation, LIST_MODULES_ALL, MODULEINFO,
 };
@@ -25,7 +41,7 @@ use windows::Win32::System::Threading::{
     GetExitCodeProcess, OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
 };
 
-const PROCESS_NAME: &str = "bm2dx.exe";
+pub(crate) const PROCESS_NAME: &str = "bm2dx.exe";
 
 #[cfg(target_os = "windows")]
 pub struct ProcessHandle {
@@ -35,7 +51,22 @@ pub struct ProcessHandle {
     pub module_size: u32,
 }
 
-#[cfg(not(target_os = "windows"))]
+// SAFETY: the handle is only passed to process and memory APIs, which may be
+// called from any thread, and is closed once on drop.
+#[cfg(target_os = "windows")]
+unsafe impl Send for ProcessHandle {}
+#[cfg(target_os = "windows")]
+unsafe impl Sync for ProcessHandle {}
+
+#[cfg(target_os = "linux")]
+pub struct ProcessHandle {
+    mem: File,
+    pub pid: u32,
+    pub base_address: u64,
+    pub module_size: u32,
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub struct ProcessHandle {
     pub pid: u32,
     pub base_address: u64,
@@ -117,7 +148,142 @@ impl ProcessInfo for ProcessHandle {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "windows")]
+impl MemoryRegions for ProcessHandle {
+    /// Walk the address space with `VirtualQueryEx`, keeping committed regions
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut regions = Vec::new();
+        let mut address: usize = 0;
+
+        loop {
+            let mut info = MEMORY_BASIC_INFORMATION::default();
+            // SAFETY: VirtualQueryEx is called with a valid process handle obtained from
+            // OpenProcess (PROCESS_QUERY_INFORMATION) and a properly sized output struct.
+            let written = unsafe {
+                VirtualQueryEx(
+                    self.handle,
+                    Some(address as *const std::ffi::c_void),
+                    &mut info,
+                    std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+                )
+            };
+            if written == 0 {
+                break;
+            }
+
+            let start = info.BaseAddress as usize;
+            if info.State == MEM_COMMIT {
+                regions.push(MemoryRegion::new(
+                    start as u64,
+                    info.RegionSize as u64,
+                    protection_from_flags(info.Protect),
+                ));
+            }
+
+            match start.checked_add(info.RegionSize) {
+                Some(next) if next > address => address = next,
+                _ => break,
+            }
+        }
+
+        Ok(regions)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn protection_from_flags(flags: PAGE_PROTECTION_FLAGS) -> Protection {
+    if flags.0 & (PAGE_GUARD.0 | PAGE_NOACCESS.0) != 0 {
+        return Protection::NONE;
+    }
+
+    // Low byte holds the access type; higher bits are modifiers (e.g. PAGE_NOCACHE)
+    let access = PAGE_PROTECTION_FLAGS(flags.0 & 0xFF);
+    [
+        (PAGE_READONLY, Protection::READ),
+        (PAGE_READWRITE, Protection::READ_WRITE),
+        (PAGE_WRITECOPY, Protection::READ_WRITE),
+        (PAGE_EXECUTE, Protection::EXECUTE),
+        (PAGE_EXECUTE_READ, Protection::READ_EXECUTE),
+        (PAGE_EXECUTE_READWRITE, Protection::READ_WRITE_EXECUTE),
+        (PAGE_EXECUTE_WRITECOPY, Protection::READ_WRITE_EXECUTE),
+    ]
+    .into_iter()
+    .find(|(flag, _)| *flag == access)
+    .map_or(Protection::NONE, |(_, protection)| protection)
+}
+
+#[cfg(target_os = "linux")]
+impl MemoryRegions for ProcessHandle {
+    /// Mappings from `/proc/<pid>/maps`
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>> {
+        Ok(procfs::read_maps(self.pid)?
+            .iter()
+            .map(procfs::MapsEntry::region)
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl MemoryRegions for ProcessHandle {
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>> {
+        Err(Error::ProcessNotFound(
+            "Windows only: process access not supported on this platform".to_string(),
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl ProcessHandle {
+    /// Find `bm2dx.exe` running under Wine/Proton and open it
+    pub fn find_and_open() -> Result<Self> {
+        procfs::ProcFsProvider::default()
+            .find_process()
+            .map_err(|e| {
+                tracing::debug!("Process detection failed: {}", e);
+                e
+            })
+    }
+
+    pub fn open(pid: u32) -> Result<Self> {
+        Self::open_module(pid, PROCESS_NAME)
+    }
+
+    /// Open a process, taking the base address and size of the named module
+    /// from `/proc/<pid>/maps`
+    pub(crate) fn open_module(pid: u32, module_name: &str) -> Result<Self> {
+        let maps = procfs::read_maps(pid)?;
+        let (base_address, module_size) =
+            procfs::module_range(&maps, module_name).ok_or_else(|| {
+                Error::ProcessOpenFailed(format!(
+                    "Module '{}' not mapped in PID {}",
+                    module_name, pid
+                ))
+            })?;
+
+        let mem = File::open(format!("/proc/{}/mem", pid)).map_err(|e| {
+            tracing::debug!("Opening /proc/{}/mem failed: {}", pid, e);
+            Error::ProcessOpenFailed(format!("Failed to open process memory: {}", e))
+        })?;
+
+        Ok(Self {
+            mem,
+            pid,
+            base_address,
+            module_size,
+        })
+    }
+
+    pub(crate) fn mem(&self) -> &File {
+        &self.mem
+    }
+
+    /// Check if the process is still running
+    pub fn is_alive(&self) -> bool {
+        procfs::is_process_alive(self.pid)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 impl ProcessHandle {
     pub fn find_and_open() -> Result<Self> {
         Err(Error::ProcessNotFound(
@@ -1,7 +1,8 @@
-//! Memory layout constants for INFINITAS data structures
+//! Memory layouts for INFINITAS data structures
 //!
-//! This module centralizes all memory layout constants used for reading game data.
-//! Constants are organized by structure type.
+//! This module centralizes the field offsets used for reading game data.
+//! Each structure declares typed [`Field`](super::Field)s and a [`StructLayout`] listing
+//! them, which decoders and debug tools share.
 //!
 //! # Memory Structure Overview
 //!
@@ -12,13 +13,16 @@
 //!
 //! # Relative Offset Relationships
 //!
-//! The offset searcher uses these approximate relationships:
+//! The offset searcher starts from these approximate relationships, narrowed
+//! by the relations recorded for earlier versions (see `RelationPriors`):
 //! - JudgeData - PlaySettings ≈ 0x2ACE00
 //! - JudgeData + SongList ≈ 0x94E000
 //! - PlaySettings + PlayData ≈ 0x2A0
 //! - JudgeData + CurrentSong ≈ 0x1E4
 
-/// Memory layout constants for JudgeData structure
+use super::from_memory::StructLayout;
+
+/// Memory layout for JudgeData structure
 ///
 /// # Structure Layout
 ///
@@ -48,40 +52,69 @@
 /// 0xDC     StateMarker2       4       Non-zero during play
 /// ```
 pub mod judge {
+    use crate::process::from_memory::{Field, StructLayout};
+
     /// Word size (4 bytes / 32-bit integer)
-    pub const WORD: u64 = 4;
+    pub const WORD: usize = 4;
 
     // Player 1 judge data (offsets 0-4)
-    pub const P1_PGREAT: u64 = 0;
-    pub const P1_GREAT: u64 = WORD;
-    pub const P1_GOOD: u64 = WORD * 2;
-    pub const P1_BAD: u64 = WORD * 3;
-    pub const P1_POOR: u64 = WORD * 4;
+    pub const P1_PGREAT: Field<u32> = Field::new("p1_pgreat", 0);
+    pub const P1_GREAT: Field<u32> = Field::new("p1_great", WORD);
+    pub const P1_GOOD: Field<u32> = Field::new("p1_good", WORD * 2);
+    pub const P1_BAD: Field<u32> = Field::new("p1_bad", WORD * 3);
+    pub const P1_POOR: Field<u32> = Field::new("p1_poor", WORD * 4);
 
     // Player 2 judge data (offsets 5-9)
-    pub const P2_PGREAT: u64 = WORD * 5;
-    pub const P2_GREAT: u64 = WORD * 6;
-    pub const P2_GOOD: u64 = WORD * 7;
-    pub const P2_BAD: u64 = WORD * 8;
-    pub const P2_POOR: u64 = WORD * 9;
+    pub const P2_PGREAT: Field<u32> = Field::new("p2_pgreat", WORD * 5);
+    pub const P2_GREAT: Field<u32> = Field::new("p2_great", WORD * 6);
+    pub const P2_GOOD: Field<u32> = Field::new("p2_good", WORD * 7);
+    pub const P2_BAD: Field<u32> = Field::new("p2_bad", WORD * 8);
+    pub const P2_POOR: Field<u32> = Field::new("p2_poor", WORD * 9);
 
     // Combo break data (offsets 10-11)
-    pub const P1_COMBO_BREAK: u64 = WORD * 10;
-    pub const P2_COMBO_BREAK: u64 = WORD * 11;
+    pub const P1_COMBO_BREAK: Field<u32> = Field::new("p1_combo_break", WORD * 10);
+    pub const P2_COMBO_BREAK: Field<u32> = Field::new("p2_combo_break", WORD * 11);
 
     // Fast/Slow data (offsets 12-15)
-    pub const P1_FAST: u64 = WORD * 12;
-    pub const P2_FAST: u64 = WORD * 13;
-    pub const P1_SLOW: u64 = WORD * 14;
-    pub const P2_SLOW: u64 = WORD * 15;
+    pub const P1_FAST: Field<u32> = Field::new("p1_fast", WORD * 12);
+    pub const P2_FAST: Field<u32> = Field::new("p2_fast", WORD * 13);
+    pub const P1_SLOW: Field<u32> = Field::new("p1_slow", WORD * 14);
+    pub const P2_SLOW: Field<u32> = Field::new("p2_slow", WORD * 15);
 
     // Measure end markers (offsets 16-17)
-    pub const P1_MEASURE_END: u64 = WORD * 16;
-    pub const P2_MEASURE_END: u64 = WORD * 17;
+    pub const P1_MEASURE_END: Field<u32> = Field::new("p1_measure_end", WORD * 16);
+    pub const P2_MEASURE_END: Field<u32> = Field::new("p2_measure_end", WORD * 17);
 
     // Game state detection markers (offsets 54-55)
-    pub const STATE_MARKER_1: u64 = WORD * 54;
-    pub const STATE_MARKER_2: u64 = WORD * 55;
+    pub const STATE_MARKER_1: Field<i32> = Field::new("state_marker_1", WORD * 54);
+    pub const STATE_MARKER_2: Field<i32> = Field::new("state_marker_2", WORD * 55);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "judge",
+        size: WORD * 56,
+        fields: &[
+            P1_PGREAT.descriptor(),
+            P1_GREAT.descriptor(),
+            P1_GOOD.descriptor(),
+            P1_BAD.descriptor(),
+            P1_POOR.descriptor(),
+            P2_PGREAT.descriptor(),
+            P2_GREAT.descriptor(),
+            P2_GOOD.descriptor(),
+            P2_BAD.descriptor(),
+            P2_POOR.descriptor(),
+            P1_COMBO_BREAK.descriptor(),
+            P2_COMBO_BREAK.descriptor(),
+            P1_FAST.descriptor(),
+            P2_FAST.descriptor(),
+            P1_SLOW.descriptor(),
+            P2_SLOW.descriptor(),
+            P1_MEASURE_END.descriptor(),
+            P2_MEASURE_END.descriptor(),
+            STATE_MARKER_1.descriptor(),
+            STATE_MARKER_2.descriptor(),
+        ],
+    };
 
     /// Size of initial zero region in song select state (18 i32 values = 72 bytes)
     /// P1 (5) + P2 (5) + CB (2) + Fast/Slow (4) + MeasureEnd (2) = 18
@@ -96,21 +129,285 @@ pub mod judge {
     pub const MAX_FAST_SLOW: i32 = 1000;
 }
 
-/// Memory layout constants for PlayData structure
+/// Memory layout for PlayData structure
+///
+/// Offsets 8-20 are unknown/unused.
 pub mod play {
-    pub const WORD: u64 = 4;
+    use crate::process::from_memory::{Field, StructLayout};
 
-    pub const SONG_ID: u64 = 0;
-    pub const DIFFICULTY: u64 = WORD;
-    pub const LAMP: u64 = WORD * 6;
+    pub const WORD: usize = 4;
+
+    pub const SONG_ID: Field<i32> = Field::new("song_id", 0);
+    pub const DIFFICULTY: Field<i32> = Field::new("difficulty", WORD);
+    pub const LAMP: Field<i32> = Field::new("lamp", WORD * 6);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "play",
+        size: WORD * 7,
+        fields: &[
+            SONG_ID.descriptor(),
+            DIFFICULTY.descriptor(),
+            LAMP.descriptor(),
+        ],
+    };
 }
 
-/// Memory layout constants for PlaySettings structure
+/// Memory layout for PlaySettings structure
+///
+/// P2 options use the same layout as P1, shifted by [`P2_OFFSET`]. Flip and
+/// battle are shared between sides.
 pub mod settings {
-    pub const WORD: u64 = 4;
+    use crate::process::from_memory::{Field, StructLayout};
+
+    pub const WORD: usize = 4;
+
+    /// P2 settings offset (4 * 15 = 60 bytes)
+    pub const P2_OFFSET: usize = WORD * 15;
+
+    pub const STYLE: Field<i32> = Field::new("style", 0);
+    pub const ASSIST: Field<i32> = Field::new("assist", WORD * 2);
+    pub const FLIP: Field<i32> = Field::new("flip", WORD * 3);
+    pub const RANGE: Field<i32> = Field::new("range", WORD * 4);
+    /// DP second side style
+    pub const STYLE2: Field<i32> = Field::new("style2", WORD * 5);
+    pub const BATTLE: Field<i32> = Field::new("battle", WORD * 8);
+    pub const H_RAN: Field<i32> = Field::new("h_ran", WORD * 9);
+
+    pub const P2_STYLE: Field<i32> = Field::new("p2_style", P2_OFFSET);
+    pub const P2_ASSIST: Field<i32> = Field::new("p2_assist", P2_OFFSET + WORD * 2);
+    pub const P2_RANGE: Field<i32> = Field::new("p2_range", P2_OFFSET + WORD * 4);
+    pub const P2_H_RAN: Field<i32> = Field::new("p2_h_ran", P2_OFFSET + WORD * 9);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "settings",
+        size: P2_OFFSET + WORD * 10,
+        fields: &[
+            STYLE.descriptor(),
+            ASSIST.descriptor(),
+            FLIP.descriptor(),
+            RANGE.descriptor(),
+            STYLE2.descriptor(),
+            BATTLE.descriptor(),
+            H_RAN.descriptor(),
+            P2_STYLE.descriptor(),
+            P2_ASSIST.descriptor(),
+            P2_RANGE.descriptor(),
+            P2_H_RAN.descriptor(),
+        ],
+    };
 
     /// Song select marker position (negative offset from PlaySettings)
-    pub const SONG_SELECT_MARKER: u64 = WORD * 6;
+    pub const SONG_SELECT_MARKER: u64 = (WORD * 6) as u64;
+}
+
+/// Memory layout for the CurrentSong structure (chart being played)
+pub mod current_song {
+    use crate::process::from_memory::{Field, StructLayout};
+
+    pub const SONG_ID: Field<i32> = Field::new("song_id", 0);
+    pub const DIFFICULTY: Field<i32> = Field::new("difficulty", 4);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "current_song",
+        size: 8,
+        fields: &[SONG_ID.descriptor(), DIFFICULTY.descriptor()],
+    };
+}
+
+/// Memory layouts for a song entry
+///
+/// Current layout (version 2026012800+):
+///
+/// ```text
+/// Offset   Field              Size    Description
+/// ──────────────────────────────────────────────────────
+/// 0x000    Title              64      Shift-JIS
+/// 0x040    Title (English)    64      Shift-JIS
+/// 0x080    Genre              64      Shift-JIS
+/// 0x0C0    Artist             64      Shift-JIS
+/// 0x100    (unknown)          192     Added in 2026012800
+/// 0x1D8    Folder             1       (estimated)
+/// 0x1E0    Levels             10      SPB..SPL, DPB..DPL
+/// 0x200    BPM max/min        8       (estimated)
+/// 0x270    Notes              40      10 x u32 (estimated)
+/// 0x330    Song ID            4
+/// ```
+///
+/// Older versions used a 0x3F0-byte entry without the three unknown string
+/// fields (folder=280, levels=288, bpm=320, notes=432, song_id=624); see
+/// [`LEGACY`](song::LEGACY). The string fields are shared by both layouts.
+pub mod song {
+    use crate::process::from_memory::{Field, ShiftJis, StructLayout};
+
+    /// String block size (64 bytes per Shift-JIS string field)
+    pub const SLAB: usize = 64;
+
+    pub const TITLE: Field<ShiftJis<SLAB>> = Field::new("title", 0);
+    pub const TITLE_ENGLISH: Field<ShiftJis<SLAB>> = Field::new("title_english", SLAB);
+    pub const GENRE: Field<ShiftJis<SLAB>> = Field::new("genre", SLAB * 2);
+    pub const ARTIST: Field<ShiftJis<SLAB>> = Field::new("artist", SLAB * 3);
+    pub const FOLDER: Field<u8> = Field::new("folder", 472);
+    pub const LEVELS: Field<[u8; 10]> = Field::new("levels", 480);
+    pub const BPM_MAX: Field<i32> = Field::new("bpm_max", 512);
+    pub const BPM_MIN: Field<i32> = Field::new("bpm_min", 516);
+    pub const NOTES: Field<[u32; 10]> = Field::new("notes", 624);
+    pub const SONG_ID: Field<i32> = Field::new("song_id", 816);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "song",
+        size: 0x4B0,
+        fields: &[
+            TITLE.descriptor(),
+            TITLE_ENGLISH.descriptor(),
+            GENRE.descriptor(),
+            ARTIST.descriptor(),
+            FOLDER.descriptor(),
+            LEVELS.descriptor(),
+            BPM_MAX.descriptor(),
+            BPM_MIN.descriptor(),
+            NOTES.descriptor(),
+            SONG_ID.descriptor(),
+        ],
+    };
+
+    /// Metadata fields of one song entry layout
+    #[derive(Debug, Clone, Copy)]
+    pub struct SongLayout {
+        /// Builds the layout applies to
+        pub name: &'static str,
+        /// Entry size (stride of the song list)
+        pub size: usize,
+        pub folder: Field<u8>,
+        pub levels: Field<[u8; 10]>,
+        pub bpm_max: Field<i32>,
+        pub bpm_min: Field<i32>,
+        pub notes: Field<[u32; 10]>,
+        pub song_id: Field<i32>,
+        /// Field descriptors for debug tools
+        pub layout: &'static StructLayout,
+    }
+
+    /// Layout of version 2026012800 and later
+    pub const CURRENT: SongLayout = SongLayout {
+        name: "2026012800+",
+        size: LAYOUT.size,
+        folder: FOLDER,
+        levels: LEVELS,
+        bpm_max: BPM_MAX,
+        bpm_min: BPM_MIN,
+        notes: NOTES,
+        song_id: SONG_ID,
+        layout: &LAYOUT,
+    };
+
+    /// Layout of versions before 2026012800
+    pub const LEGACY: SongLayout = SongLayout {
+        name: "pre-2026012800",
+        size: LEGACY_LAYOUT.size,
+        folder: LEGACY_FOLDER,
+        levels: LEGACY_LEVELS,
+        bpm_max: LEGACY_BPM_MAX,
+        bpm_min: LEGACY_BPM_MIN,
+        notes: LEGACY_NOTES,
+        song_id: LEGACY_SONG_ID,
+        layout: &LEGACY_LAYOUT,
+    };
+
+    pub const LEGACY_FOLDER: Field<u8> = Field::new("folder", 280);
+    pub const LEGACY_LEVELS: Field<[u8; 10]> = Field::new("levels", 288);
+    pub const LEGACY_BPM_MAX: Field<i32> = Field::new("bpm_max", 320);
+    pub const LEGACY_BPM_MIN: Field<i32> = Field::new("bpm_min", 324);
+    pub const LEGACY_NOTES: Field<[u32; 10]> = Field::new("notes", 432);
+    pub const LEGACY_SONG_ID: Field<i32> = Field::new("song_id", 624);
+
+    pub const LEGACY_LAYOUT: StructLayout = StructLayout {
+        name: "song_legacy",
+        size: 0x3F0,
+        fields: &[
+            TITLE.descriptor(),
+            TITLE_ENGLISH.descriptor(),
+            GENRE.descriptor(),
+            ARTIST.descriptor(),
+            LEGACY_FOLDER.descriptor(),
+            LEGACY_LEVELS.descriptor(),
+            LEGACY_BPM_MAX.descriptor(),
+            LEGACY_BPM_MIN.descriptor(),
+            LEGACY_NOTES.descriptor(),
+            LEGACY_SONG_ID.descriptor(),
+        ],
+    };
+
+    /// Known layouts, newest first
+    pub const KNOWN: &[SongLayout] = &[CURRENT, LEGACY];
+}
+
+/// Memory layout for an unlock data entry
+pub mod unlock {
+    use crate::process::from_memory::{Field, StructLayout};
+
+    pub const SONG_ID: Field<u32> = Field::new("song_id", 0);
+    /// 1 = Base, 2 = Bits, 3 = Sub
+    pub const UNLOCK_TYPE: Field<i32> = Field::new("unlock_type", 4);
+    /// Bitmask of unlocked difficulties
+    pub const UNLOCKS: Field<i32> = Field::new("unlocks", 8);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "unlock",
+        size: 32,
+        fields: &[
+            SONG_ID.descriptor(),
+            UNLOCK_TYPE.descriptor(),
+            UNLOCKS.descriptor(),
+        ],
+    };
+}
+
+/// Memory layout for a node of the score hashmap linked list
+///
+/// Offsets 28, 40 and 44 hold unknown values.
+pub mod score_node {
+    use crate::process::from_memory::{Field, StructLayout};
+
+    pub const NEXT: Field<u64> = Field::new("next", 0);
+    pub const PREV: Field<u64> = Field::new("prev", 8);
+    pub const DIFFICULTY: Field<i32> = Field::new("difficulty", 16);
+    pub const SONG_ID: Field<i32> = Field::new("song_id", 20);
+    pub const PLAY_TYPE: Field<i32> = Field::new("play_type", 24);
+    pub const SCORE: Field<u32> = Field::new("score", 32);
+    pub const MISS_COUNT: Field<u32> = Field::new("miss_count", 36);
+    pub const LAMP: Field<i32> = Field::new("lamp", 48);
+
+    pub const LAYOUT: StructLayout = StructLayout {
+        name: "score_node",
+        size: 64,
+        fields: &[
+            NEXT.descriptor(),
+            PREV.descriptor(),
+            DIFFICULTY.descriptor(),
+            SONG_ID.descriptor(),
+            PLAY_TYPE.descriptor(),
+            SCORE.descriptor(),
+            MISS_COUNT.descriptor(),
+            LAMP.descriptor(),
+        ],
+    };
+}
+
+/// All known structure layouts, for debug tools
+pub const ALL: &[&StructLayout] = &[
+    &judge::LAYOUT,
+    &play::LAYOUT,
+    &settings::LAYOUT,
+    &current_song::LAYOUT,
+    &song::LAYOUT,
+    &song::LEGACY_LAYOUT,
+    &unlock::LAYOUT,
+    &score_node::LAYOUT,
+];
+
+/// Look up a structure layout by name
+pub fn by_name(name: &str) -> Option<&'static StructLayout> {
+    ALL.iter().copied().find(|layout| layout.name == name)
 }
 
 /// Timing constants for polling and rate limiting
@@ -121,3 +418,38 @@ pub mod timing {
     /// Delay between API requests when syncing scores to avoid server overload (ms)
     pub const SERVER_SYNC_REQUEST_DELAY_MS: u64 = 20;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_fit_in_layouts() {
+        for layout in ALL {
+            for field in layout.fields {
+                assert!(
+                    field.end() <= layout.size,
+                    "{}.{} ends past the layout",
+                    layout.name,
+                    field.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_by_name() {
+        assert_eq!(by_name("judge").unwrap().size, 0xE0);
+        assert_eq!(
+            by_name("song").unwrap().field("song_id").unwrap().offset,
+            816
+        );
+        assert!(by_name("unknown").is_none());
+    }
+
+    #[test]
+    fn test_p2_settings_mirror_p1() {
+        assert_eq!(settings::P2_STYLE.offset, settings::STYLE.offset + 60);
+        assert_eq!(settings::P2_H_RAN.offset, settings::H_RAN.offset + 60);
+    }
+}
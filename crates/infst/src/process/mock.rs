@@ -1,10 +1,21 @@
 //! Mock memory reader for testing
 //!
 //! Provides a configurable mock implementation of ReadMemory trait
-//! that reads from an in-memory buffer instead of a real process.
+//! that reads from an in-memory buffer instead of a real process, plus
+//! builder helpers for the game structures most tests need.
+
+use std::ops::Range;
 
 use crate::error::{Error, Result};
 use crate::process::ReadMemory;
+use crate::process::layout::{judge, song, unlock};
+use crate::process::regions::{MemoryRegion, MemoryRegions, Protection};
+
+/// Base address of fake game images (the executable's image base)
+pub const GAME_BASE: u64 = 0x140000000;
+
+/// Version string of fake game images
+pub const GAME_VERSION: &str = "P2D:J:B:A:2026012800";
 
 /// Mock memory reader for testing
 ///
@@ -14,17 +25,23 @@ use crate::process::ReadMemory;
 pub struct MockMemoryReader {
     data: Vec<u8>,
     base: u64,
+    /// Unreadable ranges (offsets from base)
+    holes: Vec<Range<usize>>,
 }
 
 impl MockMemoryReader {
     /// Create a new mock reader with the given data at base address 0x1000
     pub fn new(data: Vec<u8>) -> Self {
-        Self { data, base: 0x1000 }
+        Self::with_base(data, 0x1000)
     }
 
     /// Create a new mock reader with custom base address
     pub fn with_base(data: Vec<u8>, base: u64) -> Self {
-        Self { data, base }
+        Self {
+            data,
+            base,
+            holes: Vec::new(),
+        }
     }
 
     /// Get the size of the underlying buffer
@@ -58,12 +75,55 @@ impl ReadMemory for MockMemoryReader {
                 ),
             });
         }
+        if let Some(hole) = self
+            .holes
+            .iter()
+            .find(|h| h.start < offset + size && offset < h.end)
+        {
+            return Err(Error::MemoryReadFailed {
+                address,
+                message: format!("Unmapped range at offset 0x{:X}", hole.start),
+            });
+        }
         Ok(self.data[offset..offset + size].to_vec())
     }
 
     fn base_address(&self) -> u64 {
         self.base
     }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        Some(self)
+    }
+}
+
+impl MemoryRegions for MockMemoryReader {
+    /// The buffer as read-write regions, split around unmapped holes
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut holes = self.holes.clone();
+        holes.sort_by_key(|h| h.start);
+
+        let region = |start: usize, end: usize| {
+            MemoryRegion::new(
+                self.base + start as u64,
+                (end - start) as u64,
+                Protection::READ_WRITE,
+            )
+        };
+
+        let mut regions = Vec::new();
+        let mut cursor = 0;
+        for hole in &holes {
+            if hole.start > cursor {
+                regions.push(region(cursor, hole.start));
+            }
+            cursor = cursor.max(hole.end);
+        }
+        if cursor < self.data.len() {
+            regions.push(region(cursor, self.data.len()));
+        }
+        Ok(regions)
+    }
 }
 
 /// Builder for creating test memory buffers
@@ -73,6 +133,7 @@ impl ReadMemory for MockMemoryReader {
 pub struct MockMemoryBuilder {
     data: Vec<u8>,
     base: u64,
+    holes: Vec<Range<usize>>,
 }
 
 impl MockMemoryBuilder {
@@ -81,9 +142,15 @@ impl MockMemoryBuilder {
         Self {
             data: Vec::new(),
             base: 0x1000,
+            holes: Vec::new(),
         }
     }
 
+    /// Create a builder for a fake game image of `size` bytes at [`GAME_BASE`]
+    pub fn game_image(size: usize) -> Self {
+        Self::new().base(GAME_BASE).with_size(size)
+    }
+
     /// Set the base address for the mock reader
     pub fn base(mut self, base: u64) -> Self {
         self.base = base;
@@ -151,11 +218,56 @@ impl MockMemoryBuilder {
         self
     }
 
+    /// Write a song entry (current layout) with SPN/SPH/SPA and DP charts at 150 BPM
+    pub fn write_song(self, offset: usize, song_id: i32, title: &str) -> Self {
+        let mut builder = self
+            .write_shift_jis(offset + song::TITLE.offset, title)
+            .write_bytes(
+                offset + song::LEVELS.offset,
+                &[0, 5, 9, 11, 0, 0, 5, 9, 11, 0],
+            )
+            .write_i32(offset + song::BPM_MAX.offset, 150)
+            .write_i32(offset + song::SONG_ID.offset, song_id);
+        for (diff, notes) in [(1, 400), (2, 700), (3, 1000)] {
+            builder = builder.write_u32(offset + song::NOTES.offset + diff * 4, notes);
+        }
+        builder
+    }
+
+    /// Write an unlock data entry of the base unlock type
+    pub fn write_unlock(self, offset: usize, song_id: i32, unlocks: i32) -> Self {
+        self.write_i32(offset + unlock::SONG_ID.offset, song_id)
+            .write_i32(offset + unlock::UNLOCK_TYPE.offset, 1)
+            .write_i32(offset + unlock::UNLOCKS.offset, unlocks)
+    }
+
+    /// Write the judgeData state markers as they are on song select
+    pub fn write_song_select_judge(self, offset: usize) -> Self {
+        self.write_i32(offset + judge::STATE_MARKER_1.offset, 50)
+            .write_i32(offset + judge::STATE_MARKER_2.offset, 50)
+    }
+
+    /// Write a RIP-relative instruction (`opcode` + rel32) at `offset` that
+    /// references `target` (also an offset from base)
+    pub fn write_rip_relative(self, offset: usize, opcode: &[u8], target: usize) -> Self {
+        let next = offset + opcode.len() + 4;
+        self.write_bytes(offset, opcode)
+            .write_i32(offset + opcode.len(), (target as i64 - next as i64) as i32)
+    }
+
+    /// Make a range unreadable, like an unmapped or guard page
+    pub fn unmapped(mut self, offset: usize, len: usize) -> Self {
+        self.ensure_size(offset + len);
+        self.holes.push(offset..offset + len);
+        self
+    }
+
     /// Build the MockMemoryReader
     pub fn build(self) -> MockMemoryReader {
         MockMemoryReader {
             data: self.data,
             base: self.base,
+            holes: self.holes,
         }
     }
 
@@ -261,6 +373,29 @@ mod tests {
         assert_eq!(value, "Hello");
     }
 
+    #[test]
+    fn test_builder_game_structures() {
+        use crate::chart::SongInfo;
+
+        let reader = MockMemoryBuilder::game_image(0x2000)
+            .write_song(0x1000, 1001, "GAMBOL")
+            .write_unlock(0x1800, 1001, 31)
+            .write_rip_relative(0x100, &[0x48, 0x8D, 0x0D], 0x1000)
+            .build();
+
+        let song = SongInfo::read_from_memory(&reader, GAME_BASE + 0x1000)
+            .unwrap()
+            .unwrap();
+        assert_eq!(song.id, 1001);
+        assert_eq!(&*song.title, "GAMBOL");
+        assert_eq!(song.total_notes[3], 1000);
+
+        assert_eq!(reader.read_i32(GAME_BASE + 0x1808).unwrap(), 31);
+
+        let disp = reader.read_i32(GAME_BASE + 0x103).unwrap();
+        assert_eq!(0x107 + disp as i64, 0x1000);
+    }
+
     #[test]
     fn test_builder_raw_bytes() {
         let reader = MockMemoryBuilder::new()
@@ -1,20 +1,42 @@
 mod bytes;
+pub mod caching_reader;
 pub mod chunked_reader;
+pub mod from_memory;
 mod handle;
 pub mod layout;
+pub mod multi_pattern;
 pub mod pattern;
+#[cfg(target_os = "linux")]
+pub mod procfs;
 pub mod provider;
 mod reader;
+pub mod regions;
+pub mod snapshot;
+pub mod timeline;
+pub mod watcher;
 
 // Mock memory reader for testing (always available for unit and integration tests)
 #[doc(hidden)]
 pub mod mock;
 
 pub use bytes::{ByteBuffer, decode_shift_jis, decode_shift_jis_to_string};
+pub use caching_reader::{CacheStats, CachingReader, DEFAULT_PAGE_SIZE};
 pub use chunked_reader::{ChunkedMemoryIterator, DEFAULT_CHUNK_SIZE, MemoryChunk};
+pub use from_memory::{
+    Field, FieldDescriptor, FieldKind, FieldType, FromMemory, ShiftJis, StructLayout,
+};
 pub use handle::*;
+pub use multi_pattern::MultiPattern;
+#[cfg(target_os = "linux")]
+pub use procfs::ProcFsProvider;
 pub use provider::{ProcessInfo, ProcessProvider};
 pub use reader::{MemoryReader, ReadMemory};
+pub use regions::{MemoryRegion, MemoryRegions, Protection, RangePlan, ScanReport};
+pub use snapshot::{MemorySnapshot, SnapshotMemoryReader, SnapshotRecorder};
+pub use timeline::{TimelineMemoryReader, TimelineState};
+pub use watcher::{
+    DEFAULT_WATCH_INTERVAL, MemoryWatcher, WatchEvent, WatchRecorder, read_watch_log,
+};
 
 // Re-export mock for convenient access in tests
 #[doc(hidden)]
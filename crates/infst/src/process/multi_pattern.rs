@@ -0,0 +1,404 @@
+//! Single-pass search for many wildcard patterns.
+//!
+//! Each pattern is reduced to one anchor: an exact byte at a fixed position
+//! before any gap, preferring bytes that are rare in code. The buffer is scanned for the anchor bytes with
+//! `memchr` (up to three per pass) and only the candidate positions are checked
+//! against the full patterns. Large buffers are split across worker threads.
+
+use std::thread;
+
+use memchr::{memchr_iter, memchr2_iter, memchr3_iter};
+
+use crate::offset::SignaturePattern;
+
+/// Minimum number of bytes handed to each worker thread.
+const MIN_BYTES_PER_THREAD: usize = 1024 * 1024;
+
+/// Bytes that are very common in x86-64 code (opcodes, prefixes, padding).
+///
+/// They make poor anchors, so they are only used when a pattern has nothing
+/// better to offer.
+const COMMON_CODE_BYTES: [u8; 12] = [
+    0x00, 0xFF, 0xCC, 0x48, 0x8B, 0x89, 0x0F, 0x24, 0x4C, 0xE8, 0x44, 0x90,
+];
+
+fn byte_cost(byte: u8) -> usize {
+    COMMON_CODE_BYTES
+        .iter()
+        .position(|&b| b == byte)
+        .map_or(0, |rank| COMMON_CODE_BYTES.len() - rank)
+}
+
+/// Anchor of a single pattern: a concrete byte at a fixed offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Anchor {
+    byte: u8,
+    offset: usize,
+}
+
+/// A compiled set of wildcard patterns searched in one traversal.
+///
+/// Patterns are [`SignaturePattern`]s (plain `Vec<Option<u8>>` byte patterns
+/// convert into them). Results are reported per pattern, in the order the
+/// patterns were given.
+///
+/// # Example
+///
+/// ```
+/// use infst::process::MultiPattern;
+///
+/// let patterns = MultiPattern::new(vec![
+///     vec![Some(0x48), Some(0x8D), None],
+///     vec![Some(0x89), None, Some(0x05)],
+/// ]);
+/// let buffer = [0x48, 0x8D, 0x0D, 0x89, 0x15, 0x05];
+/// assert_eq!(patterns.find_all(&buffer), vec![vec![0], vec![3]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiPattern {
+    patterns: Vec<SignaturePattern>,
+    /// Anchor per pattern (`None` if the head has no exact byte)
+    anchors: Vec<Option<Anchor>>,
+    /// Pattern indices keyed by anchor byte
+    by_anchor: Vec<Vec<usize>>,
+    /// Distinct anchor bytes, in groups of up to three per memchr pass
+    anchor_groups: Vec<Vec<u8>>,
+    max_len: usize,
+}
+
+impl MultiPattern {
+    /// Compile a set of wildcard patterns.
+    pub fn new<P: Into<SignaturePattern>>(patterns: Vec<P>) -> Self {
+        let patterns: Vec<SignaturePattern> = patterns.into_iter().map(Into::into).collect();
+        let heads: Vec<Vec<(usize, u8)>> = patterns.iter().map(|p| p.fixed_head()).collect();
+        let anchors = select_anchors(&heads);
+
+        let mut by_anchor = vec![Vec::new(); 256];
+        for (index, anchor) in anchors.iter().enumerate() {
+            if let Some(anchor) = anchor {
+                by_anchor[anchor.byte as usize].push(index);
+            }
+        }
+
+        let distinct: Vec<u8> = (0..=255u8)
+            .filter(|&b| !by_anchor[b as usize].is_empty())
+            .collect();
+        let anchor_groups = distinct.chunks(3).map(<[u8]>::to_vec).collect();
+        let max_len = patterns
+            .iter()
+            .map(SignaturePattern::max_len)
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            patterns,
+            anchors,
+            by_anchor,
+            anchor_groups,
+            max_len,
+        }
+    }
+
+    /// Number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Longest possible match of any pattern.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Distinct anchor bytes the buffer is prefiltered with.
+    pub fn anchor_bytes(&self) -> Vec<u8> {
+        self.anchor_groups.concat()
+    }
+
+    /// Find every match of every pattern on the current thread.
+    ///
+    /// Returns one sorted list of start offsets per pattern.
+    pub fn find_all(&self, buffer: &[u8]) -> Vec<Vec<usize>> {
+        let mut results = vec![Vec::new(); self.patterns.len()];
+        self.scan_range(buffer, 0, buffer.len(), &mut results);
+        for matches in &mut results {
+            matches.sort_unstable();
+        }
+        results
+    }
+
+    /// Find every match of every pattern, splitting the buffer across up to
+    /// `threads` worker threads.
+    ///
+    /// Returns the same result as [`find_all`](Self::find_all).
+    pub fn find_all_parallel(&self, buffer: &[u8], threads: usize) -> Vec<Vec<usize>> {
+        let threads = threads.min(buffer.len() / MIN_BYTES_PER_THREAD).max(1);
+        if threads == 1 {
+            return self.find_all(buffer);
+        }
+
+        let span = buffer.len().div_ceil(threads);
+        let partials: Vec<Vec<Vec<usize>>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|i| {
+                    let start = i * span;
+                    let end = ((i + 1) * span).min(buffer.len());
+                    scope.spawn(move || {
+                        let mut results = vec![Vec::new(); self.patterns.len()];
+                        self.scan_range(buffer, start, end, &mut results);
+                        results
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|w| w.join().expect("pattern scan worker panicked"))
+                .collect()
+        });
+
+        let mut results = vec![Vec::new(); self.patterns.len()];
+        for partial in partials {
+            for (merged, matches) in results.iter_mut().zip(partial) {
+                merged.extend(matches);
+            }
+        }
+        for matches in &mut results {
+            matches.sort_unstable();
+        }
+        results
+    }
+
+    /// Collect matches starting in `start..end` into `results`.
+    ///
+    /// Patterns may extend past `end` (into the next worker's range), so the
+    /// searched window is widened by the longest pattern.
+    fn scan_range(&self, buffer: &[u8], start: usize, end: usize, results: &mut [Vec<usize>]) {
+        if start >= end {
+            return;
+        }
+
+        // Patterns without an anchor are tried at every position
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if self.anchors[index].is_none() {
+                results[index].extend((start..end).filter(|&pos| pattern.is_match(&buffer[pos..])));
+            }
+        }
+
+        let window_end = end
+            .saturating_add(self.max_len.saturating_sub(1))
+            .min(buffer.len());
+        let window = &buffer[start..window_end];
+
+        for group in &self.anchor_groups {
+            let hits: Box<dyn Iterator<Item = usize>> = match group.as_slice() {
+                [a] => Box::new(memchr_iter(*a, window)),
+                [a, b] => Box::new(memchr2_iter(*a, *b, window)),
+                [a, b, c] => Box::new(memchr3_iter(*a, *b, *c, window)),
+                _ => unreachable!("anchor groups hold one to three bytes"),
+            };
+
+            for hit in hits {
+                let anchor_pos = start + hit;
+                for &index in &self.by_anchor[buffer[anchor_pos] as usize] {
+                    let Some(anchor) = self.anchors[index] else {
+                        continue;
+                    };
+                    let Some(match_start) = anchor_pos.checked_sub(anchor.offset) else {
+                        continue;
+                    };
+                    // Starts before `start` belong to the previous range
+                    if match_start < start || match_start >= end {
+                        continue;
+                    }
+                    if self.patterns[index].is_match(&buffer[match_start..]) {
+                        results[index].push(match_start);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pick one anchor per pattern.
+///
+/// `heads` holds the exact bytes (with their offsets) usable as anchors for
+/// each pattern. Each pattern is first anchored on its rarest byte. If that needs
+/// more than three distinct bytes (more than one memchr pass), anchors are
+/// instead shared by greedy set cover: repeatedly take the byte present in the
+/// most remaining patterns, preferring bytes that are rare in code.
+fn select_anchors(heads: &[Vec<(usize, u8)>]) -> Vec<Option<Anchor>> {
+    let rarest = |head: &[(usize, u8)], allowed: &dyn Fn(u8) -> bool| {
+        head.iter()
+            .map(|&(offset, byte)| Anchor { byte, offset })
+            .filter(|anchor| allowed(anchor.byte))
+            .min_by_key(|anchor| byte_cost(anchor.byte))
+    };
+
+    let anchors: Vec<Option<Anchor>> = heads.iter().map(|h| rarest(h, &|_| true)).collect();
+    let mut distinct: Vec<u8> = anchors.iter().flatten().map(|a| a.byte).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() <= 3 {
+        return anchors;
+    }
+
+    let present: Vec<[bool; 256]> = heads
+        .iter()
+        .map(|head| {
+            let mut set = [false; 256];
+            for &(_, byte) in head {
+                set[byte as usize] = true;
+            }
+            set
+        })
+        .collect();
+
+    let mut chosen = [false; 256];
+    let mut covered: Vec<bool> = anchors.iter().map(Option::is_none).collect();
+
+    while covered.contains(&false) {
+        let best = (0..=255u8)
+            .filter(|&b| !chosen[b as usize])
+            .max_by_key(|&b| {
+                let count = present
+                    .iter()
+                    .zip(&covered)
+                    .filter(|(set, done)| !**done && set[b as usize])
+                    .count();
+                (count, std::cmp::Reverse(byte_cost(b)))
+            })
+            .expect("an uncovered pattern has a concrete byte");
+
+        chosen[best as usize] = true;
+        for (set, done) in present.iter().zip(covered.iter_mut()) {
+            *done |= set[best as usize];
+        }
+    }
+
+    heads
+        .iter()
+        .map(|h| rarest(h, &|b| chosen[b as usize]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(pattern: &str) -> SignaturePattern {
+        pattern.parse().unwrap()
+    }
+
+    /// Try the pattern at every position, used as the reference result
+    fn naive(buffer: &[u8], pattern: &SignaturePattern) -> Vec<usize> {
+        (0..buffer.len())
+            .filter(|&i| pattern.is_match(&buffer[i..]))
+            .collect()
+    }
+
+    fn pseudo_random_code(len: usize) -> Vec<u8> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                // Skew towards common opcode bytes so anchors get plenty of hits
+                match state % 8 {
+                    0 => 0x48,
+                    1 => 0x8D,
+                    2 => 0x89,
+                    _ => (state >> 8) as u8,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_anchors_prefer_rare_bytes() {
+        let set = MultiPattern::new(vec![
+            parse("4C 8D 3D ?? ?? ?? ?? 45 89"),
+            parse("89 2D ?? ?? ?? ?? EB 0C 48 8D 0D"),
+            parse("44 89 25 ?? ?? ?? ?? 8B 00 89 05"),
+        ]);
+
+        // Each pattern gets its own rare anchor: one memchr3 pass
+        let anchors = set.anchor_bytes();
+        assert_eq!(anchors.len(), 3);
+        assert!(anchors.iter().all(|&b| byte_cost(b) == 0));
+        assert_eq!(set.max_len(), 11);
+    }
+
+    #[test]
+    fn test_anchors_are_shared_when_many_patterns() {
+        let set = MultiPattern::new(vec![
+            parse("4C 8D 3D ?? ?? ?? ?? 45 89"),
+            parse("33 C0 48 8D 0D ?? ?? ?? ??"),
+            parse("89 2D ?? ?? ?? ?? EB 0C 48 8D 0D"),
+            parse("44 89 25 ?? ?? ?? ?? 8B 00 89 05"),
+            parse("48 8D 2D ?? ?? ?? ?? 48 89 6C 24 60 33 F6 89 35"),
+        ]);
+
+        assert!(set.anchor_bytes().len() <= 3);
+    }
+
+    #[test]
+    fn test_matches_agree_with_naive_search() {
+        let patterns = vec![
+            parse("48 8D 0D ?? ?? ?? ??"),
+            parse("89 ?? 48"),
+            parse("8D 8D"),
+            parse("?? 89 ?? ?? 8D"),
+            parse("11 22 33 44"),
+            parse("4? 8D (0D|05)"),
+            parse("89 [2-5] 48 <disp> 8D"),
+            parse("(48|4C) ?? 89"),
+        ];
+        let buffer = pseudo_random_code(64 * 1024);
+        let set = MultiPattern::new(patterns.clone());
+
+        let expected: Vec<Vec<usize>> = patterns.iter().map(|p| naive(&buffer, p)).collect();
+        assert_eq!(set.find_all(&buffer), expected);
+        assert!(!expected[0].is_empty());
+    }
+
+    #[test]
+    fn test_parallel_matches_single_thread() {
+        let patterns = vec![parse("48 8D ?? 89"), parse("89 89"), parse("?? ?? 8D")];
+        let buffer = pseudo_random_code(4 * MIN_BYTES_PER_THREAD + 13);
+        let set = MultiPattern::new(patterns);
+
+        assert_eq!(set.find_all_parallel(&buffer, 4), set.find_all(&buffer));
+    }
+
+    #[test]
+    fn test_match_across_thread_boundary() {
+        let mut buffer = vec![0u8; 2 * MIN_BYTES_PER_THREAD];
+        let boundary = MIN_BYTES_PER_THREAD;
+        buffer[boundary - 2..boundary + 2].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let set = MultiPattern::new(vec![parse("DE AD ?? EF")]);
+        assert_eq!(set.find_all_parallel(&buffer, 2), vec![vec![boundary - 2]]);
+    }
+
+    #[test]
+    fn test_wildcard_only_and_empty_patterns() {
+        let set = MultiPattern::new(vec![vec![None, None], Vec::new()]);
+        assert_eq!(set.find_all(&[1, 2, 3]), vec![vec![0, 1], vec![]]);
+    }
+
+    #[test]
+    fn test_gap_match_across_thread_boundary() {
+        let mut buffer = vec![0u8; 2 * MIN_BYTES_PER_THREAD];
+        let at = MIN_BYTES_PER_THREAD - 3;
+        buffer[at] = 0xE8;
+        buffer[at + 6] = 0xC3;
+
+        let set = MultiPattern::new(vec![parse("E8 [2-8] C3")]);
+        assert_eq!(set.max_len(), 10);
+        assert_eq!(set.find_all_parallel(&buffer, 2), vec![vec![at]]);
+    }
+}
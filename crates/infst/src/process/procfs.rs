@@ -0,0 +1,356 @@
+//! Linux `/proc` process backend.
+//!
+//! INFINITAS running under Wine/Proton shows up as a regular Linux process whose
+//! `comm` (or `argv[0]`) is `bm2dx.exe`, with the PE image mapped from the
+//! executable file. This module locates that process by scanning `/proc`,
+//! derives the module base and size from `/proc/<pid>/maps`, and reads memory
+//! through `/proc/<pid>/mem`.
+//!
+//! Reading another process's memory requires ptrace access (same user and
+//! `kernel.yama.ptrace_scope` permitting it, or `CAP_SYS_PTRACE`).
+
+use std::fs;
+use std::path::Path;
+
+use tracing::debug;
+
+use crate::error::{Error, Result};
+use crate::process::ProcessHandle;
+use crate::process::provider::ProcessProvider;
+use crate::process::regions::{MemoryRegion, Protection};
+
+/// A single line of `/proc/<pid>/maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapsEntry {
+    pub start: u64,
+    pub end: u64,
+    /// Permission flags, e.g. `r-xp`
+    pub perms: String,
+    pub offset: u64,
+    /// Backing file path (None for anonymous mappings)
+    pub path: Option<String>,
+}
+
+impl MapsEntry {
+    /// Whether the mapping is readable
+    pub fn is_readable(&self) -> bool {
+        self.perms.starts_with('r')
+    }
+
+    /// Protection from the `rwx` permission flags
+    pub fn protection(&self) -> Protection {
+        let flags = self.perms.as_bytes();
+        Protection::new(
+            flags.first() == Some(&b'r'),
+            flags.get(1) == Some(&b'w'),
+            flags.get(2) == Some(&b'x'),
+        )
+    }
+
+    /// Convert to a memory region
+    pub fn region(&self) -> MemoryRegion {
+        MemoryRegion::new(self.start, self.end - self.start, self.protection())
+    }
+}
+
+/// Parse the contents of `/proc/<pid>/maps`
+///
+/// Malformed lines are skipped.
+pub fn parse_maps(text: &str) -> Vec<MapsEntry> {
+    text.lines().filter_map(parse_maps_line).collect()
+}
+
+fn parse_maps_line(line: &str) -> Option<MapsEntry> {
+    // address perms offset dev inode [path]
+    let mut fields = line.splitn(6, |c: char| c.is_ascii_whitespace());
+    let range = fields.next()?;
+    let perms = fields.next()?;
+    let offset = fields.next()?;
+    let _dev = fields.next()?;
+    let _inode = fields.next()?;
+    let path = fields
+        .next()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string);
+
+    let (start, end) = range.split_once('-')?;
+    Some(MapsEntry {
+        start: u64::from_str_radix(start, 16).ok()?,
+        end: u64::from_str_radix(end, 16).ok()?,
+        perms: perms.to_string(),
+        offset: u64::from_str_radix(offset, 16).ok()?,
+        path,
+    })
+}
+
+/// Check whether a path (Unix or Windows style) names the given executable
+fn path_matches(path: &str, name: &str) -> bool {
+    path.rsplit(['/', '\\'])
+        .next()
+        .is_some_and(|file| file.eq_ignore_ascii_case(name))
+}
+
+/// Find the address range of a mapped module
+///
+/// Returns `(base_address, module_size)` spanning every mapping backed by a
+/// file with the given name. Wine maps PE sections separately, so the span
+/// may contain anonymous gaps (e.g. `.bss`).
+pub fn module_range(entries: &[MapsEntry], module_name: &str) -> Option<(u64, u32)> {
+    let mut mappings = entries.iter().filter(|e| {
+        e.path
+            .as_deref()
+            .is_some_and(|p| path_matches(p, module_name))
+    });
+
+    let first = mappings.next()?;
+    let (start, end) = mappings.fold((first.start, first.end), |(start, end), e| {
+        (start.min(e.start), end.max(e.end))
+    });
+
+    Some((start, u32::try_from(end - start).unwrap_or(u32::MAX)))
+}
+
+/// Check whether a process matches the executable name
+///
+/// Wine sets `comm` to the Windows executable name (truncated to 15 bytes by
+/// the kernel); `argv[0]` holds the full Windows or Unix path.
+pub fn process_matches(comm: &str, cmdline: &[u8], name: &str) -> bool {
+    if comm.trim().eq_ignore_ascii_case(name) {
+        return true;
+    }
+
+    let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+    path_matches(&String::from_utf8_lossy(argv0), name)
+}
+
+/// Find the PID of a process by executable name by scanning `/proc`
+pub fn find_process_id(name: &str) -> Result<u32> {
+    Ok(find_process_ids(name)?[0])
+}
+
+/// Find the PIDs of all live processes matching the executable name, lowest first
+///
+/// Fails with [`Error::ProcessNotFound`] if there are none.
+pub fn find_process_ids(name: &str) -> Result<Vec<u32>> {
+    find_process_ids_in(Path::new("/proc"), name)
+}
+
+fn find_process_ids_in(proc_root: &Path, name: &str) -> Result<Vec<u32>> {
+    let own_pid = std::process::id();
+    let mut candidates: Vec<u32> = fs::read_dir(proc_root)?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .filter(|&pid| pid != own_pid)
+        .collect();
+    candidates.sort_unstable();
+
+    let pids: Vec<u32> = candidates
+        .into_iter()
+        .filter(|&pid| {
+            let dir = proc_root.join(pid.to_string());
+            let comm = fs::read_to_string(dir.join("comm")).unwrap_or_default();
+            let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+            process_matches(&comm, &cmdline, name) && is_alive_in(proc_root, pid)
+        })
+        .collect();
+
+    if pids.is_empty() {
+        return Err(Error::ProcessNotFound(format!(
+            "Process '{}' not found",
+            name
+        )));
+    }
+    debug!("Found {} with PIDs {:?} via /proc", name, pids);
+    Ok(pids)
+}
+
+/// Read and parse `/proc/<pid>/maps`
+pub fn read_maps(pid: u32) -> Result<Vec<MapsEntry>> {
+    let text = fs::read_to_string(format!("/proc/{}/maps", pid))
+        .map_err(|e| Error::ProcessOpenFailed(format!("Failed to read maps: {}", e)))?;
+    Ok(parse_maps(&text))
+}
+
+/// Check whether a process exists and is not a zombie
+pub fn is_process_alive(pid: u32) -> bool {
+    is_alive_in(Path::new("/proc"), pid)
+}
+
+fn is_alive_in(proc_root: &Path, pid: u32) -> bool {
+    let Ok(stat) = fs::read_to_string(proc_root.join(pid.to_string()).join("stat")) else {
+        return false;
+    };
+    // State follows the parenthesized comm, which may itself contain ')'
+    stat.rsplit_once(')')
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .is_some_and(|state| state != "Z" && state != "X")
+}
+
+/// Process provider backed by `/proc`
+///
+/// Looks for `bm2dx.exe` by default; a different executable name can be used
+/// to attach to a stand-in process.
+#[derive(Debug, Clone)]
+pub struct ProcFsProvider {
+    process_name: String,
+}
+
+impl Default for ProcFsProvider {
+    fn default() -> Self {
+        Self::new(super::handle::PROCESS_NAME)
+    }
+}
+
+impl ProcFsProvider {
+    /// Create a provider that looks for the given executable name
+    pub fn new(process_name: impl Into<String>) -> Self {
+        Self {
+            process_name: process_name.into(),
+        }
+    }
+}
+
+impl ProcessProvider for ProcFsProvider {
+    type Process = ProcessHandle;
+
+    /// Open the first matching process that has the module mapped
+    ///
+    /// A process that matches by name may not have mapped the executable yet
+    /// (e.g. right after `fork`), so those are skipped.
+    fn find_process(&self) -> Result<Self::Process> {
+        let mut result = Err(Error::ProcessNotFound(format!(
+            "Process '{}' not found",
+            self.process_name
+        )));
+        for pid in find_process_ids(&self.process_name)? {
+            result = self.open_process(pid);
+            match &result {
+                Ok(_) => break,
+                Err(e) => debug!("Skipping PID {}: {}", pid, e),
+            }
+        }
+        result
+    }
+
+    fn open_process(&self, pid: u32) -> Result<Self::Process> {
+        ProcessHandle::open_module(pid, &self.process_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINE_MAPS: &str = "\
+00010000-00011000 rw-p 00000000 00:00 0
+140000000-140001000 r--p 00000000 fd:01 1234                       /home/user/Games/beatmania IIDX INFINITAS/game/app/bm2dx.exe
+140001000-142000000 r-xp 00001000 fd:01 1234                       /home/user/Games/beatmania IIDX INFINITAS/game/app/bm2dx.exe
+142000000-142800000 rw-p 00000000 00:00 0
+142800000-143200000 rw-p 02000000 fd:01 1234                       /home/user/Games/beatmania IIDX INFINITAS/game/app/bm2dx.exe
+7f0000000000-7f0000001000 r--p 00000000 fd:01 99                   /usr/lib/wine/x86_64-unix/ntdll.so
+";
+
+    #[test]
+    fn test_parse_maps() {
+        let entries = parse_maps(WINE_MAPS);
+        assert_eq!(entries.len(), 6);
+
+        assert_eq!(entries[0].start, 0x10000);
+        assert_eq!(entries[0].path, None);
+
+        assert_eq!(entries[1].start, 0x140000000);
+        assert_eq!(entries[1].end, 0x140001000);
+        assert_eq!(entries[1].perms, "r--p");
+        assert_eq!(
+            entries[1].path.as_deref(),
+            Some("/home/user/Games/beatmania IIDX INFINITAS/game/app/bm2dx.exe")
+        );
+        assert!(entries[1].is_readable());
+    }
+
+    #[test]
+    fn test_parse_maps_skips_malformed_lines() {
+        let entries = parse_maps("garbage\nzzzz-1000 r--p 0 00:00 0\n1000-2000 r--p 0 00:00 0\n");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].start, 0x1000);
+    }
+
+    #[test]
+    fn test_module_range_spans_all_sections() {
+        let entries = parse_maps(WINE_MAPS);
+        let (base, size) = module_range(&entries, "bm2dx.exe").unwrap();
+
+        assert_eq!(base, 0x140000000);
+        assert_eq!(size, 0x3200000);
+    }
+
+    #[test]
+    fn test_module_range_is_case_insensitive() {
+        let entries = parse_maps("140000000-140001000 r--p 0 fd:01 1 /games/BM2DX.EXE\n");
+        assert_eq!(
+            module_range(&entries, "bm2dx.exe"),
+            Some((0x140000000, 0x1000))
+        );
+    }
+
+    #[test]
+    fn test_module_range_not_found() {
+        let entries = parse_maps(WINE_MAPS);
+        assert!(module_range(&entries, "other.exe").is_none());
+    }
+
+    #[test]
+    fn test_process_matches_comm() {
+        assert!(process_matches("bm2dx.exe\n", b"", "bm2dx.exe"));
+        assert!(!process_matches("wineserver\n", b"", "bm2dx.exe"));
+    }
+
+    #[test]
+    fn test_process_matches_windows_cmdline() {
+        let cmdline = b"C:\\Games\\beatmania IIDX INFINITAS\\game\\app\\bm2dx.exe\0-t\0token\0";
+        assert!(process_matches("wine64-preload", cmdline, "bm2dx.exe"));
+    }
+
+    #[test]
+    fn test_process_matches_unix_cmdline() {
+        let cmdline = b"/opt/infinitas/bm2dx.exe\0";
+        assert!(process_matches("", cmdline, "bm2dx.exe"));
+        assert!(!process_matches(
+            "",
+            b"/usr/bin/bm2dx.exe.bak\0",
+            "bm2dx.exe"
+        ));
+    }
+
+    #[test]
+    fn test_find_process_id_in_fake_proc() {
+        let dir = tempfile::tempdir().unwrap();
+        for (pid, comm, state) in [
+            (100, "bash\n", "S"),
+            (150, "bm2dx.exe\n", "Z"),
+            (200, "bm2dx.exe\n", "S"),
+            (300, "bm2dx.exe\n", "R"),
+        ] {
+            let pid_dir = dir.path().join(pid.to_string());
+            fs::create_dir(&pid_dir).unwrap();
+            fs::write(pid_dir.join("comm"), comm).unwrap();
+            fs::write(pid_dir.join("cmdline"), b"").unwrap();
+            let stat = format!("{} ({}) {} 1 0", pid, comm.trim(), state);
+            fs::write(pid_dir.join("stat"), stat).unwrap();
+        }
+        fs::create_dir(dir.path().join("self")).unwrap();
+
+        // The zombie (150) is skipped
+        assert_eq!(
+            find_process_ids_in(dir.path(), "bm2dx.exe").unwrap(),
+            [200, 300]
+        );
+        assert!(find_process_ids_in(dir.path(), "missing.exe").is_err());
+    }
+
+    #[test]
+    fn test_own_process_is_alive() {
+        assert!(is_process_alive(std::process::id()));
+        assert!(!is_process_alive(u32::MAX));
+    }
+}
@@ -1,8 +1,12 @@
-#![cfg_attr(not(target_os = "windows"), allow(dead_code, unused_variables))]
+#![cfg_attr(
+    not(any(target_os = "windows", target_os = "linux")),
+    allow(dead_code, unused_variables)
+)]
 
 use crate::error::{Error, Result};
 use crate::process::ProcessHandle;
 use crate::process::bytes::decode_shift_jis_to_string;
+use crate::process::regions::MemoryRegions;
 
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
@@ -17,6 +21,14 @@ pub trait ReadMemory {
     /// Get the base address of the memory region
     fn base_address(&self) -> u64;
 
+    /// Region enumeration for this memory source, if supported
+    ///
+    /// Range scanners use it to skip unreadable gaps. Readers without a
+    /// region map are scanned blindly.
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        None
+    }
+
     /// Read a signed 32-bit integer from memory
     fn read_i32(&self, address: u64) -> Result<i32> {
         let bytes = self.read_bytes(address, 4)?;
@@ -66,6 +78,20 @@ pub trait ReadMemory {
     }
 }
 
+impl<R: ReadMemory + ?Sized> ReadMemory for &R {
+    fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        (**self).read_bytes(address, size)
+    }
+
+    fn base_address(&self) -> u64 {
+        (**self).base_address()
+    }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        (**self).region_map()
+    }
+}
+
 pub struct MemoryReader<'a> {
     process: &'a ProcessHandle,
 }
@@ -114,7 +140,26 @@ impl<'a> MemoryReader<'a> {
         Ok(buffer)
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    fn read_bytes_impl(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        use std::os::unix::fs::FileExt;
+
+        let mut buffer = vec![0u8; size];
+
+        // Same all-or-nothing contract as the Windows path: a short read
+        // (e.g. crossing into an unmapped page) is reported as a failure.
+        self.process
+            .mem()
+            .read_exact_at(&mut buffer, address)
+            .map_err(|e| Error::MemoryReadFailed {
+                address,
+                message: e.to_string(),
+            })?;
+
+        Ok(buffer)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     fn read_bytes_impl(&self, address: u64, _size: usize) -> Result<Vec<u8>> {
         Err(Error::MemoryReadFailed {
             address,
@@ -131,6 +176,10 @@ impl ReadMemory for MemoryReader<'_> {
     fn base_address(&self) -> u64 {
         self.process.base_address
     }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        Some(self.process)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+//! Memory region enumeration.
+//!
+//! Scanners that walk large address ranges (version detection, code pattern
+//! scans, chunked reads) use the region map to read only committed, readable
+//! memory. Unreadable gaps are skipped and reported instead of failing the
+//! chunk that happens to straddle them.
+
+use std::fmt;
+use std::ops::Range;
+
+use tracing::debug;
+
+use super::ReadMemory;
+use crate::error::Result;
+
+/// Page protection of a memory region
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Protection {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Protection {
+    pub const NONE: Self = Self::new(false, false, false);
+    pub const READ: Self = Self::new(true, false, false);
+    pub const READ_WRITE: Self = Self::new(true, true, false);
+    pub const READ_EXECUTE: Self = Self::new(true, false, true);
+    pub const READ_WRITE_EXECUTE: Self = Self::new(true, true, true);
+    pub const EXECUTE: Self = Self::new(false, false, true);
+
+    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
+        Self {
+            read,
+            write,
+            execute,
+        }
+    }
+}
+
+impl fmt::Display for Protection {
+    /// Formats as `rwx`, with `-` for missing permissions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(
+            f,
+            "{}{}{}",
+            flag(self.read, 'r'),
+            flag(self.write, 'w'),
+            flag(self.execute, 'x')
+        )
+    }
+}
+
+/// A committed region of process memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub start: u64,
+    pub size: u64,
+    pub protection: Protection,
+}
+
+impl MemoryRegion {
+    pub fn new(start: u64, size: u64, protection: Protection) -> Self {
+        Self {
+            start,
+            size,
+            protection,
+        }
+    }
+
+    /// End address (exclusive)
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.size)
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.protection.read
+    }
+}
+
+/// Capability to enumerate the committed memory regions of a process
+pub trait MemoryRegions {
+    /// List committed regions, sorted by start address
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>>;
+}
+
+/// Address range split into readable spans and skipped gaps
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangePlan {
+    pub readable: Vec<Range<u64>>,
+    pub skipped: Vec<Range<u64>>,
+}
+
+impl RangePlan {
+    /// Plan that treats the whole range as readable
+    pub fn blind(start: u64, end: u64) -> Self {
+        let mut readable = Vec::new();
+        if start < end {
+            readable.push(start..end);
+        }
+        Self {
+            readable,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Split `start..end` using a region map
+    ///
+    /// Adjacent readable regions are merged into a single span. Everything
+    /// else in the range (unreadable or uncommitted) becomes a skipped gap.
+    pub fn from_regions(regions: &[MemoryRegion], start: u64, end: u64) -> Self {
+        let mut readable: Vec<Range<u64>> = Vec::new();
+        let mut sorted: Vec<&MemoryRegion> = regions
+            .iter()
+            .filter(|r| r.is_readable() && r.end() > start && r.start < end)
+            .collect();
+        sorted.sort_by_key(|r| r.start);
+
+        for region in sorted {
+            let span = region.start.max(start)..region.end().min(end);
+            match readable.last_mut() {
+                Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+                _ => readable.push(span),
+            }
+        }
+
+        let mut skipped = Vec::new();
+        let mut cursor = start;
+        for span in &readable {
+            if span.start > cursor {
+                skipped.push(cursor..span.start);
+            }
+            cursor = span.end;
+        }
+        if cursor < end {
+            skipped.push(cursor..end);
+        }
+
+        Self { readable, skipped }
+    }
+
+    /// Plan a range for a reader, falling back to a blind plan when the
+    /// reader has no region map or enumeration fails
+    pub fn for_reader<R: ReadMemory + ?Sized>(reader: &R, start: u64, end: u64) -> Self {
+        let Some(map) = reader.region_map() else {
+            return Self::blind(start, end);
+        };
+        match map.memory_regions() {
+            Ok(regions) => Self::from_regions(&regions, start, end),
+            Err(e) => {
+                debug!("Region enumeration failed, reading blindly: {}", e);
+                Self::blind(start, end)
+            }
+        }
+    }
+}
+
+/// What a range scan read and what it skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Bytes successfully read and scanned
+    pub scanned_bytes: u64,
+    /// Ranges not scanned (unreadable gaps and failed reads), in address order
+    pub skipped: Vec<Range<u64>>,
+}
+
+impl ScanReport {
+    /// Total size of the skipped ranges
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped.iter().map(|r| r.end - r.start).sum()
+    }
+
+    /// Record a skipped range, merging it with the previous one if adjacent
+    pub fn skip(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        match self.skipped.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => self.skipped.push(range),
+        }
+    }
+
+    /// Log the skipped ranges at debug level
+    pub fn log_skipped(&self, what: &str) {
+        if self.skipped.is_empty() {
+            return;
+        }
+        debug!(
+            "{}: skipped {} range(s), {:#x} bytes",
+            what,
+            self.skipped.len(),
+            self.skipped_bytes()
+        );
+        for range in &self.skipped {
+            debug!("  skipped 0x{:X}..0x{:X}", range.start, range.end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    #[test]
+    fn test_plan_merges_adjacent_and_reports_gaps() {
+        let regions = [
+            MemoryRegion::new(0x1000, 0x1000, Protection::READ_EXECUTE),
+            MemoryRegion::new(0x2000, 0x1000, Protection::READ_WRITE),
+            MemoryRegion::new(0x3000, 0x1000, Protection::NONE),
+            MemoryRegion::new(0x5000, 0x1000, Protection::READ),
+        ];
+
+        let plan = RangePlan::from_regions(&regions, 0x800, 0x5800);
+        assert_eq!(plan.readable, vec![0x1000..0x3000, 0x5000..0x5800]);
+        assert_eq!(plan.skipped, vec![0x800..0x1000, 0x3000..0x5000]);
+    }
+
+    #[test]
+    fn test_plan_for_reader_uses_mock_holes() {
+        let reader = MockMemoryBuilder::new()
+            .with_size(0x3000)
+            .unmapped(0x1000, 0x800)
+            .build();
+
+        let plan = RangePlan::for_reader(&reader, 0x1000, 0x5000);
+        assert_eq!(plan.readable, vec![0x1000..0x2000, 0x2800..0x4000]);
+        assert_eq!(plan.skipped, vec![0x2000..0x2800, 0x4000..0x5000]);
+    }
+
+    #[test]
+    fn test_scan_report_merges_adjacent_skips() {
+        let mut report = ScanReport::default();
+        report.skip(0x1000..0x2000);
+        report.skip(0x2000..0x2800);
+        report.skip(0x4000..0x4000);
+        report.skip(0x5000..0x5100);
+
+        assert_eq!(report.skipped, vec![0x1000..0x2800, 0x5000..0x5100]);
+        assert_eq!(report.skipped_bytes(), 0x1900);
+    }
+
+    #[test]
+    fn test_protection_display() {
+        assert_eq!(Protection::READ_EXECUTE.to_string(), "r-x");
+        assert_eq!(Protection::NONE.to_string(), "---");
+    }
+}
@@ -0,0 +1,836 @@
+//! Memory snapshots for offline replay.
+//!
+//! A snapshot records the memory regions infst actually reads while tracking
+//! (song list, data map hash table and its linked-list nodes, unlock data,
+//! judge/play/settings blocks and the version string area) into a single
+//! compressed file. [`SnapshotMemoryReader`] replays that file through
+//! [`ReadMemory`], so score map loading, song database parsing, unlock state
+//! reading and offset searching can run offline on any platform.
+//!
+//! # File Format
+//!
+//! The whole file is a gzip stream containing:
+//!
+//! ```text
+//! Field           Size    Description
+//! ──────────────────────────────────────────────────────
+//! magic           8       "INFSTSNP"
+//! format_version  4       u32 LE, currently 1
+//! header_len      4       u32 LE
+//! header          n       JSON-encoded SnapshotHeader
+//! region_count    4       u32 LE
+//! regions         ...     per region: address (u64 LE), length (u64 LE), bytes
+//! ```
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::chart::{SongInfo, UnlockData};
+use crate::error::{Error, Result};
+use crate::offset::OffsetsCollection;
+use crate::process::layout::settings;
+use crate::process::{
+    ByteBuffer, MemoryRegion, MemoryRegions, ProcessInfo, Protection, ReadMemory,
+};
+
+/// Magic bytes at the start of every snapshot file
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"INFSTSNP";
+
+/// Current snapshot format version
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Number of song entries recorded from the song list (matches bulk fetch)
+const SONG_LIST_ENTRIES: usize = 5000;
+
+/// Minimum number of unlock entries recorded (covers one full batch read)
+const UNLOCK_MIN_ENTRIES: usize = 5000;
+
+/// Upper bound of unlock entries walked while looking for the terminator
+const UNLOCK_MAX_ENTRIES: usize = 20000;
+
+/// Upper bound of data map nodes followed during capture
+const DATA_MAP_MAX_NODES: usize = 1_000_000;
+
+/// Data map node size (matches ScoreMap list node)
+const DATA_MAP_NODE_SIZE: usize = 64;
+
+/// Sentinel value in the data map hash table that is treated as null
+const DATA_MAP_SENTINEL: u64 = 0x494fdce0;
+
+/// Bytes recorded from JudgeData (includes state markers and CurrentSong)
+const JUDGE_DATA_SIZE: usize = 0x200;
+
+/// Bytes recorded from PlaySettings (includes P2 settings)
+const PLAY_SETTINGS_SIZE: usize = 0x100;
+
+/// Bytes recorded from PlayData
+const PLAY_DATA_SIZE: usize = 0x40;
+
+/// Bytes recorded from CurrentSong
+const CURRENT_SONG_SIZE: usize = 0x20;
+
+/// Range scanned for version strings (matches find_game_version full scan)
+const VERSION_SCAN_SIZE: usize = 80_000_000;
+
+/// Chunk size used when scanning for version strings
+const VERSION_SCAN_CHUNK: usize = 1_000_000;
+
+/// Bytes recorded around each version string match
+const VERSION_CONTEXT: usize = 64;
+
+/// Metadata describing where and when a snapshot was taken
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub pid: u32,
+    pub base_address: u64,
+    pub module_size: u32,
+    pub game_version: Option<String>,
+    pub offsets: OffsetsCollection,
+    /// Unix timestamp (seconds) of capture
+    pub created_at: u64,
+}
+
+/// A contiguous block of recorded memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotRegion {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+impl SnapshotRegion {
+    /// End address (exclusive)
+    pub fn end(&self) -> u64 {
+        self.address + self.data.len() as u64
+    }
+}
+
+/// Recorded memory regions plus capture metadata
+///
+/// Regions are kept sorted by address and never overlap; inserting a region
+/// that touches existing ones merges them.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySnapshot {
+    pub header: SnapshotHeader,
+    regions: Vec<SnapshotRegion>,
+}
+
+impl MemorySnapshot {
+    /// Create an empty snapshot with the given header
+    pub fn new(header: SnapshotHeader) -> Self {
+        Self {
+            header,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Get the recorded regions (sorted by address)
+    pub fn regions(&self) -> &[SnapshotRegion] {
+        &self.regions
+    }
+
+    /// Total number of recorded bytes
+    pub fn total_bytes(&self) -> usize {
+        self.regions.iter().map(|r| r.data.len()).sum()
+    }
+
+    /// Insert a region, merging it with any overlapping or adjacent regions
+    ///
+    /// Bytes from the new region take precedence where they overlap. Fails if
+    /// the region extends past the end of the address space.
+    pub fn insert_region(&mut self, address: u64, data: Vec<u8>) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let end = address.checked_add(data.len() as u64).ok_or_else(|| {
+            Error::InvalidSnapshot(format!(
+                "region 0x{:X} (+0x{:X}) overflows the address space",
+                address,
+                data.len()
+            ))
+        })?;
+
+        // Regions in [first, last) overlap or touch the new one
+        let first = self.regions.partition_point(|r| r.end() < address);
+        let last = self.regions.partition_point(|r| r.address <= end);
+
+        if first == last {
+            self.regions.insert(first, SnapshotRegion { address, data });
+            return Ok(());
+        }
+
+        let merged_start = self.regions[first].address.min(address);
+        let merged_end = self.regions[last - 1].end().max(end);
+        let mut merged = vec![0u8; (merged_end - merged_start) as usize];
+
+        for region in &self.regions[first..last] {
+            let offset = (region.address - merged_start) as usize;
+            merged[offset..offset + region.data.len()].copy_from_slice(&region.data);
+        }
+        let offset = (address - merged_start) as usize;
+        merged[offset..offset + data.len()].copy_from_slice(&data);
+
+        self.regions.splice(
+            first..last,
+            [SnapshotRegion {
+                address: merged_start,
+                data: merged,
+            }],
+        );
+        Ok(())
+    }
+
+    /// Find the region containing `[address, address + size)` entirely
+    fn find_containing(&self, address: u64, size: usize) -> Option<&[u8]> {
+        let index = self.regions.partition_point(|r| r.address <= address);
+        let region = self.regions.get(index.checked_sub(1)?)?;
+        let offset = (address - region.address) as usize;
+        region.data.get(offset..offset.checked_add(size)?)
+    }
+
+    /// Copy every recorded byte within `[address, address + size)` into `buffer`
+    fn copy_recorded(&self, address: u64, buffer: &mut [u8]) {
+        let end = address + buffer.len() as u64;
+        let first = self.regions.partition_point(|r| r.end() <= address);
+
+        for region in self.regions[first..].iter().take_while(|r| r.address < end) {
+            let start = region.address.max(address);
+            let stop = region.end().min(end);
+            let src = (start - region.address) as usize;
+            let dst = (start - address) as usize;
+            let len = (stop - start) as usize;
+            buffer[dst..dst + len].copy_from_slice(&region.data[src..src + len]);
+        }
+    }
+
+    /// Write the snapshot to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = File::create(path)?;
+        self.write_to(BufWriter::new(file))
+    }
+
+    /// Load a snapshot from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::read_from(BufReader::new(file))
+    }
+
+    /// Serialize the snapshot as a gzip stream
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<()> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        let header = serde_json::to_vec(&self.header)?;
+
+        encoder.write_all(SNAPSHOT_MAGIC)?;
+        encoder.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+        encoder.write_all(&(header.len() as u32).to_le_bytes())?;
+        encoder.write_all(&header)?;
+        encoder.write_all(&(self.regions.len() as u32).to_le_bytes())?;
+
+        for region in &self.regions {
+            encoder.write_all(&region.address.to_le_bytes())?;
+            encoder.write_all(&(region.data.len() as u64).to_le_bytes())?;
+            encoder.write_all(&region.data)?;
+        }
+
+        encoder.finish()?.flush()?;
+        Ok(())
+    }
+
+    /// Deserialize a snapshot from a gzip stream
+    pub fn read_from<R: Read>(reader: R) -> Result<Self> {
+        let mut raw = Vec::new();
+        GzDecoder::new(reader).read_to_end(&mut raw)?;
+
+        let invalid = |message: &str| Error::InvalidSnapshot(message.to_string());
+        let mut buf = ByteBuffer::new(&raw);
+
+        let magic = buf
+            .read_bytes(SNAPSHOT_MAGIC.len())
+            .map_err(|_| invalid("truncated magic"))?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(invalid("bad magic"));
+        }
+
+        let format_version = buf
+            .read_u32()
+            .map_err(|_| invalid("truncated format version"))?;
+        if format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(Error::InvalidSnapshot(format!(
+                "unsupported format version {} (expected {})",
+                format_version, SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+
+        let header_len = buf
+            .read_u32()
+            .map_err(|_| invalid("truncated header length"))? as usize;
+        let header_bytes = buf
+            .read_bytes(header_len)
+            .map_err(|_| invalid("truncated header"))?;
+        let header: SnapshotHeader = serde_json::from_slice(header_bytes)?;
+        if header
+            .base_address
+            .checked_add(header.module_size as u64)
+            .is_none()
+        {
+            return Err(invalid("module range overflows the address space"));
+        }
+
+        let region_count = buf
+            .read_u32()
+            .map_err(|_| invalid("truncated region count"))?;
+        let mut snapshot = Self::new(header);
+
+        for _ in 0..region_count {
+            let address = buf
+                .read_u64()
+                .map_err(|_| invalid("truncated region address"))?;
+            let len = buf
+                .read_u64()
+                .map_err(|_| invalid("truncated region length"))? as usize;
+            let data = buf
+                .read_bytes(len)
+                .map_err(|_| invalid("truncated region data"))?;
+            snapshot.insert_region(address, data.to_vec())?;
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Builder that reads the regions infst uses from a live reader
+///
+/// Every `record_*` method is best-effort: unreadable regions are logged and
+/// skipped so that a partial snapshot can still be taken from a half-loaded game.
+pub struct SnapshotRecorder<'a, R: ReadMemory> {
+    reader: &'a R,
+    snapshot: MemorySnapshot,
+}
+
+impl<'a, R: ReadMemory> SnapshotRecorder<'a, R> {
+    /// Create a recorder for the given reader
+    pub fn new(reader: &'a R) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            reader,
+            snapshot: MemorySnapshot::new(SnapshotHeader {
+                base_address: reader.base_address(),
+                created_at,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Set the process ID stored in the header
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.snapshot.header.pid = pid;
+        self
+    }
+
+    /// Set the main module size stored in the header
+    pub fn module_size(mut self, module_size: u32) -> Self {
+        self.snapshot.header.module_size = module_size;
+        self
+    }
+
+    /// Set the game version and record the memory around its version string
+    pub fn game_version(mut self, version: impl Into<String>) -> Self {
+        let version = version.into();
+        self.record_version_string(&version);
+        self.snapshot.header.game_version = Some(version);
+        self
+    }
+
+    /// Record all structures referenced by the offsets
+    pub fn record_offsets(mut self, offsets: &OffsetsCollection) -> Self {
+        self.snapshot.header.offsets = offsets.clone();
+
+        if offsets.song_list != 0 {
+            self.record_song_list(offsets.song_list);
+        }
+        if offsets.data_map != 0 {
+            self.record_data_map(offsets.data_map);
+        }
+        if offsets.unlock_data != 0 {
+            self.record_unlock_data(offsets.unlock_data);
+        }
+        if offsets.judge_data != 0 {
+            self.record(offsets.judge_data, JUDGE_DATA_SIZE);
+        }
+        if offsets.play_settings != 0 {
+            self.record(
+                offsets
+                    .play_settings
+                    .wrapping_sub(settings::SONG_SELECT_MARKER),
+                PLAY_SETTINGS_SIZE,
+            );
+        }
+        if offsets.play_data != 0 {
+            self.record(offsets.play_data, PLAY_DATA_SIZE);
+        }
+        if offsets.current_song != 0 {
+            self.record(offsets.current_song, CURRENT_SONG_SIZE);
+        }
+        self
+    }
+
+    /// Record an arbitrary region
+    pub fn record_region(mut self, address: u64, size: usize) -> Self {
+        self.record(address, size);
+        self
+    }
+
+    /// Finish recording and return the snapshot
+    pub fn finish(self) -> MemorySnapshot {
+        debug!(
+            "Snapshot captured: {} regions, {} bytes",
+            self.snapshot.regions.len(),
+            self.snapshot.total_bytes()
+        );
+        self.snapshot
+    }
+
+    fn record(&mut self, address: u64, size: usize) -> bool {
+        match self.reader.read_bytes(address, size) {
+            Ok(bytes) => self.insert(address, bytes),
+            Err(e) => {
+                debug!("Snapshot: skipping 0x{:X} (+0x{:X}): {}", address, size, e);
+                false
+            }
+        }
+    }
+
+    /// Add read bytes to the snapshot, skipping regions past the address space
+    fn insert(&mut self, address: u64, bytes: Vec<u8>) -> bool {
+        match self.snapshot.insert_region(address, bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                debug!("Snapshot: skipping 0x{:X}: {}", address, e);
+                false
+            }
+        }
+    }
+
+    /// Record the largest readable prefix of a region, halving on failure
+    fn record_largest(&mut self, address: u64, mut size: usize, min_size: usize) {
+        while size >= min_size {
+            if self.record(address, size) {
+                return;
+            }
+            size /= 2;
+        }
+    }
+
+    fn record_song_list(&mut self, song_list: u64) {
+        // Entries plus the trailing metadata table read by fetch_song_database_bulk
+        let size = SONG_LIST_ENTRIES * SongInfo::MEMORY_SIZE + SongInfo::METADATA_TABLE_OFFSET;
+        self.record_largest(song_list, size, SongInfo::MEMORY_SIZE);
+    }
+
+    fn record_unlock_data(&mut self, unlock_data: u64) {
+        let mut entries = 0usize;
+        while entries < UNLOCK_MAX_ENTRIES {
+            let address = unlock_data + (entries * UnlockData::MEMORY_SIZE) as u64;
+            match self.reader.read_u32(address) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => entries += 1,
+            }
+        }
+
+        let count = (entries + 1).max(UNLOCK_MIN_ENTRIES);
+        self.record_largest(
+            unlock_data,
+            count * UnlockData::MEMORY_SIZE,
+            UnlockData::MEMORY_SIZE,
+        );
+    }
+
+    fn record_data_map(&mut self, data_map: u64) {
+        // null_obj at -16, table start/end at 0/+8, hash map markers up to +32
+        self.record(data_map.wrapping_sub(16), 64);
+
+        let (Ok(null_obj), Ok(start), Ok(end)) = (
+            self.reader.read_u64(data_map.wrapping_sub(16)),
+            self.reader.read_u64(data_map),
+            self.reader.read_u64(data_map + 8),
+        ) else {
+            return;
+        };
+        if end <= start {
+            return;
+        }
+
+        let Ok(table) = self.reader.read_bytes(start, (end - start) as usize) else {
+            debug!(
+                "Snapshot: data map table 0x{:X}..0x{:X} unreadable",
+                start, end
+            );
+            return;
+        };
+
+        let entry_points: Vec<u64> = {
+            let buf = ByteBuffer::new(&table);
+            (0..table.len() / 8)
+                .map(|i| buf.read_u64_at(i * 8).unwrap_or(0))
+                .filter(|&addr| addr != 0 && addr != null_obj && addr != DATA_MAP_SENTINEL)
+                .collect()
+        };
+        self.insert(start, table);
+
+        let mut visited: HashSet<u64> = HashSet::new();
+        for entry_point in entry_points {
+            let mut current = entry_point;
+            while visited.len() < DATA_MAP_MAX_NODES && visited.insert(current) {
+                let Ok(node) = self.reader.read_bytes(current, DATA_MAP_NODE_SIZE) else {
+                    break;
+                };
+                let next = ByteBuffer::new(&node).read_u64_at(0).unwrap_or(0);
+                self.insert(current, node);
+
+                if next == 0 || next == null_obj {
+                    break;
+                }
+                current = next;
+            }
+        }
+    }
+
+    fn record_version_string(&mut self, version: &str) {
+        if version.is_empty() {
+            return;
+        }
+
+        let needle = version.as_bytes();
+        let base = self.reader.base_address();
+        let mut address = base;
+        let end = base + VERSION_SCAN_SIZE as u64;
+        let mut matches = Vec::new();
+
+        while address < end {
+            // Overlap chunks so matches on a boundary are not missed
+            let size = VERSION_SCAN_CHUNK.min((end - address) as usize) + needle.len();
+            let Ok(chunk) = self.reader.read_bytes(address, size) else {
+                break;
+            };
+            matches
+                .extend(memchr::memmem::find_iter(&chunk, needle).map(|pos| address + pos as u64));
+            address += VERSION_SCAN_CHUNK as u64;
+        }
+
+        matches.dedup();
+        for found in matches {
+            self.record(
+                found.saturating_sub(VERSION_CONTEXT as u64),
+                VERSION_CONTEXT * 2 + needle.len(),
+            );
+        }
+    }
+}
+
+/// Memory reader that replays a [`MemorySnapshot`]
+///
+/// Reads that fall entirely inside recorded regions return the recorded bytes.
+/// Unrecorded bytes inside the main module image (`base_address` to
+/// `base_address + module_size`) read as zeros, mirroring the fact that the
+/// image is always mapped; this lets range scans such as the offset searcher
+/// and version detection run over a sparse snapshot. Use [`strict`] to make
+/// any unrecorded byte an error instead.
+///
+/// [`strict`]: SnapshotMemoryReader::strict
+#[derive(Debug, Clone)]
+pub struct SnapshotMemoryReader {
+    snapshot: MemorySnapshot,
+    zero_fill_module: bool,
+}
+
+impl SnapshotMemoryReader {
+    /// Create a reader over an in-memory snapshot
+    pub fn new(snapshot: MemorySnapshot) -> Self {
+        Self {
+            snapshot,
+            zero_fill_module: true,
+        }
+    }
+
+    /// Load a snapshot file and create a reader over it
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(MemorySnapshot::load(path)?))
+    }
+
+    /// Fail on any unrecorded byte, even inside the module image
+    pub fn strict(mut self) -> Self {
+        self.zero_fill_module = false;
+        self
+    }
+
+    /// Get the underlying snapshot
+    pub fn snapshot(&self) -> &MemorySnapshot {
+        &self.snapshot
+    }
+
+    /// Get the snapshot header
+    pub fn header(&self) -> &SnapshotHeader {
+        &self.snapshot.header
+    }
+
+    fn within_module(&self, address: u64, size: usize) -> bool {
+        let base = self.snapshot.header.base_address;
+        let (Some(module_end), Some(end)) = (
+            base.checked_add(self.snapshot.header.module_size as u64),
+            address.checked_add(size as u64),
+        ) else {
+            return false;
+        };
+        address >= base && end <= module_end
+    }
+}
+
+impl ReadMemory for SnapshotMemoryReader {
+    fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        if let Some(bytes) = self.snapshot.find_containing(address, size) {
+            return Ok(bytes.to_vec());
+        }
+
+        if self.zero_fill_module && self.within_module(address, size) {
+            let mut buffer = vec![0u8; size];
+            self.snapshot.copy_recorded(address, &mut buffer);
+            return Ok(buffer);
+        }
+
+        Err(Error::MemoryReadFailed {
+            address,
+            message: format!("0x{:X} bytes not recorded in snapshot", size),
+        })
+    }
+
+    fn base_address(&self) -> u64 {
+        self.snapshot.header.base_address
+    }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        Some(self)
+    }
+}
+
+impl MemoryRegions for SnapshotMemoryReader {
+    /// Recorded regions, plus the module image when it is zero-filled
+    fn memory_regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut regions: Vec<MemoryRegion> = self
+            .snapshot
+            .regions()
+            .iter()
+            .map(|r| MemoryRegion::new(r.address, r.data.len() as u64, Protection::READ))
+            .collect();
+        if self.zero_fill_module {
+            let header = &self.snapshot.header;
+            regions.push(MemoryRegion::new(
+                header.base_address,
+                header.module_size as u64,
+                Protection::READ,
+            ));
+        }
+        regions.sort_by_key(|r| r.start);
+        Ok(regions)
+    }
+}
+
+impl ProcessInfo for SnapshotMemoryReader {
+    fn pid(&self) -> u32 {
+        self.snapshot.header.pid
+    }
+
+    fn base_address(&self) -> u64 {
+        self.snapshot.header.base_address
+    }
+
+    fn module_size(&self) -> u32 {
+        self.snapshot.header.module_size
+    }
+
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    fn snapshot_with(regions: &[(u64, &[u8])]) -> MemorySnapshot {
+        let mut snapshot = MemorySnapshot::new(SnapshotHeader {
+            base_address: 0x1000,
+            module_size: 0x100,
+            ..Default::default()
+        });
+        for (address, data) in regions {
+            snapshot.insert_region(*address, data.to_vec()).unwrap();
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_insert_region_merges_overlapping() {
+        let snapshot = snapshot_with(&[(0x1000, &[1, 2, 3, 4]), (0x1002, &[9, 9, 9, 9])]);
+
+        assert_eq!(snapshot.regions().len(), 1);
+        assert_eq!(snapshot.regions()[0].address, 0x1000);
+        assert_eq!(snapshot.regions()[0].data, vec![1, 2, 9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn test_insert_region_merges_adjacent_and_keeps_order() {
+        let snapshot = snapshot_with(&[(0x2000, &[5, 6]), (0x1000, &[1, 2]), (0x1002, &[3, 4])]);
+
+        assert_eq!(snapshot.regions().len(), 2);
+        assert_eq!(snapshot.regions()[0].data, vec![1, 2, 3, 4]);
+        assert_eq!(snapshot.regions()[1].address, 0x2000);
+    }
+
+    #[test]
+    fn test_insert_region_spanning_several() {
+        let snapshot = snapshot_with(&[
+            (0x1000, &[1, 1]),
+            (0x1004, &[2, 2]),
+            (0x1008, &[3, 3]),
+            (0x1001, &[7, 7, 7, 7, 7, 7, 7, 7]),
+        ]);
+
+        assert_eq!(snapshot.regions().len(), 1);
+        assert_eq!(
+            snapshot.regions()[0].data,
+            vec![1, 7, 7, 7, 7, 7, 7, 7, 7, 3]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut snapshot = snapshot_with(&[(0x1000, &[1, 2, 3, 4]), (0x5000, &[0xAB; 32])]);
+        snapshot.header.game_version = Some("P2D:J:B:A:2026012800".to_string());
+        snapshot.header.offsets.song_list = 0x1234;
+
+        let mut bytes = Vec::new();
+        snapshot.write_to(&mut bytes).unwrap();
+        let loaded = MemorySnapshot::read_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.regions(), snapshot.regions());
+        assert_eq!(loaded.header.game_version, snapshot.header.game_version);
+        assert_eq!(loaded.header.offsets.song_list, 0x1234);
+    }
+
+    #[test]
+    fn test_read_from_rejects_bad_magic() {
+        let mut bytes = Vec::new();
+        let mut encoder = GzEncoder::new(&mut bytes, Compression::default());
+        encoder.write_all(b"NOTASNAP").unwrap();
+        encoder.finish().unwrap();
+
+        let result = MemorySnapshot::read_from(bytes.as_slice());
+        assert!(matches!(result, Err(Error::InvalidSnapshot(_))));
+    }
+
+    #[test]
+    fn test_insert_region_rejects_overflow() {
+        let mut snapshot = snapshot_with(&[]);
+        let result = snapshot.insert_region(u64::MAX - 1, vec![0; 4]);
+
+        assert!(matches!(result, Err(Error::InvalidSnapshot(_))));
+        assert!(snapshot.regions().is_empty());
+    }
+
+    #[test]
+    fn test_read_from_rejects_overflowing_region() {
+        let snapshot = snapshot_with(&[(0x1000, &[1, 2, 3, 4])]);
+        let mut bytes = Vec::new();
+        snapshot.write_to(&mut bytes).unwrap();
+
+        // Rewrite the region address so that address + len wraps around
+        let mut raw = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut raw)
+            .unwrap();
+        let at = raw
+            .windows(8)
+            .rposition(|w| w == 0x1000u64.to_le_bytes())
+            .unwrap();
+        raw[at..at + 8].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+        let mut tampered = Vec::new();
+        let mut encoder = GzEncoder::new(&mut tampered, Compression::default());
+        encoder.write_all(&raw).unwrap();
+        encoder.finish().unwrap();
+
+        let result = MemorySnapshot::read_from(tampered.as_slice());
+        assert!(matches!(result, Err(Error::InvalidSnapshot(_))));
+    }
+
+    #[test]
+    fn test_reader_zero_fills_inside_module() {
+        let reader = SnapshotMemoryReader::new(snapshot_with(&[(0x1010, &[1, 2, 3, 4])]));
+
+        let bytes = reader.read_bytes(0x100E, 8).unwrap();
+        assert_eq!(bytes, vec![0, 0, 1, 2, 3, 4, 0, 0]);
+
+        // Outside the module image nothing is synthesized
+        assert!(reader.read_bytes(0x10F0, 0x20).is_err());
+    }
+
+    #[test]
+    fn test_strict_reader_requires_recorded_bytes() {
+        let reader = SnapshotMemoryReader::new(snapshot_with(&[(0x1010, &[1, 2, 3, 4])])).strict();
+
+        assert_eq!(reader.read_i32(0x1010).unwrap(), 0x04030201);
+        assert!(reader.read_bytes(0x100E, 8).is_err());
+    }
+
+    #[test]
+    fn test_reader_serves_regions_outside_module() {
+        let reader =
+            SnapshotMemoryReader::new(snapshot_with(&[(0x9000, &[0xEF, 0xBE, 0xAD, 0xDE])]));
+
+        assert_eq!(reader.read_u32(0x9000).unwrap(), 0xDEADBEEF);
+    }
+
+    #[test]
+    fn test_recorder_follows_data_map_nodes() {
+        // data_map at 0x1010: null_obj at 0x1000, table at 0x1100..0x1110
+        // bucket 0 -> node 0x1200 -> node 0x1300
+        let reader = MockMemoryBuilder::new()
+            .with_size(0x400)
+            .write_u64(0x00, 0xFFFF)
+            .write_u64(0x10, 0x1100)
+            .write_u64(0x18, 0x1110)
+            .write_u64(0x100, 0x1200)
+            .write_u64(0x200, 0x1300)
+            .write_i32(0x214, 1001)
+            .write_u64(0x300, 0xFFFF)
+            .write_i32(0x314, 1002)
+            .build();
+
+        let offsets = OffsetsCollection {
+            data_map: 0x1010,
+            ..Default::default()
+        };
+        let snapshot = SnapshotRecorder::new(&reader)
+            .record_offsets(&offsets)
+            .finish();
+        let replay = SnapshotMemoryReader::new(snapshot).strict();
+
+        assert_eq!(replay.read_u64(0x1100).unwrap(), 0x1200);
+        assert_eq!(replay.read_i32(0x1214).unwrap(), 1001);
+        assert_eq!(replay.read_i32(0x1314).unwrap(), 1002);
+        // Nothing between the table and the first node was recorded
+        assert!(replay.read_bytes(0x1180, 4).is_err());
+    }
+}
@@ -0,0 +1,453 @@
+//! Timeline replay reader
+//!
+//! Overlays a sequence of timestamped memory states on top of a base memory
+//! image, so the tracker loop can be driven through song select, play and
+//! result screen without a running game.
+//!
+//! Each state is a small JSON file:
+//!
+//! ```json
+//! {
+//!   "name": "result screen",
+//!   "at_ms": 600,
+//!   "writes": [
+//!     { "at": "judge_data+0xD8", "i32": 0 },
+//!     { "at": "play_data+0x18", "i32": 5 }
+//!   ]
+//! }
+//! ```
+//!
+//! Addresses are either absolute (`0x140000000`) or relative to a field of
+//! [`OffsetsCollection`] (`judge_data+0xD8`, `play_settings-0x18`). A state
+//! with `"exit": true` marks the process as terminated from that time on.
+//! States are cumulative: a state only lists what changed since the previous one.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::clock::{Clock, system_clock};
+use crate::error::{Error, Result};
+use crate::offset::OffsetsCollection;
+use crate::process::{MemoryRegions, ProcessInfo, ReadMemory};
+
+/// One timestamped set of memory writes
+#[derive(Debug, Clone, Default)]
+pub struct TimelineState {
+    /// Label used in logs
+    pub name: String,
+    /// Time since the start of the timeline at which the writes become visible
+    pub at_ms: u64,
+    /// Whether the process is considered terminated from this state on
+    pub exit: bool,
+    writes: Vec<(u64, Vec<u8>)>,
+}
+
+impl TimelineState {
+    /// Create an empty state that becomes visible at `at_ms`
+    pub fn new(name: impl Into<String>, at_ms: u64) -> Self {
+        Self {
+            name: name.into(),
+            at_ms,
+            ..Default::default()
+        }
+    }
+
+    /// Mark the process as terminated from this state on
+    pub fn exit(mut self) -> Self {
+        self.exit = true;
+        self
+    }
+
+    /// Write raw bytes at an absolute address
+    pub fn write_bytes(mut self, address: u64, bytes: &[u8]) -> Self {
+        self.writes.push((address, bytes.to_vec()));
+        self
+    }
+
+    /// Write an i32 at an absolute address
+    pub fn write_i32(self, address: u64, value: i32) -> Self {
+        self.write_bytes(address, &value.to_le_bytes())
+    }
+
+    /// Write a u32 at an absolute address
+    pub fn write_u32(self, address: u64, value: u32) -> Self {
+        self.write_bytes(address, &value.to_le_bytes())
+    }
+
+    /// Write a u64 at an absolute address
+    pub fn write_u64(self, address: u64, value: u64) -> Self {
+        self.write_bytes(address, &value.to_le_bytes())
+    }
+
+    /// Parse a state from JSON, resolving symbolic addresses against `offsets`
+    pub fn from_json(json: &str, offsets: &OffsetsCollection) -> Result<Self> {
+        let file: StateFile = serde_json::from_str(json)?;
+
+        let mut state = Self::new(file.name.unwrap_or_default(), file.at_ms);
+        state.exit = file.exit;
+        for write in file.writes {
+            let address = resolve_address(&write.at, offsets)?;
+            state = state.write_bytes(address, &write.value.to_bytes());
+        }
+        Ok(state)
+    }
+
+    /// Load a state from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P, offsets: &OffsetsCollection) -> Result<Self> {
+        let path = path.as_ref();
+        let mut state = Self::from_json(&fs::read_to_string(path)?, offsets)?;
+        if state.name.is_empty() {
+            state.name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(state)
+    }
+
+    /// Copy the part of each write that overlaps `[address, address + buf.len())`
+    fn apply(&self, address: u64, buf: &mut [u8]) {
+        let end = address + buf.len() as u64;
+        for (write_addr, bytes) in &self.writes {
+            let write_end = write_addr + bytes.len() as u64;
+            let start = address.max(*write_addr);
+            let stop = end.min(write_end);
+            if start >= stop {
+                continue;
+            }
+            let dst = (start - address) as usize;
+            let src = (start - write_addr) as usize;
+            let len = (stop - start) as usize;
+            buf[dst..dst + len].copy_from_slice(&bytes[src..src + len]);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StateFile {
+    #[serde(default)]
+    name: Option<String>,
+    at_ms: u64,
+    #[serde(default)]
+    exit: bool,
+    #[serde(default)]
+    writes: Vec<WriteSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WriteSpec {
+    at: String,
+    #[serde(flatten)]
+    value: WriteValue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WriteValue {
+    I32(i32),
+    U32(u32),
+    U64(u64),
+    Bytes(Vec<u8>),
+}
+
+impl WriteValue {
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::I32(v) => v.to_le_bytes().to_vec(),
+            Self::U32(v) => v.to_le_bytes().to_vec(),
+            Self::U64(v) => v.to_le_bytes().to_vec(),
+            Self::Bytes(v) => v.clone(),
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Resolve `0x1234`, `judge_data`, `judge_data+0xD8` or `play_settings-0x18`
+fn resolve_address(expr: &str, offsets: &OffsetsCollection) -> Result<u64> {
+    let invalid = || Error::InvalidOffset(format!("Invalid timeline address: {}", expr));
+
+    if let Some(value) = parse_number(expr) {
+        return Ok(value);
+    }
+
+    let (symbol, delta, negative) = match expr.find(['+', '-']) {
+        Some(pos) => {
+            let delta = parse_number(&expr[pos + 1..]).ok_or_else(invalid)?;
+            (expr[..pos].trim(), delta, &expr[pos..pos + 1] == "-")
+        }
+        None => (expr.trim(), 0, false),
+    };
+
+    let base = match symbol {
+        "song_list" => offsets.song_list,
+        "data_map" => offsets.data_map,
+        "judge_data" => offsets.judge_data,
+        "play_data" => offsets.play_data,
+        "play_settings" => offsets.play_settings,
+        "unlock_data" => offsets.unlock_data,
+        "current_song" => offsets.current_song,
+        _ => return Err(invalid()),
+    };
+    if base == 0 {
+        return Err(Error::InvalidOffset(format!(
+            "Timeline address '{}' uses unset offset {}",
+            expr, symbol
+        )));
+    }
+
+    Ok(if negative {
+        base.wrapping_sub(delta)
+    } else {
+        base.wrapping_add(delta)
+    })
+}
+
+/// Memory reader that replays timestamped states over a base image
+///
+/// Reads go to the inner reader and are then patched with every state whose
+/// `at_ms` has elapsed since the timeline was started. Time comes from a
+/// [`Clock`]; share a `ManualClock` with the tracker to replay without waiting.
+pub struct TimelineMemoryReader<R> {
+    inner: R,
+    states: Vec<TimelineState>,
+    clock: Arc<dyn Clock>,
+    started: Duration,
+    pid: u32,
+    module_size: u32,
+}
+
+impl<R: ReadMemory> TimelineMemoryReader<R> {
+    /// Create a timeline over `inner` with no states
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            states: Vec::new(),
+            clock: system_clock(),
+            started: Duration::ZERO,
+            pid: 0,
+            module_size: 0,
+        }
+    }
+
+    /// Set the PID reported through `ProcessInfo`
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = pid;
+        self
+    }
+
+    /// Set the module size reported through `ProcessInfo`
+    pub fn with_module_size(mut self, size: u32) -> Self {
+        self.module_size = size;
+        self
+    }
+
+    /// Use the given clock and start the timeline at its current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.started = clock.elapsed();
+        self.clock = clock;
+        self
+    }
+
+    /// Add a state
+    pub fn state(mut self, state: TimelineState) -> Self {
+        let pos = self.states.partition_point(|s| s.at_ms <= state.at_ms);
+        self.states.insert(pos, state);
+        self
+    }
+
+    /// Load every `*.json` state file in a directory, in file name order
+    pub fn load_dir<P: AsRef<Path>>(mut self, dir: P, offsets: &OffsetsCollection) -> Result<Self> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            self = self.state(TimelineState::load(&path, offsets)?);
+        }
+        Ok(self)
+    }
+
+    /// Restart the timeline clock
+    pub fn start(&mut self) {
+        self.started = self.clock.elapsed();
+    }
+
+    /// Milliseconds elapsed since the timeline was started
+    pub fn elapsed_ms(&self) -> u64 {
+        self.clock
+            .elapsed()
+            .saturating_sub(self.started)
+            .as_millis() as u64
+    }
+
+    /// States that are visible at the current time
+    fn active_states(&self) -> impl Iterator<Item = &TimelineState> {
+        let now = self.elapsed_ms();
+        self.states.iter().take_while(move |s| s.at_ms <= now)
+    }
+
+    /// Name of the most recent visible state
+    pub fn current_state(&self) -> Option<&str> {
+        self.active_states().last().map(|s| s.name.as_str())
+    }
+
+    /// Total length of the timeline (time of the last state)
+    pub fn duration_ms(&self) -> u64 {
+        self.states.last().map_or(0, |s| s.at_ms)
+    }
+
+    /// Get the wrapped base reader
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R: ReadMemory> ReadMemory for TimelineMemoryReader<R> {
+    fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        let mut buf = self.inner.read_bytes(address, size)?;
+        for state in self.active_states() {
+            state.apply(address, &mut buf);
+        }
+        Ok(buf)
+    }
+
+    fn base_address(&self) -> u64 {
+        self.inner.base_address()
+    }
+
+    fn region_map(&self) -> Option<&dyn MemoryRegions> {
+        self.inner.region_map()
+    }
+}
+
+impl<R: ReadMemory> ProcessInfo for TimelineMemoryReader<R> {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn base_address(&self) -> u64 {
+        self.inner.base_address()
+    }
+
+    fn module_size(&self) -> u32 {
+        self.module_size
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.active_states().any(|s| s.exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::MockMemoryReader;
+
+    fn offsets() -> OffsetsCollection {
+        OffsetsCollection {
+            judge_data: 0x1100,
+            play_settings: 0x1200,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_address() {
+        let offsets = offsets();
+        assert_eq!(resolve_address("0x1010", &offsets).unwrap(), 0x1010);
+        assert_eq!(resolve_address("judge_data", &offsets).unwrap(), 0x1100);
+        assert_eq!(
+            resolve_address("judge_data+0xD8", &offsets).unwrap(),
+            0x11D8
+        );
+        assert_eq!(
+            resolve_address("play_settings-24", &offsets).unwrap(),
+            0x11E8
+        );
+        assert!(resolve_address("unknown+4", &offsets).is_err());
+        assert!(resolve_address("data_map+4", &offsets).is_err());
+    }
+
+    #[test]
+    fn test_state_from_json() {
+        let json = r#"{
+            "name": "playing",
+            "at_ms": 300,
+            "writes": [
+                { "at": "judge_data+0xD8", "i32": 1 },
+                { "at": "0x1000", "bytes": [1, 2, 3] }
+            ]
+        }"#;
+        let state = TimelineState::from_json(json, &offsets()).unwrap();
+
+        assert_eq!(state.name, "playing");
+        assert_eq!(state.at_ms, 300);
+        assert!(!state.exit);
+        assert_eq!(state.writes.len(), 2);
+        assert_eq!(state.writes[0], (0x11D8, vec![1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_state_from_json_rejects_unknown_value_type() {
+        let json = r#"{ "at_ms": 0, "writes": [{ "at": "0x1000", "f32": 1.0 }] }"#;
+        assert!(TimelineState::from_json(json, &offsets()).is_err());
+    }
+
+    #[test]
+    fn test_writes_overlay_base_image() {
+        let timeline = TimelineMemoryReader::new(MockMemoryReader::new(vec![0; 16]))
+            .state(TimelineState::new("a", 0).write_i32(0x1004, 7))
+            .state(TimelineState::new("b", 0).write_bytes(0x1006, &[9, 9, 9, 9]));
+
+        let bytes = timeline.read_bytes(0x1000, 8).unwrap();
+        assert_eq!(bytes, vec![0, 0, 0, 0, 7, 0, 9, 9]);
+        assert_eq!(timeline.current_state(), Some("b"));
+    }
+
+    #[test]
+    fn test_future_states_are_hidden() {
+        let timeline = TimelineMemoryReader::new(MockMemoryReader::new(vec![0; 16])).state(
+            TimelineState::new("later", 60_000)
+                .write_i32(0x1000, 1)
+                .exit(),
+        );
+
+        assert_eq!(timeline.read_i32(0x1000).unwrap(), 0);
+        assert!(timeline.is_alive());
+        assert_eq!(timeline.current_state(), None);
+        assert_eq!(timeline.duration_ms(), 60_000);
+    }
+
+    #[test]
+    fn test_states_follow_manual_clock() {
+        let clock = crate::clock::ManualClock::shared();
+        let timeline = TimelineMemoryReader::new(MockMemoryReader::new(vec![0; 16]))
+            .with_clock(clock.clone())
+            .state(TimelineState::new("later", 500).write_i32(0x1000, 1));
+
+        assert_eq!(timeline.read_i32(0x1000).unwrap(), 0);
+        clock.sleep_ms(500);
+        assert_eq!(timeline.elapsed_ms(), 500);
+        assert_eq!(timeline.read_i32(0x1000).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_exit_state_terminates_process() {
+        let timeline = TimelineMemoryReader::new(MockMemoryReader::new(vec![0; 16]))
+            .state(TimelineState::new("gone", 0).exit());
+        assert!(!timeline.is_alive());
+    }
+}
@@ -0,0 +1,448 @@
+//! Memory watch API.
+//!
+//! [`MemoryWatcher`] polls a set of address ranges and reports which bytes
+//! changed since the previous poll. It replaces repeated hexdumps when
+//! reverse-engineering new fields: watch a structure, play, and see which
+//! offsets move.
+//!
+//! Events can be recorded as JSON lines with [`WatchRecorder`] and read back
+//! with [`read_watch_log`].
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::ReadMemory;
+use crate::clock::{Clock, system_clock};
+use crate::error::{Error, Result};
+
+/// Default polling interval
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A run of bytes that changed between two polls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchEvent {
+    /// Address of the first changed byte (or word, see
+    /// [`MemoryWatcher::with_granularity`])
+    pub address: u64,
+    pub old_bytes: Vec<u8>,
+    pub new_bytes: Vec<u8>,
+    /// Clock time of the poll that saw the change
+    pub timestamp: Duration,
+}
+
+impl WatchEvent {
+    /// Offset of the change from the start of the watched range at `base`
+    pub fn offset_from(&self, base: u64) -> u64 {
+        self.address.saturating_sub(base)
+    }
+}
+
+/// A watched address range and its last seen contents
+#[derive(Debug, Clone)]
+struct WatchedRange {
+    address: u64,
+    size: usize,
+    last: Option<Vec<u8>>,
+    previous: Option<Vec<u8>>,
+}
+
+/// Polls memory ranges and reports changes
+///
+/// The first poll records a baseline and reports nothing. Each later poll
+/// compares against the previous one.
+pub struct MemoryWatcher<R: ReadMemory> {
+    reader: R,
+    ranges: Vec<WatchedRange>,
+    interval: Duration,
+    granularity: usize,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R: ReadMemory> MemoryWatcher<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            ranges: Vec::new(),
+            interval: DEFAULT_WATCH_INTERVAL,
+            granularity: 1,
+            clock: system_clock(),
+        }
+    }
+
+    /// Add an address range to watch
+    pub fn watch(mut self, address: u64, size: usize) -> Self {
+        self.add_range(address, size);
+        self
+    }
+
+    /// Add an address range to an existing watcher
+    pub fn add_range(&mut self, address: u64, size: usize) {
+        self.ranges.push(WatchedRange {
+            address,
+            size,
+            last: None,
+            previous: None,
+        });
+    }
+
+    /// Set the polling interval used by [`run`](Self::run)
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Report changes in aligned words of `granularity` bytes
+    ///
+    /// With a granularity of 4, a change to one byte of an `i32` field is
+    /// reported as the whole field. Alignment is relative to the start of
+    /// each watched range.
+    pub fn with_granularity(mut self, granularity: usize) -> Self {
+        self.granularity = granularity.max(1);
+        self
+    }
+
+    /// Use a custom clock for timestamps and sleeping
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Contents of the range starting at `address` as of the last poll
+    pub fn last_read(&self, address: u64) -> Option<&[u8]> {
+        self.ranges
+            .iter()
+            .find(|range| range.address == address)
+            .and_then(|range| range.last.as_deref())
+    }
+
+    /// Contents of the range starting at `address` as of the poll before the
+    /// last, i.e. the bytes the last poll's events were diffed against
+    pub fn previous_read(&self, address: u64) -> Option<&[u8]> {
+        self.ranges
+            .iter()
+            .find(|range| range.address == address)
+            .and_then(|range| range.previous.as_deref())
+    }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Read every range once and return the changes since the last poll
+    ///
+    /// Fails if a range cannot be read; its baseline is kept so the next
+    /// successful poll still reports changes against it.
+    pub fn poll(&mut self) -> Result<Vec<WatchEvent>> {
+        let timestamp = self.clock.elapsed();
+        let mut events = Vec::new();
+
+        for range in &mut self.ranges {
+            let current = self.reader.read_bytes(range.address, range.size)?;
+            if let Some(last) = &range.last {
+                events.extend(diff(
+                    range.address,
+                    last,
+                    &current,
+                    self.granularity,
+                    timestamp,
+                ));
+            }
+            range.previous = range.last.replace(current);
+        }
+
+        Ok(events)
+    }
+
+    /// Poll at the configured interval until `stop` is set
+    ///
+    /// `on_event` is called for every change with the watcher, so it can look
+    /// at the whole range through [`last_read`](Self::last_read) and
+    /// [`previous_read`](Self::previous_read). An error from it ends the run.
+    ///
+    /// Read failures after the first poll are logged and skipped, since
+    /// memory can be briefly unavailable while the game changes screens.
+    pub fn run<F>(&mut self, stop: &AtomicBool, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(&Self, &WatchEvent) -> Result<()>,
+    {
+        self.poll()?;
+        while !stop.load(Ordering::SeqCst) {
+            self.clock.sleep(self.interval);
+            match self.poll() {
+                Ok(events) => {
+                    for event in &events {
+                        on_event(self, event)?;
+                    }
+                }
+                Err(e) => debug!("Watch poll failed: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare two reads of the same range and group changed words into events
+fn diff(
+    base: u64,
+    old: &[u8],
+    new: &[u8],
+    granularity: usize,
+    timestamp: Duration,
+) -> Vec<WatchEvent> {
+    let len = old.len().min(new.len());
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+
+    for start in (0..len).step_by(granularity) {
+        let end = (start + granularity).min(len);
+        if old[start..end] == new[start..end] {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.1 == start => run.1 = end,
+            _ => runs.push((start, end)),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(start, end)| WatchEvent {
+            address: base + start as u64,
+            old_bytes: old[start..end].to_vec(),
+            new_bytes: new[start..end].to_vec(),
+            timestamp,
+        })
+        .collect()
+}
+
+/// One line of a watch log
+#[derive(Debug, Serialize, Deserialize)]
+struct WatchRecord {
+    elapsed_ms: u64,
+    address: String,
+    old: String,
+    new: String,
+}
+
+impl From<&WatchEvent> for WatchRecord {
+    fn from(event: &WatchEvent) -> Self {
+        Self {
+            elapsed_ms: event.timestamp.as_millis() as u64,
+            address: format!("0x{:X}", event.address),
+            old: format_hex(&event.old_bytes),
+            new: format_hex(&event.new_bytes),
+        }
+    }
+}
+
+impl TryFrom<WatchRecord> for WatchEvent {
+    type Error = Error;
+
+    fn try_from(record: WatchRecord) -> Result<Self> {
+        let digits = record.address.trim_start_matches("0x");
+        let address = u64::from_str_radix(digits, 16).map_err(|e| {
+            Error::InvalidOffset(format!("Invalid address '{}': {}", record.address, e))
+        })?;
+        Ok(Self {
+            address,
+            old_bytes: parse_hex(&record.old)?,
+            new_bytes: parse_hex(&record.new)?,
+            timestamp: Duration::from_millis(record.elapsed_ms),
+        })
+    }
+}
+
+/// Writes watch events as JSON lines
+///
+/// Each line looks like
+/// `{"elapsed_ms":1200,"address":"0x1431B08BC","old":"00 00 00 00","new":"20 03 00 00"}`.
+pub struct WatchRecorder<W: Write> {
+    writer: W,
+}
+
+impl WatchRecorder<File> {
+    /// Create (or truncate) a log file
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> WatchRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Append one event and flush, so the log survives an abrupt exit
+    pub fn record(&mut self, event: &WatchEvent) -> Result<()> {
+        let line = serde_json::to_string(&WatchRecord::from(event))?;
+        writeln!(self.writer, "{}", line)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Read a log written by [`WatchRecorder`]
+pub fn read_watch_log<P: AsRef<Path>>(path: P) -> Result<Vec<WatchEvent>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: WatchRecord = serde_json::from_str(&line)?;
+        events.push(record.try_into()?);
+    }
+    Ok(events)
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    text.split_whitespace()
+        .map(|token| {
+            u8::from_str_radix(token, 16)
+                .map_err(|e| Error::InvalidOffset(format!("Invalid hex byte '{}': {}", token, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::process::{
+        MockMemoryBuilder, MockMemoryReader, TimelineMemoryReader, TimelineState,
+    };
+
+    const BASE: u64 = 0x10000;
+
+    fn timeline(clock: Arc<ManualClock>) -> TimelineMemoryReader<MockMemoryReader> {
+        let image = MockMemoryBuilder::new().base(BASE).with_size(0x100).build();
+        TimelineMemoryReader::new(image)
+            .with_clock(clock)
+            .state(TimelineState::new("idle", 0))
+            .state(TimelineState::new("pgreat", 150).write_u32(BASE + 0x10, 800))
+            .state(
+                TimelineState::new("great", 250)
+                    .write_u32(BASE + 0x10, 801)
+                    .write_u32(BASE + 0x14, 1),
+            )
+    }
+
+    #[test]
+    fn test_poll_reports_changed_bytes() {
+        let clock = ManualClock::shared();
+        let mut watcher = MemoryWatcher::new(timeline(clock.clone()))
+            .watch(BASE, 0x40)
+            .with_clock(clock.clone());
+
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.last_read(BASE).map(<[u8]>::len), Some(0x40));
+
+        clock.sleep_ms(200);
+        let events = watcher.poll().unwrap();
+        assert_eq!(
+            events,
+            vec![WatchEvent {
+                address: BASE + 0x10,
+                old_bytes: vec![0x00, 0x00],
+                new_bytes: vec![0x20, 0x03],
+                timestamp: Duration::from_millis(200),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_granularity_reports_whole_words() {
+        let clock = ManualClock::shared();
+        let mut watcher = MemoryWatcher::new(timeline(clock.clone()))
+            .watch(BASE, 0x40)
+            .with_granularity(4)
+            .with_clock(clock.clone());
+
+        clock.sleep_ms(200);
+        watcher.poll().unwrap();
+        clock.sleep_ms(100);
+        let events = watcher.poll().unwrap();
+
+        // Adjacent changed words are merged into one event
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].offset_from(BASE), 0x10);
+        assert_eq!(events[0].old_bytes, [0x20, 0x03, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(events[0].new_bytes, [0x21, 0x03, 0, 0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_run_until_stopped() {
+        let clock = ManualClock::shared();
+        let mut watcher = MemoryWatcher::new(timeline(clock.clone()))
+            .watch(BASE, 0x40)
+            .with_interval(Duration::from_millis(100))
+            .with_clock(clock.clone());
+
+        let stop = AtomicBool::new(false);
+        let mut seen = Vec::new();
+        watcher
+            .run(&stop, |watcher, event| {
+                seen.push(event.timestamp);
+                let before = watcher.previous_read(BASE).unwrap();
+                let offset = event.offset_from(BASE) as usize;
+                assert_eq!(
+                    before[offset..offset + event.old_bytes.len()],
+                    event.old_bytes
+                );
+                if event.timestamp >= Duration::from_millis(300) {
+                    stop.store(true, Ordering::SeqCst);
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            seen,
+            // Two separate fields change at 300ms
+            [200, 300, 300].map(Duration::from_millis)
+        );
+    }
+
+    #[test]
+    fn test_recorder_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("watch.jsonl");
+        let event = WatchEvent {
+            address: 0x1431B08BC,
+            old_bytes: vec![0, 0, 0, 0],
+            new_bytes: vec![0x20, 0x03, 0, 0],
+            timestamp: Duration::from_millis(1200),
+        };
+
+        let mut recorder = WatchRecorder::create(&path).unwrap();
+        recorder.record(&event).unwrap();
+        recorder.record(&event).unwrap();
+        drop(recorder);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(r#"{"elapsed_ms":1200,"address":"0x1431B08BC","#));
+        assert_eq!(read_watch_log(&path).unwrap(), vec![event.clone(), event]);
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::Result;
 use crate::play::PlayType;
+use crate::process::layout::judge;
+use crate::process::{FromMemory, StructLayout};
 
 /// Raw judge data for a single player side (P1 or P2)
 #[derive(Debug, Clone, Default)]
@@ -30,6 +33,38 @@ pub struct RawJudgeData {
     pub p2: PlayerJudge,
 }
 
+impl FromMemory for RawJudgeData {
+    const LAYOUT: &'static StructLayout = &judge::LAYOUT;
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let p1 = PlayerJudge {
+            pgreat: judge::P1_PGREAT.decode(bytes)?,
+            great: judge::P1_GREAT.decode(bytes)?,
+            good: judge::P1_GOOD.decode(bytes)?,
+            bad: judge::P1_BAD.decode(bytes)?,
+            poor: judge::P1_POOR.decode(bytes)?,
+            combo_break: judge::P1_COMBO_BREAK.decode(bytes)?,
+            fast: judge::P1_FAST.decode(bytes)?,
+            slow: judge::P1_SLOW.decode(bytes)?,
+            measure_end: judge::P1_MEASURE_END.decode(bytes)?,
+        };
+
+        let p2 = PlayerJudge {
+            pgreat: judge::P2_PGREAT.decode(bytes)?,
+            great: judge::P2_GREAT.decode(bytes)?,
+            good: judge::P2_GOOD.decode(bytes)?,
+            bad: judge::P2_BAD.decode(bytes)?,
+            poor: judge::P2_POOR.decode(bytes)?,
+            combo_break: judge::P2_COMBO_BREAK.decode(bytes)?,
+            fast: judge::P2_FAST.decode(bytes)?,
+            slow: judge::P2_SLOW.decode(bytes)?,
+            measure_end: judge::P2_MEASURE_END.decode(bytes)?,
+        };
+
+        Ok(Self { p1, p2 })
+    }
+}
+
 /// Judge information from a play
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Judge {
@@ -96,6 +131,24 @@ impl Judge {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::MockMemoryBuilder;
+
+    #[test]
+    fn test_raw_judge_data_read() {
+        let reader = MockMemoryBuilder::new()
+            .base(0x1000)
+            .with_size(0x100)
+            .write_u32(judge::P1_PGREAT.offset, 800)
+            .write_u32(judge::P1_COMBO_BREAK.offset, 12)
+            .write_u32(judge::P2_SLOW.offset, 55)
+            .build();
+
+        let raw = RawJudgeData::read(&reader, 0x1000).unwrap();
+        assert_eq!(raw.p1.pgreat, 800);
+        assert_eq!(raw.p1.combo_break, 12);
+        assert_eq!(raw.p2.slow, 55);
+        assert!(RawJudgeData::read(&reader, 0x1080).is_err());
+    }
 
     #[test]
     fn test_player_judge_total_notes() {
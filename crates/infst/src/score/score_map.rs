@@ -2,7 +2,8 @@ use std::collections::{HashMap, HashSet};
 
 use crate::chart::{Difficulty, SongInfo};
 use crate::error::Result;
-use crate::process::{ByteBuffer, ReadMemory};
+use crate::process::layout::score_node;
+use crate::process::{ByteBuffer, FromMemory, ReadMemory, StructLayout};
 use crate::score::Lamp;
 
 /// Score data for a single song (all difficulties)
@@ -66,25 +67,24 @@ struct ListNode {
     lamp: i32,
 }
 
-impl ListNode {
-    const SIZE: usize = 64;
-
-    fn from_bytes(bytes: &[u8]) -> Self {
-        let buf = ByteBuffer::new(bytes);
-        Self {
-            next: buf.read_u64_at(0).unwrap_or(0),
-            prev: buf.read_u64_at(8).unwrap_or(0),
-            diff: buf.read_i32_at(16).unwrap_or(0),
-            song: buf.read_i32_at(20).unwrap_or(0),
-            playtype: buf.read_i32_at(24).unwrap_or(0),
-            // uk2 at 28-31
-            score: buf.read_u32_at(32).unwrap_or(0),
-            miss_count: buf.read_u32_at(36).unwrap_or(0),
-            // uk3 at 40-43, uk4 at 44-47
-            lamp: buf.read_i32_at(48).unwrap_or(0),
-        }
+impl FromMemory for ListNode {
+    const LAYOUT: &'static StructLayout = &score_node::LAYOUT;
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            next: score_node::NEXT.decode(bytes)?,
+            prev: score_node::PREV.decode(bytes)?,
+            diff: score_node::DIFFICULTY.decode(bytes)?,
+            song: score_node::SONG_ID.decode(bytes)?,
+            playtype: score_node::PLAY_TYPE.decode(bytes)?,
+            score: score_node::SCORE.decode(bytes)?,
+            miss_count: score_node::MISS_COUNT.decode(bytes)?,
+            lamp: score_node::LAMP.decode(bytes)?,
+        })
     }
+}
 
+impl ListNode {
     fn key(&self) -> (u32, i32, i32) {
         (self.song as u32, self.diff, self.playtype)
     }
@@ -183,10 +183,9 @@ impl ScoreMap {
             visited.insert(current_addr);
 
             // Read error = end of chain (not a fatal error)
-            let Ok(buffer) = reader.read_bytes(current_addr, ListNode::SIZE) else {
+            let Ok(node) = ListNode::read(reader, current_addr) else {
                 break;
             };
-            let node = ListNode::from_bytes(&buffer);
             let song_id = node.song as u32;
             let next_addr = node.next;
 
@@ -360,7 +359,7 @@ mod tests {
     }
 
     #[test]
-    fn test_list_node_from_bytes() {
+    fn test_list_node_decode() {
         // Create test bytes for ListNode (64 bytes)
         let mut bytes = [0u8; 64];
 
@@ -381,7 +380,7 @@ mod tests {
         // lamp (4 bytes at offset 48)
         bytes[48..52].copy_from_slice(&5i32.to_le_bytes()); // HardClear
 
-        let node = ListNode::from_bytes(&bytes);
+        let node = ListNode::decode(&bytes).unwrap();
 
         assert_eq!(node.next, 0x1234567890ABCDEF);
         assert_eq!(node.diff, 3);
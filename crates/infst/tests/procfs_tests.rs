@@ -0,0 +1,105 @@
+//! Linux `/proc` backend tests
+//!
+//! Uses stand-in processes instead of the game: the test binary itself (to read
+//! a known buffer) and a renamed copy of `sleep` (to exercise process discovery).
+
+#![cfg(target_os = "linux")]
+
+use std::path::Path;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use infst::process::{MemoryReader, ProcFsProvider, ProcessInfo, ProcessProvider, ReadMemory};
+
+fn own_exe_name() -> String {
+    std::env::current_exe()
+        .unwrap()
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Copy `sleep` under a new name and start it
+fn spawn_stand_in(dir: &Path, name: &str) -> Option<Child> {
+    let sleep = ["/bin/sleep", "/usr/bin/sleep"]
+        .into_iter()
+        .map(Path::new)
+        .find(|p| p.exists())?;
+    let exe = dir.join(name);
+    std::fs::copy(sleep, &exe).ok()?;
+    Command::new(&exe).arg("30").spawn().ok()
+}
+
+#[test]
+fn test_read_known_buffer_from_own_process() {
+    let provider = ProcFsProvider::new(own_exe_name());
+    let process = provider.open_process(std::process::id()).unwrap();
+    let reader = MemoryReader::new(&process);
+
+    let buffer: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+    let address = buffer.as_ptr() as u64;
+
+    assert_eq!(reader.read_bytes(address, buffer.len()).unwrap(), buffer);
+    assert_eq!(reader.read_u32(address + 4).unwrap(), 0x07060504);
+}
+
+#[test]
+fn test_module_range_points_at_own_executable() {
+    let provider = ProcFsProvider::new(own_exe_name());
+    let process = provider.open_process(std::process::id()).unwrap();
+    let reader = MemoryReader::new(&process);
+
+    assert!(process.module_size() > 0);
+    assert!(process.is_alive());
+    assert_eq!(
+        reader.read_bytes(process.base_address(), 4).unwrap(),
+        b"\x7fELF"
+    );
+}
+
+#[test]
+fn test_unmapped_read_fails() {
+    let provider = ProcFsProvider::new(own_exe_name());
+    let process = provider.open_process(std::process::id()).unwrap();
+    let reader = MemoryReader::new(&process);
+
+    assert!(reader.read_bytes(0, 16).is_err());
+}
+
+#[test]
+fn test_find_stand_in_process() {
+    let dir = tempfile::tempdir().unwrap();
+    // Unique per test run so concurrent runs don't find each other's stand-in
+    let name = format!("standin-{}.exe", std::process::id());
+    let Some(mut child) = spawn_stand_in(dir.path(), &name) else {
+        eprintln!("skipping: no sleep binary available");
+        return;
+    };
+
+    // The child is only found once it has exec'd and mapped the module
+    let provider = ProcFsProvider::new(&name);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let result = loop {
+        let result = provider.find_process();
+        if result.is_ok() || Instant::now() >= deadline {
+            break result;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let process = result.unwrap();
+    assert_eq!(process.pid(), child.id());
+    assert!(process.module_size() > 0);
+}
+
+#[test]
+fn test_missing_process_not_found() {
+    assert!(
+        ProcFsProvider::new("no-such-process.exe")
+            .find_process()
+            .is_err()
+    );
+}
@@ -12,8 +12,8 @@ fn test_validate_judge_data_candidate_valid() {
     let reader = MockMemoryBuilder::new()
         .base(0x1000)
         .with_size(0x100)
-        .write_i32(judge::STATE_MARKER_1 as usize, 50) // Valid marker (0-100)
-        .write_i32(judge::STATE_MARKER_2 as usize, 50) // Valid marker (0-100)
+        .write_i32(judge::STATE_MARKER_1.offset, 50) // Valid marker (0-100)
+        .write_i32(judge::STATE_MARKER_2.offset, 50) // Valid marker (0-100)
         .build();
 
     assert!(reader.validate_judge_data_candidate(0x1000));
@@ -24,8 +24,8 @@ fn test_validate_judge_data_candidate_invalid_markers() {
     let reader = MockMemoryBuilder::new()
         .base(0x1000)
         .with_size(0x100)
-        .write_i32(judge::STATE_MARKER_1 as usize, 200) // Invalid marker (> 100)
-        .write_i32(judge::STATE_MARKER_2 as usize, 50)
+        .write_i32(judge::STATE_MARKER_1.offset, 200) // Invalid marker (> 100)
+        .write_i32(judge::STATE_MARKER_2.offset, 50)
         .build();
 
     // Should fail because first marker is > 100
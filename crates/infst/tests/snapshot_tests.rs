@@ -0,0 +1,157 @@
+//! Snapshot capture and replay tests
+//!
+//! Builds a small fake game image, records it into a snapshot file, and checks
+//! that the loaders produce identical results when replayed offline.
+
+use infst::chart::{SongInfo, UnlockData, fetch_song_database_bulk, get_unlock_states};
+use infst::offset::{OffsetSearcher, OffsetsCollection};
+use infst::process::mock::{GAME_BASE, GAME_VERSION};
+use infst::process::{
+    MemorySnapshot, MockMemoryBuilder, MockMemoryReader, ProcessInfo, ReadMemory,
+    SnapshotMemoryReader, SnapshotRecorder,
+};
+use infst::score::{Lamp, ScoreMap};
+
+const SONG_LIST: usize = 0x100000;
+const UNLOCK_DATA: usize = 0x800000;
+const DATA_MAP: usize = 0x900000;
+const TABLE: usize = 0x910000;
+const NODES: usize = 0x920000;
+const IMAGE_SIZE: usize = 0x1000000;
+const NULL_OBJ: u64 = 0xDEAD0000;
+
+fn write_node(
+    builder: MockMemoryBuilder,
+    offset: usize,
+    next: u64,
+    song_id: i32,
+    diff: i32,
+    score: u32,
+    lamp: i32,
+) -> MockMemoryBuilder {
+    builder
+        .write_u64(offset, next)
+        .write_i32(offset + 16, diff)
+        .write_i32(offset + 20, song_id)
+        .write_i32(offset + 24, 0)
+        .write_u32(offset + 32, score)
+        .write_u32(offset + 36, 12)
+        .write_i32(offset + 48, lamp)
+}
+
+fn build_game_image() -> MockMemoryReader {
+    let mut builder = MockMemoryBuilder::game_image(IMAGE_SIZE);
+
+    for (i, (song_id, title)) in [(1000, "5.1.1."), (1001, "GAMBOL"), (1002, "piano ambient")]
+        .into_iter()
+        .enumerate()
+    {
+        let unlocks = if song_id == 1000 { 462 } else { 31 };
+        builder = builder
+            .write_song(SONG_LIST + i * SongInfo::MEMORY_SIZE, song_id, title)
+            .write_unlock(UNLOCK_DATA + i * UnlockData::MEMORY_SIZE, song_id, unlocks);
+    }
+
+    builder = builder
+        .write_u64(DATA_MAP - 16, NULL_OBJ)
+        .write_u64(DATA_MAP, GAME_BASE + TABLE as u64)
+        .write_u64(DATA_MAP + 8, GAME_BASE + TABLE as u64 + 16)
+        .write_u64(TABLE, GAME_BASE + NODES as u64)
+        .write_u64(TABLE + 8, NULL_OBJ);
+    builder = write_node(
+        builder,
+        NODES,
+        GAME_BASE + NODES as u64 + 64,
+        1000,
+        3,
+        1500,
+        5,
+    );
+    builder = write_node(builder, NODES + 64, 0, 1002, 2, 900, 4);
+
+    builder.build()
+}
+
+fn offsets() -> OffsetsCollection {
+    OffsetsCollection {
+        version: GAME_VERSION.to_string(),
+        song_list: GAME_BASE + SONG_LIST as u64,
+        unlock_data: GAME_BASE + UNLOCK_DATA as u64,
+        data_map: GAME_BASE + DATA_MAP as u64,
+        ..Default::default()
+    }
+}
+
+fn capture_and_reload(reader: &MockMemoryReader) -> SnapshotMemoryReader {
+    let snapshot = SnapshotRecorder::new(reader)
+        .pid(4242)
+        .module_size(IMAGE_SIZE as u32)
+        .record_offsets(&offsets())
+        .finish();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.snap");
+    snapshot.save(&path).unwrap();
+
+    SnapshotMemoryReader::new(MemorySnapshot::load(&path).unwrap())
+}
+
+#[test]
+fn test_snapshot_header_round_trip() {
+    let live = build_game_image();
+    let replay = capture_and_reload(&live);
+
+    assert_eq!(replay.pid(), 4242);
+    assert_eq!(ReadMemory::base_address(&replay), GAME_BASE);
+    assert_eq!(replay.module_size(), IMAGE_SIZE as u32);
+    assert_eq!(
+        replay.header().offsets.data_map,
+        GAME_BASE + DATA_MAP as u64
+    );
+}
+
+#[test]
+fn test_song_database_replays_offline() {
+    let live = build_game_image();
+    let replay = capture_and_reload(&live);
+    let offsets = offsets();
+
+    let expected = fetch_song_database_bulk(&live, offsets.song_list).unwrap();
+    let actual = fetch_song_database_bulk(&replay, offsets.song_list).unwrap();
+
+    assert_eq!(actual.len(), 3);
+    assert_eq!(actual.len(), expected.len());
+    assert_eq!(actual[&1001].title.as_ref(), "GAMBOL");
+}
+
+#[test]
+fn test_score_map_and_unlocks_replay_offline() {
+    let live = build_game_image();
+    let replay = capture_and_reload(&live).strict();
+    let offsets = offsets();
+
+    let song_db = fetch_song_database_bulk(&replay, offsets.song_list).unwrap();
+
+    let scores = ScoreMap::load_from_memory(&replay, offsets.data_map, &song_db).unwrap();
+    assert_eq!(scores.len(), 2);
+    let song = scores.get(1000).unwrap();
+    assert_eq!(song.score[3], 1500);
+    assert_eq!(song.lamp[3], Lamp::HardClear);
+    assert_eq!(scores.get(1002).unwrap().score[2], 900);
+
+    let unlocks = get_unlock_states(&replay, offsets.unlock_data, &song_db).unwrap();
+    assert_eq!(unlocks.len(), 3);
+    assert_eq!(unlocks[&1000].unlocks, 462);
+}
+
+#[test]
+fn test_offset_searcher_runs_on_snapshot() {
+    let live = build_game_image();
+    let replay = capture_and_reload(&live);
+
+    let mut searcher = OffsetSearcher::new(&replay);
+    let unlock_data = searcher
+        .search_unlock_data_offset(offsets().song_list)
+        .unwrap();
+    assert_eq!(unlock_data, GAME_BASE + UNLOCK_DATA as u64);
+}
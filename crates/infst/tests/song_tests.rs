@@ -123,7 +123,8 @@ mod song_info_read {
         assert_eq!(&*song.title, "Test Song");
         assert_eq!(song.folder, 5);
         assert_eq!(song.levels, [0, 3, 6, 9, 12, 0, 3, 6, 9, 12]);
-        assert_eq!(&*song.bpm, "130~150");
+        assert_eq!(song.bpm.to_string(), "130~150");
+        assert!(song.bpm.is_soflan());
     }
 
     #[test]
@@ -141,7 +142,7 @@ mod song_info_read {
         let result = SongInfo::read_from_memory(&reader, 0x1000).unwrap();
 
         let song = result.unwrap();
-        assert_eq!(&*song.bpm, "180");
+        assert_eq!(song.bpm.to_string(), "180");
     }
 
     #[test]
@@ -159,7 +160,7 @@ mod song_info_read {
         let result = SongInfo::read_from_memory(&reader, 0x1000).unwrap();
 
         let song = result.unwrap();
-        assert_eq!(&*song.bpm, "160");
+        assert_eq!(song.bpm.to_string(), "160");
     }
 
     #[test]
@@ -397,7 +398,7 @@ mod song_info_defaults {
         assert!(song.title_english.is_empty());
         assert!(song.artist.is_empty());
         assert!(song.genre.is_empty());
-        assert!(song.bpm.is_empty());
+        assert!(song.bpm.is_unknown());
         assert_eq!(song.folder, 0);
         assert_eq!(song.levels, [0u8; 10]);
         assert_eq!(song.total_notes, [0u32; 10]);
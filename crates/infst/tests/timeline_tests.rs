@@ -0,0 +1,237 @@
+//! End-to-end tracker loop tests driven by a memory timeline
+//!
+//! The game is replaced by a fake memory image plus the declarative states in
+//! `tests/fixtures/timeline`: idle song select, playing, a result screen whose
+//! lamp is written late, and a return to song select before the game exits.
+
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use infst::chart::{SongCache, SongInfo, UnlockData, fetch_song_database_bulk};
+use infst::clock::{Clock, ManualClock};
+use infst::infst::{Infst, InfstConfig};
+use infst::offset::OffsetsCollection;
+use infst::process::mock::{GAME_BASE, GAME_VERSION};
+use infst::process::{
+    CachingReader, MockMemoryBuilder, MockMemoryReader, TimelineMemoryReader, TimelineState,
+};
+
+const SONG_LIST: u64 = 0x100000;
+const UNLOCK_DATA: u64 = 0x800000;
+const DATA_MAP: u64 = 0x900000;
+const JUDGE_DATA: u64 = 0xA00000;
+const PLAY_SETTINGS: u64 = 0xA10000;
+const IMAGE_SIZE: usize = 0x1000000;
+
+fn offsets() -> OffsetsCollection {
+    OffsetsCollection {
+        version: GAME_VERSION.to_string(),
+        song_list: GAME_BASE + SONG_LIST,
+        data_map: GAME_BASE + DATA_MAP,
+        judge_data: GAME_BASE + JUDGE_DATA,
+        play_data: GAME_BASE + PLAY_SETTINGS + 0x2A0,
+        play_settings: GAME_BASE + PLAY_SETTINGS,
+        unlock_data: GAME_BASE + UNLOCK_DATA,
+        current_song: GAME_BASE + JUDGE_DATA + 0x1E4,
+    }
+}
+
+fn build_game_image() -> MockMemoryReader {
+    let mut builder = MockMemoryBuilder::game_image(IMAGE_SIZE);
+
+    for (i, (song_id, title)) in [(1000, "5.1.1."), (1001, "GAMBOL")].into_iter().enumerate() {
+        builder = builder
+            .write_song(
+                SONG_LIST as usize + i * SongInfo::MEMORY_SIZE,
+                song_id,
+                title,
+            )
+            .write_unlock(
+                UNLOCK_DATA as usize + i * UnlockData::MEMORY_SIZE,
+                song_id,
+                31,
+            );
+    }
+
+    // Empty score table (start == end)
+    let table = GAME_BASE + DATA_MAP + 0x100;
+    builder
+        .write_u64(DATA_MAP as usize, table)
+        .write_u64(DATA_MAP as usize + 8, table)
+        .build()
+}
+
+fn fixture_dir() -> &'static Path {
+    Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/timeline"
+    ))
+}
+
+fn read_session_tsv(dir: &Path) -> String {
+    let mut sessions: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "tsv"))
+        .collect();
+    assert_eq!(sessions.len(), 1, "expected exactly one session file");
+    std::fs::read_to_string(sessions.pop().unwrap()).unwrap()
+}
+
+#[test]
+fn test_fixture_states_load_in_order() {
+    let timeline = TimelineMemoryReader::new(build_game_image())
+        .load_dir(fixture_dir(), &offsets())
+        .unwrap();
+
+    assert_eq!(timeline.duration_ms(), 4300);
+    assert_eq!(timeline.current_state(), Some("idle song select"));
+}
+
+#[test]
+fn test_tracker_loop_records_result_from_timeline() {
+    let offsets = offsets();
+    let image = build_game_image();
+    let song_db = fetch_song_database_bulk(&image, offsets.song_list).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = InfstConfig::builder()
+        .session_dir(dir.path().join("sessions"))
+        .tracker_path(dir.path().join("tracker.tsv"))
+        .build();
+    let clock = ManualClock::shared();
+    let mut infst = Infst::with_config(offsets.clone(), config);
+    infst.set_song_db(song_db);
+    infst.set_clock(clock.clone());
+
+    let timeline = TimelineMemoryReader::new(image)
+        .with_pid(4242)
+        .with_module_size(IMAGE_SIZE as u32)
+        .with_clock(clock.clone())
+        .load_dir(fixture_dir(), &offsets)
+        .unwrap();
+
+    infst
+        .run_with_reader(&timeline, &timeline, &AtomicBool::new(false))
+        .unwrap();
+
+    let tsv = read_session_tsv(&dir.path().join("sessions"));
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(lines.len(), 2, "header plus one play:\n{}", tsv);
+
+    let header: Vec<&str> = lines[0].split('\t').collect();
+    let row: Vec<&str> = lines[1].split('\t').collect();
+    let column = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+
+    assert_eq!(column("title"), "GAMBOL");
+    assert_eq!(column("difficulty"), "SPA");
+    assert_eq!(column("notecount"), "1000");
+    assert_eq!(column("lamp"), "CLEAR");
+    assert_eq!(column("exscore"), "1750");
+    assert_eq!(column("pgreat"), "800");
+    assert_eq!(column("great"), "150");
+    assert_eq!(column("combobreak"), "12");
+    assert_eq!(column("fast"), "40");
+    assert_eq!(column("slow"), "55");
+
+    // Returning to song select triggers the tracker export
+    assert!(dir.path().join("tracker.tsv").exists());
+
+    // The loop stops on the first poll after the exit state, in virtual time
+    let elapsed = clock.elapsed();
+    assert!(elapsed >= Duration::from_millis(4300));
+    assert!(elapsed < Duration::from_millis(4500));
+}
+
+#[test]
+fn test_songs_discovered_in_session_are_cached() {
+    let offsets = offsets();
+    let image = build_game_image();
+    let mut song_db = fetch_song_database_bulk(&image, offsets.song_list).unwrap();
+    // GAMBOL has not been scrolled to yet
+    song_db.remove(&1001);
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join("songs.json");
+    let config = InfstConfig::builder()
+        .session_dir(dir.path().join("sessions"))
+        .auto_export(false)
+        .build();
+    let clock = ManualClock::shared();
+    let mut infst = Infst::with_config(offsets.clone(), config);
+    infst.set_song_db(song_db.clone());
+    infst.set_song_cache(SongCache::new(&offsets.version), &cache_path);
+    infst.set_clock(clock.clone());
+
+    let timeline = TimelineMemoryReader::new(image)
+        .with_clock(clock.clone())
+        .load_dir(fixture_dir(), &offsets)
+        .unwrap();
+    infst
+        .run_with_reader(&timeline, &timeline, &AtomicBool::new(false))
+        .unwrap();
+
+    let cache = SongCache::load_from_path(&cache_path, &offsets.version);
+    assert_eq!(cache.songs[&1001].title.as_ref(), "GAMBOL");
+    assert!(cache.songs.contains_key(&1000));
+}
+
+#[test]
+fn test_result_without_lamp_is_not_recorded() {
+    let offsets = offsets();
+    let image = build_game_image();
+    let song_db = fetch_song_database_bulk(&image, offsets.song_list).unwrap();
+    let clock = ManualClock::shared();
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = InfstConfig::builder()
+        .session_dir(dir.path())
+        .auto_export(false)
+        .build();
+    let mut infst = Infst::with_config(offsets.clone(), config);
+    infst.set_song_db(song_db);
+    infst.set_clock(clock.clone());
+
+    // Lamp never gets written: the result is dropped after polling gives up
+    let timeline = TimelineMemoryReader::new(image)
+        .with_clock(clock.clone())
+        .state(TimelineState::load(fixture_dir().join("00_song_select.json"), &offsets).unwrap())
+        .state(TimelineState::load(fixture_dir().join("01_playing.json"), &offsets).unwrap())
+        .state(TimelineState::load(fixture_dir().join("02_result_screen.json"), &offsets).unwrap())
+        .state(TimelineState::new("game closed", 6000).exit());
+
+    infst
+        .run_with_reader(&timeline, &timeline, &AtomicBool::new(false))
+        .unwrap();
+
+    let tsv = read_session_tsv(dir.path());
+    assert_eq!(tsv.lines().count(), 1, "only the header:\n{}", tsv);
+}
+
+#[test]
+fn test_result_capture_coalesces_reads() {
+    let offsets = offsets();
+    let image = build_game_image();
+    let song_db = fetch_song_database_bulk(&image, offsets.song_list).unwrap();
+    let clock = ManualClock::shared();
+
+    let mut infst = Infst::new(offsets.clone());
+    infst.set_song_db(song_db);
+
+    let timeline = TimelineMemoryReader::new(image)
+        .with_clock(clock.clone())
+        .load_dir(fixture_dir(), &offsets)
+        .unwrap();
+    clock.sleep_ms(3100);
+
+    let cache = CachingReader::new(&timeline);
+    let play_data = infst.fetch_play_data(&cache).unwrap();
+    assert_eq!(play_data.ex_score, 1750);
+
+    // Judge, settings and play data are each decoded from a single read,
+    // and live on a handful of pages
+    let stats = cache.stats();
+    assert!(stats.misses <= 3, "{:?}", stats);
+    assert!(stats.hits + stats.misses <= 4, "{:?}", stats);
+}
@@ -20,8 +20,8 @@ mod judge_data {
             .base(0x1000)
             .with_size(0x100)
             // First 72 bytes are zeros (judgment region)
-            .write_i32(judge::STATE_MARKER_1 as usize, 0) // Marker in range 0-100
-            .write_i32(judge::STATE_MARKER_2 as usize, 0)
+            .write_i32(judge::STATE_MARKER_1.offset, 0) // Marker in range 0-100
+            .write_i32(judge::STATE_MARKER_2.offset, 0)
             .build();
 
         assert!(reader.validate_judge_data_candidate(0x1000));
@@ -38,8 +38,8 @@ mod judge_data {
             .write_i32(8, 10) // P1 good
             .write_i32(12, 5) // P1 bad
             .write_i32(16, 2) // P1 poor
-            .write_i32(judge::STATE_MARKER_1 as usize, 50)
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 50)
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         assert!(reader.validate_judge_data_candidate(0x1000));
@@ -50,8 +50,8 @@ mod judge_data {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, 150) // Invalid: > 100
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 150) // Invalid: > 100
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         assert!(!reader.validate_judge_data_candidate(0x1000));
@@ -62,8 +62,8 @@ mod judge_data {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, -1) // Invalid: negative
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, -1) // Invalid: negative
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         assert!(!reader.validate_judge_data_candidate(0x1000));
@@ -74,8 +74,8 @@ mod judge_data {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, 50)
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 50)
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         // Unaligned address should fail
@@ -91,8 +91,8 @@ mod judge_data {
             .base(0x1000)
             .with_size(0x100)
             .write_i32(0, 6000) // Invalid: > MAX_NOTES
-            .write_i32(judge::STATE_MARKER_1 as usize, 50)
-            .write_i32(judge::STATE_MARKER_2 as usize, 50)
+            .write_i32(judge::STATE_MARKER_1.offset, 50)
+            .write_i32(judge::STATE_MARKER_2.offset, 50)
             .build();
 
         assert!(!reader.validate_judge_data_candidate(0x1000));
@@ -104,8 +104,8 @@ mod judge_data {
         let reader = MockMemoryBuilder::new()
             .base(0x1000)
             .with_size(0x100)
-            .write_i32(judge::STATE_MARKER_1 as usize, 0) // Min valid
-            .write_i32(judge::STATE_MARKER_2 as usize, 100) // Max valid
+            .write_i32(judge::STATE_MARKER_1.offset, 0) // Min valid
+            .write_i32(judge::STATE_MARKER_2.offset, 100) // Max valid
             .build();
 
         assert!(reader.validate_judge_data_candidate(0x1000));